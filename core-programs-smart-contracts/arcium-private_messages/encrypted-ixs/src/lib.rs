@@ -4,6 +4,77 @@ use arcis::*;
 mod circuits {
     use arcis::*;
 
+    // ============================================================================
+    // PRIMITIVES - Helpers partagés par tous les circuits
+    // ============================================================================
+    //
+    // Toutes ces fonctions ont une forme constante : pas de branche dépendant
+    // d'une valeur secrète, les deux côtés d'une sélection sont toujours évalués.
+    // ============================================================================
+
+    // Certains helpers ne sont pas encore utilisés par un circuit
+    #[allow(dead_code)]
+    pub mod primitives {
+        use arcis::*;
+
+        /// Égalité de deux tableaux d'octets, indépendante des données.
+        /// Accumule les différences octet par octet puis réduit en un seul bit.
+        pub fn array_eq<const N: usize>(a: &[u8; N], b: &[u8; N]) -> bool {
+            let mut diff = false;
+            for i in 0..N {
+                diff |= a[i] != b[i];
+            }
+            !diff
+        }
+
+        /// Égalité de deux hashes de 32 octets : 1 si égaux, 0 sinon
+        pub fn hash_eq(a: &[u8; 32], b: &[u8; 32]) -> u8 {
+            array_eq(a, b) as u8
+        }
+
+        /// Sélection conditionnelle : `a` si `cond`, sinon `b`
+        pub fn select_u128(cond: bool, a: u128, b: u128) -> u128 {
+            if cond {
+                a
+            } else {
+                b
+            }
+        }
+
+        /// Vrai si `x` est présent dans `set` (parcourt toujours tout l'ensemble)
+        pub fn contains_u128<const N: usize>(set: &[u128; N], x: u128) -> bool {
+            let mut found = false;
+            for member in set.iter() {
+                found |= *member == x;
+            }
+            found
+        }
+
+        /// Sélection conditionnelle : `a` si `cond`, sinon `b`
+        pub fn select_u64(cond: bool, a: u64, b: u64) -> u64 {
+            if cond {
+                a
+            } else {
+                b
+            }
+        }
+
+        /// Minimum de deux valeurs
+        pub fn min_u64(a: u64, b: u64) -> u64 {
+            select_u64(a < b, a, b)
+        }
+
+        /// Maximum de deux valeurs
+        pub fn max_u64(a: u64, b: u64) -> u64 {
+            select_u64(a > b, a, b)
+        }
+
+        /// Vrai si `lo <= x <= hi`
+        pub fn in_range_u64(x: u64, lo: u64, hi: u64) -> bool {
+            (x >= lo) & (x <= hi)
+        }
+    }
+
     // ============================================================================
     // PRIVATE MESSAGES - Encrypted Instructions
     // ============================================================================
@@ -38,15 +109,178 @@ mod circuits {
     ) -> Enc<Shared, u8> {
         let check = input.to_arcis();
 
-        // Compare les deux hashes de manière chiffrée
-        let mut is_match: u8 = 1;
+        // Compare les deux hashes de manière chiffrée, sans branche
+        let is_match = primitives::hash_eq(&check.recipient_hash, &check.requester_hash);
+
+        input.owner.from_arcis(is_match)
+    }
+
+    // ============================================================================
+    // ENCRYPTED SET MEMBERSHIP - Allowlists privées et ACL de groupe
+    // ============================================================================
+    //
+    // Un ensemble de taille fixe (32 emplacements) d'empreintes u128, chiffré
+    // pour le MXE et stocké on-chain. La valeur 0 marque un emplacement vide.
+    // ============================================================================
+
+    /// Ensemble chiffré d'empreintes de membres
+    pub struct MemberSet {
+        members: [u128; 32],
+    }
+
+    /// Crée un ensemble vide chiffré pour le MXE
+    #[instruction]
+    pub fn init_member_set(mxe: Mxe) -> Enc<Mxe, MemberSet> {
+        mxe.from_arcis(MemberSet { members: [0; 32] })
+    }
+
+    /// Écrit l'empreinte chiffrée d'un membre dans l'emplacement `slot` (public)
+    #[instruction]
+    pub fn add_set_member(
+        member_ctxt: Enc<Shared, u128>,
+        slot: u8,
+        set_ctxt: Enc<Mxe, MemberSet>,
+    ) -> Enc<Mxe, MemberSet> {
+        let member = member_ctxt.to_arcis();
+        let mut set = set_ctxt.to_arcis();
+
         for i in 0..32 {
-            if check.recipient_hash[i] != check.requester_hash[i] {
-                is_match = 0;
-            }
+            set.members[i] = primitives::select_u128(i as u8 == slot, member, set.members[i]);
         }
 
-        input.owner.from_arcis(is_match)
+        set_ctxt.owner.from_arcis(set)
+    }
+
+    /// Vérifie si l'empreinte chiffrée appartient à l'ensemble.
+    /// Retourne un bit d'appartenance chiffré pour le demandeur.
+    #[instruction]
+    pub fn check_set_membership(
+        element_ctxt: Enc<Shared, u128>,
+        set_ctxt: Enc<Mxe, MemberSet>,
+    ) -> Enc<Shared, bool> {
+        let element = element_ctxt.to_arcis();
+        let set = set_ctxt.to_arcis();
+
+        let is_member = primitives::contains_u128(&set.members, element) & (element != 0);
+
+        element_ctxt.owner.from_arcis(is_member)
+    }
+
+    // ============================================================================
+    // ENCRYPTED RANGE CHECK - Montant minimum / validation de paiement privés
+    // ============================================================================
+
+    /// Vérifie qu'un montant chiffré est dans l'intervalle public [min, max].
+    /// Seul le booléen chiffré est renvoyé, le montant n'est jamais révélé.
+    #[instruction]
+    pub fn check_amount_range(amount_ctxt: Enc<Shared, u64>, min: u64, max: u64) -> Enc<Shared, bool> {
+        let amount = amount_ctxt.to_arcis();
+
+        amount_ctxt.owner.from_arcis(primitives::in_range_u64(amount, min, max))
+    }
+
+    // ============================================================================
+    // PRIVATE TOKEN-BUCKET RATE LIMITING
+    // ============================================================================
+    //
+    // L'état du seau (jetons restants, dernier remplissage) reste chiffré pour le
+    // MXE dans un compte on-chain : seul "envoi autorisé ou non" est révélé,
+    // jamais le nombre de messages envoyés.
+    // ============================================================================
+
+    /// État chiffré d'un seau à jetons
+    pub struct TokenBucket {
+        tokens: u64,
+        last_refill: u64,
+    }
+
+    /// Crée un seau plein
+    #[instruction]
+    pub fn init_rate_bucket(mxe: Mxe, capacity: u64, now: u64) -> Enc<Mxe, TokenBucket> {
+        mxe.from_arcis(TokenBucket {
+            tokens: capacity,
+            last_refill: now,
+        })
+    }
+
+    /// Remplit le seau d'un jeton par `refill_secs` écoulées (plafonné à `capacity`)
+    /// puis consomme un jeton si possible. Révèle uniquement si l'envoi est autorisé.
+    #[instruction]
+    pub fn consume_rate_token(
+        bucket_ctxt: Enc<Mxe, TokenBucket>,
+        now: u64,
+        capacity: u64,
+        refill_secs: u64,
+    ) -> (Enc<Mxe, TokenBucket>, bool) {
+        let mut bucket = bucket_ctxt.to_arcis();
+
+        let elapsed = primitives::max_u64(now, bucket.last_refill) - bucket.last_refill;
+        let refilled = elapsed / refill_secs;
+        bucket.tokens = primitives::min_u64(bucket.tokens + refilled, capacity);
+        bucket.last_refill += refilled * refill_secs;
+
+        let allowed = bucket.tokens > 0;
+        bucket.tokens -= allowed as u64;
+
+        (bucket_ctxt.owner.from_arcis(bucket), allowed.reveal())
+    }
+
+    // ============================================================================
+    // ENCRYPTED POLL TALLY - Sondages secrets dans les conversations
+    // ============================================================================
+    //
+    // Chaque bulletin (0 = oui, 1 = non, 2 = abstention) est chiffré par le votant
+    // et ajouté au décompte chiffré pour le MXE. Seuls les totaux sont révélés,
+    // et uniquement au créateur du sondage : ni les admins du groupe ni le
+    // créateur ne voient les votes individuels.
+    // ============================================================================
+
+    /// Décompte chiffré d'un sondage
+    pub struct PollTally {
+        yes: u64,
+        no: u64,
+        abstain: u64,
+    }
+
+    /// Crée un décompte à zéro
+    #[instruction]
+    pub fn init_poll_tally(mxe: Mxe) -> Enc<Mxe, PollTally> {
+        mxe.from_arcis(PollTally {
+            yes: 0,
+            no: 0,
+            abstain: 0,
+        })
+    }
+
+    /// Ajoute un bulletin chiffré au décompte si le votant est membre de l'ensemble
+    /// du sondage. `voter` est l'empreinte publique du signataire, calculée par le
+    /// programme. Un choix invalide n'est compté nulle part ; seule l'éligibilité
+    /// est révélée.
+    #[instruction]
+    pub fn cast_poll_vote(
+        ballot_ctxt: Enc<Shared, u8>,
+        tally_ctxt: Enc<Mxe, PollTally>,
+        voter: u128,
+        set_ctxt: Enc<Mxe, MemberSet>,
+    ) -> (Enc<Mxe, PollTally>, bool) {
+        let choice = ballot_ctxt.to_arcis();
+        let mut tally = tally_ctxt.to_arcis();
+        let set = set_ctxt.to_arcis();
+
+        let eligible = primitives::contains_u128(&set.members, voter) & (voter != 0);
+        tally.yes += (eligible & (choice == 0)) as u64;
+        tally.no += (eligible & (choice == 1)) as u64;
+        tally.abstain += (eligible & (choice == 2)) as u64;
+
+        (tally_ctxt.owner.from_arcis(tally), eligible.reveal())
+    }
+
+    /// Rechiffre les totaux pour le créateur du sondage
+    #[instruction]
+    pub fn reveal_poll_tally(tally_ctxt: Enc<Mxe, PollTally>, creator: Shared) -> Enc<Shared, PollTally> {
+        let tally = tally_ctxt.to_arcis();
+
+        creator.from_arcis(tally)
     }
 
     // ============================================================================
@@ -0,0 +1,153 @@
+// ============================================================================
+// DEVNET TOOLS - feature `devnet-tools`
+// ============================================================================
+//
+// Peuple un environnement de démo en une instruction : enregistre des
+// utilisateurs de démo (sans leur signature) et crée des messages entre eux.
+// Les utilisateurs déjà enregistrés sont réutilisés, on peut donc relancer le
+// même seed sur un devnet existant.
+//
+// Réservé au localnet et au devnet : un build devnet-tools ne doit jamais être
+// déployé sur le mainnet.
+// ============================================================================
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{create_account, CreateAccount};
+use xray_common::seeds::*;
+
+use crate::{ErrorCode, MessageAccount, MessageSent, UserAccount, UserRegistered, MAX_MESSAGE_SIZE};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct DemoUser {
+    pub wallet: Pubkey,
+    pub x25519_pubkey: [u8; 32],
+}
+
+/// Message entre deux utilisateurs de démo (indices dans `users`)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct DemoMessage {
+    pub sender: u8,
+    pub recipient: u8,
+    pub encrypted_content: Vec<u8>,
+    pub nonce: [u8; 24],
+}
+
+#[derive(Accounts)]
+pub struct DevnetSeed<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    // remaining_accounts : le PDA de chaque DemoUser, puis celui de chaque
+    // DemoMessage, dans l'ordre
+}
+
+pub(crate) fn seed<'info>(
+    ctx: Context<'_, '_, 'info, 'info, DevnetSeed<'info>>,
+    users: Vec<DemoUser>,
+    messages: Vec<DemoMessage>,
+) -> Result<()> {
+    require!(
+        ctx.remaining_accounts.len() == users.len() + messages.len(),
+        ErrorCode::Unauthorized
+    );
+    let (user_infos, message_infos) = ctx.remaining_accounts.split_at(users.len());
+
+    // Utilisateurs : créés, ou relus s'ils existent déjà
+    let mut accounts = Vec::with_capacity(users.len());
+    for (demo, info) in users.iter().zip(user_infos) {
+        let (address, bump) = Pubkey::find_program_address(&[USER_SEED, demo.wallet.as_ref()], &crate::ID);
+        require_keys_eq!(info.key(), address, ErrorCode::Unauthorized);
+
+        if info.owner == &crate::ID {
+            accounts.push(UserAccount::try_deserialize(&mut &info.try_borrow_data()?[..])?);
+            continue;
+        }
+        create_pda(
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+            info,
+            &[USER_SEED, demo.wallet.as_ref(), &[bump]],
+            UserAccount::SIZE,
+        )?;
+        accounts.push(UserAccount {
+            wallet: demo.wallet,
+            x25519_pubkey: demo.x25519_pubkey,
+            message_count: 0,
+            bump,
+        });
+        emit!(UserRegistered {
+            wallet: demo.wallet,
+            x25519_pubkey: demo.x25519_pubkey,
+        });
+    }
+
+    // Messages, indexés par le compteur du destinataire comme dans send_message
+    let now = Clock::get()?.unix_timestamp;
+    for (demo, info) in messages.into_iter().zip(message_infos) {
+        require!(demo.encrypted_content.len() <= MAX_MESSAGE_SIZE, ErrorCode::MessageTooLong);
+        let sender = accounts.get(demo.sender as usize).ok_or(ErrorCode::Unauthorized)?.wallet;
+        let recipient = accounts.get_mut(demo.recipient as usize).ok_or(ErrorCode::Unauthorized)?;
+
+        let index = recipient.message_count.to_le_bytes();
+        let seeds: &[&[u8]] = &[MESSAGE_SEED, sender.as_ref(), recipient.wallet.as_ref(), &index];
+        let (address, bump) = Pubkey::find_program_address(seeds, &crate::ID);
+        require_keys_eq!(info.key(), address, ErrorCode::Unauthorized);
+        create_pda(
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+            info,
+            &[MESSAGE_SEED, sender.as_ref(), recipient.wallet.as_ref(), &index, &[bump]],
+            MessageAccount::SIZE,
+        )?;
+
+        let message = MessageAccount {
+            sender,
+            recipient: recipient.wallet,
+            nonce: demo.nonce,
+            timestamp: now,
+            is_read: false,
+            bump,
+            encrypted_content: demo.encrypted_content,
+        };
+        message.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+        recipient.message_count += 1;
+
+        emit!(MessageSent {
+            sender,
+            recipient: recipient.wallet,
+            timestamp: now,
+            message_index: recipient.message_count,
+        });
+    }
+
+    // Écrit les utilisateurs (compteurs de messages à jour)
+    for (account, info) in accounts.iter().zip(user_infos) {
+        account.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+    }
+
+    Ok(())
+}
+
+/// Alloue un PDA de `space` octets appartenant au programme, payé par `payer`
+fn create_pda<'info>(
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    account: &AccountInfo<'info>,
+    signer_seeds: &[&[u8]],
+    space: usize,
+) -> Result<()> {
+    create_account(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            CreateAccount {
+                from: payer.to_account_info(),
+                to: account.clone(),
+            },
+            &[signer_seeds],
+        ),
+        Rent::get()?.minimum_balance(space),
+        space as u64,
+        &crate::ID,
+    )
+}
@@ -1,5 +1,19 @@
 use anchor_lang::prelude::*;
 use arcium_anchor::prelude::*;
+use arcium_anchor::{MXEEncryptedStruct, SharedEncryptedStruct};
+#[cfg(not(feature = "mock-mpc"))]
+use arcium_client::idl::arcium::types::CallbackAccount;
+use solana_sha256_hasher::hash;
+use xray_common::seeds::*;
+use xray_common::sizes::{self, MAX_MESSAGE_SIZE, MEMBER_SET_SLOTS, NOTIFY_ALL};
+
+#[cfg(feature = "mock-mpc")]
+mod mock_mpc;
+
+#[cfg(feature = "devnet-tools")]
+mod devnet_tools;
+#[cfg(feature = "devnet-tools")]
+pub use devnet_tools::*;
 
 // ============================================================================
 // PRIVATE MESSAGES - Solana Program
@@ -19,12 +33,35 @@ use arcium_anchor::prelude::*;
 // Offsets pour les définitions de computation Arcium
 const COMP_DEF_OFFSET_TEST_ADD: u32 = comp_def_offset("test_add");
 const COMP_DEF_OFFSET_VERIFY_AND_REVEAL_SENDER: u32 = comp_def_offset("verify_and_reveal_sender");
+const COMP_DEF_OFFSET_INIT_MEMBER_SET: u32 = comp_def_offset("init_member_set");
+const COMP_DEF_OFFSET_ADD_SET_MEMBER: u32 = comp_def_offset("add_set_member");
+const COMP_DEF_OFFSET_CHECK_SET_MEMBERSHIP: u32 = comp_def_offset("check_set_membership");
+const COMP_DEF_OFFSET_INIT_RATE_BUCKET: u32 = comp_def_offset("init_rate_bucket");
+const COMP_DEF_OFFSET_CONSUME_RATE_TOKEN: u32 = comp_def_offset("consume_rate_token");
+const COMP_DEF_OFFSET_INIT_POLL_TALLY: u32 = comp_def_offset("init_poll_tally");
+const COMP_DEF_OFFSET_CAST_POLL_VOTE: u32 = comp_def_offset("cast_poll_vote");
+const COMP_DEF_OFFSET_REVEAL_POLL_TALLY: u32 = comp_def_offset("reveal_poll_tally");
 
 declare_id!("A8r4vLoD79gtdwvyHBY7bXzRSXjFNBbuXic9cPHUJa2s");
 
-// Taille maximale du contenu chiffré d'un message (en bytes)
-// 256 bytes = ~170 caractères après chiffrement
-const MAX_MESSAGE_SIZE: usize = 256;
+// Limite d'envoi des messages privés (seau à jetons chiffré) :
+// 10 messages max, un jeton regagné toutes les 6 minutes
+const RATE_BUCKET_CAPACITY: u64 = 10;
+const RATE_REFILL_SECS: u64 = 360;
+
+// Délai après lequel une computation restée sans callback (abandonnée par le
+// cluster) peut être débloquée par les instructions reset_*_pending
+pub const PENDING_TIMEOUT_SECS: i64 = 3600;
+
+// Comme `require!`, mais signale l'échec avant (voir `report_failure`)
+macro_rules! require_reported {
+    ($cond:expr, $err:expr, $signer:expr, $context:expr $(,)?) => {
+        if !($cond) {
+            report_failure(u32::from($err), $signer, $context);
+            return Err($err.into());
+        }
+    };
+}
 
 #[arcium_program]
 pub mod private_messages {
@@ -50,6 +87,7 @@ pub mod private_messages {
             x25519_pubkey,
         });
 
+        count(&mut ctx.accounts.metrics, |metrics| &mut metrics.users_registered);
         Ok(())
     }
 
@@ -80,9 +118,11 @@ pub mod private_messages {
         encrypted_content: Vec<u8>,
         nonce: [u8; 24],  // Nonce pour XChaCha20-Poly1305 ou similaire
     ) -> Result<()> {
-        require!(
+        require_reported!(
             encrypted_content.len() <= MAX_MESSAGE_SIZE,
-            ErrorCode::MessageTooLong
+            ErrorCode::MessageTooLong,
+            ctx.accounts.sender.key(),
+            encrypted_content.len() as u64,
         );
 
         let message = &mut ctx.accounts.message_account;
@@ -105,6 +145,7 @@ pub mod private_messages {
             message_index: recipient_user.message_count,
         });
 
+        count(&mut ctx.accounts.metrics, |metrics| &mut metrics.messages_sent);
         Ok(())
     }
 
@@ -113,9 +154,11 @@ pub mod private_messages {
         let message = &mut ctx.accounts.message_account;
 
         // Vérifie que c'est bien le destinataire qui marque comme lu
-        require!(
+        require_reported!(
             ctx.accounts.reader.key() == message.recipient,
-            ErrorCode::Unauthorized
+            ErrorCode::Unauthorized,
+            ctx.accounts.reader.key(),
+            0,
         );
 
         message.is_read = true;
@@ -124,6 +167,34 @@ pub mod private_messages {
             sender: message.sender,
             recipient: message.recipient,
             timestamp: message.timestamp,
+            message: message.key(),
+        });
+
+        Ok(())
+    }
+
+    // ========================================================================
+    // NOTIFICATIONS
+    // ========================================================================
+
+    /// Choisit les événements notifiés au wallet (NOTIFY_* combinés, 0 = aucun)
+    /// Seul le choix est on-chain : les destinations (webhook, push) restent chez le service
+    pub fn set_notification_prefs(ctx: Context<SetNotificationPrefs>, flags: u8) -> Result<()> {
+        require_reported!(
+            flags & !NOTIFY_ALL == 0,
+            ErrorCode::InvalidNotificationFlags,
+            ctx.accounts.owner.key(),
+            flags as u64,
+        );
+
+        let prefs = &mut ctx.accounts.notification_prefs;
+        prefs.owner = ctx.accounts.owner.key();
+        prefs.flags = flags;
+        prefs.bump = ctx.bumps.notification_prefs;
+
+        emit!(NotificationPrefsUpdated {
+            owner: prefs.owner,
+            flags,
         });
 
         Ok(())
@@ -150,6 +221,10 @@ pub mod private_messages {
     ) -> Result<()> {
         ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
 
+        #[cfg(feature = "mock-mpc")]
+        apply_test_add_output(mock_mpc::test_add(computation_offset, &ciphertext_a, &ciphertext_b, pubkey, nonce));
+
+        #[cfg(not(feature = "mock-mpc"))]
         let args = ArgBuilder::new()
             .x25519_pubkey(pubkey)
             .plaintext_u128(nonce)
@@ -157,6 +232,7 @@ pub mod private_messages {
             .encrypted_u8(ciphertext_b)
             .build();
 
+        #[cfg(not(feature = "mock-mpc"))]
         queue_computation(
             ctx.accounts,
             computation_offset,
@@ -188,10 +264,7 @@ pub mod private_messages {
             Err(_) => return Err(ErrorCode::AbortedComputation.into()),
         };
 
-        emit!(TestAddResult {
-            result: o.ciphertexts[0],
-            nonce: o.nonce.to_le_bytes(),
-        });
+        apply_test_add_output(o);
 
         Ok(())
     }
@@ -213,6 +286,7 @@ pub mod private_messages {
     /// Envoie un message privé avec métadonnées cachées
     /// sender_hash et recipient_hash sont chiffrés avec la clé du MXE
     /// Personne sur la blockchain ne peut voir qui envoie à qui
+    #[allow(clippy::too_many_arguments)]
     pub fn send_private_message(
         ctx: Context<SendPrivateMessage>,
         message_index: u64,
@@ -226,11 +300,19 @@ pub mod private_messages {
         mpc_pubkey: [u8; 32],
         mpc_nonce: u128,
     ) -> Result<()> {
-        require!(
+        let sender = ctx.accounts.sender.key();
+        require_reported!(
             encrypted_content.len() <= MAX_MESSAGE_SIZE,
-            ErrorCode::MessageTooLong
+            ErrorCode::MessageTooLong,
+            sender,
+            encrypted_content.len() as u64,
         );
 
+        // Consomme l'autorisation d'envoi accordée par le MPC (request_send_token)
+        let bucket = &mut ctx.accounts.rate_bucket;
+        require_reported!(bucket.send_authorized, ErrorCode::RateLimited, sender, 0);
+        bucket.send_authorized = false;
+
         // Stocke le message avec les métadonnées chiffrées
         let message = &mut ctx.accounts.private_message_account;
         message.encrypted_sender_hash = encrypted_sender_hash;
@@ -251,6 +333,7 @@ pub mod private_messages {
             // Note: on n'émet PAS sender/recipient car c'est justement ce qu'on cache!
         });
 
+        count(&mut ctx.accounts.metrics, |metrics| &mut metrics.messages_sent);
         Ok(())
     }
 
@@ -269,8 +352,18 @@ pub mod private_messages {
 
         let message = &ctx.accounts.private_message_account;
 
+        #[cfg(feature = "mock-mpc")]
+        apply_verify_and_reveal_sender_output(mock_mpc::verify_and_reveal_sender(
+            computation_offset,
+            &message.encrypted_recipient_hash,
+            &encrypted_requester_hash,
+            mpc_pubkey,
+            mpc_nonce,
+        ));
+
         // Construit les arguments pour le circuit verify_and_reveal_sender
         // AccessCheck { recipient_hash, requester_hash }
+        #[cfg(not(feature = "mock-mpc"))]
         let builder = ArgBuilder::new()
             .x25519_pubkey(mpc_pubkey)
             .plaintext_u128(mpc_nonce)
@@ -279,8 +372,10 @@ pub mod private_messages {
             // requester_hash (32 bytes encrypted) - from caller
             .encrypted_u8(encrypted_requester_hash);
 
+        #[cfg(not(feature = "mock-mpc"))]
         let args = builder.build();
 
+        #[cfg(not(feature = "mock-mpc"))]
         queue_computation(
             ctx.accounts,
             computation_offset,
@@ -289,12 +384,13 @@ pub mod private_messages {
             vec![VerifyAndRevealSenderCallback::callback_ix(
                 computation_offset,
                 &ctx.accounts.mxe_account,
-                &[],
+                &with_metrics(Vec::new(), &ctx.accounts.metrics),
             )?],
             1,
             0,
         )?;
 
+        count_queued(&mut ctx.accounts.metrics);
         Ok(())
     }
 
@@ -312,224 +408,2042 @@ pub mod private_messages {
             Ok(VerifyAndRevealSenderOutput { field_0 }) => field_0,
             Err(_) => return Err(ErrorCode::AbortedComputation.into()),
         };
+        count(&mut ctx.accounts.metrics, |metrics| &mut metrics.computations_completed);
 
-        // Le résultat contient is_authorized (1 byte chiffré)
-        // Le requester peut le déchiffrer avec sa clé
-        emit!(PrivateAccessVerified {
-            encrypted_result: result.ciphertexts[0],
-            nonce: result.nonce.to_le_bytes(),
-        });
+        apply_verify_and_reveal_sender_output(result);
 
         Ok(())
     }
-}
 
-// ============================================================================
-// ACCOUNT STRUCTURES
-// ============================================================================
+    // ========================================================================
+    // ENCRYPTED SET MEMBERSHIP (via Arcium MPC)
+    // ========================================================================
+    //
+    // Brique de base pour les allowlists privées et les ACL de groupe :
+    // l'ensemble des membres reste chiffré on-chain, et le MPC répond à
+    // "cet élément est-il membre ?" par un bit chiffré pour le demandeur.
 
-/// Compte utilisateur - stocke la clé publique X25519 pour le chiffrement
-#[account]
-pub struct UserAccount {
-    /// Wallet Solana de l'utilisateur
-    pub wallet: Pubkey,
-    /// Clé publique X25519 pour le chiffrement des messages
-    pub x25519_pubkey: [u8; 32],
-    /// Nombre de messages reçus
-    pub message_count: u64,
-    /// Bump pour le PDA
-    pub bump: u8,
-}
+    /// Initialise le circuit init_member_set
+    pub fn init_member_set_comp_def(ctx: Context<InitMemberSetCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
 
-impl UserAccount {
-    pub const SIZE: usize = 8 + 32 + 32 + 8 + 1;
-}
+    /// Initialise le circuit add_set_member
+    pub fn init_add_set_member_comp_def(ctx: Context<InitAddSetMemberCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
 
-/// Compte message - stocke un message chiffré
-#[account]
-pub struct MessageAccount {
-    /// Expéditeur du message
-    pub sender: Pubkey,
-    /// Destinataire du message
-    pub recipient: Pubkey,
-    /// Contenu chiffré (max 256 bytes)
-    pub encrypted_content: Vec<u8>,
-    /// Nonce utilisé pour le chiffrement
-    pub nonce: [u8; 24],
-    /// Timestamp Unix
-    pub timestamp: i64,
-    /// Message lu ou non
-    pub is_read: bool,
-    /// Bump pour le PDA
-    pub bump: u8,
-}
+    /// Initialise le circuit check_set_membership
+    pub fn init_check_set_membership_comp_def(ctx: Context<InitCheckSetMembershipCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
 
-impl MessageAccount {
-    // 8 (discriminator) + 32 + 32 + 4 + 256 + 24 + 8 + 1 + 1
-    pub const SIZE: usize = 8 + 32 + 32 + 4 + MAX_MESSAGE_SIZE + 24 + 8 + 1 + 1;
-}
+    /// Crée un ensemble chiffré vide (l'appelant en devient l'autorité)
+    pub fn create_membership_set(
+        ctx: Context<CreateMembershipSet>,
+        computation_offset: u64,
+        set_id: u64,
+        mxe_nonce: u128,
+    ) -> Result<()> {
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
 
-/// Message privé avec métadonnées cachées (via Arcium MPC)
-/// Les identités sender/recipient sont hashées et chiffrées
-#[account]
-pub struct PrivateMessageAccount {
-    /// Hash chiffré du sender (personne ne peut voir qui a envoyé)
-    pub encrypted_sender_hash: [u8; 32],
-    /// Hash chiffré du recipient (personne ne peut voir qui reçoit)
-    pub encrypted_recipient_hash: [u8; 32],
-    /// Contenu chiffré (avec la clé X25519 du destinataire)
-    pub encrypted_content: Vec<u8>,
-    /// Nonce pour le chiffrement du contenu
-    pub nonce: [u8; 24],
-    /// Timestamp (seule métadonnée publique)
-    pub timestamp: i64,
-    /// Clé publique MPC utilisée pour chiffrer les métadonnées
-    pub mpc_pubkey: [u8; 32],
-    /// Nonce MPC
-    pub mpc_nonce: u128,
-    /// Bump pour le PDA
-    pub bump: u8,
-}
+        let set = &mut ctx.accounts.membership_set;
+        set.authority = ctx.accounts.authority.key();
+        set.set_id = set_id;
+        set.member_count = 0;
+        set.computation_pending = true;
+        set.pending_since = Clock::get()?.unix_timestamp;
+        set.pending_computation = computation_offset;
+        set.bump = ctx.bumps.membership_set;
 
-impl PrivateMessageAccount {
-    // 8 (disc) + 32 + 32 + 4 + 256 + 24 + 8 + 32 + 16 + 1
-    pub const SIZE: usize = 8 + 32 + 32 + 4 + MAX_MESSAGE_SIZE + 24 + 8 + 32 + 16 + 1;
-}
+        #[cfg(feature = "mock-mpc")]
+        apply_init_member_set_output(set, mock_mpc::init_member_set(computation_offset, mxe_nonce));
 
-/// Compteur global de messages privés
-#[account]
-pub struct PrivateMessageCounter {
-    pub count: u64,
-    pub bump: u8,
-}
+        #[cfg(not(feature = "mock-mpc"))]
+        let args = ArgBuilder::new().plaintext_u128(mxe_nonce).build();
 
-impl PrivateMessageCounter {
-    pub const SIZE: usize = 8 + 8 + 1;
-}
+        #[cfg(not(feature = "mock-mpc"))]
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![InitMemberSetCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &with_metrics(
+                    vec![CallbackAccount {
+                        pubkey: ctx.accounts.membership_set.key(),
+                        is_writable: true,
+                    }],
+                    &ctx.accounts.metrics,
+                ),
+            )?],
+            1,
+            0,
+        )?;
 
-// ============================================================================
-// CONTEXT STRUCTURES
-// ============================================================================
+        count_queued(&mut ctx.accounts.metrics);
+        Ok(())
+    }
 
-#[derive(Accounts)]
-pub struct RegisterUser<'info> {
-    #[account(mut)]
-    pub owner: Signer<'info>,
+    #[arcium_callback(encrypted_ix = "init_member_set")]
+    pub fn init_member_set_callback(
+        ctx: Context<InitMemberSetCallback>,
+        output: SignedComputationOutputs<InitMemberSetOutput>,
+    ) -> Result<()> {
+        let set = &ctx.accounts.membership_set;
+        require_current_computation(
+            set.computation_pending,
+            set.pending_computation,
+            &ctx.accounts.computation_account,
+            &ctx.accounts.mxe_account,
+        )?;
 
-    #[account(
-        init,
-        payer = owner,
-        space = UserAccount::SIZE,
-        seeds = [b"user", owner.key().as_ref()],
-        bump
-    )]
-    pub user_account: Account<'info, UserAccount>,
+        let state = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(InitMemberSetOutput { field_0 }) => field_0,
+            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+        };
+        count(&mut ctx.accounts.metrics, |metrics| &mut metrics.computations_completed);
 
-    pub system_program: Program<'info, System>,
-}
+        apply_init_member_set_output(&mut ctx.accounts.membership_set, state);
 
-#[derive(Accounts)]
-pub struct UpdateUserKey<'info> {
-    pub owner: Signer<'info>,
+        Ok(())
+    }
 
-    #[account(
-        mut,
-        seeds = [b"user", owner.key().as_ref()],
-        bump = user_account.bump,
-        // La contrainte seeds garantit déjà que owner == wallet
-    )]
-    pub user_account: Account<'info, UserAccount>,
-}
+    /// Ajoute l'empreinte chiffrée d'un membre dans le prochain emplacement libre
+    /// (autorité de l'ensemble uniquement)
+    pub fn add_set_member(
+        ctx: Context<AddSetMember>,
+        computation_offset: u64,
+        encrypted_member: [u8; 32],
+        member_pubkey: [u8; 32],
+        member_nonce: u128,
+    ) -> Result<()> {
+        let authority = ctx.accounts.authority.key();
+        let set = &ctx.accounts.membership_set;
+        require_reported!(!set.computation_pending, ErrorCode::ComputationPending, authority, 0);
+        require_reported!(
+            (set.member_count as usize) < MEMBER_SET_SLOTS,
+            ErrorCode::MemberSetFull,
+            authority,
+            set.member_count as u64,
+        );
 
-#[derive(Accounts)]
-#[instruction(encrypted_content: Vec<u8>, nonce: [u8; 24])]
-pub struct SendMessage<'info> {
-    #[account(mut)]
-    pub sender: Signer<'info>,
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
 
-    /// Le compte utilisateur du destinataire (pour récupérer sa clé publique)
-    #[account(
-        mut,
-        seeds = [b"user", recipient_user.wallet.as_ref()],
-        bump = recipient_user.bump
-    )]
-    pub recipient_user: Account<'info, UserAccount>,
+        let set = &mut ctx.accounts.membership_set;
+        set.computation_pending = true;
+        set.pending_since = Clock::get()?.unix_timestamp;
+        set.pending_computation = computation_offset;
 
-    /// Le PDA pour stocker le message
-    /// Seeds: ["message", sender, recipient, message_count]
-    #[account(
-        init,
-        payer = sender,
-        space = MessageAccount::SIZE,
-        seeds = [
-            b"message",
-            sender.key().as_ref(),
-            recipient_user.wallet.as_ref(),
-            &recipient_user.message_count.to_le_bytes()
-        ],
-        bump
-    )]
-    pub message_account: Account<'info, MessageAccount>,
+        #[cfg(feature = "mock-mpc")]
+        {
+            // Chiffrement identité : la clé et le nonce du membre ne servent pas
+            let _ = (member_pubkey, member_nonce);
+            let state = mock_mpc::add_set_member(
+                computation_offset,
+                &encrypted_member,
+                set.member_count,
+                &set.encrypted_members,
+                set.state_nonce,
+            );
+            apply_add_set_member_output(set, state);
+        }
+
+        #[cfg(not(feature = "mock-mpc"))]
+        let args = ArgBuilder::new()
+            .x25519_pubkey(member_pubkey)
+            .plaintext_u128(member_nonce)
+            .encrypted_u128(encrypted_member)
+            .plaintext_u8(set.member_count)
+            .plaintext_u128(set.state_nonce)
+            .account(set.key(), MembershipSet::ENCRYPTED_MEMBERS_OFFSET, MembershipSet::ENCRYPTED_MEMBERS_LEN)
+            .build();
 
-    pub system_program: Program<'info, System>,
-}
+        #[cfg(not(feature = "mock-mpc"))]
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![AddSetMemberCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &with_metrics(
+                    vec![CallbackAccount {
+                        pubkey: ctx.accounts.membership_set.key(),
+                        is_writable: true,
+                    }],
+                    &ctx.accounts.metrics,
+                ),
+            )?],
+            1,
+            0,
+        )?;
 
-#[derive(Accounts)]
-pub struct MarkAsRead<'info> {
-    pub reader: Signer<'info>,
+        count_queued(&mut ctx.accounts.metrics);
+        Ok(())
+    }
 
-    #[account(
-        mut,
-        constraint = message_account.recipient == reader.key() @ ErrorCode::Unauthorized
-    )]
-    pub message_account: Account<'info, MessageAccount>,
-}
+    #[arcium_callback(encrypted_ix = "add_set_member")]
+    pub fn add_set_member_callback(
+        ctx: Context<AddSetMemberCallback>,
+        output: SignedComputationOutputs<AddSetMemberOutput>,
+    ) -> Result<()> {
+        let set = &ctx.accounts.membership_set;
+        require_current_computation(
+            set.computation_pending,
+            set.pending_computation,
+            &ctx.accounts.computation_account,
+            &ctx.accounts.mxe_account,
+        )?;
 
-// ============================================================================
-// ARCIUM COMPUTATION CONTEXTS
-// ============================================================================
+        let state = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(AddSetMemberOutput { field_0 }) => field_0,
+            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+        };
+        count(&mut ctx.accounts.metrics, |metrics| &mut metrics.computations_completed);
 
-#[init_computation_definition_accounts("test_add", payer)]
-#[derive(Accounts)]
-pub struct InitTestAddCompDef<'info> {
-    #[account(mut)]
-    pub payer: Signer<'info>,
-    #[account(mut, address = derive_mxe_pda!())]
-    pub mxe_account: Box<Account<'info, MXEAccount>>,
-    #[account(mut)]
-    /// CHECK: comp_def_account, checked by arcium program
-    pub comp_def_account: UncheckedAccount<'info>,
-    pub arcium_program: Program<'info, Arcium>,
-    pub system_program: Program<'info, System>,
-}
+        apply_add_set_member_output(&mut ctx.accounts.membership_set, state);
 
-#[queue_computation_accounts("test_add", payer)]
-#[derive(Accounts)]
-#[instruction(computation_offset: u64)]
-pub struct TestAdd<'info> {
-    #[account(mut)]
-    pub payer: Signer<'info>,
-    #[account(
-        init_if_needed,
-        space = 9,
-        payer = payer,
-        seeds = [&SIGN_PDA_SEED],
-        bump,
-        address = derive_sign_pda!(),
-    )]
-    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
-    #[account(address = derive_mxe_pda!())]
-    pub mxe_account: Account<'info, MXEAccount>,
-    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
-    /// CHECK: mempool_account
-    pub mempool_account: UncheckedAccount<'info>,
-    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
-    /// CHECK: executing_pool
+        Ok(())
+    }
+
+    /// Débloque l'ensemble quand sa computation n'a jamais reçu de callback
+    /// (abandonnée) après PENDING_TIMEOUT_SECS : le membre non ajouté peut être
+    /// soumis à nouveau. Si c'était la création, l'ensemble est fermé pour
+    /// pouvoir être recréé.
+    pub fn reset_membership_set_pending(ctx: Context<ResetMembershipSetPending>) -> Result<()> {
+        let authority = ctx.accounts.authority.key();
+        let set = &mut ctx.accounts.membership_set;
+        let pending_since = set.pending_since;
+        expire_pending(&mut set.computation_pending, pending_since, authority)?;
+
+        if set.state_nonce == 0 {
+            set.close(ctx.accounts.authority.to_account_info())?;
+        }
+
+        count(&mut ctx.accounts.metrics, |metrics| &mut metrics.computations_failed);
+        Ok(())
+    }
+
+    /// Demande si une empreinte chiffrée appartient à l'ensemble.
+    /// Le résultat (bit chiffré) est émis dans un event, lisible par le demandeur seul.
+    pub fn check_set_membership(
+        ctx: Context<CheckSetMembership>,
+        computation_offset: u64,
+        encrypted_element: [u8; 32],
+        element_pubkey: [u8; 32],
+        element_nonce: u128,
+    ) -> Result<()> {
+        let set = &ctx.accounts.membership_set;
+        require_reported!(!set.computation_pending, ErrorCode::ComputationPending, ctx.accounts.payer.key(), 0);
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        #[cfg(feature = "mock-mpc")]
+        apply_check_set_membership_output(
+            set.key(),
+            mock_mpc::check_set_membership(
+                computation_offset,
+                &encrypted_element,
+                &set.encrypted_members,
+                element_pubkey,
+                element_nonce,
+            ),
+        );
+
+        #[cfg(not(feature = "mock-mpc"))]
+        let args = ArgBuilder::new()
+            .x25519_pubkey(element_pubkey)
+            .plaintext_u128(element_nonce)
+            .encrypted_u128(encrypted_element)
+            .plaintext_u128(set.state_nonce)
+            .account(set.key(), MembershipSet::ENCRYPTED_MEMBERS_OFFSET, MembershipSet::ENCRYPTED_MEMBERS_LEN)
+            .build();
+
+        #[cfg(not(feature = "mock-mpc"))]
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![CheckSetMembershipCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &with_metrics(
+                    vec![CallbackAccount {
+                        pubkey: ctx.accounts.membership_set.key(),
+                        is_writable: false,
+                    }],
+                    &ctx.accounts.metrics,
+                ),
+            )?],
+            1,
+            0,
+        )?;
+
+        count_queued(&mut ctx.accounts.metrics);
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "check_set_membership")]
+    pub fn check_set_membership_callback(
+        ctx: Context<CheckSetMembershipCallback>,
+        output: SignedComputationOutputs<CheckSetMembershipOutput>,
+    ) -> Result<()> {
+        let result = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(CheckSetMembershipOutput { field_0 }) => field_0,
+            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+        };
+        count(&mut ctx.accounts.metrics, |metrics| &mut metrics.computations_completed);
+
+        apply_check_set_membership_output(ctx.accounts.membership_set.key(), result);
+
+        Ok(())
+    }
+
+    // ========================================================================
+    // PRIVATE TOKEN-BUCKET RATE LIMITING (via Arcium MPC)
+    // ========================================================================
+    //
+    // Chaque sender a un seau à jetons dont l'état reste chiffré. Avant chaque
+    // send_private_message, request_send_token fait consommer un jeton par le MPC ;
+    // seul le résultat "autorisé / refusé" est révélé.
+
+    /// Initialise le circuit init_rate_bucket
+    pub fn init_rate_bucket_comp_def(ctx: Context<InitRateBucketCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    /// Initialise le circuit consume_rate_token
+    pub fn init_consume_rate_token_comp_def(ctx: Context<InitConsumeRateTokenCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    /// Crée le seau à jetons (plein) du sender
+    pub fn create_rate_bucket(
+        ctx: Context<CreateRateBucket>,
+        computation_offset: u64,
+        mxe_nonce: u128,
+    ) -> Result<()> {
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let bucket = &mut ctx.accounts.rate_bucket;
+        bucket.sender = ctx.accounts.sender.key();
+        bucket.send_authorized = false;
+        bucket.computation_pending = true;
+        bucket.pending_since = Clock::get()?.unix_timestamp;
+        bucket.pending_computation = computation_offset;
+        bucket.bump = ctx.bumps.rate_bucket;
+
+        let now = Clock::get()?.unix_timestamp as u64;
+
+        #[cfg(feature = "mock-mpc")]
+        apply_init_rate_bucket_output(
+            bucket,
+            mock_mpc::init_rate_bucket(computation_offset, mxe_nonce, RATE_BUCKET_CAPACITY, now),
+        );
+
+        #[cfg(not(feature = "mock-mpc"))]
+        let args = ArgBuilder::new()
+            .plaintext_u128(mxe_nonce)
+            .plaintext_u64(RATE_BUCKET_CAPACITY)
+            .plaintext_u64(now)
+            .build();
+
+        #[cfg(not(feature = "mock-mpc"))]
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![InitRateBucketCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &with_metrics(
+                    vec![CallbackAccount {
+                        pubkey: ctx.accounts.rate_bucket.key(),
+                        is_writable: true,
+                    }],
+                    &ctx.accounts.metrics,
+                ),
+            )?],
+            1,
+            0,
+        )?;
+
+        count_queued(&mut ctx.accounts.metrics);
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "init_rate_bucket")]
+    pub fn init_rate_bucket_callback(
+        ctx: Context<InitRateBucketCallback>,
+        output: SignedComputationOutputs<InitRateBucketOutput>,
+    ) -> Result<()> {
+        let bucket = &ctx.accounts.rate_bucket;
+        require_current_computation(
+            bucket.computation_pending,
+            bucket.pending_computation,
+            &ctx.accounts.computation_account,
+            &ctx.accounts.mxe_account,
+        )?;
+
+        let state = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(InitRateBucketOutput { field_0 }) => field_0,
+            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+        };
+        count(&mut ctx.accounts.metrics, |metrics| &mut metrics.computations_completed);
+
+        apply_init_rate_bucket_output(&mut ctx.accounts.rate_bucket, state);
+
+        Ok(())
+    }
+
+    /// Demande au MPC de consommer un jeton pour le prochain message privé
+    pub fn request_send_token(ctx: Context<RequestSendToken>, computation_offset: u64) -> Result<()> {
+        let sender = ctx.accounts.sender.key();
+        let bucket = &ctx.accounts.rate_bucket;
+        require_reported!(!bucket.computation_pending, ErrorCode::ComputationPending, sender, 0);
+        require_reported!(!bucket.send_authorized, ErrorCode::SendAlreadyAuthorized, sender, 0);
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let bucket = &mut ctx.accounts.rate_bucket;
+        bucket.computation_pending = true;
+        bucket.pending_since = Clock::get()?.unix_timestamp;
+        bucket.pending_computation = computation_offset;
+
+        let now = bucket.pending_since as u64;
+
+        #[cfg(feature = "mock-mpc")]
+        {
+            let (state, allowed) = mock_mpc::consume_rate_token(
+                computation_offset,
+                &bucket.encrypted_state,
+                bucket.state_nonce,
+                now,
+                RATE_BUCKET_CAPACITY,
+                RATE_REFILL_SECS,
+            );
+            apply_consume_rate_token_output(bucket, state, allowed);
+        }
+
+        #[cfg(not(feature = "mock-mpc"))]
+        let args = ArgBuilder::new()
+            .plaintext_u128(bucket.state_nonce)
+            .account(bucket.key(), RateBucket::ENCRYPTED_STATE_OFFSET, RateBucket::ENCRYPTED_STATE_LEN)
+            .plaintext_u64(now)
+            .plaintext_u64(RATE_BUCKET_CAPACITY)
+            .plaintext_u64(RATE_REFILL_SECS)
+            .build();
+
+        #[cfg(not(feature = "mock-mpc"))]
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ConsumeRateTokenCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &with_metrics(
+                    vec![CallbackAccount {
+                        pubkey: ctx.accounts.rate_bucket.key(),
+                        is_writable: true,
+                    }],
+                    &ctx.accounts.metrics,
+                ),
+            )?],
+            1,
+            0,
+        )?;
+
+        count_queued(&mut ctx.accounts.metrics);
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "consume_rate_token")]
+    pub fn consume_rate_token_callback(
+        ctx: Context<ConsumeRateTokenCallback>,
+        output: SignedComputationOutputs<ConsumeRateTokenOutput>,
+    ) -> Result<()> {
+        let bucket = &ctx.accounts.rate_bucket;
+        require_current_computation(
+            bucket.computation_pending,
+            bucket.pending_computation,
+            &ctx.accounts.computation_account,
+            &ctx.accounts.mxe_account,
+        )?;
+
+        let (state, allowed) = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(ConsumeRateTokenOutput {
+                field_0: ConsumeRateTokenOutputStruct0 { field_0, field_1 },
+            }) => (field_0, field_1),
+            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+        };
+        count(&mut ctx.accounts.metrics, |metrics| &mut metrics.computations_completed);
+
+        apply_consume_rate_token_output(&mut ctx.accounts.rate_bucket, state, allowed);
+
+        Ok(())
+    }
+
+    /// Débloque le seau du sender quand sa computation n'a jamais reçu de
+    /// callback (abandonnée) après PENDING_TIMEOUT_SECS. Si c'était la création
+    /// du seau, il est fermé pour pouvoir être recréé.
+    pub fn reset_rate_bucket_pending(ctx: Context<ResetRateBucketPending>) -> Result<()> {
+        let sender = ctx.accounts.sender.key();
+        let bucket = &mut ctx.accounts.rate_bucket;
+        let pending_since = bucket.pending_since;
+        expire_pending(&mut bucket.computation_pending, pending_since, sender)?;
+
+        if bucket.state_nonce == 0 {
+            bucket.close(ctx.accounts.sender.to_account_info())?;
+        }
+
+        count(&mut ctx.accounts.metrics, |metrics| &mut metrics.computations_failed);
+        Ok(())
+    }
+
+    // ========================================================================
+    // ENCRYPTED POLLS (via Arcium MPC)
+    // ========================================================================
+    //
+    // Sondages oui / non / abstention dans une conversation. Le décompte reste
+    // chiffré on-chain ; à la clôture, seuls les totaux sont rechiffrés pour le
+    // créateur. Le reçu PollBallot empêche de voter deux fois sans révéler le choix.
+
+    /// Initialise le circuit init_poll_tally
+    pub fn init_poll_tally_comp_def(ctx: Context<InitPollTallyCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    /// Initialise le circuit cast_poll_vote
+    pub fn init_cast_poll_vote_comp_def(ctx: Context<InitCastPollVoteCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    /// Initialise le circuit reveal_poll_tally
+    pub fn init_reveal_poll_tally_comp_def(ctx: Context<InitRevealPollTallyCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    /// Crée un sondage avec un décompte chiffré à zéro. Seuls les membres de
+    /// `membership_set` (le groupe de la conversation) pourront voter.
+    pub fn create_poll(
+        ctx: Context<CreatePoll>,
+        computation_offset: u64,
+        poll_id: u64,
+        question_hash: [u8; 32],
+        closes_at: i64,
+        mxe_nonce: u128,
+    ) -> Result<()> {
+        require_reported!(
+            closes_at > Clock::get()?.unix_timestamp,
+            ErrorCode::InvalidPollDeadline,
+            ctx.accounts.creator.key(),
+            0,
+        );
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let poll = &mut ctx.accounts.poll;
+        poll.creator = ctx.accounts.creator.key();
+        poll.membership_set = ctx.accounts.membership_set.key();
+        poll.poll_id = poll_id;
+        poll.question_hash = question_hash;
+        poll.closes_at = closes_at;
+        poll.voter_count = 0;
+        poll.computation_pending = true;
+        poll.pending_since = Clock::get()?.unix_timestamp;
+        poll.pending_computation = computation_offset;
+        poll.bump = ctx.bumps.poll;
+
+        #[cfg(feature = "mock-mpc")]
+        apply_init_poll_tally_output(poll, mock_mpc::init_poll_tally(computation_offset, mxe_nonce));
+
+        #[cfg(not(feature = "mock-mpc"))]
+        let args = ArgBuilder::new().plaintext_u128(mxe_nonce).build();
+
+        #[cfg(not(feature = "mock-mpc"))]
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![InitPollTallyCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &with_metrics(
+                    vec![CallbackAccount {
+                        pubkey: ctx.accounts.poll.key(),
+                        is_writable: true,
+                    }],
+                    &ctx.accounts.metrics,
+                ),
+            )?],
+            1,
+            0,
+        )?;
+
+        emit!(PollCreated {
+            poll: ctx.accounts.poll.key(),
+            creator: ctx.accounts.creator.key(),
+            question_hash,
+            closes_at,
+        });
+
+        count_queued(&mut ctx.accounts.metrics);
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "init_poll_tally")]
+    pub fn init_poll_tally_callback(
+        ctx: Context<InitPollTallyCallback>,
+        output: SignedComputationOutputs<InitPollTallyOutput>,
+    ) -> Result<()> {
+        let poll = &ctx.accounts.poll;
+        require_current_computation(
+            poll.computation_pending,
+            poll.pending_computation,
+            &ctx.accounts.computation_account,
+            &ctx.accounts.mxe_account,
+        )?;
+
+        let state = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(InitPollTallyOutput { field_0 }) => field_0,
+            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+        };
+        count(&mut ctx.accounts.metrics, |metrics| &mut metrics.computations_completed);
+
+        apply_init_poll_tally_output(&mut ctx.accounts.poll, state);
+
+        Ok(())
+    }
+
+    /// Vote avec un bulletin chiffré (0 = oui, 1 = non, 2 = abstention).
+    /// Le MPC ne compte le bulletin que si le votant est membre de l'ensemble du
+    /// sondage ; sinon le reçu reste non compté.
+    pub fn cast_poll_vote(
+        ctx: Context<CastPollVote>,
+        computation_offset: u64,
+        encrypted_choice: [u8; 32],
+        voter_pubkey: [u8; 32],
+        voter_nonce: u128,
+    ) -> Result<()> {
+        let voter = ctx.accounts.voter.key();
+        let poll = &ctx.accounts.poll;
+        require_reported!(!poll.computation_pending, ErrorCode::ComputationPending, voter, 0);
+        let now = Clock::get()?.unix_timestamp;
+        require_reported!(now < poll.closes_at, ErrorCode::PollClosed, voter, 0);
+        // Un bulletin non compté (computation abandonnée puis débloquée) peut être refait
+        require_reported!(!ctx.accounts.ballot.counted, ErrorCode::AlreadyVoted, voter, 0);
+        // L'ensemble est lu par le circuit : il ne doit pas être en cours de modification
+        let set = &ctx.accounts.membership_set;
+        require_reported!(!set.computation_pending, ErrorCode::ComputationPending, voter, 0);
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let ballot = &mut ctx.accounts.ballot;
+        ballot.poll = ctx.accounts.poll.key();
+        ballot.voter = ctx.accounts.voter.key();
+        ballot.bump = ctx.bumps.ballot;
+
+        let poll = &mut ctx.accounts.poll;
+        poll.computation_pending = true;
+        poll.pending_since = now;
+        poll.pending_computation = computation_offset;
+
+        #[cfg(feature = "mock-mpc")]
+        {
+            // Chiffrement identité : la clé et le nonce du votant ne servent pas
+            let _ = (voter_pubkey, voter_nonce);
+            let (state, eligible) = mock_mpc::cast_poll_vote(
+                computation_offset,
+                &encrypted_choice,
+                &poll.encrypted_tally,
+                poll.state_nonce,
+                member_fingerprint(&voter),
+                &ctx.accounts.membership_set.encrypted_members,
+            );
+            apply_cast_poll_vote_output(poll, &mut ctx.accounts.ballot, state, eligible);
+        }
+
+        #[cfg(not(feature = "mock-mpc"))]
+        let args = ArgBuilder::new()
+            .x25519_pubkey(voter_pubkey)
+            .plaintext_u128(voter_nonce)
+            .encrypted_u8(encrypted_choice)
+            .plaintext_u128(poll.state_nonce)
+            .account(poll.key(), Poll::ENCRYPTED_TALLY_OFFSET, Poll::ENCRYPTED_TALLY_LEN)
+            .plaintext_u128(member_fingerprint(&voter))
+            .plaintext_u128(set.state_nonce)
+            .account(set.key(), MembershipSet::ENCRYPTED_MEMBERS_OFFSET, MembershipSet::ENCRYPTED_MEMBERS_LEN)
+            .build();
+
+        #[cfg(not(feature = "mock-mpc"))]
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![CastPollVoteCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &with_metrics(
+                    vec![
+                        CallbackAccount {
+                            pubkey: ctx.accounts.poll.key(),
+                            is_writable: true,
+                        },
+                        CallbackAccount {
+                            pubkey: ctx.accounts.ballot.key(),
+                            is_writable: true,
+                        },
+                    ],
+                    &ctx.accounts.metrics,
+                ),
+            )?],
+            1,
+            0,
+        )?;
+
+        count_queued(&mut ctx.accounts.metrics);
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "cast_poll_vote")]
+    pub fn cast_poll_vote_callback(
+        ctx: Context<CastPollVoteCallback>,
+        output: SignedComputationOutputs<CastPollVoteOutput>,
+    ) -> Result<()> {
+        let poll = &ctx.accounts.poll;
+        require_current_computation(
+            poll.computation_pending,
+            poll.pending_computation,
+            &ctx.accounts.computation_account,
+            &ctx.accounts.mxe_account,
+        )?;
+
+        let (state, eligible) = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(CastPollVoteOutput {
+                field_0: CastPollVoteOutputStruct0 { field_0, field_1 },
+            }) => (field_0, field_1),
+            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+        };
+        count(&mut ctx.accounts.metrics, |metrics| &mut metrics.computations_completed);
+
+        apply_cast_poll_vote_output(&mut ctx.accounts.poll, &mut ctx.accounts.ballot, state, eligible);
+
+        Ok(())
+    }
+
+    /// Débloque le sondage quand sa computation n'a jamais reçu de callback
+    /// (abandonnée) après PENDING_TIMEOUT_SECS. Ouvert à tous : un votant dont
+    /// le bulletin n'a pas été compté peut ensuite revoter. Si c'était la
+    /// création, le sondage est fermé (rente rendue au créateur).
+    pub fn reset_poll_pending(ctx: Context<ResetPollPending>) -> Result<()> {
+        let caller = ctx.accounts.caller.key();
+        let poll = &mut ctx.accounts.poll;
+        let pending_since = poll.pending_since;
+        expire_pending(&mut poll.computation_pending, pending_since, caller)?;
+
+        if poll.state_nonce == 0 {
+            poll.close(ctx.accounts.creator.to_account_info())?;
+        }
+
+        count(&mut ctx.accounts.metrics, |metrics| &mut metrics.computations_failed);
+        Ok(())
+    }
+
+    /// Révèle les totaux au créateur, une fois le sondage clos
+    pub fn reveal_poll_tally(
+        ctx: Context<RevealPollTally>,
+        computation_offset: u64,
+        creator_pubkey: [u8; 32],
+        creator_nonce: u128,
+    ) -> Result<()> {
+        let creator = ctx.accounts.creator.key();
+        let poll = &ctx.accounts.poll;
+        require_reported!(!poll.computation_pending, ErrorCode::ComputationPending, creator, 0);
+        require_reported!(Clock::get()?.unix_timestamp >= poll.closes_at, ErrorCode::PollStillOpen, creator, 0);
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        #[cfg(feature = "mock-mpc")]
+        apply_reveal_poll_tally_output(
+            poll,
+            mock_mpc::reveal_poll_tally(computation_offset, &poll.encrypted_tally, creator_pubkey, creator_nonce),
+        );
+
+        #[cfg(not(feature = "mock-mpc"))]
+        let args = ArgBuilder::new()
+            .plaintext_u128(poll.state_nonce)
+            .account(poll.key(), Poll::ENCRYPTED_TALLY_OFFSET, Poll::ENCRYPTED_TALLY_LEN)
+            .x25519_pubkey(creator_pubkey)
+            .plaintext_u128(creator_nonce)
+            .build();
+
+        #[cfg(not(feature = "mock-mpc"))]
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![RevealPollTallyCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &with_metrics(
+                    vec![CallbackAccount {
+                        pubkey: ctx.accounts.poll.key(),
+                        is_writable: false,
+                    }],
+                    &ctx.accounts.metrics,
+                ),
+            )?],
+            1,
+            0,
+        )?;
+
+        count_queued(&mut ctx.accounts.metrics);
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "reveal_poll_tally")]
+    pub fn reveal_poll_tally_callback(
+        ctx: Context<RevealPollTallyCallback>,
+        output: SignedComputationOutputs<RevealPollTallyOutput>,
+    ) -> Result<()> {
+        let result = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(RevealPollTallyOutput { field_0 }) => field_0,
+            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+        };
+        count(&mut ctx.accounts.metrics, |metrics| &mut metrics.computations_completed);
+
+        apply_reveal_poll_tally_output(&ctx.accounts.poll, result);
+
+        Ok(())
+    }
+
+    // ========================================================================
+    // MÉTRIQUES D'USAGE
+    // ========================================================================
+
+    /// Crée les compteurs d'usage globaux (sans permission, une seule fois).
+    /// Les instructions qui reçoivent le compte metrics en dernier compte
+    /// optionnel incrémentent ses compteurs.
+    pub fn initialize_metrics(ctx: Context<InitializeMetrics>) -> Result<()> {
+        ctx.accounts.metrics.bump = ctx.bumps.metrics;
+        Ok(())
+    }
+
+    /// Vue en lecture seule des compteurs (simuler la transaction pour lire
+    /// les données de retour)
+    pub fn get_metrics(ctx: Context<GetMetrics>) -> Result<MetricsView> {
+        Ok(MetricsView::of(&ctx.accounts.metrics))
+    }
+
+    // ========================================================================
+    // DEVNET TOOLS (feature `devnet-tools`, jamais sur le mainnet)
+    // ========================================================================
+
+    /// Enregistre des utilisateurs de démo et crée des messages entre eux
+    #[cfg(feature = "devnet-tools")]
+    pub fn devnet_seed<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DevnetSeed<'info>>,
+        users: Vec<DemoUser>,
+        messages: Vec<DemoMessage>,
+    ) -> Result<()> {
+        devnet_tools::seed(ctx, users, messages)
+    }
+}
+
+// ============================================================================
+// TÉLÉMÉTRIE
+// ============================================================================
+
+// Avec la feature `telemetry`, émet InstructionFailed juste avant un échec de
+// validation : l'événement reste dans les logs de la transaction échouée, ce qui
+// permet de suivre les taux d'échec par cause (codes : xray_common::errors)
+fn report_failure(code: u32, signer: Pubkey, context: u64) {
+    #[cfg(feature = "telemetry")]
+    emit!(InstructionFailed {
+        code,
+        program: crate::ID,
+        signer,
+        context,
+        timestamp: Clock::get().map_or(0, |clock| clock.unix_timestamp),
+    });
+    #[cfg(not(feature = "telemetry"))]
+    let _ = (code, signer, context);
+}
+
+// ============================================================================
+// MÉTRIQUES D'USAGE
+// ============================================================================
+
+// Incrémente un compteur d'usage, si l'appelant a passé le compte metrics
+fn count(metrics: &mut Option<Account<MessagesMetrics>>, counter: impl FnOnce(&mut MessagesMetrics) -> &mut u64) {
+    if let Some(metrics) = metrics {
+        let value = counter(metrics);
+        *value = value.saturating_add(1);
+    }
+}
+
+// Lève le verrou d'une computation restée sans callback, une fois le délai passé
+// (un échec est signalé au nom de `signer`)
+fn expire_pending(pending: &mut bool, pending_since: i64, signer: Pubkey) -> Result<()> {
+    require_reported!(*pending, ErrorCode::NoComputationPending, signer, 0);
+    require_reported!(
+        Clock::get()?.unix_timestamp >= pending_since.saturating_add(PENDING_TIMEOUT_SECS),
+        ErrorCode::PendingNotExpired,
+        signer,
+        0,
+    );
+    *pending = false;
+    Ok(())
+}
+
+/// Empreinte d'un wallet dans un MembershipSet : les 16 premiers octets de
+/// sha256(pubkey), en little-endian (voir xray_client::crypto::member_fingerprint)
+pub fn member_fingerprint(wallet: &Pubkey) -> u128 {
+    let digest = hash(wallet.as_ref()).to_bytes();
+    let mut fingerprint = [0u8; 16];
+    fingerprint.copy_from_slice(&digest[..16]);
+    u128::from_le_bytes(fingerprint)
+}
+
+// Un callback ne s'applique qu'à la computation en attente sur le compte :
+// un callback tardif (après un reset) ou d'une autre computation est rejeté
+fn require_current_computation(
+    pending: bool,
+    pending_computation: u64,
+    computation_account: &AccountInfo,
+    mxe_account: &MXEAccount,
+) -> Result<()> {
+    require!(
+        pending
+            && computation_account.key()
+                == derive_comp_pda!(pending_computation, mxe_account, ErrorCode::ClusterNotSet),
+        ErrorCode::StaleCallback
+    );
+    Ok(())
+}
+
+// Compte une computation mise en file. En mock-mpc le résultat est appliqué
+// immédiatement et aucun callback ne viendra : elle est aussi comptée terminée.
+fn count_queued(metrics: &mut Option<Account<MessagesMetrics>>) {
+    count(metrics, |metrics| &mut metrics.computations_queued);
+    #[cfg(feature = "mock-mpc")]
+    count(metrics, |metrics| &mut metrics.computations_completed);
+}
+
+// Comptes du callback d'une computation, plus le compte metrics si l'appelant
+// l'a passé, pour que le callback compte la computation terminée
+#[cfg(not(feature = "mock-mpc"))]
+fn with_metrics(
+    mut accounts: Vec<CallbackAccount>,
+    metrics: &Option<Account<MessagesMetrics>>,
+) -> Vec<CallbackAccount> {
+    if let Some(metrics) = metrics {
+        accounts.push(CallbackAccount {
+            pubkey: metrics.key(),
+            is_writable: true,
+        });
+    }
+    accounts
+}
+
+// ============================================================================
+// RÉSULTATS MPC
+// ============================================================================
+//
+// Appliqués par les callbacks une fois la sortie vérifiée, ou directement par
+// l'instruction qui met en file avec la feature `mock-mpc`.
+
+fn apply_test_add_output(result: SharedEncryptedStruct<1>) {
+    emit!(TestAddResult {
+        result: result.ciphertexts[0],
+        nonce: result.nonce.to_le_bytes(),
+    });
+}
+
+fn apply_verify_and_reveal_sender_output(result: SharedEncryptedStruct<1>) {
+    // Le résultat contient is_authorized (1 byte chiffré)
+    // Le requester peut le déchiffrer avec sa clé
+    emit!(PrivateAccessVerified {
+        encrypted_result: result.ciphertexts[0],
+        nonce: result.nonce.to_le_bytes(),
+    });
+}
+
+fn apply_init_member_set_output(set: &mut MembershipSet, state: MXEEncryptedStruct<MEMBER_SET_SLOTS>) {
+    set.encrypted_members = state.ciphertexts;
+    set.state_nonce = state.nonce;
+    set.computation_pending = false;
+}
+
+fn apply_add_set_member_output(set: &mut MembershipSet, state: MXEEncryptedStruct<MEMBER_SET_SLOTS>) {
+    set.encrypted_members = state.ciphertexts;
+    set.state_nonce = state.nonce;
+    set.member_count += 1;
+    set.computation_pending = false;
+}
+
+fn apply_check_set_membership_output(membership_set: Pubkey, result: SharedEncryptedStruct<1>) {
+    emit!(SetMembershipChecked {
+        membership_set,
+        encrypted_result: result.ciphertexts[0],
+        nonce: result.nonce.to_le_bytes(),
+    });
+}
+
+fn apply_init_rate_bucket_output(bucket: &mut RateBucket, state: MXEEncryptedStruct<2>) {
+    bucket.encrypted_state = state.ciphertexts;
+    bucket.state_nonce = state.nonce;
+    bucket.computation_pending = false;
+}
+
+fn apply_consume_rate_token_output(bucket: &mut RateBucket, state: MXEEncryptedStruct<2>, allowed: bool) {
+    bucket.encrypted_state = state.ciphertexts;
+    bucket.state_nonce = state.nonce;
+    bucket.send_authorized = allowed;
+    bucket.computation_pending = false;
+}
+
+fn apply_init_poll_tally_output(poll: &mut Poll, state: MXEEncryptedStruct<3>) {
+    poll.encrypted_tally = state.ciphertexts;
+    poll.state_nonce = state.nonce;
+    poll.computation_pending = false;
+}
+
+fn apply_cast_poll_vote_output(poll: &mut Poll, ballot: &mut PollBallot, state: MXEEncryptedStruct<3>, eligible: bool) {
+    poll.encrypted_tally = state.ciphertexts;
+    poll.state_nonce = state.nonce;
+    poll.voter_count += eligible as u32;
+    poll.computation_pending = false;
+    ballot.counted = eligible;
+}
+
+fn apply_reveal_poll_tally_output(poll: &Account<'_, Poll>, result: SharedEncryptedStruct<3>) {
+    emit!(PollTallyRevealed {
+        poll: poll.key(),
+        voter_count: poll.voter_count,
+        encrypted_totals: result.ciphertexts,
+        nonce: result.nonce.to_le_bytes(),
+    });
+}
+
+// ============================================================================
+// ACCOUNT STRUCTURES
+// ============================================================================
+
+/// Compte utilisateur - stocke la clé publique X25519 pour le chiffrement
+#[account]
+pub struct UserAccount {
+    /// Wallet Solana de l'utilisateur
+    pub wallet: Pubkey,
+    /// Clé publique X25519 pour le chiffrement des messages
+    pub x25519_pubkey: [u8; 32],
+    /// Nombre de messages reçus
+    pub message_count: u64,
+    /// Bump pour le PDA
+    pub bump: u8,
+}
+
+impl UserAccount {
+    pub const SIZE: usize = sizes::USER_ACCOUNT_SIZE;
+}
+
+/// Compte message - stocke un message chiffré
+/// Layout stable jusqu'à `recipient` : les clients filtrent sur ces offsets (voir
+/// xray_common::layout). Les champs après le contenu variable n'ont pas d'offset fixe.
+#[account]
+pub struct MessageAccount {
+    /// Expéditeur du message
+    pub sender: Pubkey,
+    /// Destinataire du message
+    pub recipient: Pubkey,
+    /// Contenu chiffré (max 256 bytes)
+    pub encrypted_content: Vec<u8>,
+    /// Nonce utilisé pour le chiffrement
+    pub nonce: [u8; 24],
+    /// Timestamp Unix
+    pub timestamp: i64,
+    /// Message lu ou non
+    pub is_read: bool,
+    /// Bump pour le PDA
+    pub bump: u8,
+}
+
+impl MessageAccount {
+    pub const SIZE: usize = sizes::MESSAGE_ACCOUNT_SIZE;
+}
+
+/// Message privé avec métadonnées cachées (via Arcium MPC)
+/// Les identités sender/recipient sont hashées et chiffrées
+#[account]
+pub struct PrivateMessageAccount {
+    /// Hash chiffré du sender (personne ne peut voir qui a envoyé)
+    pub encrypted_sender_hash: [u8; 32],
+    /// Hash chiffré du recipient (personne ne peut voir qui reçoit)
+    pub encrypted_recipient_hash: [u8; 32],
+    /// Contenu chiffré (avec la clé X25519 du destinataire)
+    pub encrypted_content: Vec<u8>,
+    /// Nonce pour le chiffrement du contenu
+    pub nonce: [u8; 24],
+    /// Timestamp (seule métadonnée publique)
+    pub timestamp: i64,
+    /// Clé publique MPC utilisée pour chiffrer les métadonnées
+    pub mpc_pubkey: [u8; 32],
+    /// Nonce MPC
+    pub mpc_nonce: u128,
+    /// Bump pour le PDA
+    pub bump: u8,
+}
+
+impl PrivateMessageAccount {
+    pub const SIZE: usize = sizes::PRIVATE_MESSAGE_ACCOUNT_SIZE;
+}
+
+/// Compteur global de messages privés
+#[account]
+pub struct PrivateMessageCounter {
+    pub count: u64,
+    pub bump: u8,
+}
+
+impl PrivateMessageCounter {
+    pub const SIZE: usize = sizes::PRIVATE_MESSAGE_COUNTER_SIZE;
+}
+
+/// Préférences de notification d'un wallet (lues par le service de notifications)
+#[account]
+pub struct NotificationPrefs {
+    pub owner: Pubkey,
+    /// Événements notifiés (NOTIFY_MESSAGES | NOTIFY_ACCESS_VERIFIED | NOTIFY_POSTS)
+    pub flags: u8,
+    /// Bump pour le PDA
+    pub bump: u8,
+}
+
+impl NotificationPrefs {
+    pub const SIZE: usize = sizes::NOTIFICATION_PREFS_SIZE;
+}
+
+/// Ensemble chiffré de membres (allowlist privée / ACL de groupe)
+/// Les empreintes restent chiffrées pour le MXE, seule l'autorité peut en ajouter
+#[account]
+pub struct MembershipSet {
+    /// Empreintes chiffrées (Enc<Mxe, MemberSet>), en premier pour un offset fixe
+    pub encrypted_members: [[u8; 32]; MEMBER_SET_SLOTS],
+    /// Nonce du chiffrement MXE de l'ensemble
+    pub state_nonce: u128,
+    /// Autorité de l'ensemble
+    pub authority: Pubkey,
+    /// Identifiant choisi par l'autorité
+    pub set_id: u64,
+    /// Nombre d'emplacements occupés
+    pub member_count: u8,
+    /// Une computation modifie l'ensemble
+    pub computation_pending: bool,
+    /// Mise en file de cette computation (voir reset_membership_set_pending)
+    pub pending_since: i64,
+    /// Offset de cette computation, seul callback accepté
+    pub pending_computation: u64,
+    /// Bump pour le PDA
+    pub bump: u8,
+}
+
+impl MembershipSet {
+    pub const SIZE: usize = sizes::MEMBERSHIP_SET_SIZE;
+    pub const ENCRYPTED_MEMBERS_OFFSET: u32 = 8;
+    pub const ENCRYPTED_MEMBERS_LEN: u32 = (32 * MEMBER_SET_SLOTS) as u32;
+}
+
+/// Seau à jetons chiffré d'un sender (limite d'envoi des messages privés)
+/// Seul `send_authorized` est public : le nombre de messages envoyés reste caché
+#[account]
+pub struct RateBucket {
+    /// État chiffré (Enc<Mxe, TokenBucket>), en premier pour un offset fixe
+    pub encrypted_state: [[u8; 32]; 2],
+    /// Nonce du chiffrement MXE de l'état
+    pub state_nonce: u128,
+    /// Propriétaire du seau
+    pub sender: Pubkey,
+    /// Le MPC a autorisé un envoi, consommé par send_private_message
+    pub send_authorized: bool,
+    /// Une computation modifie le seau
+    pub computation_pending: bool,
+    /// Mise en file de cette computation (voir reset_rate_bucket_pending)
+    pub pending_since: i64,
+    /// Offset de cette computation, seul callback accepté
+    pub pending_computation: u64,
+    /// Bump pour le PDA
+    pub bump: u8,
+}
+
+impl RateBucket {
+    pub const SIZE: usize = sizes::RATE_BUCKET_SIZE;
+    pub const ENCRYPTED_STATE_OFFSET: u32 = 8;
+    pub const ENCRYPTED_STATE_LEN: u32 = 32 * 2;
+}
+
+/// Sondage chiffré (oui / non / abstention)
+/// Le décompte reste chiffré pour le MXE jusqu'à la révélation au créateur
+#[account]
+pub struct Poll {
+    /// Décompte chiffré (Enc<Mxe, PollTally>), en premier pour un offset fixe
+    pub encrypted_tally: [[u8; 32]; 3],
+    /// Nonce du chiffrement MXE du décompte
+    pub state_nonce: u128,
+    /// Créateur du sondage (seul destinataire des totaux)
+    pub creator: Pubkey,
+    /// Ensemble des votants autorisés (MembershipSet du groupe)
+    pub membership_set: Pubkey,
+    /// Identifiant choisi par le créateur
+    pub poll_id: u64,
+    /// Hash de la question (le texte reste dans la conversation chiffrée)
+    pub question_hash: [u8; 32],
+    /// Fin des votes
+    pub closes_at: i64,
+    /// Nombre de bulletins comptés
+    pub voter_count: u32,
+    /// Une computation modifie le décompte
+    pub computation_pending: bool,
+    /// Mise en file de cette computation (voir reset_poll_pending)
+    pub pending_since: i64,
+    /// Offset de cette computation, seul callback accepté
+    pub pending_computation: u64,
+    /// Bump pour le PDA
+    pub bump: u8,
+}
+
+impl Poll {
+    pub const SIZE: usize = sizes::POLL_SIZE;
+    pub const ENCRYPTED_TALLY_OFFSET: u32 = 8;
+    pub const ENCRYPTED_TALLY_LEN: u32 = 32 * 3;
+}
+
+/// Reçu de vote : un seul bulletin par votant et par sondage
+#[account]
+pub struct PollBallot {
+    pub poll: Pubkey,
+    pub voter: Pubkey,
+    /// Le callback a compté le bulletin (sinon le votant peut revoter)
+    pub counted: bool,
+    pub bump: u8,
+}
+
+impl PollBallot {
+    pub const SIZE: usize = sizes::POLL_BALLOT_SIZE;
+}
+
+/// Compteurs d'usage globaux. Seules les instructions qui reçoivent ce compte
+/// comptent, et une transaction échouée annule ses compteurs : une computation
+/// abandonnée reste en file sans être terminée jusqu'à ce qu'un reset_*_pending
+/// la débloque et la compte en échec.
+#[account]
+pub struct MessagesMetrics {
+    pub users_registered: u64,
+    /// Messages simples et messages privés
+    pub messages_sent: u64,
+    pub computations_queued: u64,
+    pub computations_completed: u64,
+    /// Computations abandonnées, débloquées par un reset_*_pending
+    pub computations_failed: u64,
+    /// Bump pour le PDA
+    pub bump: u8,
+}
+
+impl MessagesMetrics {
+    pub const SIZE: usize = sizes::MESSAGES_METRICS_SIZE;
+}
+
+/// Données de retour de get_metrics
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct MetricsView {
+    pub users_registered: u64,
+    pub messages_sent: u64,
+    pub computations_queued: u64,
+    pub computations_completed: u64,
+    pub computations_failed: u64,
+    /// En file mais ni terminées ni en échec : en cours, ou abandonnées et pas encore débloquées
+    pub computations_unfinished: u64,
+}
+
+impl MetricsView {
+    pub fn of(metrics: &MessagesMetrics) -> Self {
+        Self {
+            users_registered: metrics.users_registered,
+            messages_sent: metrics.messages_sent,
+            computations_queued: metrics.computations_queued,
+            computations_completed: metrics.computations_completed,
+            computations_failed: metrics.computations_failed,
+            computations_unfinished: metrics
+                .computations_queued
+                .saturating_sub(metrics.computations_completed)
+                .saturating_sub(metrics.computations_failed),
+        }
+    }
+}
+
+// ============================================================================
+// CONTEXT STRUCTURES
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct RegisterUser<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = UserAccount::SIZE,
+        seeds = [USER_SEED, owner.key().as_ref()],
+        bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Compteurs d'usage (optionnel, voir `count`)
+    #[account(
+        mut,
+        seeds = [METRICS_SEED],
+        bump = metrics.bump
+    )]
+    pub metrics: Option<Account<'info, MessagesMetrics>>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateUserKey<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [USER_SEED, owner.key().as_ref()],
+        bump = user_account.bump,
+        // La contrainte seeds garantit déjà que owner == wallet
+    )]
+    pub user_account: Account<'info, UserAccount>,
+}
+
+#[derive(Accounts)]
+#[instruction(encrypted_content: Vec<u8>, nonce: [u8; 24])]
+pub struct SendMessage<'info> {
+    #[account(mut)]
+    pub sender: Signer<'info>,
+
+    /// Le compte utilisateur du destinataire (pour récupérer sa clé publique)
+    #[account(
+        mut,
+        seeds = [USER_SEED, recipient_user.wallet.as_ref()],
+        bump = recipient_user.bump
+    )]
+    pub recipient_user: Account<'info, UserAccount>,
+
+    /// Le PDA pour stocker le message
+    /// Seeds: ["message", sender, recipient, message_count]
+    #[account(
+        init,
+        payer = sender,
+        space = MessageAccount::SIZE,
+        seeds = [
+            MESSAGE_SEED,
+            sender.key().as_ref(),
+            recipient_user.wallet.as_ref(),
+            &recipient_user.message_count.to_le_bytes()
+        ],
+        bump
+    )]
+    pub message_account: Account<'info, MessageAccount>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Compteurs d'usage (optionnel, voir `count`)
+    #[account(
+        mut,
+        seeds = [METRICS_SEED],
+        bump = metrics.bump
+    )]
+    pub metrics: Option<Account<'info, MessagesMetrics>>,
+}
+
+#[derive(Accounts)]
+pub struct MarkAsRead<'info> {
+    pub reader: Signer<'info>,
+
+    // Destinataire vérifié dans mark_as_read, pour signaler l'échec
+    #[account(mut)]
+    pub message_account: Account<'info, MessageAccount>,
+}
+
+#[derive(Accounts)]
+pub struct SetNotificationPrefs<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = NotificationPrefs::SIZE,
+        seeds = [NOTIFICATION_PREFS_SEED, owner.key().as_ref()],
+        bump
+    )]
+    pub notification_prefs: Account<'info, NotificationPrefs>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// ============================================================================
+// ARCIUM COMPUTATION CONTEXTS
+// ============================================================================
+
+#[init_computation_definition_accounts("test_add", payer)]
+#[derive(Accounts)]
+pub struct InitTestAddCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[queue_computation_accounts("test_add", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct TestAdd<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_TEST_ADD))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("test_add")]
+#[derive(Accounts)]
+pub struct TestAddCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_TEST_ADD))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+}
+
+// ============================================================================
+// PRIVATE MESSAGE CONTEXTS (with hidden metadata)
+// ============================================================================
+
+#[init_computation_definition_accounts("verify_and_reveal_sender", payer)]
+#[derive(Accounts)]
+pub struct InitVerifySenderCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    message_index: u64,
+    encrypted_sender_hash: [u8; 32],
+    encrypted_recipient_hash: [u8; 32],
+    encrypted_content: Vec<u8>,
+    nonce: [u8; 24],
+)]
+pub struct SendPrivateMessage<'info> {
+    #[account(mut)]
+    pub sender: Signer<'info>,
+
+    /// Compteur global de messages privés
+    #[account(
+        init_if_needed,
+        payer = sender,
+        space = PrivateMessageCounter::SIZE,
+        seeds = [PRIVATE_MESSAGE_COUNTER_SEED],
+        bump
+    )]
+    pub private_message_counter: Account<'info, PrivateMessageCounter>,
+
+    /// Le message privé - utilise le message_index passé en paramètre
+    #[account(
+        init,
+        payer = sender,
+        space = PrivateMessageAccount::SIZE,
+        seeds = [
+            PRIVATE_MESSAGE_SEED,
+            sender.key().as_ref(),
+            &message_index.to_le_bytes()
+        ],
+        bump
+    )]
+    pub private_message_account: Account<'info, PrivateMessageAccount>,
+
+    /// Seau à jetons chiffré du sender (limite d'envoi)
+    #[account(
+        mut,
+        seeds = [RATE_BUCKET_SEED, sender.key().as_ref()],
+        bump = rate_bucket.bump
+    )]
+    pub rate_bucket: Box<Account<'info, RateBucket>>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Compteurs d'usage (optionnel, voir `count`)
+    #[account(
+        mut,
+        seeds = [METRICS_SEED],
+        bump = metrics.bump
+    )]
+    pub metrics: Option<Account<'info, MessagesMetrics>>,
+}
+
+#[queue_computation_accounts("verify_and_reveal_sender", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct VerifyPrivateMessageAccess<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Le message privé à vérifier
+    pub private_message_account: Account<'info, PrivateMessageAccount>,
+
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_VERIFY_AND_REVEAL_SENDER))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+
+    /// Compteurs d'usage (optionnel, voir `count`)
+    #[account(
+        mut,
+        seeds = [METRICS_SEED],
+        bump = metrics.bump
+    )]
+    pub metrics: Option<Account<'info, MessagesMetrics>>,
+}
+
+#[callback_accounts("verify_and_reveal_sender")]
+#[derive(Accounts)]
+pub struct VerifyAndRevealSenderCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_VERIFY_AND_REVEAL_SENDER))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [METRICS_SEED], bump = metrics.bump)]
+    pub metrics: Option<Account<'info, MessagesMetrics>>,
+}
+
+// ============================================================================
+// ENCRYPTED SET MEMBERSHIP CONTEXTS
+// ============================================================================
+
+#[init_computation_definition_accounts("init_member_set", payer)]
+#[derive(Accounts)]
+pub struct InitMemberSetCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("add_set_member", payer)]
+#[derive(Accounts)]
+pub struct InitAddSetMemberCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("check_set_membership", payer)]
+#[derive(Accounts)]
+pub struct InitCheckSetMembershipCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[queue_computation_accounts("init_member_set", authority)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, set_id: u64)]
+pub struct CreateMembershipSet<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        init,
+        payer = authority,
+        space = MembershipSet::SIZE,
+        seeds = [MEMBERSHIP_SET_SEED, authority.key().as_ref(), set_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub membership_set: Box<Account<'info, MembershipSet>>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = authority,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_MEMBER_SET))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(mut, seeds = [METRICS_SEED], bump = metrics.bump)]
+    pub metrics: Option<Account<'info, MessagesMetrics>>,
+}
+
+#[callback_accounts("init_member_set")]
+#[derive(Accounts)]
+pub struct InitMemberSetCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_MEMBER_SET))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub membership_set: Box<Account<'info, MembershipSet>>,
+    #[account(mut, seeds = [METRICS_SEED], bump = metrics.bump)]
+    pub metrics: Option<Account<'info, MessagesMetrics>>,
+}
+
+#[queue_computation_accounts("add_set_member", authority)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct AddSetMember<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [MEMBERSHIP_SET_SEED, authority.key().as_ref(), membership_set.set_id.to_le_bytes().as_ref()],
+        bump = membership_set.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub membership_set: Box<Account<'info, MembershipSet>>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = authority,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_ADD_SET_MEMBER))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(mut, seeds = [METRICS_SEED], bump = metrics.bump)]
+    pub metrics: Option<Account<'info, MessagesMetrics>>,
+}
+
+#[callback_accounts("add_set_member")]
+#[derive(Accounts)]
+pub struct AddSetMemberCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_ADD_SET_MEMBER))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub membership_set: Box<Account<'info, MembershipSet>>,
+    #[account(mut, seeds = [METRICS_SEED], bump = metrics.bump)]
+    pub metrics: Option<Account<'info, MessagesMetrics>>,
+}
+
+#[derive(Accounts)]
+pub struct ResetMembershipSetPending<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [MEMBERSHIP_SET_SEED, authority.key().as_ref(), membership_set.set_id.to_le_bytes().as_ref()],
+        bump = membership_set.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub membership_set: Box<Account<'info, MembershipSet>>,
+    #[account(mut, seeds = [METRICS_SEED], bump = metrics.bump)]
+    pub metrics: Option<Account<'info, MessagesMetrics>>,
+}
+
+#[queue_computation_accounts("check_set_membership", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct CheckSetMembership<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        seeds = [MEMBERSHIP_SET_SEED, membership_set.authority.as_ref(), membership_set.set_id.to_le_bytes().as_ref()],
+        bump = membership_set.bump
+    )]
+    pub membership_set: Box<Account<'info, MembershipSet>>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CHECK_SET_MEMBERSHIP))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(mut, seeds = [METRICS_SEED], bump = metrics.bump)]
+    pub metrics: Option<Account<'info, MessagesMetrics>>,
+}
+
+#[callback_accounts("check_set_membership")]
+#[derive(Accounts)]
+pub struct CheckSetMembershipCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CHECK_SET_MEMBERSHIP))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    pub membership_set: Box<Account<'info, MembershipSet>>,
+    #[account(mut, seeds = [METRICS_SEED], bump = metrics.bump)]
+    pub metrics: Option<Account<'info, MessagesMetrics>>,
+}
+
+// ============================================================================
+// RATE LIMITING CONTEXTS
+// ============================================================================
+
+#[init_computation_definition_accounts("init_rate_bucket", payer)]
+#[derive(Accounts)]
+pub struct InitRateBucketCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("consume_rate_token", payer)]
+#[derive(Accounts)]
+pub struct InitConsumeRateTokenCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[queue_computation_accounts("init_rate_bucket", sender)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct CreateRateBucket<'info> {
+    #[account(mut)]
+    pub sender: Signer<'info>,
+    #[account(
+        init,
+        payer = sender,
+        space = RateBucket::SIZE,
+        seeds = [RATE_BUCKET_SEED, sender.key().as_ref()],
+        bump
+    )]
+    pub rate_bucket: Box<Account<'info, RateBucket>>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = sender,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_RATE_BUCKET))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(mut, seeds = [METRICS_SEED], bump = metrics.bump)]
+    pub metrics: Option<Account<'info, MessagesMetrics>>,
+}
+
+#[callback_accounts("init_rate_bucket")]
+#[derive(Accounts)]
+pub struct InitRateBucketCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_RATE_BUCKET))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub rate_bucket: Box<Account<'info, RateBucket>>,
+    #[account(mut, seeds = [METRICS_SEED], bump = metrics.bump)]
+    pub metrics: Option<Account<'info, MessagesMetrics>>,
+}
+
+#[queue_computation_accounts("consume_rate_token", sender)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct RequestSendToken<'info> {
+    #[account(mut)]
+    pub sender: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [RATE_BUCKET_SEED, sender.key().as_ref()],
+        bump = rate_bucket.bump
+    )]
+    pub rate_bucket: Box<Account<'info, RateBucket>>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = sender,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
     pub executing_pool: UncheckedAccount<'info>,
     #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
     /// CHECK: computation_account
     pub computation_account: UncheckedAccount<'info>,
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_TEST_ADD))]
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CONSUME_RATE_TOKEN))]
     pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
     #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
     pub cluster_account: Account<'info, Cluster>,
@@ -539,13 +2453,15 @@ pub struct TestAdd<'info> {
     pub clock_account: Account<'info, ClockAccount>,
     pub system_program: Program<'info, System>,
     pub arcium_program: Program<'info, Arcium>,
+    #[account(mut, seeds = [METRICS_SEED], bump = metrics.bump)]
+    pub metrics: Option<Account<'info, MessagesMetrics>>,
 }
 
-#[callback_accounts("test_add")]
+#[callback_accounts("consume_rate_token")]
 #[derive(Accounts)]
-pub struct TestAddCallback<'info> {
+pub struct ConsumeRateTokenCallback<'info> {
     pub arcium_program: Program<'info, Arcium>,
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_TEST_ADD))]
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CONSUME_RATE_TOKEN))]
     pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
     #[account(address = derive_mxe_pda!())]
     pub mxe_account: Account<'info, MXEAccount>,
@@ -556,15 +2472,33 @@ pub struct TestAddCallback<'info> {
     #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
     /// CHECK: instructions_sysvar
     pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub rate_bucket: Box<Account<'info, RateBucket>>,
+    #[account(mut, seeds = [METRICS_SEED], bump = metrics.bump)]
+    pub metrics: Option<Account<'info, MessagesMetrics>>,
+}
+
+#[derive(Accounts)]
+pub struct ResetRateBucketPending<'info> {
+    #[account(mut)]
+    pub sender: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [RATE_BUCKET_SEED, sender.key().as_ref()],
+        bump = rate_bucket.bump
+    )]
+    pub rate_bucket: Box<Account<'info, RateBucket>>,
+    #[account(mut, seeds = [METRICS_SEED], bump = metrics.bump)]
+    pub metrics: Option<Account<'info, MessagesMetrics>>,
 }
 
 // ============================================================================
-// PRIVATE MESSAGE CONTEXTS (with hidden metadata)
+// ENCRYPTED POLL CONTEXTS
 // ============================================================================
 
-#[init_computation_definition_accounts("verify_and_reveal_sender", payer)]
+#[init_computation_definition_accounts("init_poll_tally", payer)]
 #[derive(Accounts)]
-pub struct InitVerifySenderCompDef<'info> {
+pub struct InitPollTallyCompDef<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
     #[account(mut, address = derive_mxe_pda!())]
@@ -576,59 +2510,217 @@ pub struct InitVerifySenderCompDef<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[init_computation_definition_accounts("cast_poll_vote", payer)]
 #[derive(Accounts)]
-#[instruction(
-    message_index: u64,
-    encrypted_sender_hash: [u8; 32],
-    encrypted_recipient_hash: [u8; 32],
-    encrypted_content: Vec<u8>,
-    nonce: [u8; 24],
-)]
-pub struct SendPrivateMessage<'info> {
+pub struct InitCastPollVoteCompDef<'info> {
     #[account(mut)]
-    pub sender: Signer<'info>,
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-    /// Compteur global de messages privés
+#[init_computation_definition_accounts("reveal_poll_tally", payer)]
+#[derive(Accounts)]
+pub struct InitRevealPollTallyCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[queue_computation_accounts("init_poll_tally", creator)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, poll_id: u64)]
+pub struct CreatePoll<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
     #[account(
-        init_if_needed,
-        payer = sender,
-        space = PrivateMessageCounter::SIZE,
-        seeds = [b"private_message_counter"],
+        init,
+        payer = creator,
+        space = Poll::SIZE,
+        seeds = [POLL_SEED, creator.key().as_ref(), poll_id.to_le_bytes().as_ref()],
         bump
     )]
-    pub private_message_counter: Account<'info, PrivateMessageCounter>,
+    pub poll: Box<Account<'info, Poll>>,
+    /// Votants autorisés
+    pub membership_set: Box<Account<'info, MembershipSet>>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = creator,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_POLL_TALLY))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(mut, seeds = [METRICS_SEED], bump = metrics.bump)]
+    pub metrics: Option<Account<'info, MessagesMetrics>>,
+}
 
-    /// Le message privé - utilise le message_index passé en paramètre
+#[callback_accounts("init_poll_tally")]
+#[derive(Accounts)]
+pub struct InitPollTallyCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_POLL_TALLY))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub poll: Box<Account<'info, Poll>>,
+    #[account(mut, seeds = [METRICS_SEED], bump = metrics.bump)]
+    pub metrics: Option<Account<'info, MessagesMetrics>>,
+}
+
+#[queue_computation_accounts("cast_poll_vote", voter)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct CastPollVote<'info> {
+    #[account(mut)]
+    pub voter: Signer<'info>,
     #[account(
-        init,
-        payer = sender,
-        space = PrivateMessageAccount::SIZE,
-        seeds = [
-            b"private_message",
-            sender.key().as_ref(),
-            &message_index.to_le_bytes()
-        ],
+        mut,
+        seeds = [POLL_SEED, poll.creator.as_ref(), poll.poll_id.to_le_bytes().as_ref()],
+        bump = poll.bump
+    )]
+    pub poll: Box<Account<'info, Poll>>,
+    #[account(
+        init_if_needed,
+        payer = voter,
+        space = PollBallot::SIZE,
+        seeds = [POLL_BALLOT_SEED, poll.key().as_ref(), voter.key().as_ref()],
         bump
     )]
-    pub private_message_account: Account<'info, PrivateMessageAccount>,
-
+    pub ballot: Account<'info, PollBallot>,
+    #[account(address = poll.membership_set)]
+    pub membership_set: Box<Account<'info, MembershipSet>>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = voter,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_POLL_VOTE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
     pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(mut, seeds = [METRICS_SEED], bump = metrics.bump)]
+    pub metrics: Option<Account<'info, MessagesMetrics>>,
 }
 
-#[queue_computation_accounts("verify_and_reveal_sender", payer)]
+#[callback_accounts("cast_poll_vote")]
 #[derive(Accounts)]
-#[instruction(computation_offset: u64)]
-pub struct VerifyPrivateMessageAccess<'info> {
+pub struct CastPollVoteCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_POLL_VOTE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
     #[account(mut)]
-    pub payer: Signer<'info>,
+    pub poll: Box<Account<'info, Poll>>,
+    #[account(mut)]
+    pub ballot: Account<'info, PollBallot>,
+    #[account(mut, seeds = [METRICS_SEED], bump = metrics.bump)]
+    pub metrics: Option<Account<'info, MessagesMetrics>>,
+}
 
-    /// Le message privé à vérifier
-    pub private_message_account: Account<'info, PrivateMessageAccount>,
+#[derive(Accounts)]
+pub struct ResetPollPending<'info> {
+    pub caller: Signer<'info>,
+    /// CHECK: créateur du sondage, vérifié par has_one (reçoit la rente si le sondage est fermé)
+    #[account(mut)]
+    pub creator: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [POLL_SEED, poll.creator.as_ref(), poll.poll_id.to_le_bytes().as_ref()],
+        bump = poll.bump,
+        has_one = creator @ ErrorCode::Unauthorized
+    )]
+    pub poll: Box<Account<'info, Poll>>,
+    #[account(mut, seeds = [METRICS_SEED], bump = metrics.bump)]
+    pub metrics: Option<Account<'info, MessagesMetrics>>,
+}
 
+#[queue_computation_accounts("reveal_poll_tally", creator)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct RevealPollTally<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(
+        seeds = [POLL_SEED, creator.key().as_ref(), poll.poll_id.to_le_bytes().as_ref()],
+        bump = poll.bump,
+        has_one = creator @ ErrorCode::Unauthorized
+    )]
+    pub poll: Box<Account<'info, Poll>>,
     #[account(
         init_if_needed,
         space = 9,
-        payer = payer,
+        payer = creator,
         seeds = [&SIGN_PDA_SEED],
         bump,
         address = derive_sign_pda!(),
@@ -645,7 +2737,7 @@ pub struct VerifyPrivateMessageAccess<'info> {
     #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
     /// CHECK: computation_account
     pub computation_account: UncheckedAccount<'info>,
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_VERIFY_AND_REVEAL_SENDER))]
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_POLL_TALLY))]
     pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
     #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
     pub cluster_account: Account<'info, Cluster>,
@@ -655,13 +2747,15 @@ pub struct VerifyPrivateMessageAccess<'info> {
     pub clock_account: Account<'info, ClockAccount>,
     pub system_program: Program<'info, System>,
     pub arcium_program: Program<'info, Arcium>,
+    #[account(mut, seeds = [METRICS_SEED], bump = metrics.bump)]
+    pub metrics: Option<Account<'info, MessagesMetrics>>,
 }
 
-#[callback_accounts("verify_and_reveal_sender")]
+#[callback_accounts("reveal_poll_tally")]
 #[derive(Accounts)]
-pub struct VerifyAndRevealSenderCallback<'info> {
+pub struct RevealPollTallyCallback<'info> {
     pub arcium_program: Program<'info, Arcium>,
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_VERIFY_AND_REVEAL_SENDER))]
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_POLL_TALLY))]
     pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
     #[account(address = derive_mxe_pda!())]
     pub mxe_account: Account<'info, MXEAccount>,
@@ -672,6 +2766,39 @@ pub struct VerifyAndRevealSenderCallback<'info> {
     #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
     /// CHECK: instructions_sysvar
     pub instructions_sysvar: AccountInfo<'info>,
+    pub poll: Box<Account<'info, Poll>>,
+    #[account(mut, seeds = [METRICS_SEED], bump = metrics.bump)]
+    pub metrics: Option<Account<'info, MessagesMetrics>>,
+}
+
+// ============================================================================
+// USAGE METRICS CONTEXTS
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct InitializeMetrics<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = MessagesMetrics::SIZE,
+        seeds = [METRICS_SEED],
+        bump
+    )]
+    pub metrics: Account<'info, MessagesMetrics>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct GetMetrics<'info> {
+    #[account(
+        seeds = [METRICS_SEED],
+        bump = metrics.bump
+    )]
+    pub metrics: Account<'info, MessagesMetrics>,
 }
 
 // ============================================================================
@@ -703,6 +2830,13 @@ pub struct MessageRead {
     pub sender: Pubkey,
     pub recipient: Pubkey,
     pub timestamp: i64,
+    pub message: Pubkey,
+}
+
+#[event]
+pub struct NotificationPrefsUpdated {
+    pub owner: Pubkey,
+    pub flags: u8,
 }
 
 #[event]
@@ -728,10 +2862,48 @@ pub struct PrivateAccessVerified {
     pub nonce: [u8; 16],
 }
 
+#[event]
+pub struct SetMembershipChecked {
+    pub membership_set: Pubkey,
+    /// Bit d'appartenance chiffré pour le demandeur
+    pub encrypted_result: [u8; 32],
+    pub nonce: [u8; 16],
+}
+
+#[event]
+pub struct PollCreated {
+    pub poll: Pubkey,
+    pub creator: Pubkey,
+    pub question_hash: [u8; 32],
+    pub closes_at: i64,
+}
+
+#[event]
+pub struct PollTallyRevealed {
+    pub poll: Pubkey,
+    pub voter_count: u32,
+    /// Totaux chiffrés pour le créateur (oui, non, abstention)
+    pub encrypted_totals: [[u8; 32]; 3],
+    pub nonce: [u8; 16],
+}
+
 // ============================================================================
 // ERRORS
 // ============================================================================
 
+/// Échec de validation (émis avec la feature `telemetry`, voir report_failure)
+#[event]
+pub struct InstructionFailed {
+    pub code: u32,
+    /// Programme du code (les deux programmes numérotent à partir de 6000)
+    pub program: Pubkey,
+    pub signer: Pubkey,
+    pub context: u64,
+    pub timestamp: i64,
+}
+
+// Codes 6000..6999 (xray_common::errors::PRIVATE_MESSAGES_ERRORS) : les codes déjà
+// déployés ne changent pas, les nouvelles variantes vont à la fin
 #[error_code]
 pub enum ErrorCode {
     #[msg("The computation was aborted")]
@@ -742,4 +2914,28 @@ pub enum ErrorCode {
     MessageTooLong,
     #[msg("Unauthorized action")]
     Unauthorized,
+    #[msg("A computation is already pending")]
+    ComputationPending,
+    #[msg("Membership set is full")]
+    MemberSetFull,
+    #[msg("Send rate limit reached, request a send token first")]
+    RateLimited,
+    #[msg("A send token is already authorized")]
+    SendAlreadyAuthorized,
+    #[msg("Poll deadline must be in the future")]
+    InvalidPollDeadline,
+    #[msg("Poll is closed")]
+    PollClosed,
+    #[msg("Poll is still open")]
+    PollStillOpen,
+    #[msg("Unknown notification flags")]
+    InvalidNotificationFlags,
+    #[msg("No computation is pending")]
+    NoComputationPending,
+    #[msg("The pending computation has not timed out yet")]
+    PendingNotExpired,
+    #[msg("Ballot already counted")]
+    AlreadyVoted,
+    #[msg("Callback does not match the pending computation")]
+    StaleCallback,
 }
@@ -0,0 +1,169 @@
+// ============================================================================
+// MOCK MPC - feature `mock-mpc`
+// ============================================================================
+//
+// Remplace queue_computation : chaque circuit de encrypted-ixs est évalué en
+// clair, immédiatement, dans l'instruction qui l'aurait mis en file, et son
+// résultat est appliqué comme le ferait le callback. Aucun cluster Arcium
+// n'intervient (les comptes MXE / cluster / comp def restent requis par les
+// contextes, voir xray-testkit::fixtures).
+//
+// Le "chiffrement" est l'identité : un chiffré de 32 octets contient la valeur
+// en little-endian, et les hashes d'identité sont passés tels quels. Les
+// clients en mode mock envoient donc leurs entrées en clair.
+//
+// Réservé au localnet et à la CI : un build mock-mpc ne doit jamais être déployé.
+// ============================================================================
+
+use anchor_lang::prelude::*;
+use arcium_anchor::{MXEEncryptedStruct, SharedEncryptedStruct};
+use xray_common::sizes::MEMBER_SET_SLOTS;
+
+/// "Chiffre" une valeur : ses octets little-endian, complétés par des zéros
+pub fn encode(value: u128) -> [u8; 32] {
+    let mut ciphertext = [0u8; 32];
+    ciphertext[..16].copy_from_slice(&value.to_le_bytes());
+    ciphertext
+}
+
+/// "Déchiffre" une valeur écrite par [`encode`]
+pub fn decode(ciphertext: &[u8; 32]) -> u128 {
+    let mut value = [0u8; 16];
+    value.copy_from_slice(&ciphertext[..16]);
+    u128::from_le_bytes(value)
+}
+
+fn computed(computation_offset: u64) {
+    msg!("mock-mpc: computation {} exécutée immédiatement", computation_offset);
+}
+
+fn mxe<const N: usize>(nonce: u128, ciphertexts: [[u8; 32]; N]) -> MXEEncryptedStruct<N> {
+    MXEEncryptedStruct { nonce, ciphertexts }
+}
+
+fn shared<const N: usize>(encryption_key: [u8; 32], nonce: u128, ciphertexts: [[u8; 32]; N]) -> SharedEncryptedStruct<N> {
+    SharedEncryptedStruct { encryption_key, nonce, ciphertexts }
+}
+
+pub fn test_add(
+    computation_offset: u64,
+    a: &[u8; 32],
+    b: &[u8; 32],
+    pubkey: [u8; 32],
+    nonce: u128,
+) -> SharedEncryptedStruct<1> {
+    computed(computation_offset);
+    let sum = decode(a) as u8 as u16 + decode(b) as u8 as u16;
+    shared(pubkey, nonce, [encode(sum.into())])
+}
+
+/// 1 si le requester est le destinataire, 0 sinon
+pub fn verify_and_reveal_sender(
+    computation_offset: u64,
+    recipient_hash: &[u8; 32],
+    requester_hash: &[u8; 32],
+    pubkey: [u8; 32],
+    nonce: u128,
+) -> SharedEncryptedStruct<1> {
+    computed(computation_offset);
+    let is_match = recipient_hash == requester_hash;
+    shared(pubkey, nonce, [encode(is_match.into())])
+}
+
+pub fn init_member_set(computation_offset: u64, nonce: u128) -> MXEEncryptedStruct<MEMBER_SET_SLOTS> {
+    computed(computation_offset);
+    mxe(nonce, [encode(0); MEMBER_SET_SLOTS])
+}
+
+pub fn add_set_member(
+    computation_offset: u64,
+    member: &[u8; 32],
+    slot: u8,
+    members: &[[u8; 32]; MEMBER_SET_SLOTS],
+    nonce: u128,
+) -> MXEEncryptedStruct<MEMBER_SET_SLOTS> {
+    computed(computation_offset);
+    let mut members = *members;
+    members[slot as usize] = encode(decode(member));
+    mxe(nonce, members)
+}
+
+/// Un élément nul (emplacement vide) n'est jamais membre
+pub fn check_set_membership(
+    computation_offset: u64,
+    element: &[u8; 32],
+    members: &[[u8; 32]; MEMBER_SET_SLOTS],
+    pubkey: [u8; 32],
+    nonce: u128,
+) -> SharedEncryptedStruct<1> {
+    computed(computation_offset);
+    let element = decode(element);
+    let is_member = element != 0 && members.iter().any(|member| decode(member) == element);
+    shared(pubkey, nonce, [encode(is_member.into())])
+}
+
+pub fn init_rate_bucket(computation_offset: u64, nonce: u128, capacity: u64, now: u64) -> MXEEncryptedStruct<2> {
+    computed(computation_offset);
+    mxe(nonce, [encode(capacity.into()), encode(now.into())])
+}
+
+/// Même remplissage que le circuit : un jeton par `refill_secs` écoulées, plafonné à `capacity`
+pub fn consume_rate_token(
+    computation_offset: u64,
+    state: &[[u8; 32]; 2],
+    nonce: u128,
+    now: u64,
+    capacity: u64,
+    refill_secs: u64,
+) -> (MXEEncryptedStruct<2>, bool) {
+    computed(computation_offset);
+    let mut tokens = decode(&state[0]) as u64;
+    let mut last_refill = decode(&state[1]) as u64;
+
+    let refilled = now.saturating_sub(last_refill) / refill_secs;
+    tokens = tokens.saturating_add(refilled).min(capacity);
+    last_refill += refilled * refill_secs;
+
+    let allowed = tokens > 0;
+    tokens -= allowed as u64;
+
+    (mxe(nonce, [encode(tokens.into()), encode(last_refill.into())]), allowed)
+}
+
+pub fn init_poll_tally(computation_offset: u64, nonce: u128) -> MXEEncryptedStruct<3> {
+    computed(computation_offset);
+    mxe(nonce, [encode(0); 3])
+}
+
+/// 0 = oui, 1 = non, 2 = abstention ; un choix invalide n'est compté nulle part,
+/// le bulletin d'un non-membre non plus
+pub fn cast_poll_vote(
+    computation_offset: u64,
+    choice: &[u8; 32],
+    tally: &[[u8; 32]; 3],
+    nonce: u128,
+    voter: u128,
+    members: &[[u8; 32]; MEMBER_SET_SLOTS],
+) -> (MXEEncryptedStruct<3>, bool) {
+    computed(computation_offset);
+    let eligible = voter != 0 && members.iter().any(|member| decode(member) == voter);
+    let mut tally = *tally;
+    if let Some(total) = usize::try_from(decode(choice))
+        .ok()
+        .filter(|_| eligible)
+        .and_then(|choice| tally.get_mut(choice))
+    {
+        *total = encode(decode(total) + 1);
+    }
+    (mxe(nonce, tally), eligible)
+}
+
+pub fn reveal_poll_tally(
+    computation_offset: u64,
+    tally: &[[u8; 32]; 3],
+    pubkey: [u8; 32],
+    nonce: u128,
+) -> SharedEncryptedStruct<3> {
+    computed(computation_offset);
+    shared(pubkey, nonce, *tally)
+}
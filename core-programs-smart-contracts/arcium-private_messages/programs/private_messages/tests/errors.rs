@@ -0,0 +1,42 @@
+//! `ErrorCode` against the registry in `xray_common::errors`: codes, names and messages.
+
+use private_messages::ErrorCode;
+use xray_common::errors::{self, PRIVATE_MESSAGES_ERRORS};
+
+/// Every variant, in declaration order
+const VARIANTS: &[ErrorCode] = &[
+    ErrorCode::AbortedComputation,
+    ErrorCode::ClusterNotSet,
+    ErrorCode::MessageTooLong,
+    ErrorCode::Unauthorized,
+    ErrorCode::ComputationPending,
+    ErrorCode::MemberSetFull,
+    ErrorCode::RateLimited,
+    ErrorCode::SendAlreadyAuthorized,
+    ErrorCode::InvalidPollDeadline,
+    ErrorCode::PollClosed,
+    ErrorCode::PollStillOpen,
+    ErrorCode::InvalidNotificationFlags,
+    ErrorCode::NoComputationPending,
+    ErrorCode::PendingNotExpired,
+    ErrorCode::AlreadyVoted,
+    ErrorCode::StaleCallback,
+];
+
+#[test]
+fn variants_match_the_registry() {
+    assert_eq!(VARIANTS.len(), PRIVATE_MESSAGES_ERRORS.errors.len(), "register new ErrorCode variants in xray_common::errors");
+    for (variant, registered) in VARIANTS.iter().zip(PRIVATE_MESSAGES_ERRORS.iter()) {
+        assert_eq!(u32::from(*variant), registered.code);
+        assert_eq!(variant.name(), registered.name);
+        assert_eq!(variant.to_string(), registered.msg);
+    }
+}
+
+#[test]
+fn codes_resolve_to_this_program() {
+    for variant in VARIANTS {
+        let code = u32::from(*variant);
+        assert_eq!(errors::lookup_in(&private_messages::ID, code).map(|registered| registered.code), Some(code));
+    }
+}
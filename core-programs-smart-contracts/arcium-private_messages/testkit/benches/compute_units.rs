@@ -0,0 +1,168 @@
+//! Compute units consumed by the hot instructions, across payload sizes, checked against
+//! the ceilings in `cu-budgets.toml`. Exits non-zero when one is exceeded, so it can run
+//! locally before a push: `cargo bench -p xray-testkit`.
+//!
+//! Needs both programs built (`anchor build` in each workspace); a missing binary fails
+//! the run.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::ExitCode;
+
+use serde::Deserialize;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use xray_client::crypto::identity_hash;
+use xray_client::instructions::post::CreatePost;
+use xray_client::instructions::private_messages as ix;
+use xray_client::mpc::MpcCipher;
+use xray_client::xray_common::pda;
+use xray_testkit::fixtures::{self, MXE_X25519_PUBKEY, SOL_USD_PRICE_UPDATE};
+use xray_testkit::{Harness, Processed, CLUSTER_OFFSET};
+
+/// Encrypted message sizes, up to `MAX_MESSAGE_SIZE`
+const MESSAGE_SIZES: &[usize] = &[0, 64, 128, 256];
+/// Post content lengths, up to `MAX_CONTENT_LEN`
+const CONTENT_SIZES: &[usize] = &[1, 128, 512];
+
+/// `cu-budgets.toml`: instruction name → compute unit ceiling
+#[derive(Deserialize)]
+struct Budgets {
+    budgets: BTreeMap<String, u64>,
+}
+
+struct Measurement {
+    instruction: &'static str,
+    payload: usize,
+    compute_units: u64,
+}
+
+#[derive(Default)]
+struct Report {
+    measurements: Vec<Measurement>,
+}
+
+impl Report {
+    fn record(&mut self, instruction: &'static str, payload: usize, processed: Processed) {
+        let processed = processed.unwrap();
+        self.measurements.push(Measurement { instruction, payload, compute_units: processed.compute_units });
+    }
+}
+
+async fn private_messages(report: &mut Report) {
+    let mut harness = Harness::start().await;
+    let cipher = MpcCipher::new(&MXE_X25519_PUBKEY);
+
+    let recipient = harness.funded_wallet();
+    harness
+        .process(&[ix::register_user(recipient.pubkey(), [1; 32])], &[&recipient])
+        .await
+        .unwrap();
+
+    for (message_count, &size) in MESSAGE_SIZES.iter().enumerate() {
+        let sender = harness.funded_wallet();
+        let send = ix::send_message(sender.pubkey(), recipient.pubkey(), message_count as u64, vec![7; size], [4; 24]);
+        report.record("send_message", size, harness.process(&[send], &[&sender]).await);
+    }
+
+    // MPC queue instructions have fixed-size arguments: measured once
+    let sender = harness.funded_wallet();
+    let offset = Harness::computation_offset();
+    let create = ix::create_rate_bucket(sender.pubkey(), CLUSTER_OFFSET, offset, 1);
+    report.record("create_rate_bucket", 0, harness.process(&[create], &[&sender]).await);
+
+    for &size in MESSAGE_SIZES {
+        let sender = harness.funded_wallet();
+        harness.authorize_send(&sender).await;
+
+        let hashes =
+            cipher.encrypt_identity_hashes(&[&identity_hash(&recipient.pubkey()), &identity_hash(&sender.pubkey())], 7);
+        let message = ix::PrivateMessage {
+            message_index: 0,
+            encrypted_sender_hash: hashes[1],
+            encrypted_recipient_hash: hashes[0],
+            encrypted_content: vec![7; size],
+            nonce: [4; 24],
+            mpc_pubkey: cipher.public_key,
+            mpc_nonce: 7,
+        };
+        let send = ix::send_private_message(sender.pubkey(), &message);
+        report.record("send_private_message", size, harness.process(&[send], &[&sender]).await);
+
+        if size == 0 {
+            let offset = Harness::computation_offset();
+            let request = ix::request_send_token(sender.pubkey(), CLUSTER_OFFSET, offset);
+            report.record("request_send_token", 0, harness.process(&[request], &[&sender]).await);
+
+            let offset = Harness::computation_offset();
+            let verify = ix::verify_private_message_access(
+                recipient.pubkey(),
+                pda::private_message_pda(&sender.pubkey(), 0).0,
+                CLUSTER_OFFSET,
+                offset,
+                cipher.encrypt_identity_hash(&identity_hash(&recipient.pubkey()), 9),
+                cipher.public_key,
+                9,
+            );
+            report.record("verify_private_message_access", 0, harness.process(&[verify], &[&recipient]).await);
+        }
+    }
+}
+
+/// `create_post` with the bid going through the treasury and split three ways (three CPIs)
+async fn post_program(report: &mut Report) {
+    let mut harness = Harness::start_post().await;
+    let epoch = harness.epoch().await;
+
+    for &size in CONTENT_SIZES {
+        // A fresh author per case, so every post creates its author index and stats
+        let author = harness.funded_wallet();
+        let post = CreatePost::new(
+            author.pubkey(),
+            "bench.sol",
+            "c".repeat(size),
+            LAMPORTS_PER_SOL,
+            SOL_USD_PRICE_UPDATE,
+            epoch,
+            &fixtures::post_config(Pubkey::default()),
+        );
+        report.record("create_post", size, harness.process(&[post.instruction()], &[&author]).await);
+    }
+}
+
+fn read_budgets() -> Budgets {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("cu-budgets.toml");
+    let budgets = std::fs::read_to_string(&path).unwrap_or_else(|err| panic!("{}: {err}", path.display()));
+    toml::from_str(&budgets).unwrap_or_else(|err| panic!("{}: {err}", path.display()))
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let budgets = read_budgets().budgets;
+    let mut report = Report::default();
+    private_messages(&mut report).await;
+    post_program(&mut report).await;
+
+    let mut over_budget = 0;
+    println!("{:<32} {:>8} {:>10} {:>10}", "instruction", "payload", "CU", "budget");
+    for measurement in &report.measurements {
+        let budget = budgets.get(measurement.instruction).copied();
+        let exceeded = budget.is_some_and(|budget| measurement.compute_units > budget);
+        over_budget += exceeded as usize;
+        println!(
+            "{:<32} {:>8} {:>10} {:>10}{}",
+            measurement.instruction,
+            measurement.payload,
+            measurement.compute_units,
+            budget.map_or("-".to_string(), |budget| budget.to_string()),
+            if exceeded { "  OVER BUDGET" } else { "" },
+        );
+    }
+
+    if over_budget > 0 {
+        eprintln!("{over_budget} measurement(s) over their budget in cu-budgets.toml");
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,240 @@
+//! Arcium accounts a program reads when it queues a computation or takes a callback,
+//! serialized the way the Arcium program stores them. The cluster is set up with
+//! [`CLUSTER_BLS_SECRET`] so the harness can sign outputs the callbacks accept.
+//!
+//! `post-msg-program` gets its config, treasury and a Pyth SOL/USD price update.
+
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use arcium_client::idl::arcium::accounts::{ClockAccount, Cluster, ComputationDefinitionAccount, FeePool, MXEAccount};
+use arcium_client::idl::arcium::types::{
+    Activation, BN254G2BLSPublicKey, CircuitSource, ComputationDefinitionMeta, ComputationSignature, Epoch,
+    MxeStatus, OffChainCircuitSource, SetUnset, Timestamp, UtilityPubkeys,
+};
+use arcium_client::ARCIUM_PROGRAM_ID;
+use solana_alt_bn128_bls::{G2CompressedPoint, PrivKey};
+use solana_sdk::account::Account;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use xray_client::accounts::{discriminator, Config};
+use xray_client::xray_common::ids::{WALLET_1, WALLET_2, WALLET_3};
+use xray_client::xray_common::pda;
+
+/// Secret key of the mocked cluster (any scalar below the BN254 group order works)
+pub const CLUSTER_BLS_SECRET: [u8; 32] = [
+    0x21, 0x6f, 0x05, 0xb4, 0x64, 0xd2, 0xca, 0xb2, 0x72, 0x95, 0x4c, 0x66, 0x0d, 0xd4, 0x5c, 0xf8, 0xab, 0x0b,
+    0x26, 0x13, 0x65, 0x4d, 0xcc, 0xc7, 0x4c, 0x11, 0x55, 0xfe, 0xba, 0xaf, 0xb5, 0xc9,
+];
+
+/// x25519 key the mocked MXE advertises (clients encrypt their MPC inputs to it)
+pub const MXE_X25519_PUBKEY: [u8; 32] = [9; 32];
+
+/// Offset of `slot` in a computation account, hashed into the signed output
+pub const COMPUTATION_SLOT_OFFSET: usize = 100;
+/// Offset of `slot_counter` in a computation account
+pub const COMPUTATION_SLOT_COUNTER_OFFSET: usize = 108;
+
+/// Arcium-owned account holding `value` behind its Anchor discriminator
+pub fn arcium_account<T: AnchorSerialize + Discriminator>(value: &T) -> Account {
+    let mut data = T::DISCRIMINATOR.to_vec();
+    value.serialize(&mut data).expect("fixture serializes");
+    owned_by_arcium(data)
+}
+
+fn owned_by_arcium(data: Vec<u8>) -> Account {
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: ARCIUM_PROGRAM_ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// MXE of `mxe_program`, assigned to the cluster at `cluster_offset`
+pub fn mxe_account(mxe_program: &Pubkey, cluster_offset: u32, computation_definitions: Vec<u32>) -> (Pubkey, Account) {
+    let (address, bump) = arcium_client::pda::mxe_acc_w_bump(mxe_program);
+    let mxe = MXEAccount {
+        cluster: Some(cluster_offset),
+        keygen_offset: 0,
+        key_recovery_init_offset: 0,
+        mxe_program_id: *mxe_program,
+        authority: None,
+        utility_pubkeys: SetUnset::Set(UtilityPubkeys {
+            x25519_pubkey: MXE_X25519_PUBKEY,
+            ed25519_verifying_key: [0; 32],
+            elgamal_pubkey: [0; 32],
+            pubkey_validity_proof: [0; 64],
+        }),
+        fallback_clusters: vec![],
+        rejected_clusters: vec![],
+        computation_definitions,
+        status: MxeStatus::Active,
+        bump,
+    };
+    (address, arcium_account(&mxe))
+}
+
+/// Active one-node cluster whose aggregated BLS key is [`CLUSTER_BLS_SECRET`]'s
+pub fn cluster_account(cluster_offset: u32) -> (Pubkey, Account) {
+    let (address, bump) = arcium_client::pda::cluster_acc_w_bump(cluster_offset);
+    let bls_public_key =
+        G2CompressedPoint::try_from(&PrivKey(CLUSTER_BLS_SECRET)).expect("cluster secret is a valid scalar");
+    let cluster = Cluster {
+        td_info: None,
+        authority: None,
+        cluster_size: 1,
+        activation: Activation { activation_epoch: Epoch(0), deactivation_epoch: Epoch(u64::MAX) },
+        max_capacity: u64::MAX,
+        cu_price: 0,
+        cu_price_proposals: [0; 32],
+        last_updated_epoch: Epoch(0),
+        nodes: vec![],
+        pending_nodes: vec![],
+        bls_public_key: SetUnset::Set(BN254G2BLSPublicKey(bls_public_key.0)),
+        bump,
+    };
+    (address, arcium_account(&cluster))
+}
+
+/// Finalized definition of `circuit` for `mxe_program`; the circuit itself is never fetched
+pub fn comp_def_account(mxe_program: &Pubkey, circuit: &str) -> (Pubkey, Account) {
+    let (address, bump) = arcium_client::pda::computation_definition_acc_w_bump(mxe_program, arcium_client::pda::comp_def_offset(circuit));
+    let comp_def = ComputationDefinitionAccount {
+        finalization_authority: None,
+        cu_amount: 0,
+        definition: ComputationDefinitionMeta {
+            circuit_len: 0,
+            signature: ComputationSignature { parameters: vec![], outputs: vec![] },
+        },
+        circuit_source: CircuitSource::OffChain(OffChainCircuitSource {
+            source: format!("mock://{circuit}"),
+            hash: [0; 32],
+        }),
+        bump,
+    };
+    (address, arcium_account(&comp_def))
+}
+
+pub fn fee_pool_account() -> (Pubkey, Account) {
+    let (address, bump) = arcium_client::pda::fee_pool_acc_w_bump();
+    (address, arcium_account(&FeePool { bump }))
+}
+
+pub fn clock_account() -> (Pubkey, Account) {
+    let (address, bump) = arcium_client::pda::clock_acc_w_bump();
+    let clock = ClockAccount {
+        start_epoch: Epoch(0),
+        current_epoch: Epoch(0),
+        start_epoch_timestamp: Timestamp { timestamp: 0 },
+        bump,
+    };
+    (address, arcium_account(&clock))
+}
+
+/// Computation account as the callback reads it: only the slot and slot counter matter,
+/// the rest of the layout is left zeroed
+pub fn computation_account(slot: u64, slot_counter: u16) -> Account {
+    let mut data = vec![0u8; COMPUTATION_SLOT_COUNTER_OFFSET + 2];
+    data[COMPUTATION_SLOT_OFFSET..COMPUTATION_SLOT_OFFSET + 8].copy_from_slice(&slot.to_le_bytes());
+    data[COMPUTATION_SLOT_COUNTER_OFFSET..].copy_from_slice(&slot_counter.to_le_bytes());
+    owned_by_arcium(data)
+}
+
+// ----------------------------------------------------------------------------
+// post-msg-program
+// ----------------------------------------------------------------------------
+
+/// Pyth Solana receiver, owner of `PriceUpdateV2` accounts
+pub const PYTH_RECEIVER_PROGRAM_ID: Pubkey = solana_sdk::pubkey!("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ");
+
+/// Pyth SOL/USD feed (`SOL_USD_FEED_ID` in the program)
+pub const SOL_USD_FEED_ID: [u8; 32] = [
+    0xef, 0x0d, 0x8b, 0x6f, 0xda, 0x2c, 0xeb, 0xa4, 0x1d, 0xa1, 0x5d, 0x40, 0x95, 0xd1, 0xda, 0x39, 0x2a, 0x0d,
+    0x2f, 0x8e, 0xd0, 0xc6, 0xc7, 0xbc, 0x0f, 0x4c, 0xfa, 0xc8, 0xc2, 0x80, 0xb5, 0x6d,
+];
+
+/// Where the harness publishes the SOL/USD price update
+pub const SOL_USD_PRICE_UPDATE: Pubkey = Pubkey::new_from_array([0x50; 32]);
+
+/// Rent-exempt minimum kept in the treasury (`TREASURY_MIN_BALANCE` in the program)
+pub const TREASURY_MIN_BALANCE: u64 = 890_880;
+
+/// Post-msg-program account holding `value` behind its Anchor discriminator
+fn post_program_account<T: AnchorSerialize>(name: &str, value: &T) -> Account {
+    let mut data = discriminator(name).to_vec();
+    value.serialize(&mut data).expect("fixture serializes");
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: xray_client::xray_common::POST_MSG_PROGRAM_ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// Config with a $1 minimum bid and every optional mechanism (rate limit, stake,
+/// vesting, receipts, referrals) turned off
+pub fn post_config(admin: Pubkey) -> Config {
+    Config {
+        admin,
+        min_bid_usd: 1_000_000,
+        referral_bps: 0,
+        receipt_tree: Pubkey::default(),
+        rate_window_secs: 0,
+        max_posts_per_window: 0,
+        rate_waiver_bid_usd: 0,
+        min_stake: 0,
+        stake_cooldown_secs: 0,
+        vesting_vault: Pubkey::default(),
+        pending_admin: Pubkey::default(),
+        escrow_window_secs: 0,
+        escrow_keep_fee_bps: 0,
+        upvote_lamports: 0,
+        treasury_version: 1,
+        view_attestor: Pubkey::default(),
+        bump: pda::config_pda().1,
+    }
+}
+
+/// [`post_config`] at the config PDA
+pub fn post_config_account(admin: Pubkey) -> (Pubkey, Account) {
+    (pda::config_pda().0, post_program_account("Config", &post_config(admin)))
+}
+
+/// Treasury PDA at its rent-exempt minimum, so each bid is split right away
+pub fn treasury_account() -> (Pubkey, Account) {
+    let address = pda::treasury_pda(1).0;
+    (address, Account::new(TREASURY_MIN_BALANCE, 0, &anchor_lang::system_program::ID))
+}
+
+/// The three revenue wallets, funded so small transfers keep them rent exempt
+pub fn revenue_wallet_accounts() -> Vec<(Pubkey, Account)> {
+    [WALLET_1, WALLET_2, WALLET_3]
+        .into_iter()
+        .map(|wallet| (wallet, Account::new(LAMPORTS_PER_SOL, 0, &anchor_lang::system_program::ID)))
+        .collect()
+}
+
+/// Fully verified SOL/USD `PriceUpdateV2` worth `price * 10^exponent` USD, published at `publish_time`
+pub fn price_update_account(price: i64, exponent: i32, publish_time: i64) -> Account {
+    let mut data = discriminator("PriceUpdateV2").to_vec();
+    data.extend_from_slice(Pubkey::default().as_ref()); // write_authority
+    data.push(1); // VerificationLevel::Full
+    data.extend_from_slice(&SOL_USD_FEED_ID);
+    data.extend_from_slice(&price.to_le_bytes());
+    data.extend_from_slice(&0u64.to_le_bytes()); // conf
+    data.extend_from_slice(&exponent.to_le_bytes());
+    data.extend_from_slice(&publish_time.to_le_bytes());
+    data.extend_from_slice(&publish_time.to_le_bytes()); // prev_publish_time
+    data.extend_from_slice(&price.to_le_bytes()); // ema_price
+    data.extend_from_slice(&0u64.to_le_bytes()); // ema_conf
+    data.extend_from_slice(&0u64.to_le_bytes()); // posted_slot
+
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: PYTH_RECEIVER_PROGRAM_ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
@@ -0,0 +1,369 @@
+//! An in-process bank running the `private_messages` SBF binary and the mocked Arcium
+//! program, with the MXE, cluster and computation definitions already in place
+//! ([`Harness::start`]), or the `post-msg-program` binary with its config, treasury, price
+//! feed and its own MXE ([`Harness::start_post`]).
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::Discriminator;
+use arcium_anchor::{HasSize, MXEEncryptedStruct, SignedComputationOutputs};
+use arcium_client::idl::arcium::client::args;
+use arcium_client::{pda, ARCIUM_PROGRAM_ID};
+use base64::prelude::{Engine, BASE64_STANDARD};
+use private_messages::{ConsumeRateTokenOutput, ConsumeRateTokenOutputStruct0, InitRateBucketOutput};
+use solana_alt_bn128_bls::{PrivKey, Sha256Normalized};
+use solana_program_test::{processor, ProgramTest, ProgramTestContext};
+use solana_sdk::account::Account;
+use solana_sdk::bpf_loader;
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::transaction::{Transaction, TransactionError};
+use xray_client::instructions::private_messages as ix;
+use xray_client::xray_common::POST_MSG_PROGRAM_ID;
+
+use crate::fixtures::{self, CLUSTER_BLS_SECRET, SOL_USD_PRICE_UPDATE};
+use crate::mock_arcium::{self, QueuedComputation};
+
+/// Cluster the mocked MXE is assigned to
+pub const CLUSTER_OFFSET: u32 = 1;
+
+/// Circuits of `private_messages`, each given a computation definition
+pub const CIRCUITS: &[&str] = &[
+    "test_add",
+    "verify_and_reveal_sender",
+    "init_member_set",
+    "add_set_member",
+    "check_set_membership",
+    "init_rate_bucket",
+    "consume_rate_token",
+    "init_poll_tally",
+    "cast_poll_vote",
+    "reveal_poll_tally",
+];
+
+/// Circuits of `post-msg-program`, each given a computation definition
+pub const POST_CIRCUITS: &[&str] = &[
+    "init_sealed_auction",
+    "place_sealed_bid",
+    "resolve_sealed_auction",
+    "reveal_content_key",
+];
+
+/// `private_messages.so` from `SBF_OUT_DIR`, else from the workspace's `target/deploy`
+pub fn program_binary() -> PathBuf {
+    let dir = match std::env::var_os("SBF_OUT_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => Path::new(env!("CARGO_MANIFEST_DIR")).join("../target/deploy"),
+    };
+    dir.join("private_messages.so")
+}
+
+/// `post_msg_program.so` from `SBF_OUT_DIR`, else from the post program's `target/deploy`
+pub fn post_program_binary() -> PathBuf {
+    let dir = match std::env::var_os("SBF_OUT_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => Path::new(env!("CARGO_MANIFEST_DIR")).join("../../post-msg-program/target/deploy"),
+    };
+    dir.join("post_msg_program.so")
+}
+
+/// Deploys the SBF binary at `binary` as `program_id`; panics if it has not been built
+fn add_sbf_program(program_test: &mut ProgramTest, program_id: Pubkey, binary: &Path) {
+    let program = std::fs::read(binary)
+        .unwrap_or_else(|err| panic!("{}: {err}, run `anchor build` first", binary.display()));
+    program_test.add_account(
+        program_id,
+        Account {
+            lamports: Rent::default().minimum_balance(program.len()),
+            data: program,
+            owner: bpf_loader::ID,
+            executable: true,
+            rent_epoch: 0,
+        },
+    );
+}
+
+/// Adds the mocked Arcium program, and the MXE of `mxe_program` on [`CLUSTER_OFFSET`] with a
+/// computation definition for each of `circuits`
+fn add_mock_arcium(program_test: &mut ProgramTest, mxe_program: &Pubkey, circuits: &[&str]) {
+    // The mock makes no CPI, so it can run natively next to the SBF program
+    program_test.prefer_bpf(false);
+    program_test.add_program("arcium", ARCIUM_PROGRAM_ID, processor!(mock_arcium::process_instruction));
+
+    let comp_defs = circuits.iter().map(|circuit| pda::comp_def_offset(circuit)).collect();
+    let accounts = [
+        fixtures::mxe_account(mxe_program, CLUSTER_OFFSET, comp_defs),
+        fixtures::cluster_account(CLUSTER_OFFSET),
+        fixtures::fee_pool_account(),
+        fixtures::clock_account(),
+    ];
+    for (address, account) in accounts {
+        program_test.add_account(address, account);
+    }
+    for circuit in circuits {
+        let (address, account) = fixtures::comp_def_account(mxe_program, circuit);
+        program_test.add_account(address, account);
+    }
+}
+
+/// Result, logs and compute units of a processed transaction
+#[derive(Debug)]
+pub struct Processed {
+    pub result: std::result::Result<(), TransactionError>,
+    pub logs: Vec<String>,
+    /// Compute units consumed by all of the transaction's instructions
+    pub compute_units: u64,
+}
+
+impl Processed {
+    /// Panics with the logs if the transaction failed
+    pub fn unwrap(self) -> Self {
+        if let Err(err) = &self.result {
+            panic!("transaction failed: {err}\n{}", self.logs.join("\n"));
+        }
+        self
+    }
+
+    /// Custom program error the transaction failed with (see `xray_common::errors` for the ranges)
+    pub fn error_code(&self) -> Option<u32> {
+        match self.result {
+            Err(TransactionError::InstructionError(_, InstructionError::Custom(code))) => Some(code),
+            _ => None,
+        }
+    }
+
+    /// Events of type `E` emitted by the transaction, in order
+    pub fn events<E: Discriminator + AnchorDeserialize>(&self) -> Vec<E> {
+        self.logs
+            .iter()
+            .filter_map(|line| line.strip_prefix("Program data: "))
+            .filter_map(|data| BASE64_STANDARD.decode(data).ok())
+            .filter_map(|data| {
+                let payload = data.strip_prefix(E::DISCRIMINATOR)?;
+                E::try_from_slice(payload).ok()
+            })
+            .collect()
+    }
+}
+
+pub struct Harness {
+    pub context: ProgramTestContext,
+}
+
+impl Harness {
+    /// Panics while the program has not been built. Tests using it only build with the
+    /// `sbf-tests` feature: run them with `anchor build` then
+    /// `cargo test -p xray-testkit --features sbf-tests`
+    pub async fn start() -> Self {
+        let mut program_test = ProgramTest::default();
+        add_sbf_program(&mut program_test, private_messages::ID, &program_binary());
+        add_mock_arcium(&mut program_test, &private_messages::ID, CIRCUITS);
+
+        Self { context: program_test.start_with_context().await }
+    }
+
+    /// Bank running `post-msg-program` with the fixture config (see
+    /// [`fixtures::post_config_account`]), SOL at $150 and the mocked Arcium program for its
+    /// [`POST_CIRCUITS`]; panics while it has not been built
+    pub async fn start_post() -> Self {
+        Self::start_post_with_admin(Pubkey::new_unique()).await
+    }
+
+    /// [`Harness::start_post`] with `admin` as the config admin (for the admin-only instructions)
+    pub async fn start_post_with_admin(admin: Pubkey) -> Self {
+        let mut program_test = ProgramTest::default();
+        add_sbf_program(&mut program_test, POST_MSG_PROGRAM_ID, &post_program_binary());
+        add_mock_arcium(&mut program_test, &POST_MSG_PROGRAM_ID, POST_CIRCUITS);
+
+        let accounts = [fixtures::post_config_account(admin), fixtures::treasury_account()]
+            .into_iter()
+            .chain(fixtures::revenue_wallet_accounts());
+        for (address, account) in accounts {
+            program_test.add_account(address, account);
+        }
+
+        let mut harness = Self { context: program_test.start_with_context().await };
+        harness.set_sol_usd_price(15_000_000_000, -8).await;
+        harness
+    }
+
+    /// Publishes a fresh SOL/USD price at [`SOL_USD_PRICE_UPDATE`], stamped with the bank's clock
+    pub async fn set_sol_usd_price(&mut self, price: i64, exponent: i32) {
+        let clock: Clock = self.context.banks_client.get_sysvar().await.expect("clock sysvar");
+        let account = fixtures::price_update_account(price, exponent, clock.unix_timestamp);
+        self.context.set_account(&SOL_USD_PRICE_UPDATE, &account.into());
+    }
+
+    /// Current epoch of the bank (keys `post-msg-program`'s revenue snapshot)
+    pub async fn epoch(&mut self) -> u64 {
+        let clock: Clock = self.context.banks_client.get_sysvar().await.expect("clock sysvar");
+        clock.epoch
+    }
+
+    /// Current unix timestamp of the bank
+    pub async fn unix_timestamp(&mut self) -> i64 {
+        let clock: Clock = self.context.banks_client.get_sysvar().await.expect("clock sysvar");
+        clock.unix_timestamp
+    }
+
+    /// Moves the bank's clock `secs` seconds forward
+    pub async fn advance_clock(&mut self, secs: i64) {
+        let mut clock: Clock = self.context.banks_client.get_sysvar().await.expect("clock sysvar");
+        clock.unix_timestamp += secs;
+        self.context.set_sysvar(&clock);
+    }
+
+    /// The program account at `address`, `None` if it does not exist
+    pub async fn account<T: AccountDeserialize>(&mut self, address: &Pubkey) -> Option<T> {
+        let account = self.context.banks_client.get_account(*address).await.expect("banks client")?;
+        Some(T::try_deserialize(&mut account.data.as_slice()).expect("account deserializes"))
+    }
+
+    /// A new system account holding 10 SOL
+    pub fn funded_wallet(&mut self) -> Keypair {
+        let wallet = Keypair::new();
+        self.fund(&wallet.pubkey());
+        wallet
+    }
+
+    /// Makes `wallet` a system account holding 10 SOL
+    pub fn fund(&mut self, wallet: &Pubkey) {
+        let account = Account::new(10 * LAMPORTS_PER_SOL, 0, &anchor_lang::system_program::ID);
+        self.context.set_account(wallet, &account.into());
+    }
+
+    /// Lamports held by `address` (0 if it does not exist)
+    pub async fn balance(&mut self, address: &Pubkey) -> u64 {
+        self.context.banks_client.get_balance(*address).await.expect("banks client")
+    }
+
+    /// Computation offset never used before in this process
+    pub fn computation_offset() -> u64 {
+        static NEXT: AtomicU64 = AtomicU64::new(1);
+        NEXT.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// Sends `instructions` paid by the context payer and signed by `signers` too
+    pub async fn process(&mut self, instructions: &[Instruction], signers: &[&Keypair]) -> Processed {
+        let blockhash = self.context.get_new_latest_blockhash().await.expect("blockhash");
+        let mut all_signers = vec![&self.context.payer];
+        all_signers.extend_from_slice(signers);
+        let tx = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.context.payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+
+        let processed = self
+            .context
+            .banks_client
+            .process_transaction_with_metadata(tx)
+            .await
+            .expect("banks client");
+        let (logs, compute_units) = processed
+            .metadata
+            .map(|metadata| (metadata.log_messages, metadata.compute_units_consumed))
+            .unwrap_or_default();
+        Processed { result: processed.result, logs, compute_units }
+    }
+
+    /// Creates `sender`'s rate bucket and has the MPC grant it one send token, so the next
+    /// `send_private_message` goes through
+    pub async fn authorize_send(&mut self, sender: &Keypair) {
+        let offset = Self::computation_offset();
+        self.process(&[ix::create_rate_bucket(sender.pubkey(), CLUSTER_OFFSET, offset, 1)], &[sender])
+            .await
+            .unwrap();
+        let state = |nonce| MXEEncryptedStruct { nonce, ciphertexts: [[1; 32], [2; 32]] };
+        self.callback(offset, &InitRateBucketOutput { field_0: state(2) }).await.unwrap();
+
+        let offset = Self::computation_offset();
+        self.process(&[ix::request_send_token(sender.pubkey(), CLUSTER_OFFSET, offset)], &[sender])
+            .await
+            .unwrap();
+        let output = ConsumeRateTokenOutput {
+            field_0: ConsumeRateTokenOutputStruct0 { field_0: state(3), field_1: true },
+        };
+        self.callback(offset, &output).await.unwrap();
+    }
+
+    /// What the program queued under `computation_offset`; panics if nothing was
+    pub fn queued(&self, computation_offset: u64) -> QueuedComputation {
+        mock_arcium::queued(&pda::computation_acc(CLUSTER_OFFSET, computation_offset))
+            .unwrap_or_else(|| panic!("no computation queued at offset {computation_offset}"))
+    }
+
+    /// Answers the computation with `output`, signed by the cluster, as the Arcium program would
+    pub async fn callback<O: HasSize + AnchorSerialize + AnchorDeserialize>(
+        &mut self,
+        computation_offset: u64,
+        output: &O,
+    ) -> Processed {
+        self.callback_signed_by(computation_offset, output, &PrivKey(CLUSTER_BLS_SECRET)).await
+    }
+
+    /// [`Harness::callback`] with the output signed by `signer` instead of the cluster
+    pub async fn callback_signed_by<O: HasSize + AnchorSerialize + AnchorDeserialize>(
+        &mut self,
+        computation_offset: u64,
+        output: &O,
+        signer: &PrivKey,
+    ) -> Processed {
+        let slot = self.place_computation_account(computation_offset).await;
+        let mut serialized = Vec::new();
+        output.serialize(&mut serialized).expect("output serializes");
+
+        // Signed message: output || slot || slot counter (see arcium-anchor verify_output)
+        let message = [serialized.as_slice(), &slot.to_le_bytes(), &0u16.to_le_bytes()].concat();
+        let signature = signer.sign::<Sha256Normalized, _>(&message).expect("bls signature");
+
+        let outputs = SignedComputationOutputs::<O>::Success(serialized, signature.0);
+        let instructions = [self.arcium_callback_instruction(), self.callback_instruction(computation_offset, &outputs)];
+        self.process(&instructions, &[]).await
+    }
+
+    /// Reports the computation as failed
+    pub async fn abort<O: HasSize + AnchorSerialize + AnchorDeserialize>(&mut self, computation_offset: u64) -> Processed {
+        self.place_computation_account(computation_offset).await;
+        let outputs = SignedComputationOutputs::<O>::Failure;
+        let instructions = [self.arcium_callback_instruction(), self.callback_instruction(computation_offset, &outputs)];
+        self.process(&instructions, &[]).await
+    }
+
+    /// The Arcium instruction a callback must directly follow
+    pub fn arcium_callback_instruction(&self) -> Instruction {
+        Instruction::new_with_bytes(ARCIUM_PROGRAM_ID, args::CallbackComputation::DISCRIMINATOR, vec![])
+    }
+
+    /// The callback instruction the program registered when queueing `computation_offset`
+    pub fn callback_instruction<O: HasSize + AnchorSerialize + AnchorDeserialize>(
+        &self,
+        computation_offset: u64,
+        outputs: &SignedComputationOutputs<O>,
+    ) -> Instruction {
+        let queued = self.queued(computation_offset);
+        let callback = queued.callback_instructions.first().expect("a callback instruction");
+
+        let mut data = callback.discriminator.clone();
+        outputs.serialize(&mut data).expect("outputs serialize");
+        let accounts = callback
+            .accounts
+            .iter()
+            .map(|account| AccountMeta { pubkey: account.pubkey, is_signer: false, is_writable: account.is_writable })
+            .collect();
+        Instruction { program_id: callback.program_id, accounts, data }
+    }
+
+    /// Writes the computation account the callback reads, stamped with the current slot
+    async fn place_computation_account(&mut self, computation_offset: u64) -> u64 {
+        let slot = self.context.banks_client.get_root_slot().await.expect("slot");
+        let address = pda::computation_acc(CLUSTER_OFFSET, computation_offset);
+        self.context.set_account(&address, &fixtures::computation_account(slot, 0).into());
+        slot
+    }
+}
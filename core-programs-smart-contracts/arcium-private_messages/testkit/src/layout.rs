@@ -0,0 +1,20 @@
+//! Serialized sizes of program accounts, checked against their `SIZE` constants
+//! (from `xray_common::sizes`) by both programs' layout tests.
+
+use anchor_lang::{AccountSerialize, AnchorDeserialize};
+use xray_client::xray_common::sizes::DISCRIMINATOR;
+
+/// Serialized length, discriminator included
+pub fn space<T: AccountSerialize>(account: &T) -> usize {
+    let mut data = Vec::new();
+    account.try_serialize(&mut data).expect("account serializes");
+    data.len()
+}
+
+/// Fixed-size account read from `size` zeroed bytes: fails if `size` is too small,
+/// and the round trip is shorter than `size` if it is too large
+pub fn assert_fixed_size<T: AccountSerialize + AnchorDeserialize>(size: usize) {
+    let zeroed = vec![0u8; size - DISCRIMINATOR];
+    let account = T::deserialize(&mut zeroed.as_slice()).expect("SIZE is smaller than the layout");
+    assert_eq!(space(&account), size, "SIZE is larger than the layout");
+}
@@ -0,0 +1,18 @@
+//! Test harness for `private_messages` without a live Arcium cluster.
+//!
+//! The built program (`target/deploy/private_messages.so`, from `arcium build`) runs in a
+//! `solana-program-test` bank next to a mocked Arcium program that records queued
+//! computations. Tests answer them with [`Harness::callback`], which signs the output with
+//! the fixture cluster's BLS key, so the program's callbacks run their real signature and
+//! instruction-sequence checks.
+//!
+//! The program is loaded as SBF rather than linked natively: Anchor's CPIs (account
+//! creation included) only work inside the VM.
+
+pub mod fixtures;
+pub mod harness;
+pub mod layout;
+pub mod mock_arcium;
+
+pub use harness::{Harness, Processed, CLUSTER_OFFSET, POST_CIRCUITS};
+pub use mock_arcium::QueuedComputation;
@@ -0,0 +1,77 @@
+//! Stand-in for the Arcium program. `queue_computation` is recorded instead of reaching a
+//! cluster, so a test can read what the program asked for and answer it through
+//! [`crate::Harness::callback`]; `callback_computation` is accepted as is, since the callback
+//! instruction that follows it only checks that it is there.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::solana_program::program_error::ProgramError;
+use anchor_lang::Discriminator;
+use arcium_client::idl::arcium::client::args;
+use arcium_client::idl::arcium::types::{ArgumentList, CallbackInstruction};
+
+/// A `queue_computation` call, keyed by its computation account
+#[derive(Debug, Clone)]
+pub struct QueuedComputation {
+    pub computation_offset: u64,
+    pub comp_def_offset: u32,
+    pub args: ArgumentList,
+    pub callback_instructions: Vec<CallbackInstruction>,
+}
+
+/// `QueueComputation` arguments up to the callback instructions (the fee fields after them
+/// are not used here)
+#[derive(AnchorDeserialize)]
+struct QueueComputationArgs {
+    comp_offset: u64,
+    computation_definition_offset: u32,
+    _cluster_index: Option<u16>,
+    args: ArgumentList,
+    _mxe_program: Pubkey,
+    _callback_url: Option<String>,
+    custom_callback_instructions: Vec<CallbackInstruction>,
+}
+
+// QueueComputation accounts: signer, sign_seed, comp, ...
+const QUEUE_COMPUTATION_ACCOUNT_INDEX: usize = 2;
+
+/// Shared by every bank of the process; computation accounts are unique per computation
+/// offset, which [`crate::Harness::computation_offset`] never repeats
+fn queue() -> &'static Mutex<HashMap<Pubkey, QueuedComputation>> {
+    static QUEUE: OnceLock<Mutex<HashMap<Pubkey, QueuedComputation>>> = OnceLock::new();
+    QUEUE.get_or_init(Default::default)
+}
+
+pub fn queued(computation_account: &Pubkey) -> Option<QueuedComputation> {
+    queue().lock().unwrap().get(computation_account).cloned()
+}
+
+pub fn process_instruction(_program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let (discriminator, mut data) = data.split_at_checked(8).ok_or(ProgramError::InvalidInstructionData)?;
+
+    if discriminator == args::QueueComputation::DISCRIMINATOR {
+        let queued = QueueComputationArgs::deserialize(&mut data).map_err(|_| ProgramError::InvalidInstructionData)?;
+        let computation_account = accounts
+            .get(QUEUE_COMPUTATION_ACCOUNT_INDEX)
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+        msg!("mock arcium: queued computation {}", queued.comp_offset);
+
+        queue().lock().unwrap().insert(
+            computation_account.key(),
+            QueuedComputation {
+                computation_offset: queued.comp_offset,
+                comp_def_offset: queued.computation_definition_offset,
+                args: queued.args,
+                callback_instructions: queued.custom_callback_instructions,
+            },
+        );
+        Ok(())
+    } else if discriminator == args::CallbackComputation::DISCRIMINATOR {
+        Ok(())
+    } else {
+        Err(ProgramError::InvalidInstructionData)
+    }
+}
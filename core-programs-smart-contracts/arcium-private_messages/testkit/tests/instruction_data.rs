@@ -0,0 +1,119 @@
+//! Instruction data built by `xray-client` decoded with the program's own argument types,
+//! for arbitrary payloads: catches drift between the builders and the handlers' signatures.
+
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use private_messages::instruction;
+use proptest::prelude::*;
+use solana_sdk::pubkey::Pubkey;
+use xray_client::instructions::private_messages as ix;
+use xray_client::xray_common::sizes::MAX_MESSAGE_SIZE;
+
+/// Arguments of `data`, which must start with `T`'s discriminator and hold nothing else
+fn decode<T: Discriminator + AnchorDeserialize>(data: &[u8]) -> T {
+    let args = data.strip_prefix(T::DISCRIMINATOR).expect("instruction discriminator");
+    T::try_from_slice(args).expect("instruction arguments")
+}
+
+/// Content lengths on both sides of the program's limit
+fn content() -> impl Strategy<Value = Vec<u8>> {
+    prop::collection::vec(any::<u8>(), 0..=2 * MAX_MESSAGE_SIZE)
+}
+
+proptest! {
+    #[test]
+    fn register_user(x25519_pubkey in any::<[u8; 32]>()) {
+        let args: instruction::RegisterUser = decode(&ix::register_user(Pubkey::new_unique(), x25519_pubkey).data);
+        prop_assert_eq!(args.x25519_pubkey, x25519_pubkey);
+    }
+
+    #[test]
+    fn send_message(encrypted_content in content(), nonce in any::<[u8; 24]>(), message_count in any::<u64>()) {
+        let data = ix::send_message(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            message_count,
+            encrypted_content.clone(),
+            nonce,
+        )
+        .data;
+
+        let args: instruction::SendMessage = decode(&data);
+        prop_assert_eq!(args.encrypted_content, encrypted_content);
+        prop_assert_eq!(args.nonce, nonce);
+    }
+
+    #[test]
+    fn send_private_message(
+        message_index in any::<u64>(),
+        encrypted_sender_hash in any::<[u8; 32]>(),
+        encrypted_recipient_hash in any::<[u8; 32]>(),
+        encrypted_content in content(),
+        nonce in any::<[u8; 24]>(),
+        mpc_pubkey in any::<[u8; 32]>(),
+        mpc_nonce in any::<u128>(),
+    ) {
+        let message = ix::PrivateMessage {
+            message_index,
+            encrypted_sender_hash,
+            encrypted_recipient_hash,
+            encrypted_content: encrypted_content.clone(),
+            nonce,
+            mpc_pubkey,
+            mpc_nonce,
+        };
+
+        let args: instruction::SendPrivateMessage = decode(&ix::send_private_message(Pubkey::new_unique(), &message).data);
+        prop_assert_eq!(args.message_index, message_index);
+        prop_assert_eq!(args.encrypted_sender_hash, encrypted_sender_hash);
+        prop_assert_eq!(args.encrypted_recipient_hash, encrypted_recipient_hash);
+        prop_assert_eq!(args.encrypted_content, encrypted_content);
+        prop_assert_eq!(args.nonce, nonce);
+        prop_assert_eq!(args.mpc_pubkey, mpc_pubkey);
+        prop_assert_eq!(args.mpc_nonce, mpc_nonce);
+    }
+
+    #[test]
+    fn verify_private_message_access(
+        computation_offset in any::<u64>(),
+        encrypted_requester_hash in any::<[u8; 32]>(),
+        mpc_pubkey in any::<[u8; 32]>(),
+        mpc_nonce in any::<u128>(),
+    ) {
+        let data = ix::verify_private_message_access(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            xray_testkit::CLUSTER_OFFSET,
+            computation_offset,
+            encrypted_requester_hash,
+            mpc_pubkey,
+            mpc_nonce,
+        )
+        .data;
+
+        let args: instruction::VerifyPrivateMessageAccess = decode(&data);
+        prop_assert_eq!(args.computation_offset, computation_offset);
+        prop_assert_eq!(args.encrypted_requester_hash, encrypted_requester_hash);
+        prop_assert_eq!(args.mpc_pubkey, mpc_pubkey);
+        prop_assert_eq!(args.mpc_nonce, mpc_nonce);
+    }
+
+    #[test]
+    fn rate_bucket(computation_offset in any::<u64>(), mxe_nonce in any::<u128>()) {
+        let sender = Pubkey::new_unique();
+
+        let data = ix::create_rate_bucket(sender, xray_testkit::CLUSTER_OFFSET, computation_offset, mxe_nonce).data;
+        let args: instruction::CreateRateBucket = decode(&data);
+        prop_assert_eq!(args.computation_offset, computation_offset);
+        prop_assert_eq!(args.mxe_nonce, mxe_nonce);
+
+        let data = ix::request_send_token(sender, xray_testkit::CLUSTER_OFFSET, computation_offset).data;
+        let args: instruction::RequestSendToken = decode(&data);
+        prop_assert_eq!(args.computation_offset, computation_offset);
+    }
+
+    #[test]
+    fn set_notification_prefs(flags in any::<u8>()) {
+        let args: instruction::SetNotificationPrefs = decode(&ix::set_notification_prefs(Pubkey::new_unique(), flags).data);
+        prop_assert_eq!(args.flags, flags);
+    }
+}
@@ -0,0 +1,171 @@
+//! `SIZE` constants (from `xray_common::sizes`) against the accounts' actual Borsh layout.
+
+use anchor_lang::prelude::*;
+use post_msg_program::*;
+use proptest::prelude::*;
+use xray_client::xray_common::layout::{POST_AUTHOR_OFFSET, POST_TARGET_OFFSET};
+use xray_client::xray_common::sizes::{AUTHOR_INDEX_RECENT, MAX_CONTENT_LEN, MAX_TARGET_LEN, TARGET_SPACE};
+use xray_testkit::layout::{assert_fixed_size, space};
+
+#[test]
+fn fixed_size_accounts_match_their_size() {
+    assert_fixed_size::<Config>(Config::SIZE);
+    assert_fixed_size::<Referral>(Referral::SIZE);
+    assert_fixed_size::<Charity>(Charity::SIZE);
+    assert_fixed_size::<AuthorStats>(AuthorStats::SIZE);
+    assert_fixed_size::<StakeAccount>(StakeAccount::SIZE);
+    assert_fixed_size::<RevenueSnapshot>(RevenueSnapshot::SIZE);
+    assert_fixed_size::<VestingVault>(VestingVault::SIZE);
+    assert_fixed_size::<PostEscrow>(PostEscrow::SIZE);
+    assert_fixed_size::<MintTreasury>(MintTreasury::SIZE);
+    assert_fixed_size::<UpvoteReceipt>(UpvoteReceipt::SIZE);
+    assert_fixed_size::<Auction>(Auction::SIZE);
+    assert_fixed_size::<SealedBid>(SealedBid::SIZE);
+    assert_fixed_size::<RefundVault>(RefundVault::SIZE);
+    assert_fixed_size::<TargetIdentity>(TargetIdentity::SIZE);
+    assert_fixed_size::<SealedContent>(SealedContent::SIZE);
+    assert_fixed_size::<PostMetrics>(PostMetrics::SIZE);
+}
+
+fn post(target: String, content: String) -> Post {
+    Post {
+        author: Pubkey::new_unique(),
+        target,
+        content,
+        bid: u64::MAX,
+        timestamp: i64::MAX,
+        bump: 255,
+        version: POST_VERSION,
+        total_boosts: u64::MAX,
+        rank_score: u64::MAX,
+        rank_updated_at: i64::MAX,
+        bid_mint: Pubkey::new_unique(),
+        upvotes: u64::MAX,
+        flags: u8::MAX,
+        views: u64::MAX,
+        charity: Pubkey::new_unique(),
+        charity_amount: u64::MAX,
+        license: u8::MAX,
+        content_signature: [u8::MAX; 64],
+    }
+}
+
+fn target_account(target: String) -> TargetAccount {
+    TargetAccount {
+        target,
+        owner: Pubkey::new_unique(),
+        pinned_post: Pubkey::new_unique(),
+        pinned_until: i64::MAX,
+        pin_fee: u64::MAX,
+        bump: 255,
+        identity_key: Pubkey::new_unique(),
+        verified_at: i64::MAX,
+    }
+}
+
+fn official_reply(content: String) -> OfficialReply {
+    OfficialReply {
+        post: Pubkey::new_unique(),
+        owner: Pubkey::new_unique(),
+        content,
+        timestamp: i64::MAX,
+        bump: 255,
+    }
+}
+
+fn sponsorship(target: String, content: String) -> Sponsorship {
+    Sponsorship {
+        sponsor: Pubkey::new_unique(),
+        target,
+        content,
+        bid_per_period: u64::MAX,
+        period_secs: i64::MAX,
+        periods_remaining: u32::MAX,
+        next_period_at: i64::MAX,
+        bump: 255,
+    }
+}
+
+fn scheduled_post(target: String, content: String) -> ScheduledPost {
+    ScheduledPost {
+        author: Pubkey::new_unique(),
+        target,
+        content,
+        bid: u64::MAX,
+        publish_at: i64::MAX,
+        bump: 255,
+    }
+}
+
+fn author_index(recent_posts: usize) -> AuthorIndex {
+    AuthorIndex {
+        author: Pubkey::new_unique(),
+        post_count: u64::MAX,
+        recent_posts: (0..recent_posts).map(|_| Pubkey::new_unique()).collect(),
+        bump: 255,
+    }
+}
+
+#[test]
+fn variable_size_accounts_at_their_limits_fill_their_size() {
+    let target = "t".repeat(TARGET_SPACE);
+    let content = "c".repeat(MAX_CONTENT_LEN);
+
+    assert_eq!(space(&post(target.clone(), content.clone())), Post::SIZE);
+    assert_eq!(space(&target_account(target.clone())), TargetAccount::SIZE);
+    assert_eq!(space(&official_reply(content.clone())), OfficialReply::SIZE);
+    assert_eq!(space(&sponsorship(target.clone(), content.clone())), Sponsorship::SIZE);
+    assert_eq!(space(&scheduled_post(target, content)), ScheduledPost::SIZE);
+    assert_eq!(space(&author_index(AUTHOR_INDEX_RECENT)), AuthorIndex::SIZE);
+}
+
+/// Strings accepted by the validation, up to `max_bytes` (multi-byte characters included)
+fn bounded_string(max_bytes: usize) -> impl Strategy<Value = String> {
+    any::<String>().prop_map(move |s| {
+        let mut end = s.len().min(max_bytes);
+        while !s.is_char_boundary(end) {
+            end -= 1;
+        }
+        s[..end].to_string()
+    })
+}
+
+proptest! {
+    #[test]
+    fn post_author_and_target_sit_at_their_stable_offsets(
+        target in bounded_string(MAX_TARGET_LEN),
+        content in bounded_string(MAX_CONTENT_LEN),
+    ) {
+        let post = post(target.clone(), content);
+        let mut data = Vec::new();
+        post.try_serialize(&mut data).unwrap();
+
+        prop_assert_eq!(&data[POST_AUTHOR_OFFSET..][..32], post.author.as_ref());
+        prop_assert_eq!(&data[POST_TARGET_OFFSET..][..4], &(target.len() as u32).to_le_bytes()[..]);
+        prop_assert_eq!(&data[POST_TARGET_OFFSET + 4..][..target.len()], target.as_bytes());
+    }
+
+    #[test]
+    fn accounts_with_valid_fields_fit_their_size(
+        target in bounded_string(MAX_TARGET_LEN),
+        content in bounded_string(MAX_CONTENT_LEN),
+        recent_posts in 0..=AUTHOR_INDEX_RECENT,
+    ) {
+        prop_assert!(space(&post(target.clone(), content.clone())) <= Post::SIZE);
+        prop_assert!(space(&target_account(target.clone())) <= TargetAccount::SIZE);
+        prop_assert!(space(&official_reply(content.clone())) <= OfficialReply::SIZE);
+        prop_assert!(space(&sponsorship(target.clone(), content.clone())) <= Sponsorship::SIZE);
+        prop_assert!(space(&scheduled_post(target, content)) <= ScheduledPost::SIZE);
+        prop_assert!(space(&author_index(recent_posts)) <= AuthorIndex::SIZE);
+    }
+
+    #[test]
+    fn validated_posts_fit_a_post_account(
+        target in proptest::string::string_regex("[a-z0-9.]{1,80}").unwrap(),
+        content in any::<String>(),
+    ) {
+        if let Ok(content) = validate_post_fields(&target, &content) {
+            prop_assert!(space(&post(target, content)) <= Post::SIZE);
+        }
+    }
+}
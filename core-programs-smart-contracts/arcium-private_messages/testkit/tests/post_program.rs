@@ -0,0 +1,285 @@
+//! post-msg-program money paths: sealed-bid auctions (through the mocked cluster), vesting,
+//! escrowed posts, sponsorships and scheduled posts.
+//! Needs `post_msg_program.so`: `anchor build`, then `cargo test -p xray-testkit --features sbf-tests`.
+
+use arcium_anchor::MXEEncryptedStruct;
+use post_msg_program::{
+    released_refund, Auction, AuctionStatus, AuthorIndex, InitSealedAuctionOutput, PlaceSealedBidOutput, Post,
+    PostError, VestingVault, BPS_DENOMINATOR, PENDING_TIMEOUT_SECS, SCHEDULE_CRANK_REWARD,
+};
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use xray_client::instructions::post::{self as ix, CreatePost};
+use xray_client::accounts::Config;
+use xray_client::xray_common::ids::WALLET_2;
+use xray_client::xray_common::pda;
+use xray_testkit::fixtures::{self, SOL_USD_PRICE_UPDATE};
+use xray_testkit::{Harness, Processed, CLUSTER_OFFSET};
+
+const AUCTION_ID: u64 = 1;
+const DEPOSIT: u64 = LAMPORTS_PER_SOL / 2;
+/// Above the fixture's $1 minimum at $150/SOL
+const BID: u64 = LAMPORTS_PER_SOL / 10;
+
+fn auction_state(nonce: u128) -> MXEEncryptedStruct<3> {
+    MXEEncryptedStruct { nonce, ciphertexts: [[1; 32], [2; 32], [3; 32]] }
+}
+
+/// Creates a post by `author` on `target` under `config` and returns its address
+async fn create_post(harness: &mut Harness, author: &Keypair, target: &str, config: &Config) -> Pubkey {
+    let epoch = harness.epoch().await;
+    let post = CreatePost::new(author.pubkey(), target, "featured", BID, SOL_USD_PRICE_UPDATE, epoch, config);
+    harness.process(&[post.instruction()], &[author]).await.unwrap();
+    post.post_address()
+}
+
+/// Opens auction [`AUCTION_ID`], ending in a day, and answers its initialization
+async fn open_auction(harness: &mut Harness, admin: &Keypair) {
+    let end_time = harness.unix_timestamp().await + 86_400;
+    let offset = Harness::computation_offset();
+    let create = ix::create_auction(admin.pubkey(), CLUSTER_OFFSET, offset, AUCTION_ID, DEPOSIT, 0, end_time, 3_600, 1);
+    harness.process(&[create], &[admin]).await.unwrap();
+    harness.callback(offset, &InitSealedAuctionOutput { field_0: auction_state(2) }).await.unwrap();
+}
+
+#[tokio::test]
+async fn aborted_bid_is_reset_and_refunded() {
+    let admin = Keypair::new();
+    let mut harness = Harness::start_post_with_admin(admin.pubkey()).await;
+    harness.fund(&admin.pubkey());
+    let auction = pda::auction_pda(AUCTION_ID).0;
+    open_auction(&mut harness, &admin).await;
+
+    let bidder = harness.funded_wallet();
+    let post = create_post(&mut harness, &bidder, "auction.sol", &fixtures::post_config(admin.pubkey())).await;
+    let before_bid = harness.balance(&bidder.pubkey()).await;
+
+    let offset = Harness::computation_offset();
+    let bid = ix::place_sealed_bid(bidder.pubkey(), AUCTION_ID, post, CLUSTER_OFFSET, offset, [7; 32], [8; 32], 9);
+    harness.process(&[bid], &[&bidder]).await.unwrap();
+    assert!(harness.abort::<PlaceSealedBidOutput>(offset).await.result.is_err());
+
+    // The auction stays busy until the timeout, then ends without a winner
+    let early = harness.process(&[ix::reset_auction_pending(bidder.pubkey(), AUCTION_ID)], &[&bidder]).await;
+    assert_eq!(early.error_code(), Some(PostError::PendingNotExpired.into()));
+
+    harness.advance_clock(PENDING_TIMEOUT_SECS).await;
+    harness.process(&[ix::reset_auction_pending(bidder.pubkey(), AUCTION_ID)], &[&bidder]).await.unwrap();
+    let reset: Auction = harness.account(&auction).await.expect("auction");
+    assert!(!reset.computation_pending);
+    assert_eq!(reset.status, AuctionStatus::NoWinner);
+
+    let late = harness.callback(offset, &PlaceSealedBidOutput { field_0: auction_state(3) }).await;
+    assert_eq!(late.error_code(), Some(PostError::StaleCallback.into()));
+
+    // Deposit and bid receipt rent come back in full
+    harness.process(&[ix::withdraw_sealed_bid(bidder.pubkey(), AUCTION_ID)], &[&bidder]).await.unwrap();
+    assert_eq!(harness.balance(&bidder.pubkey()).await, before_bid);
+    assert!(harness.account::<Auction>(&auction).await.is_some());
+}
+
+#[tokio::test]
+async fn vested_tranche_is_claimed_on_the_original_schedule() {
+    let admin = Keypair::new();
+    let mut harness = Harness::start_post_with_admin(admin.pubkey()).await;
+    harness.fund(&admin.pubkey());
+    let duration = 1_000;
+    harness.process(&[ix::initialize_vesting(admin.pubkey(), duration)], &[&admin]).await.unwrap();
+
+    // The post's 10% tranche goes to the vault instead of WALLET_2
+    let vault = pda::vesting_vault_pda().0;
+    let rent = harness.balance(&vault).await;
+    let config = Config { vesting_vault: vault, ..fixtures::post_config(admin.pubkey()) };
+    let author = harness.funded_wallet();
+    create_post(&mut harness, &author, "vesting.sol", &config).await;
+    let received = harness.balance(&vault).await - rent;
+    assert!(received > 0);
+
+    let start = harness.account::<VestingVault>(&vault).await.expect("vault").start;
+    let before = harness.balance(&WALLET_2).await;
+    harness.advance_clock(duration / 4).await;
+    let now = harness.unix_timestamp().await;
+    harness.process(&[ix::claim_vested()], &[]).await.unwrap();
+    let first = harness.balance(&WALLET_2).await - before;
+    assert_eq!(first, released_refund(received, start, duration, now));
+
+    // The second claim completes the schedule rather than restarting it
+    harness.advance_clock(duration).await;
+    harness.process(&[ix::claim_vested()], &[]).await.unwrap();
+    assert_eq!(harness.balance(&WALLET_2).await - before, received);
+    assert_eq!(harness.balance(&vault).await, rent);
+
+    let empty = harness.process(&[ix::claim_vested()], &[]).await;
+    assert_eq!(empty.error_code(), Some(PostError::NothingToClaim.into()));
+}
+
+const ESCROW_TARGET: &str = "escrow.sol";
+const ESCROW_WINDOW: i64 = 3_600;
+const KEEP_FEE_BPS: u16 = 500;
+
+/// Bank where `owner` has registered [`ESCROW_TARGET`], and an author with an escrowed post on it
+async fn escrowed_post(owner: Pubkey) -> (Harness, Keypair, Pubkey) {
+    let admin = Keypair::new();
+    let mut harness = Harness::start_post_with_admin(admin.pubkey()).await;
+    harness.fund(&admin.pubkey());
+    let setup = [
+        ix::set_escrow_params(admin.pubkey(), ESCROW_WINDOW, KEEP_FEE_BPS),
+        ix::register_target(admin.pubkey(), ESCROW_TARGET, owner),
+    ];
+    harness.process(&setup, &[&admin]).await.unwrap();
+
+    let author = harness.funded_wallet();
+    let create = ix::create_post_escrowed(author.pubkey(), ESCROW_TARGET, "escrowed", BID, SOL_USD_PRICE_UPDATE, false);
+    harness.process(&[create], &[&author]).await.unwrap();
+    let post = pda::post_pda(&author.pubkey(), ESCROW_TARGET).0;
+    (harness, author, post)
+}
+
+#[tokio::test]
+async fn unaccepted_escrow_is_reclaimed_minus_the_keep_fee() {
+    let (mut harness, author, post) = escrowed_post(Pubkey::new_unique()).await;
+    let escrow = pda::post_escrow_pda(&post).0;
+    let held = harness.balance(&escrow).await;
+
+    let treasury_version = fixtures::post_config(Pubkey::default()).treasury_version;
+    let reclaim = ix::reclaim_escrow(author.pubkey(), post, treasury_version);
+    let early = harness.process(&[reclaim.clone()], &[&author]).await;
+    assert_eq!(early.error_code(), Some(PostError::EscrowStillOpen.into()));
+
+    // Bid minus the fee, and the escrow rent, go back to the author
+    harness.advance_clock(ESCROW_WINDOW).await;
+    let before = harness.balance(&author.pubkey()).await;
+    harness.process(&[reclaim], &[&author]).await.unwrap();
+    let fee = BID * KEEP_FEE_BPS as u64 / BPS_DENOMINATOR;
+    assert_eq!(harness.balance(&author.pubkey()).await - before, held - fee);
+    assert_eq!(harness.balance(&escrow).await, 0);
+    assert!(harness.account::<Post>(&post).await.is_some());
+}
+
+/// `accept_post` by `owner` for the escrowed post of `author`
+async fn accept(harness: &mut Harness, owner: &Keypair, author: &Keypair) -> Processed {
+    harness.fund(&owner.pubkey());
+    let epoch = harness.epoch().await;
+    let config = fixtures::post_config(Pubkey::default());
+    let accept = ix::accept_post(owner.pubkey(), author.pubkey(), ESCROW_TARGET, epoch, &config);
+    harness.process(&[accept], &[owner]).await
+}
+
+#[tokio::test]
+async fn escrow_accepted_before_its_deadline_is_released() {
+    let owner = Keypair::new();
+    let (mut harness, author, post) = escrowed_post(owner.pubkey()).await;
+
+    harness.advance_clock(ESCROW_WINDOW / 2).await;
+    accept(&mut harness, &owner, &author).await.unwrap();
+    assert_eq!(harness.balance(&pda::post_escrow_pda(&post).0).await, 0);
+}
+
+#[tokio::test]
+async fn lapsed_escrow_can_no_longer_be_accepted() {
+    let owner = Keypair::new();
+    let (mut harness, author, post) = escrowed_post(owner.pubkey()).await;
+
+    harness.advance_clock(ESCROW_WINDOW).await;
+    let lapsed = accept(&mut harness, &owner, &author).await;
+    assert_eq!(lapsed.error_code(), Some(PostError::EscrowExpired.into()));
+    assert!(harness.balance(&pda::post_escrow_pda(&post).0).await > BID);
+}
+
+#[tokio::test]
+async fn cancelled_sponsorship_refunds_every_period() {
+    const TARGET: &str = "sponsored.sol";
+
+    let mut harness = Harness::start_post().await;
+    let sponsor = harness.funded_wallet();
+    let before = harness.balance(&sponsor.pubkey()).await;
+
+    let create = ix::create_sponsorship(sponsor.pubkey(), TARGET, "sponsored", BID, 3_600, 3, SOL_USD_PRICE_UPDATE);
+    harness.process(&[create], &[&sponsor]).await.unwrap();
+    let sponsorship = pda::sponsorship_pda(&sponsor.pubkey(), TARGET).0;
+    assert_eq!(before - harness.balance(&sponsor.pubkey()).await, harness.balance(&sponsorship).await);
+    assert!(harness.balance(&sponsorship).await > 3 * BID);
+
+    harness.process(&[ix::cancel_sponsorship(sponsor.pubkey(), TARGET)], &[&sponsor]).await.unwrap();
+    assert_eq!(harness.balance(&sponsor.pubkey()).await, before);
+    assert_eq!(harness.balance(&sponsorship).await, 0);
+}
+
+#[tokio::test]
+async fn cancelled_sponsorship_hands_its_post_to_the_sponsor() {
+    const TARGET: &str = "handed.sol";
+
+    let mut harness = Harness::start_post().await;
+    let sponsor = harness.funded_wallet();
+    let create = ix::create_sponsorship(sponsor.pubkey(), TARGET, "sponsored", BID, 3_600, 3, SOL_USD_PRICE_UPDATE);
+    harness.process(&[create], &[&sponsor]).await.unwrap();
+
+    let cranker = harness.funded_wallet();
+    let epoch = harness.epoch().await;
+    let config = fixtures::post_config(Pubkey::default());
+    let period = ix::process_sponsorship_period(cranker.pubkey(), sponsor.pubkey(), TARGET, epoch, &config, false);
+    harness.process(&[period], &[&cranker]).await.unwrap();
+    let post = pda::post_pda(&pda::sponsorship_pda(&sponsor.pubkey(), TARGET).0, TARGET).0;
+
+    // The post no longer points at the closed sponsorship, and its new author can close it
+    harness.process(&[ix::cancel_sponsorship(sponsor.pubkey(), TARGET)], &[&sponsor]).await.unwrap();
+    let handed: Post = harness.account(&post).await.expect("post");
+    assert_eq!(handed.author, sponsor.pubkey());
+
+    harness.process(&[ix::close_post(sponsor.pubkey(), post, false)], &[&sponsor]).await.unwrap();
+    assert_eq!(harness.balance(&post).await, 0);
+}
+
+#[tokio::test]
+async fn scheduled_post_is_published_by_a_rewarded_cranker() {
+    const TARGET: &str = "scheduled.sol";
+
+    let mut harness = Harness::start_post().await;
+    let config = fixtures::post_config(Pubkey::default());
+    // Someone else posts first, so the epoch's revenue snapshot already exists
+    let other = harness.funded_wallet();
+    create_post(&mut harness, &other, "other.sol", &config).await;
+
+    let author = harness.funded_wallet();
+    let publish_at = harness.unix_timestamp().await + 600;
+    let schedule = ix::schedule_post(author.pubkey(), TARGET, "later", BID, publish_at, SOL_USD_PRICE_UPDATE, false);
+    harness.process(&[schedule], &[&author]).await.unwrap();
+
+    let cranker = harness.funded_wallet();
+    let epoch = harness.epoch().await;
+    let publish = ix::publish_scheduled_post(cranker.pubkey(), author.pubkey(), TARGET, epoch, &config);
+    let early = harness.process(&[publish.clone()], &[&cranker]).await;
+    assert_eq!(early.error_code(), Some(PostError::NotYetPublishable.into()));
+
+    // The cranker gets the post and index rent back plus the reward
+    harness.advance_clock(600).await;
+    let before = harness.balance(&cranker.pubkey()).await;
+    harness.process(&[publish], &[&cranker]).await.unwrap();
+    assert_eq!(harness.balance(&cranker.pubkey()).await - before, SCHEDULE_CRANK_REWARD);
+
+    let post = pda::post_pda(&author.pubkey(), TARGET).0;
+    let published: Post = harness.account(&post).await.expect("post");
+    assert_eq!((published.author, published.bid), (author.pubkey(), BID));
+    let index: AuthorIndex = harness.account(&pda::author_index_pda(&author.pubkey()).0).await.expect("author index");
+    assert_eq!(index.recent_posts, vec![post]);
+    assert_eq!(harness.balance(&pda::scheduled_post_pda(&author.pubkey(), TARGET).0).await, 0);
+}
+
+#[tokio::test]
+async fn cancelled_scheduled_post_refunds_the_escrow() {
+    const TARGET: &str = "cancelled.sol";
+
+    let mut harness = Harness::start_post().await;
+    let author = harness.funded_wallet();
+    let before = harness.balance(&author.pubkey()).await;
+    let publish_at = harness.unix_timestamp().await + 600;
+    let schedule = ix::schedule_post(author.pubkey(), TARGET, "never", BID, publish_at, SOL_USD_PRICE_UPDATE, false);
+    harness.process(&[schedule], &[&author]).await.unwrap();
+
+    harness.process(&[ix::cancel_scheduled_post(author.pubkey(), TARGET)], &[&author]).await.unwrap();
+    // Only the author stats account, created on scheduling, stays paid for
+    let stats_rent = harness.balance(&pda::author_stats_pda(&author.pubkey()).0).await;
+    assert_eq!(before - harness.balance(&author.pubkey()).await, stats_rent);
+    assert_eq!(harness.balance(&pda::scheduled_post_pda(&author.pubkey(), TARGET).0).await, 0);
+}
@@ -0,0 +1,227 @@
+//! send_private_message → verify_private_message_access → callback, against the mocked cluster.
+//! Needs `private_messages.so`: `anchor build`, then `cargo test -p xray-testkit --features sbf-tests`.
+
+use arcium_anchor::prelude::CallbackError;
+use arcium_anchor::{MXEEncryptedStruct, SharedEncryptedStruct};
+use private_messages::{
+    ConsumeRateTokenOutput, ConsumeRateTokenOutputStruct0, ErrorCode, InitRateBucketOutput, PrivateAccessVerified, PrivateMessageSent, RateBucket,
+    VerifyAndRevealSenderOutput, PENDING_TIMEOUT_SECS,
+};
+use solana_alt_bn128_bls::PrivKey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use xray_client::crypto::identity_hash;
+use xray_client::instructions::private_messages as ix;
+use xray_client::mpc::MpcCipher;
+use xray_client::xray_common::pda;
+use xray_testkit::fixtures::MXE_X25519_PUBKEY;
+use xray_testkit::{Harness, CLUSTER_OFFSET};
+
+fn mxe_state(nonce: u128) -> MXEEncryptedStruct<2> {
+    MXEEncryptedStruct { nonce, ciphertexts: [[1; 32], [2; 32]] }
+}
+
+fn private_message(sender: &Pubkey, recipient: &Pubkey, message_index: u64) -> ix::PrivateMessage {
+    let cipher = MpcCipher::new(&MXE_X25519_PUBKEY);
+    let mpc_nonce = 7;
+    let hashes = cipher.encrypt_identity_hashes(&[&identity_hash(recipient), &identity_hash(sender)], mpc_nonce);
+    ix::PrivateMessage {
+        message_index,
+        encrypted_sender_hash: hashes[1],
+        encrypted_recipient_hash: hashes[0],
+        encrypted_content: b"sealed".to_vec(),
+        nonce: [4; 24],
+        mpc_pubkey: cipher.public_key,
+        mpc_nonce,
+    }
+}
+
+/// Sends a private message from a new sender to `recipient` and returns its address
+async fn send(harness: &mut Harness, recipient: &Pubkey) -> (Pubkey, ix::PrivateMessage) {
+    let sender = harness.funded_wallet();
+    harness.authorize_send(&sender).await;
+
+    let message = private_message(&sender.pubkey(), recipient, 0);
+    let sent = harness
+        .process(&[ix::send_private_message(sender.pubkey(), &message)], &[&sender])
+        .await
+        .unwrap();
+    assert_eq!(sent.events::<PrivateMessageSent>().len(), 1);
+
+    (pda::private_message_pda(&sender.pubkey(), 0).0, message)
+}
+
+/// Queues the access check for `requester` and returns its computation offset
+async fn verify_access(harness: &mut Harness, requester: &Keypair, message: Pubkey) -> u64 {
+    let cipher = MpcCipher::new(&MXE_X25519_PUBKEY);
+    let requester_hash = cipher.encrypt_identity_hash(&identity_hash(&requester.pubkey()), 9);
+    let offset = Harness::computation_offset();
+    let verify = ix::verify_private_message_access(
+        requester.pubkey(),
+        message,
+        CLUSTER_OFFSET,
+        offset,
+        requester_hash,
+        cipher.public_key,
+        9,
+    );
+    harness.process(&[verify], &[requester]).await.unwrap();
+    offset
+}
+
+fn access_result(encrypted_result: [u8; 32]) -> VerifyAndRevealSenderOutput {
+    VerifyAndRevealSenderOutput {
+        field_0: SharedEncryptedStruct { encryption_key: [5; 32], nonce: 11, ciphertexts: [encrypted_result] },
+    }
+}
+
+#[tokio::test]
+async fn access_check_round_trip() {
+    let mut harness = Harness::start().await;
+    let recipient = harness.funded_wallet();
+    let (message, sent) = send(&mut harness, &recipient.pubkey()).await;
+
+    let offset = verify_access(&mut harness, &recipient, message).await;
+    let queued = harness.queued(offset);
+    assert!(queued.args.byte_arrays.contains(&sent.encrypted_recipient_hash));
+
+    let verified = harness.callback(offset, &access_result([42; 32])).await.unwrap();
+    let events = verified.events::<PrivateAccessVerified>();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].encrypted_result, [42; 32]);
+    assert_eq!(events[0].nonce, 11u128.to_le_bytes());
+}
+
+#[tokio::test]
+async fn callback_rejects_output_not_signed_by_the_cluster() {
+    let mut harness = Harness::start().await;
+    let recipient = harness.funded_wallet();
+    let (message, _) = send(&mut harness, &recipient.pubkey()).await;
+    let offset = verify_access(&mut harness, &recipient, message).await;
+
+    let forger = PrivKey([3; 32]);
+    let forged = harness.callback_signed_by(offset, &access_result([42; 32]), &forger).await;
+    assert_eq!(forged.error_code(), Some(ErrorCode::AbortedComputation.into()));
+    assert!(forged.events::<PrivateAccessVerified>().is_empty());
+}
+
+#[tokio::test]
+async fn callback_rejects_aborted_computation() {
+    let mut harness = Harness::start().await;
+    let recipient = harness.funded_wallet();
+    let (message, _) = send(&mut harness, &recipient.pubkey()).await;
+    let offset = verify_access(&mut harness, &recipient, message).await;
+
+    let aborted = harness.abort::<VerifyAndRevealSenderOutput>(offset).await;
+    assert_eq!(aborted.error_code(), Some(ErrorCode::AbortedComputation.into()));
+}
+
+#[tokio::test]
+async fn callback_must_follow_the_arcium_instruction() {
+    let mut harness = Harness::start().await;
+    let recipient = harness.funded_wallet();
+    let (message, _) = send(&mut harness, &recipient.pubkey()).await;
+    let offset = verify_access(&mut harness, &recipient, message).await;
+
+    let outputs = arcium_anchor::SignedComputationOutputs::<VerifyAndRevealSenderOutput>::Failure;
+    let callback = harness.callback_instruction(offset, &outputs);
+    let direct = harness.process(&[callback], &[]).await;
+    assert_eq!(direct.error_code(), Some(CallbackError::InvalidCallbackTx.into()));
+}
+
+#[tokio::test]
+async fn send_needs_a_token_from_the_mpc() {
+    let mut harness = Harness::start().await;
+    let sender = harness.funded_wallet();
+    let recipient = Pubkey::new_unique();
+
+    let offset = Harness::computation_offset();
+    harness
+        .process(&[ix::create_rate_bucket(sender.pubkey(), CLUSTER_OFFSET, offset, 1)], &[&sender])
+        .await
+        .unwrap();
+    harness.callback(offset, &InitRateBucketOutput { field_0: mxe_state(2) }).await.unwrap();
+
+    let message = private_message(&sender.pubkey(), &recipient, 0);
+    let sent = harness.process(&[ix::send_private_message(sender.pubkey(), &message)], &[&sender]).await;
+    assert_eq!(sent.error_code(), Some(ErrorCode::RateLimited.into()));
+}
+
+#[tokio::test]
+async fn aborted_send_token_request_can_be_reset() {
+    let mut harness = Harness::start().await;
+    let sender = harness.funded_wallet();
+    let bucket = pda::rate_bucket_pda(&sender.pubkey()).0;
+
+    let offset = Harness::computation_offset();
+    harness
+        .process(&[ix::create_rate_bucket(sender.pubkey(), CLUSTER_OFFSET, offset, 1)], &[&sender])
+        .await
+        .unwrap();
+    harness.callback(offset, &InitRateBucketOutput { field_0: mxe_state(2) }).await.unwrap();
+
+    let offset = Harness::computation_offset();
+    harness
+        .process(&[ix::request_send_token(sender.pubkey(), CLUSTER_OFFSET, offset)], &[&sender])
+        .await
+        .unwrap();
+    let aborted = harness.abort::<ConsumeRateTokenOutput>(offset).await;
+    assert_eq!(aborted.error_code(), Some(ErrorCode::AbortedComputation.into()));
+
+    // The aborted callback left the bucket locked
+    let retry = ix::request_send_token(sender.pubkey(), CLUSTER_OFFSET, Harness::computation_offset());
+    let locked = harness.process(&[retry], &[&sender]).await;
+    assert_eq!(locked.error_code(), Some(ErrorCode::ComputationPending.into()));
+
+    let early = harness.process(&[ix::reset_rate_bucket_pending(sender.pubkey())], &[&sender]).await;
+    assert_eq!(early.error_code(), Some(ErrorCode::PendingNotExpired.into()));
+
+    harness.advance_clock(PENDING_TIMEOUT_SECS).await;
+    harness.process(&[ix::reset_rate_bucket_pending(sender.pubkey())], &[&sender]).await.unwrap();
+    let reset: RateBucket = harness.account(&bucket).await.expect("initialized bucket is kept");
+    assert!(!reset.computation_pending);
+
+    let retry = ix::request_send_token(sender.pubkey(), CLUSTER_OFFSET, Harness::computation_offset());
+    harness.process(&[retry], &[&sender]).await.unwrap();
+}
+
+#[tokio::test]
+async fn late_callback_after_reset_is_rejected() {
+    let mut harness = Harness::start().await;
+    let sender = harness.funded_wallet();
+    let bucket = pda::rate_bucket_pda(&sender.pubkey()).0;
+
+    let offset = Harness::computation_offset();
+    harness
+        .process(&[ix::create_rate_bucket(sender.pubkey(), CLUSTER_OFFSET, offset, 1)], &[&sender])
+        .await
+        .unwrap();
+    harness.callback(offset, &InitRateBucketOutput { field_0: mxe_state(2) }).await.unwrap();
+
+    let stale = Harness::computation_offset();
+    harness
+        .process(&[ix::request_send_token(sender.pubkey(), CLUSTER_OFFSET, stale)], &[&sender])
+        .await
+        .unwrap();
+    harness.advance_clock(PENDING_TIMEOUT_SECS).await;
+    harness.process(&[ix::reset_rate_bucket_pending(sender.pubkey())], &[&sender]).await.unwrap();
+
+    let granted = |nonce| ConsumeRateTokenOutput {
+        field_0: ConsumeRateTokenOutputStruct0 { field_0: mxe_state(nonce), field_1: true },
+    };
+    let reset = harness.callback(stale, &granted(3)).await;
+    assert_eq!(reset.error_code(), Some(ErrorCode::StaleCallback.into()));
+
+    let current = Harness::computation_offset();
+    harness
+        .process(&[ix::request_send_token(sender.pubkey(), CLUSTER_OFFSET, current)], &[&sender])
+        .await
+        .unwrap();
+    let superseded = harness.callback(stale, &granted(3)).await;
+    assert_eq!(superseded.error_code(), Some(ErrorCode::StaleCallback.into()));
+
+    harness.callback(current, &granted(4)).await.unwrap();
+    let authorized: RateBucket = harness.account(&bucket).await.expect("bucket");
+    assert!(authorized.send_authorized);
+    assert_eq!(authorized.state_nonce, 4);
+}
@@ -0,0 +1,90 @@
+//! `SIZE` constants (from `xray_common::sizes`) against the accounts' actual Borsh layout.
+
+use anchor_lang::prelude::*;
+use private_messages::*;
+use proptest::prelude::*;
+use xray_client::xray_common::layout::*;
+use xray_client::xray_common::sizes::MAX_MESSAGE_SIZE;
+use xray_testkit::layout::{assert_fixed_size, space};
+
+#[test]
+fn fixed_size_accounts_match_their_size() {
+    assert_fixed_size::<UserAccount>(UserAccount::SIZE);
+    assert_fixed_size::<PrivateMessageCounter>(PrivateMessageCounter::SIZE);
+    assert_fixed_size::<NotificationPrefs>(NotificationPrefs::SIZE);
+    assert_fixed_size::<MembershipSet>(MembershipSet::SIZE);
+    assert_fixed_size::<RateBucket>(RateBucket::SIZE);
+    assert_fixed_size::<Poll>(Poll::SIZE);
+    assert_fixed_size::<PollBallot>(PollBallot::SIZE);
+    assert_fixed_size::<MessagesMetrics>(MessagesMetrics::SIZE);
+}
+
+fn message(encrypted_content: Vec<u8>, nonce: [u8; 24]) -> MessageAccount {
+    MessageAccount {
+        sender: Pubkey::new_unique(),
+        recipient: Pubkey::new_unique(),
+        encrypted_content,
+        nonce,
+        timestamp: i64::MAX,
+        is_read: true,
+        bump: 255,
+    }
+}
+
+fn private_message(encrypted_content: Vec<u8>, nonce: [u8; 24], mpc_nonce: u128) -> PrivateMessageAccount {
+    PrivateMessageAccount {
+        encrypted_sender_hash: [1; 32],
+        encrypted_recipient_hash: [2; 32],
+        encrypted_content,
+        nonce,
+        timestamp: i64::MAX,
+        mpc_pubkey: [3; 32],
+        mpc_nonce,
+        bump: 255,
+    }
+}
+
+#[test]
+fn messages_at_the_content_limit_fill_their_size() {
+    let content = vec![0xff; MAX_MESSAGE_SIZE];
+
+    assert_eq!(space(&message(content.clone(), [0xff; 24])), MessageAccount::SIZE);
+    assert_eq!(space(&private_message(content, [0xff; 24], u128::MAX)), PrivateMessageAccount::SIZE);
+}
+
+proptest! {
+    #[test]
+    fn message_fields_sit_at_their_stable_offsets(
+        content in prop::collection::vec(any::<u8>(), 0..=MAX_MESSAGE_SIZE),
+        nonce in any::<[u8; 24]>(),
+    ) {
+        let account = message(content.clone(), nonce);
+        let mut data = Vec::new();
+        account.try_serialize(&mut data).unwrap();
+
+        prop_assert_eq!(&data[MESSAGE_SENDER_OFFSET..][..32], account.sender.as_ref());
+        prop_assert_eq!(&data[MESSAGE_RECIPIENT_OFFSET..][..32], account.recipient.as_ref());
+        prop_assert_eq!(&data[MESSAGE_CONTENT_OFFSET..][..4], &(content.len() as u32).to_le_bytes()[..]);
+        prop_assert_eq!(&data[MESSAGE_CONTENT_OFFSET + 4..][..content.len()], &content[..]);
+    }
+
+    #[test]
+    fn messages_within_the_limit_fit_their_size(
+        content in prop::collection::vec(any::<u8>(), 0..=MAX_MESSAGE_SIZE),
+        nonce in any::<[u8; 24]>(),
+        mpc_nonce in any::<u128>(),
+    ) {
+        prop_assert!(space(&message(content.clone(), nonce)) <= MessageAccount::SIZE);
+        prop_assert!(space(&private_message(content, nonce, mpc_nonce)) <= PrivateMessageAccount::SIZE);
+    }
+
+    #[test]
+    fn stored_messages_round_trip(content in prop::collection::vec(any::<u8>(), 0..=MAX_MESSAGE_SIZE), nonce in any::<[u8; 24]>()) {
+        let mut data = Vec::new();
+        message(content.clone(), nonce).try_serialize(&mut data).unwrap();
+
+        let stored = MessageAccount::try_deserialize(&mut data.as_slice()).unwrap();
+        prop_assert_eq!(stored.encrypted_content, content);
+        prop_assert_eq!(stored.nonce, nonce);
+    }
+}
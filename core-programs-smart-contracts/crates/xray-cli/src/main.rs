@@ -0,0 +1,456 @@
+//! `xray`: exercise the X-RAY programs from the command line.
+//!
+//! Messaging keys are derived locally from the wallet keypair (same derivation as the web
+//! client, so both read the same inbox); contents are encrypted and decrypted locally and
+//! never leave the machine in clear.
+//!
+//! The wallet comes from a Solana keypair file or from an encrypted `xray-keys` keystore
+//! (`--keystore`, passphrase from `XRAY_KEYSTORE_PASSPHRASE` or prompted); `xray keys`
+//! creates, imports, exports and rotates keystores.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand};
+use solana_sdk::hash::hashv;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{keypair_from_seed, read_keypair_file, Keypair, Signer};
+use xray_client::accounts::{Config, Post, UserAccount};
+use xray_client::flows::{XrayClient, DEFAULT_CLUSTER_OFFSET};
+use xray_client::instructions::{post, private_messages};
+use xray_client::xray_common::license;
+use xray_client::xray_common::sizes::{NOTIFY_ACCESS_VERIFIED, NOTIFY_MESSAGES, NOTIFY_POSTS};
+use xray_client::{crypto, filters, pda};
+use xray_keys::{Kdf, Keystore};
+use zeroize::Zeroizing;
+
+const PASSPHRASE_ENV: &str = "XRAY_KEYSTORE_PASSPHRASE";
+const NEW_PASSPHRASE_ENV: &str = "XRAY_KEYSTORE_NEW_PASSPHRASE";
+
+#[derive(Parser)]
+#[command(name = "xray", version, about = "X-RAY post and private message client")]
+struct Cli {
+    /// RPC endpoint
+    #[arg(long, short = 'u', env = "XRAY_RPC_URL", default_value = "https://api.devnet.solana.com")]
+    url: String,
+
+    /// Wallet keypair file (defaults to the Solana CLI keypair)
+    #[arg(long, short = 'k', env = "XRAY_KEYPAIR")]
+    keypair: Option<PathBuf>,
+
+    /// Encrypted keystore holding the wallet, instead of a keypair file
+    #[arg(long, env = "XRAY_KEYSTORE", conflicts_with = "keypair")]
+    keystore: Option<PathBuf>,
+
+    /// Arcium cluster for the MPC commands
+    #[arg(long, env = "XRAY_CLUSTER_OFFSET", default_value_t = DEFAULT_CLUSTER_OFFSET)]
+    cluster_offset: u32,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Register the wallet's messaging key (or update it if it changed)
+    Register,
+    /// Send an encrypted message
+    Send { recipient: Pubkey, message: String },
+    /// List received messages, decrypted
+    Inbox {
+        /// Only unread messages
+        #[arg(long)]
+        unread: bool,
+    },
+    /// Decrypt a message sent or received by the wallet
+    Read {
+        message: Pubkey,
+        /// Also mark it as read on-chain (recipient only)
+        #[arg(long)]
+        mark_read: bool,
+    },
+    /// Send a private message (sender and recipient hidden behind MPC-encrypted hashes)
+    SendPrivate { recipient: Pubkey, message: String },
+    /// Ask the MPC whether the wallet is the recipient of a private message
+    VerifyAccess { private_message: Pubkey },
+    /// Choose the events the notification service pushes for this wallet (none if no flag)
+    Notifications {
+        /// New messages received
+        #[arg(long)]
+        messages: bool,
+        /// Results of the wallet's private message access checks
+        #[arg(long)]
+        access: bool,
+        /// Posts on targets the wallet owns
+        #[arg(long)]
+        posts: bool,
+    },
+    /// Create a post
+    Post {
+        target: String,
+        content: String,
+        /// Bid in lamports
+        #[arg(long)]
+        bid: u64,
+        /// Pyth SOL/USD price update account
+        #[arg(long, env = "XRAY_PRICE_UPDATE")]
+        price_update: Pubkey,
+        /// Also DM the post address to the target's registered owner, if they have a messaging account
+        #[arg(long)]
+        notify_target: bool,
+    },
+    /// Sign one of your posts' content and record its license (set once)
+    Provenance {
+        post: Pubkey,
+        /// none, all-rights-reserved, cc0, cc-by, cc-by-sa, cc-by-nc or cc-by-nd
+        #[arg(long, default_value = "cc-by", value_parser = parse_license)]
+        license: u8,
+    },
+    /// Claim a handle target ("@name") with the identity key the admin attested for it
+    ProveTarget {
+        target: String,
+        /// Keypair file of the attested identity key
+        #[arg(long)]
+        identity: PathBuf,
+    },
+    /// Show the usage counters of both programs
+    Metrics {
+        /// Create the counters of a program that has none yet
+        #[arg(long)]
+        init: bool,
+    },
+    /// Seed a `devnet-tools` build with demo users, messages and posts (the same wallets
+    /// for the same --seed; users and posts that already exist are kept)
+    DevnetSeed {
+        /// Demo wallets are derived from this string
+        #[arg(long, default_value = "xray-demo")]
+        seed: String,
+        /// Number of demo users, each messaging the next one
+        #[arg(long, default_value_t = 4, value_parser = clap::value_parser!(u8).range(2..=8))]
+        users: u8,
+        /// Lamports moved into the treasury
+        #[arg(long, default_value_t = 0)]
+        treasury_lamports: u64,
+        /// Skip the posts and the treasury (they need the post program admin's wallet)
+        #[arg(long)]
+        no_posts: bool,
+    },
+    /// Manage encrypted keystores
+    Keys {
+        #[command(subcommand)]
+        command: KeysCommand,
+    },
+}
+
+#[derive(Subcommand)]
+enum KeysCommand {
+    /// Create a keystore for a new wallet
+    Create {
+        path: PathBuf,
+        /// Argon2id instead of scrypt
+        #[arg(long)]
+        argon2: bool,
+    },
+    /// Encrypt an existing Solana keypair file into a keystore
+    Import {
+        keypair: PathBuf,
+        path: PathBuf,
+        /// Argon2id instead of scrypt
+        #[arg(long)]
+        argon2: bool,
+    },
+    /// Write a keystore's wallet to a plaintext Solana keypair file
+    Export { path: PathBuf, keypair: PathBuf },
+    /// Re-encrypt a keystore under a new passphrase
+    Rotate {
+        path: PathBuf,
+        /// Switch to Argon2id
+        #[arg(long)]
+        argon2: bool,
+    },
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    if let Command::Keys { command } = cli.command {
+        return keys(command);
+    }
+    let wallet = match cli.keystore {
+        Some(path) => unlock_keystore(&path)?,
+        None => load_keypair(cli.keypair)?,
+    };
+    let client = XrayClient::new(cli.url).with_cluster_offset(cli.cluster_offset);
+
+    match cli.command {
+        Command::Register => register(&client, &wallet),
+        Command::Send { recipient, message } => {
+            let (signature, address) = client.send_message(&wallet, &recipient, &message)?;
+            println!("message {address}\nsignature {signature}");
+            Ok(())
+        }
+        Command::Inbox { unread } => inbox(&client, &wallet, unread),
+        Command::Read { message, mark_read } => {
+            println!("{}", client.read_message(&wallet, &message)?);
+            if mark_read {
+                let ix = private_messages::mark_as_read(wallet.pubkey(), message);
+                println!("marked as read: {}", client.send(&[ix], &wallet, &[])?);
+            }
+            Ok(())
+        }
+        Command::SendPrivate { recipient, message } => {
+            let (signature, address) = client.send_private_message(&wallet, &recipient, &message)?;
+            println!("private message {address}\nsignature {signature}");
+            Ok(())
+        }
+        Command::VerifyAccess { private_message } => {
+            if client.verify_private_message_access(&wallet, &private_message)? {
+                println!("access granted: {} is the recipient", wallet.pubkey());
+            } else {
+                println!("access denied");
+            }
+            Ok(())
+        }
+        Command::Notifications { messages, access, posts } => {
+            let flags = [(messages, NOTIFY_MESSAGES), (access, NOTIFY_ACCESS_VERIFIED), (posts, NOTIFY_POSTS)]
+                .into_iter()
+                .filter(|(enabled, _)| *enabled)
+                .fold(0, |flags, (_, flag)| flags | flag);
+            let ix = private_messages::set_notification_prefs(wallet.pubkey(), flags);
+            println!("notification preferences set: {}", client.send(&[ix], &wallet, &[])?);
+            Ok(())
+        }
+        Command::Post { target, content, bid, price_update, notify_target: false } => {
+            let (signature, address) = client.create_post(&wallet, &target, &content, bid, price_update)?;
+            println!("post {address}\nsignature {signature}");
+            Ok(())
+        }
+        Command::Post { target, content, bid, price_update, notify_target: true } => {
+            let (signature, address, message) =
+                client.create_post_notifying_target(&wallet, &target, &content, bid, price_update)?;
+            println!("post {address}\nsignature {signature}");
+            match message {
+                Some(message) => println!("target owner notified: {message}"),
+                None => println!("target has no registered owner with a messaging account, not notified"),
+            }
+            Ok(())
+        }
+        Command::Provenance { post, license } => {
+            println!("provenance recorded: {}", client.set_post_provenance(&wallet, &post, license)?);
+            Ok(())
+        }
+        Command::ProveTarget { target, identity } => {
+            let identity = load_keypair(Some(identity))?;
+            let signature = client.prove_target_ownership(&wallet, &identity, &target)?;
+            println!("{target} claimed by {}: {signature}", wallet.pubkey());
+            Ok(())
+        }
+        Command::Metrics { init } => metrics(&client, &wallet, init),
+        Command::DevnetSeed { seed, users, treasury_lamports, no_posts } => {
+            devnet_seed(&client, &wallet, &seed, users, (!no_posts).then_some(treasury_lamports))
+        }
+        Command::Keys { .. } => unreachable!("handled before loading the wallet"),
+    }
+}
+
+fn load_keypair(path: Option<PathBuf>) -> Result<Keypair> {
+    let path = match path {
+        Some(path) => path,
+        None => {
+            let home = std::env::var_os("HOME").context("HOME is not set, pass --keypair")?;
+            PathBuf::from(home).join(".config/solana/id.json")
+        }
+    };
+    read_keypair_file(&path).map_err(|err| anyhow::anyhow!("cannot read keypair {}: {err}", path.display()))
+}
+
+fn unlock_keystore(path: &Path) -> Result<Keypair> {
+    let keystore = Keystore::load(path).with_context(|| format!("cannot read keystore {}", path.display()))?;
+    let passphrase = xray_keys::read_passphrase(PASSPHRASE_ENV, &format!("passphrase for {}: ", keystore.wallet))?;
+    Ok(keystore.unlock(&passphrase)?.wallet)
+}
+
+/// New passphrase from `XRAY_KEYSTORE_NEW_PASSPHRASE`, else prompted twice
+fn new_passphrase() -> Result<Zeroizing<String>> {
+    let passphrase = xray_keys::read_passphrase(NEW_PASSPHRASE_ENV, "new passphrase: ")?;
+    if std::env::var_os(NEW_PASSPHRASE_ENV).is_none() {
+        let confirmation = xray_keys::read_passphrase(NEW_PASSPHRASE_ENV, "confirm passphrase: ")?;
+        anyhow::ensure!(passphrase == confirmation, "passphrases do not match");
+    }
+    Ok(passphrase)
+}
+
+fn kdf(argon2: bool) -> Kdf {
+    if argon2 {
+        Kdf::argon2id()
+    } else {
+        Kdf::scrypt()
+    }
+}
+
+fn keys(command: KeysCommand) -> Result<()> {
+    match command {
+        KeysCommand::Create { path, argon2 } => {
+            let (keystore, _) = Keystore::create(&new_passphrase()?, kdf(argon2))?;
+            keystore.save(&path)?;
+            println!("wallet {}\nx25519 public key {}", keystore.wallet, hex(&keystore.x25519_public));
+        }
+        KeysCommand::Import { keypair, path, argon2 } => {
+            let keystore = Keystore::import_keypair_file(&keypair, &new_passphrase()?, kdf(argon2))?;
+            keystore.save(&path)?;
+            println!("wallet {}\nx25519 public key {}", keystore.wallet, hex(&keystore.x25519_public));
+        }
+        KeysCommand::Export { path, keypair } => {
+            let keystore = Keystore::load(&path)?;
+            let passphrase = xray_keys::read_passphrase(PASSPHRASE_ENV, "passphrase: ")?;
+            keystore.export_keypair_file(&passphrase, &keypair)?;
+            println!("wallet {} written to {} (unencrypted)", keystore.wallet, keypair.display());
+        }
+        KeysCommand::Rotate { path, argon2 } => {
+            let keystore = Keystore::load(&path)?;
+            let passphrase = xray_keys::read_passphrase(PASSPHRASE_ENV, "current passphrase: ")?;
+            let rotated = keystore.rotate(&passphrase, &new_passphrase()?, argon2.then(Kdf::argon2id))?;
+            rotated.save(&path)?;
+            println!("keystore {} re-encrypted", path.display());
+        }
+    }
+    Ok(())
+}
+
+fn register(client: &XrayClient, wallet: &Keypair) -> Result<()> {
+    let keys = crypto::derive_x25519_keypair(&wallet.to_bytes());
+    let existing: Option<UserAccount> = client.fetch_optional(&pda::user_pda(&wallet.pubkey()).0)?;
+
+    match existing {
+        Some(user) if user.x25519_pubkey == keys.public => println!("already registered"),
+        Some(_) => {
+            let ix = private_messages::update_user_key(wallet.pubkey(), keys.public);
+            println!("messaging key updated: {}", client.send(&[ix], wallet, &[])?);
+        }
+        None => {
+            let (signature, _) = client.register_user(wallet)?;
+            println!("registered: {signature}");
+        }
+    }
+    println!("x25519 public key {}", hex(&keys.public));
+    Ok(())
+}
+
+fn inbox(client: &XrayClient, wallet: &Keypair, unread_only: bool) -> Result<()> {
+    // `is_read` follows the variable-length content, so it is filtered here rather than by the RPC node
+    let mut messages = client.messages(vec![filters::message_recipient(&wallet.pubkey())])?;
+    if unread_only {
+        messages.retain(|(_, message)| !message.is_read);
+    }
+    if messages.is_empty() {
+        println!("no messages");
+    }
+
+    for (address, message) in messages {
+        let text = client
+            .read_message(wallet, &address)
+            .unwrap_or_else(|err| format!("<{err}>"));
+        let status = if message.is_read { " " } else { "*" };
+        println!("{status} {address}  from {}  at {}\n    {text}", message.sender, message.timestamp);
+    }
+    Ok(())
+}
+
+fn metrics(client: &XrayClient, wallet: &Keypair, init: bool) -> Result<()> {
+    let (mut post_metrics, mut messages_metrics) = client.metrics()?;
+    if init && (post_metrics.is_none() || messages_metrics.is_none()) {
+        let mut ixs = Vec::new();
+        if post_metrics.is_none() {
+            ixs.push(post::initialize_metrics(wallet.pubkey()));
+        }
+        if messages_metrics.is_none() {
+            ixs.push(private_messages::initialize_metrics(wallet.pubkey()));
+        }
+        println!("metrics initialized: {}", client.send(&ixs, wallet, &[])?);
+        (post_metrics, messages_metrics) = client.metrics()?;
+    }
+
+    match post_metrics {
+        Some(m) => println!(
+            "post program: {} posts, {} messages, {} upvotes, {} boosts, {}/{} computations completed ({} failed)",
+            m.posts_created,
+            m.messages_sent,
+            m.upvotes,
+            m.boosts,
+            m.computations_completed,
+            m.computations_queued,
+            m.computations_failed
+        ),
+        None => println!("post program: metrics not initialized"),
+    }
+    match messages_metrics {
+        Some(m) => println!(
+            "private_messages: {} users, {} messages, {}/{} computations completed ({} failed)",
+            m.users_registered, m.messages_sent, m.computations_completed, m.computations_queued, m.computations_failed
+        ),
+        None => println!("private_messages: metrics not initialized"),
+    }
+    Ok(())
+}
+
+/// Demo data for `devnet_seed`; `treasury_lamports` is `None` to skip the posts
+fn devnet_seed(
+    client: &XrayClient,
+    wallet: &Keypair,
+    seed: &str,
+    user_count: u8,
+    treasury_lamports: Option<u64>,
+) -> Result<()> {
+    let wallets = (0..user_count)
+        .map(|i| keypair_from_seed(hashv(&[seed.as_bytes(), &[i]]).as_ref()).map_err(|err| anyhow::anyhow!("{err}")))
+        .collect::<Result<Vec<_>>>()?;
+    let keys: Vec<_> = wallets.iter().map(|demo| crypto::derive_x25519_keypair(&demo.to_bytes())).collect();
+
+    let mut counts = Vec::new();
+    for demo in &wallets {
+        let user: Option<UserAccount> = client.fetch_optional(&pda::user_pda(&demo.pubkey()).0)?;
+        counts.push(user.map_or(0, |user| user.message_count));
+    }
+    let users = wallets
+        .iter()
+        .zip(&keys)
+        .map(|(demo, keys)| private_messages::DemoUser { wallet: demo.pubkey(), x25519_pubkey: keys.public })
+        .collect();
+    let messages = (0..user_count)
+        .map(|sender| {
+            let recipient = (sender + 1) % user_count;
+            let text = format!("Hello from demo user {sender}");
+            let (encrypted_content, nonce) =
+                crypto::encrypt_message(&text, &keys[recipient as usize].public, &keys[sender as usize].secret);
+            private_messages::DemoMessage { sender, recipient, encrypted_content, nonce }
+        })
+        .collect();
+    let ix = private_messages::devnet_seed(wallet.pubkey(), users, &counts, messages);
+    println!("users and messages seeded: {}", client.send(&[ix], wallet, &[])?);
+
+    if let Some(treasury_lamports) = treasury_lamports {
+        let mut posts = Vec::new();
+        for (i, demo) in wallets.iter().enumerate() {
+            let target = format!("demo-{i}");
+            if client.fetch_optional::<Post>(&pda::post_pda(&demo.pubkey(), &target).0)?.is_none() {
+                let content = format!("Demo post {i} ({seed})");
+                posts.push(post::DemoPost { author: demo.pubkey(), target, content, bid: 10_000_000 });
+            }
+        }
+        let config: Config = client.fetch(&pda::config_pda().0)?;
+        let ix = post::devnet_seed(wallet.pubkey(), config.treasury_version, treasury_lamports, posts);
+        println!("treasury and posts seeded: {}", client.send(&[ix], wallet, &[])?);
+    }
+
+    for demo in &wallets {
+        println!("demo user {}", demo.pubkey());
+    }
+    Ok(())
+}
+
+fn parse_license(name: &str) -> std::result::Result<u8, String> {
+    license::from_name(name).ok_or_else(|| format!("unknown license {name:?}"))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
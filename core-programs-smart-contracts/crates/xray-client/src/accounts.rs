@@ -0,0 +1,309 @@
+//! Account mirrors of both programs and their decoding.
+//!
+//! Accounts created before a layout change are shorter than the current layout
+//! (fields are only ever appended, see `migrate_post`); [`decode`] zero-fills the
+//! missing tail so those fields read as their defaults.
+
+use anchor_lang::prelude::*;
+use sha2::{Digest, Sha256};
+use xray_common::sizes;
+
+use crate::error::{ClientError, Result};
+
+/// An account type of one of the X-RAY programs
+pub trait XrayAccount: AnchorDeserialize {
+    /// Struct name in the program, used for the Anchor discriminator
+    const NAME: &'static str;
+    /// Space allocated by the program, discriminator included
+    const SIZE: usize;
+}
+
+/// Anchor account discriminator: `sha256("account:<Name>")[..8]`
+pub fn discriminator(name: &str) -> [u8; 8] {
+    let hash = Sha256::digest(format!("account:{name}").as_bytes());
+    let mut out = [0u8; 8];
+    out.copy_from_slice(&hash[..8]);
+    out
+}
+
+/// Decodes raw account data after checking its discriminator
+pub fn decode<T: XrayAccount>(data: &[u8]) -> Result<T> {
+    if data.len() < 8 || data[..8] != discriminator(T::NAME) {
+        return Err(ClientError::InvalidDiscriminator(T::NAME));
+    }
+    let mut body = data[8..].to_vec();
+    if body.len() < T::SIZE - 8 {
+        body.resize(T::SIZE - 8, 0);
+    }
+    Ok(T::deserialize(&mut body.as_slice())?)
+}
+
+macro_rules! xray_account {
+    ($name:ident, $size:expr) => {
+        impl XrayAccount for $name {
+            const NAME: &'static str = stringify!($name);
+            const SIZE: usize = $size;
+        }
+    };
+}
+
+// ----------------------------------------------------------------------------
+// post-msg-program
+// ----------------------------------------------------------------------------
+
+#[derive(AnchorDeserialize, AnchorSerialize, Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    pub admin: Pubkey,
+    pub min_bid_usd: u64,
+    pub referral_bps: u16,
+    pub receipt_tree: Pubkey,
+    pub rate_window_secs: i64,
+    pub max_posts_per_window: u16,
+    pub rate_waiver_bid_usd: u64,
+    pub min_stake: u64,
+    pub stake_cooldown_secs: i64,
+    pub vesting_vault: Pubkey,
+    pub pending_admin: Pubkey,
+    pub escrow_window_secs: i64,
+    pub escrow_keep_fee_bps: u16,
+    pub upvote_lamports: u64,
+    pub treasury_version: u8,
+    pub view_attestor: Pubkey,
+    pub bump: u8,
+}
+xray_account!(Config, sizes::CONFIG_SIZE);
+
+impl Config {
+    /// Recipient of the 10% tranche (the vesting vault once vesting is enabled)
+    pub fn tranche_recipient(&self) -> Pubkey {
+        if self.vesting_vault == Pubkey::default() {
+            xray_common::ids::WALLET_2
+        } else {
+            self.vesting_vault
+        }
+    }
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, Debug, Clone, PartialEq, Eq)]
+pub struct Post {
+    pub author: Pubkey,
+    pub target: String,
+    pub content: String,
+    pub bid: u64,
+    pub timestamp: i64,
+    pub bump: u8,
+    pub version: u8,
+    pub total_boosts: u64,
+    pub rank_score: u64,
+    pub rank_updated_at: i64,
+    pub bid_mint: Pubkey,
+    pub upvotes: u64,
+    pub flags: u8,
+    pub views: u64,
+    pub charity: Pubkey,
+    pub charity_amount: u64,
+    pub license: u8,
+    pub content_signature: [u8; 64],
+}
+xray_account!(Post, sizes::POST_SIZE);
+
+impl Post {
+    /// Whether the author recorded a license and content signature (`set_post_provenance`)
+    pub fn has_provenance(&self) -> bool {
+        self.content_signature != [0; 64]
+    }
+
+    /// Checks `content_signature` against the author and the content, as the program did
+    /// when it was recorded (false when there is none)
+    pub fn verify_provenance(&self) -> bool {
+        let signature = solana_sdk::signature::Signature::from(self.content_signature);
+        self.has_provenance() && signature.verify(self.author.as_ref(), &crate::instructions::post::content_hash(&self.content))
+    }
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, Debug, Clone, PartialEq, Eq)]
+pub struct Referral {
+    pub wallet: Pubkey,
+    pub total_earned: u64,
+    pub referral_count: u64,
+    pub bump: u8,
+}
+xray_account!(Referral, sizes::REFERRAL_SIZE);
+
+#[derive(AnchorDeserialize, AnchorSerialize, Debug, Clone, PartialEq, Eq)]
+pub struct Charity {
+    pub wallet: Pubkey,
+    pub share_bps: u16,
+    pub total_received: u64,
+    pub bump: u8,
+}
+xray_account!(Charity, sizes::CHARITY_SIZE);
+
+#[derive(AnchorDeserialize, AnchorSerialize, Debug, Clone, PartialEq, Eq)]
+pub struct AuthorIndex {
+    pub author: Pubkey,
+    pub post_count: u64,
+    pub recent_posts: Vec<Pubkey>,
+    pub bump: u8,
+}
+xray_account!(AuthorIndex, sizes::AUTHOR_INDEX_SIZE);
+
+#[derive(AnchorDeserialize, AnchorSerialize, Debug, Clone, PartialEq, Eq)]
+pub struct AuthorStats {
+    pub author: Pubkey,
+    pub window_start: i64,
+    pub window_posts: u16,
+    pub total_posts: u64,
+    pub relay_nonce: u64,
+    pub bump: u8,
+}
+xray_account!(AuthorStats, sizes::AUTHOR_STATS_SIZE);
+
+#[derive(AnchorDeserialize, AnchorSerialize, Debug, Clone, PartialEq, Eq)]
+pub struct StakeAccount {
+    pub author: Pubkey,
+    pub amount: u64,
+    pub unlock_requested_at: i64,
+    pub bump: u8,
+}
+xray_account!(StakeAccount, sizes::STAKE_ACCOUNT_SIZE);
+
+#[derive(AnchorDeserialize, AnchorSerialize, Debug, Clone, PartialEq, Eq)]
+pub struct TargetAccount {
+    pub target: String,
+    pub owner: Pubkey,
+    pub pinned_post: Pubkey,
+    pub pinned_until: i64,
+    pub pin_fee: u64,
+    pub bump: u8,
+    pub identity_key: Pubkey,
+    pub verified_at: i64,
+}
+xray_account!(TargetAccount, sizes::TARGET_ACCOUNT_SIZE);
+
+#[derive(AnchorDeserialize, AnchorSerialize, Debug, Clone, PartialEq, Eq)]
+pub struct TargetIdentity {
+    pub identity_key: Pubkey,
+    pub attested_by: Pubkey,
+    pub attested_at: i64,
+    pub bump: u8,
+}
+xray_account!(TargetIdentity, sizes::TARGET_IDENTITY_SIZE);
+
+#[derive(AnchorDeserialize, AnchorSerialize, Debug, Clone, PartialEq, Eq)]
+pub struct UpvoteReceipt {
+    pub post: Pubkey,
+    pub voter: Pubkey,
+    pub timestamp: i64,
+    pub bump: u8,
+}
+xray_account!(UpvoteReceipt, sizes::UPVOTE_RECEIPT_SIZE);
+
+/// Global usage counters at `pda::post_metrics_pda()`
+#[derive(AnchorDeserialize, AnchorSerialize, Debug, Clone, PartialEq, Eq)]
+pub struct PostMetrics {
+    pub posts_created: u64,
+    pub messages_sent: u64,
+    pub computations_queued: u64,
+    pub computations_completed: u64,
+    pub computations_failed: u64,
+    pub upvotes: u64,
+    pub boosts: u64,
+    pub bump: u8,
+}
+xray_account!(PostMetrics, sizes::POST_METRICS_SIZE);
+
+// ----------------------------------------------------------------------------
+// private_messages
+// ----------------------------------------------------------------------------
+
+#[derive(AnchorDeserialize, AnchorSerialize, Debug, Clone, PartialEq, Eq)]
+pub struct UserAccount {
+    pub wallet: Pubkey,
+    pub x25519_pubkey: [u8; 32],
+    pub message_count: u64,
+    pub bump: u8,
+}
+xray_account!(UserAccount, sizes::USER_ACCOUNT_SIZE);
+
+#[derive(AnchorDeserialize, AnchorSerialize, Debug, Clone, PartialEq, Eq)]
+pub struct MessageAccount {
+    pub sender: Pubkey,
+    pub recipient: Pubkey,
+    pub encrypted_content: Vec<u8>,
+    pub nonce: [u8; 24],
+    pub timestamp: i64,
+    pub is_read: bool,
+    pub bump: u8,
+}
+xray_account!(MessageAccount, sizes::MESSAGE_ACCOUNT_SIZE);
+
+#[derive(AnchorDeserialize, AnchorSerialize, Debug, Clone, PartialEq, Eq)]
+pub struct PrivateMessageAccount {
+    pub encrypted_sender_hash: [u8; 32],
+    pub encrypted_recipient_hash: [u8; 32],
+    pub encrypted_content: Vec<u8>,
+    pub nonce: [u8; 24],
+    pub timestamp: i64,
+    pub mpc_pubkey: [u8; 32],
+    pub mpc_nonce: u128,
+    pub bump: u8,
+}
+xray_account!(PrivateMessageAccount, sizes::PRIVATE_MESSAGE_ACCOUNT_SIZE);
+
+#[derive(AnchorDeserialize, AnchorSerialize, Debug, Clone, PartialEq, Eq)]
+pub struct PrivateMessageCounter {
+    pub count: u64,
+    pub bump: u8,
+}
+xray_account!(PrivateMessageCounter, sizes::PRIVATE_MESSAGE_COUNTER_SIZE);
+
+#[derive(AnchorDeserialize, AnchorSerialize, Debug, Clone, PartialEq, Eq)]
+pub struct RateBucket {
+    pub encrypted_state: [[u8; 32]; 2],
+    pub state_nonce: u128,
+    pub sender: Pubkey,
+    pub send_authorized: bool,
+    pub computation_pending: bool,
+    pub pending_since: i64,
+    pub pending_computation: u64,
+    pub bump: u8,
+}
+xray_account!(RateBucket, sizes::RATE_BUCKET_SIZE);
+
+#[derive(AnchorDeserialize, AnchorSerialize, Debug, Clone, PartialEq, Eq)]
+pub struct Poll {
+    pub encrypted_tally: [[u8; 32]; 3],
+    pub state_nonce: u128,
+    pub creator: Pubkey,
+    pub membership_set: Pubkey,
+    pub poll_id: u64,
+    pub question_hash: [u8; 32],
+    pub closes_at: i64,
+    pub voter_count: u32,
+    pub computation_pending: bool,
+    pub pending_since: i64,
+    pub pending_computation: u64,
+    pub bump: u8,
+}
+xray_account!(Poll, sizes::POLL_SIZE);
+
+#[derive(AnchorDeserialize, AnchorSerialize, Debug, Clone, PartialEq, Eq)]
+pub struct NotificationPrefs {
+    pub owner: Pubkey,
+    pub flags: u8,
+    pub bump: u8,
+}
+xray_account!(NotificationPrefs, sizes::NOTIFICATION_PREFS_SIZE);
+
+/// Global usage counters at `pda::private_messages_metrics_pda()`
+#[derive(AnchorDeserialize, AnchorSerialize, Debug, Clone, PartialEq, Eq)]
+pub struct MessagesMetrics {
+    pub users_registered: u64,
+    pub messages_sent: u64,
+    pub computations_queued: u64,
+    pub computations_completed: u64,
+    pub computations_failed: u64,
+    pub bump: u8,
+}
+xray_account!(MessagesMetrics, sizes::MESSAGES_METRICS_SIZE);
@@ -0,0 +1,33 @@
+//! Message encryption shared with the web client, see [`xray_crypto::message`] (same
+//! implementation as the frontends' WASM build).
+
+use anchor_lang::prelude::Pubkey;
+
+use crate::error::{ClientError, Result};
+
+pub use xray_crypto::message::{
+    derive_x25519_keypair, encrypt_message, X25519Keypair, MSG_PREFIX_TO_RECIPIENT, MSG_PREFIX_TO_SENDER,
+};
+
+/// Decrypts a message exchanged with `other_party_pubkey` (either direction)
+pub fn decrypt_message(
+    encrypted_content: &[u8],
+    nonce: &[u8; 24],
+    other_party_pubkey: &[u8; 32],
+    my_secret: &[u8; 32],
+) -> Result<String> {
+    xray_crypto::message::decrypt_message(encrypted_content, nonce, other_party_pubkey, my_secret)
+        .map_err(|_| ClientError::Decrypt)
+}
+
+/// Identity hash hidden in private message metadata (`sha256(pubkey)`), before MPC encryption
+pub fn identity_hash(wallet: &Pubkey) -> [u8; 32] {
+    xray_crypto::message::identity_hash(wallet)
+}
+
+/// Fingerprint of `wallet` in a membership set (first 16 bytes of [`identity_hash`], little-endian),
+/// what `cast_poll_vote` checks a voter against
+pub fn member_fingerprint(wallet: &Pubkey) -> u128 {
+    let hash = identity_hash(wallet);
+    u128::from_le_bytes(hash[..16].try_into().expect("16 bytes"))
+}
@@ -0,0 +1,47 @@
+use anchor_lang::prelude::Pubkey;
+
+pub type Result<T> = std::result::Result<T, ClientError>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ClientError {
+    #[error("account {0} not found")]
+    AccountNotFound(Pubkey),
+    #[error("account data is not a {0}")]
+    InvalidDiscriminator(&'static str),
+    #[error("failed to decode account: {0}")]
+    Decode(#[from] std::io::Error),
+    #[error("recipient {0} is not registered")]
+    RecipientNotRegistered(Pubkey),
+    #[error("encrypted message is {0} bytes, the maximum is {1}")]
+    MessageTooLong(usize, usize),
+    #[error("decryption failed")]
+    Decrypt,
+    #[error("MXE of {0} has no x25519 key yet")]
+    MxeKeyUnavailable(Pubkey),
+    #[error("send rate limit reached, retry later")]
+    RateLimited,
+    #[error("timed out waiting for {0}")]
+    Timeout(&'static str),
+    #[error("rpc error: {0}")]
+    Rpc(#[from] Box<solana_rpc_client_api::client_error::Error>),
+    #[error("pubsub error: {0}")]
+    Pubsub(#[from] Box<solana_pubsub_client::nonblocking::pubsub_client::PubsubClientError>),
+    #[error("log subscription for {0} closed")]
+    SubscriptionClosed(Pubkey),
+    #[error("invalid transaction signature {0}")]
+    InvalidSignature(String),
+    #[error("rpc response has no slot context")]
+    MissingContext,
+}
+
+impl From<solana_rpc_client_api::client_error::Error> for ClientError {
+    fn from(err: solana_rpc_client_api::client_error::Error) -> Self {
+        ClientError::Rpc(Box::new(err))
+    }
+}
+
+impl From<solana_pubsub_client::nonblocking::pubsub_client::PubsubClientError> for ClientError {
+    fn from(err: solana_pubsub_client::nonblocking::pubsub_client::PubsubClientError) -> Self {
+        ClientError::Pubsub(Box::new(err))
+    }
+}
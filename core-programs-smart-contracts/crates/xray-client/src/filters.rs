@@ -0,0 +1,57 @@
+//! `getProgramAccounts` filters on the stable offsets of [`xray_common::layout`], so the RPC
+//! node does the filtering. Combine them with [`account`] and pass them to
+//! [`XrayClient::messages`](crate::flows::XrayClient::messages) /
+//! [`XrayClient::posts`](crate::flows::XrayClient::posts), or to [`program_accounts_config`].
+
+use anchor_lang::prelude::Pubkey;
+use solana_account_decoder_client_types::UiAccountEncoding;
+use solana_rpc_client_api::config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_rpc_client_api::filter::{Memcmp, RpcFilterType};
+use solana_sdk::commitment_config::CommitmentConfig;
+use xray_common::layout::{MESSAGE_RECIPIENT_OFFSET, MESSAGE_SENDER_OFFSET, POST_AUTHOR_OFFSET, POST_TARGET_OFFSET};
+
+use crate::accounts::{self, XrayAccount};
+
+fn memcmp(offset: usize, bytes: &[u8]) -> RpcFilterType {
+    RpcFilterType::Memcmp(Memcmp::new_base58_encoded(offset, bytes))
+}
+
+/// Accounts of type `T` (Anchor discriminator)
+pub fn account<T: XrayAccount>() -> RpcFilterType {
+    memcmp(0, &accounts::discriminator(T::NAME))
+}
+
+/// Messages received by `recipient`
+pub fn message_recipient(recipient: &Pubkey) -> RpcFilterType {
+    memcmp(MESSAGE_RECIPIENT_OFFSET, recipient.as_ref())
+}
+
+/// Messages sent by `sender`
+pub fn message_sender(sender: &Pubkey) -> RpcFilterType {
+    memcmp(MESSAGE_SENDER_OFFSET, sender.as_ref())
+}
+
+/// Posts written by `author`
+pub fn post_author(author: &Pubkey) -> RpcFilterType {
+    memcmp(POST_AUTHOR_OFFSET, author.as_ref())
+}
+
+/// Posts on exactly `target` (length prefix included, so "alice" does not match "alice.sol")
+pub fn post_target(target: &str) -> RpcFilterType {
+    let mut bytes = (target.len() as u32).to_le_bytes().to_vec();
+    bytes.extend_from_slice(target.as_bytes());
+    memcmp(POST_TARGET_OFFSET, &bytes)
+}
+
+/// `getProgramAccounts` configuration: `filters`, base64 data, at `commitment`
+pub fn program_accounts_config(filters: Vec<RpcFilterType>, commitment: CommitmentConfig) -> RpcProgramAccountsConfig {
+    RpcProgramAccountsConfig {
+        filters: Some(filters),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(commitment),
+            ..RpcAccountInfoConfig::default()
+        },
+        ..RpcProgramAccountsConfig::default()
+    }
+}
@@ -0,0 +1,493 @@
+//! High-level flows over a blocking `RpcClient`: fetch what the instruction needs,
+//! build it, sign and confirm.
+
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::Event;
+use crypto_box::aead::rand_core::RngCore;
+use crypto_box::aead::OsRng;
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_rpc_client_api::config::RpcTransactionConfig;
+use solana_rpc_client_api::filter::RpcFilterType;
+use solana_rpc_client_api::request::RpcRequest;
+use solana_rpc_client_api::response::{OptionalContext, RpcKeyedAccount};
+use solana_sdk::account::Account;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_sdk::transaction::Transaction;
+use solana_transaction_status_client_types::UiTransactionEncoding;
+use xray_common::events::private_messages::PrivateAccessVerified;
+use xray_common::ids::{POST_MSG_PROGRAM_ID, PRIVATE_MESSAGES_PROGRAM_ID};
+use xray_common::pda;
+use xray_common::sizes::MAX_MESSAGE_SIZE;
+
+use crate::accounts::{
+    self, Config, MessageAccount, MessagesMetrics, Post, PostMetrics, PrivateMessageCounter, RateBucket, UserAccount,
+    XrayAccount,
+};
+use crate::crypto::{self, X25519Keypair};
+use crate::error::{ClientError, Result};
+use crate::filters;
+use crate::instructions::{post, private_messages};
+use crate::mpc::{self, MpcCipher};
+use crate::stream::{self, EventEnvelope};
+
+/// Arcium cluster the web client queues computations on (devnet)
+pub const DEFAULT_CLUSTER_OFFSET: u32 = 456;
+/// How long MPC flows wait for their callback
+pub const MPC_TIMEOUT: Duration = Duration::from_secs(120);
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+pub struct XrayClient {
+    rpc: RpcClient,
+    cluster_offset: u32,
+}
+
+impl XrayClient {
+    pub fn new(rpc_url: impl Into<String>) -> Self {
+        Self::from_rpc(RpcClient::new(rpc_url.into()))
+    }
+
+    pub fn from_rpc(rpc: RpcClient) -> Self {
+        Self { rpc, cluster_offset: DEFAULT_CLUSTER_OFFSET }
+    }
+
+    /// Arcium cluster used by the MPC flows
+    pub fn with_cluster_offset(mut self, cluster_offset: u32) -> Self {
+        self.cluster_offset = cluster_offset;
+        self
+    }
+
+    pub fn rpc(&self) -> &RpcClient {
+        &self.rpc
+    }
+
+    /// Fetches and decodes an account, `None` if it does not exist
+    pub fn fetch_optional<T: XrayAccount>(&self, address: &Pubkey) -> Result<Option<T>> {
+        let account = self
+            .rpc
+            .get_account_with_commitment(address, self.rpc.commitment())?
+            .value;
+        account.map(|account| accounts::decode(&account.data)).transpose()
+    }
+
+    pub fn fetch<T: XrayAccount>(&self, address: &Pubkey) -> Result<T> {
+        self.fetch_optional(address)?
+            .ok_or(ClientError::AccountNotFound(*address))
+    }
+
+    /// Signs `instructions` with `payer` (fee payer) and `signers`, sends and confirms
+    pub fn send(&self, instructions: &[Instruction], payer: &Keypair, signers: &[&Keypair]) -> Result<Signature> {
+        let mut all_signers: Vec<&Keypair> = vec![payer];
+        all_signers.extend(signers.iter().filter(|s| s.pubkey() != payer.pubkey()));
+
+        let blockhash = self.rpc.get_latest_blockhash()?;
+        let tx = Transaction::new_signed_with_payer(instructions, Some(&payer.pubkey()), &all_signers, blockhash);
+        Ok(self.rpc.send_and_confirm_transaction(&tx)?)
+    }
+
+    // ------------------------------------------------------------------------
+    // Messaging
+    // ------------------------------------------------------------------------
+
+    /// Registers `owner` with the messaging key derived from its wallet (same derivation as the web client)
+    pub fn register_user(&self, owner: &Keypair) -> Result<(Signature, X25519Keypair)> {
+        let keys = crypto::derive_x25519_keypair(&owner.to_bytes());
+        let ix = private_messages::register_user(owner.pubkey(), keys.public);
+        Ok((self.send(&[ix], owner, &[])?, keys))
+    }
+
+    /// Encrypts `text` for `recipient` and sends it; returns the signature and the message address
+    pub fn send_message(&self, sender: &Keypair, recipient: &Pubkey, text: &str) -> Result<(Signature, Pubkey)> {
+        let recipient_user: UserAccount = self
+            .fetch_optional(&pda::user_pda(recipient).0)?
+            .ok_or(ClientError::RecipientNotRegistered(*recipient))?;
+
+        let keys = crypto::derive_x25519_keypair(&sender.to_bytes());
+        let (encrypted, nonce) = crypto::encrypt_message(text, &recipient_user.x25519_pubkey, &keys.secret);
+        if encrypted.len() > MAX_MESSAGE_SIZE {
+            return Err(ClientError::MessageTooLong(encrypted.len(), MAX_MESSAGE_SIZE));
+        }
+
+        let message = pda::message_pda(&sender.pubkey(), recipient, recipient_user.message_count).0;
+        let ix = private_messages::send_message(
+            sender.pubkey(),
+            *recipient,
+            recipient_user.message_count,
+            encrypted,
+            nonce,
+        );
+        Ok((self.send(&[ix], sender, &[])?, message))
+    }
+
+    /// Fetches and decrypts a message sent or received by `reader`
+    pub fn read_message(&self, reader: &Keypair, message: &Pubkey) -> Result<String> {
+        let account: MessageAccount = self.fetch(message)?;
+        let other_party = if account.recipient == reader.pubkey() {
+            account.sender
+        } else {
+            account.recipient
+        };
+        let other_user: UserAccount = self.fetch(&pda::user_pda(&other_party).0)?;
+
+        let keys = crypto::derive_x25519_keypair(&reader.to_bytes());
+        crypto::decrypt_message(&account.encrypted_content, &account.nonce, &other_user.x25519_pubkey, &keys.secret)
+    }
+
+    /// Messages received by `recipient`, oldest first
+    pub fn inbox(&self, recipient: &Pubkey) -> Result<Vec<(Pubkey, MessageAccount)>> {
+        self.messages(vec![filters::message_recipient(recipient)])
+    }
+
+    /// Messages matching `filters` (see [`filters`]), oldest first
+    pub fn messages(&self, filters: Vec<RpcFilterType>) -> Result<Vec<(Pubkey, MessageAccount)>> {
+        let mut messages = self.program_accounts::<MessageAccount>(&PRIVATE_MESSAGES_PROGRAM_ID, filters)?;
+        messages.sort_by_key(|(_, message)| message.timestamp);
+        Ok(messages)
+    }
+
+    /// Posts matching `filters` (see [`filters`]), newest first
+    pub fn posts(&self, filters: Vec<RpcFilterType>) -> Result<Vec<(Pubkey, Post)>> {
+        let mut posts = self.program_accounts::<Post>(&POST_MSG_PROGRAM_ID, filters)?;
+        posts.sort_by_key(|(_, post)| std::cmp::Reverse(post.timestamp));
+        Ok(posts)
+    }
+
+    /// Usage counters of both programs, `None` for a program whose metrics are not initialized
+    pub fn metrics(&self) -> Result<(Option<PostMetrics>, Option<MessagesMetrics>)> {
+        Ok((
+            self.fetch_optional(&pda::post_metrics_pda().0)?,
+            self.fetch_optional(&pda::private_messages_metrics_pda().0)?,
+        ))
+    }
+
+    /// Every account owned by `program_id`, raw, with the slot the RPC node served them at
+    /// (at least `min_context_slot` if given)
+    pub fn program_state(&self, program_id: &Pubkey, min_context_slot: Option<u64>) -> Result<(u64, Vec<(Pubkey, Account)>)> {
+        let mut config = filters::program_accounts_config(Vec::new(), self.rpc.commitment());
+        config.with_context = Some(true);
+        config.account_config.min_context_slot = min_context_slot;
+
+        let response: OptionalContext<Vec<RpcKeyedAccount>> =
+            self.rpc.send(RpcRequest::GetProgramAccounts, serde_json::json!([program_id.to_string(), config]))?;
+        let OptionalContext::Context(response) = response else {
+            return Err(ClientError::MissingContext);
+        };
+        let accounts = response
+            .value
+            .into_iter()
+            .filter_map(|keyed| Some((Pubkey::from_str(&keyed.pubkey).ok()?, keyed.account.decode::<Account>()?)))
+            .collect();
+        Ok((response.context.slot, accounts))
+    }
+
+    /// Accounts of type `T` owned by `program_id` that also match `filters`
+    fn program_accounts<T: XrayAccount>(
+        &self,
+        program_id: &Pubkey,
+        filters: Vec<RpcFilterType>,
+    ) -> Result<Vec<(Pubkey, T)>> {
+        let filters = [vec![filters::account::<T>()], filters].concat();
+        let config = filters::program_accounts_config(filters, self.rpc.commitment());
+        self.rpc
+            .get_program_accounts_with_config(program_id, config)?
+            .into_iter()
+            .map(|(address, account)| Ok((address, accounts::decode::<T>(&account.data)?)))
+            .collect()
+    }
+
+    // ------------------------------------------------------------------------
+    // Private messages (MPC)
+    // ------------------------------------------------------------------------
+
+    /// x25519 key of the private_messages MXE
+    pub fn mxe_x25519_pubkey(&self) -> Result<[u8; 32]> {
+        let address = arcium_client::pda::mxe_acc(&PRIVATE_MESSAGES_PROGRAM_ID);
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())?
+            .value
+            .ok_or(ClientError::AccountNotFound(address))?;
+        mpc::mxe_x25519_pubkey(&PRIVATE_MESSAGES_PROGRAM_ID, &account.data)
+    }
+
+    /// Grants `sender` one send token: creates the rate bucket on first use, then has the
+    /// MPC consume a token from it and waits for the callback
+    pub fn acquire_send_token(&self, sender: &Keypair) -> Result<()> {
+        let bucket_address = pda::rate_bucket_pda(&sender.pubkey()).0;
+
+        let bucket = match self.fetch_optional::<RateBucket>(&bucket_address)? {
+            Some(bucket) if bucket.computation_pending => self.wait_for_bucket(&bucket_address)?,
+            Some(bucket) => bucket,
+            None => {
+                let ix = private_messages::create_rate_bucket(
+                    sender.pubkey(),
+                    self.cluster_offset,
+                    OsRng.next_u64(),
+                    mpc::random_nonce(),
+                );
+                self.send(&[ix], sender, &[])?;
+                self.wait_for_bucket(&bucket_address)?
+            }
+        };
+        if bucket.send_authorized {
+            return Ok(());
+        }
+
+        let ix = private_messages::request_send_token(sender.pubkey(), self.cluster_offset, OsRng.next_u64());
+        self.send(&[ix], sender, &[])?;
+        if self.wait_for_bucket(&bucket_address)?.send_authorized {
+            Ok(())
+        } else {
+            Err(ClientError::RateLimited)
+        }
+    }
+
+    /// Encrypts `text` for `recipient` and sends it as a private message (identities hidden
+    /// behind MPC-encrypted hashes); returns the signature and the message address
+    pub fn send_private_message(&self, sender: &Keypair, recipient: &Pubkey, text: &str) -> Result<(Signature, Pubkey)> {
+        let recipient_user: UserAccount = self
+            .fetch_optional(&pda::user_pda(recipient).0)?
+            .ok_or(ClientError::RecipientNotRegistered(*recipient))?;
+
+        let keys = crypto::derive_x25519_keypair(&sender.to_bytes());
+        let (encrypted_content, nonce) = crypto::encrypt_message(text, &recipient_user.x25519_pubkey, &keys.secret);
+        if encrypted_content.len() > MAX_MESSAGE_SIZE {
+            return Err(ClientError::MessageTooLong(encrypted_content.len(), MAX_MESSAGE_SIZE));
+        }
+
+        self.acquire_send_token(sender)?;
+
+        let message_index = self
+            .fetch_optional::<PrivateMessageCounter>(&pda::private_message_counter_pda().0)?
+            .map_or(0, |counter| counter.count);
+        let cipher = MpcCipher::new(&self.mxe_x25519_pubkey()?);
+        let mpc_nonce = mpc::random_nonce();
+        // One pass, recipient first: it is the hash the access check decrypts under mpc_nonce
+        let [encrypted_recipient_hash, encrypted_sender_hash] = cipher
+            .encrypt_identity_hashes(
+                &[&crypto::identity_hash(recipient), &crypto::identity_hash(&sender.pubkey())],
+                mpc_nonce,
+            )
+            .try_into()
+            .expect("one ciphertext per hash");
+        let message = private_messages::PrivateMessage {
+            message_index,
+            encrypted_sender_hash,
+            encrypted_recipient_hash,
+            encrypted_content,
+            nonce,
+            mpc_pubkey: cipher.public_key,
+            mpc_nonce,
+        };
+
+        let address = pda::private_message_pda(&sender.pubkey(), message_index).0;
+        let ix = private_messages::send_private_message(sender.pubkey(), &message);
+        Ok((self.send(&[ix], sender, &[])?, address))
+    }
+
+    /// Asks the MPC whether `requester` is the recipient of `private_message` and waits for
+    /// the answer, encrypted back to a one-off client key
+    pub fn verify_private_message_access(&self, requester: &Keypair, private_message: &Pubkey) -> Result<bool> {
+        let cipher = MpcCipher::new(&self.mxe_x25519_pubkey()?);
+        let mpc_nonce = mpc::random_nonce();
+        let computation_offset = OsRng.next_u64();
+
+        let ix = private_messages::verify_private_message_access(
+            requester.pubkey(),
+            *private_message,
+            self.cluster_offset,
+            computation_offset,
+            cipher.encrypt_identity_hash(&crypto::identity_hash(&requester.pubkey()), mpc_nonce),
+            cipher.public_key,
+            mpc_nonce,
+        );
+        self.send(&[ix], requester, &[])?;
+
+        // The callback transaction touches the computation account
+        let computation = arcium_client::pda::computation_acc(self.cluster_offset, computation_offset);
+        let event: PrivateAccessVerified = self.poll("the access verification callback", || {
+            self.find_event(&computation, &PRIVATE_MESSAGES_PROGRAM_ID)
+        })?;
+
+        let result = cipher.decrypt(&[event.encrypted_result], u128::from_le_bytes(event.nonce));
+        Ok(result[0][0] == 1)
+    }
+
+    fn wait_for_bucket(&self, address: &Pubkey) -> Result<RateBucket> {
+        self.poll("the rate bucket computation", || {
+            Ok(self
+                .fetch_optional::<RateBucket>(address)?
+                .filter(|bucket| !bucket.computation_pending))
+        })
+    }
+
+    /// Calls `check` every few seconds until it returns a value or [`MPC_TIMEOUT`] elapses
+    fn poll<T>(&self, what: &'static str, mut check: impl FnMut() -> Result<Option<T>>) -> Result<T> {
+        let deadline = Instant::now() + MPC_TIMEOUT;
+        loop {
+            if let Some(value) = check()? {
+                return Ok(value);
+            }
+            if Instant::now() >= deadline {
+                return Err(ClientError::Timeout(what));
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    /// First `E` emitted by `program_id` in a successful transaction touching `address`
+    pub fn find_event<E: Event>(&self, address: &Pubkey, program_id: &Pubkey) -> Result<Option<E>> {
+        for status in self.rpc.get_signatures_for_address(address)? {
+            if status.err.is_some() {
+                continue;
+            }
+            let signature = Signature::from_str(&status.signature)
+                .map_err(|_| ClientError::InvalidSignature(status.signature.clone()))?;
+            let config = RpcTransactionConfig {
+                encoding: Some(UiTransactionEncoding::Base64),
+                commitment: Some(self.rpc.commitment()),
+                max_supported_transaction_version: Some(0),
+            };
+            let transaction = self.rpc.get_transaction_with_config(&signature, config)?;
+            let slot = transaction.slot;
+            let logs: Vec<String> = transaction
+                .transaction
+                .meta
+                .and_then(|meta| meta.log_messages.into())
+                .unwrap_or_default();
+
+            for (program_id, data) in stream::parse_logs(&logs, &[*program_id]) {
+                let raw = EventEnvelope { program_id, slot, signature, event: data };
+                if let Some(event) = raw.decode::<E>()? {
+                    return Ok(Some(event));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    // ------------------------------------------------------------------------
+    // Posts
+    // ------------------------------------------------------------------------
+
+    /// `create_post` with the split accounts resolved from the on-chain config
+    /// (tranche recipient, receipt tree, stake requirement); returns the signature and the post address
+    pub fn create_post(
+        &self,
+        author: &Keypair,
+        target: &str,
+        content: &str,
+        bid: u64,
+        price_update: Pubkey,
+    ) -> Result<(Signature, Pubkey)> {
+        let builder = self.create_post_builder(author, target, content, bid, price_update)?;
+        let post = builder.post_address();
+        Ok((self.send(&[builder.instruction()], author, &[])?, post))
+    }
+
+    /// `create_post`, plus a courtesy DM carrying the post address to the target's registered
+    /// owner in the same transaction when that owner has a messaging account; returns the
+    /// signature, the post address and the message address (if one was sent)
+    pub fn create_post_notifying_target(
+        &self,
+        author: &Keypair,
+        target: &str,
+        content: &str,
+        bid: u64,
+        price_update: Pubkey,
+    ) -> Result<(Signature, Pubkey, Option<Pubkey>)> {
+        let builder = self.create_post_builder(author, target, content, bid, price_update)?;
+        let post = builder.post_address();
+        let mut instructions = vec![builder.instruction()];
+
+        let notice = self.target_notice(author, &post, target)?;
+        if let Some((ix, _)) = &notice {
+            instructions.push(ix.clone());
+        }
+        Ok((self.send(&instructions, author, &[])?, post, notice.map(|(_, message)| message)))
+    }
+
+    /// `notify_post_target` for `post` and the message address, or `None` when `target` has
+    /// no registered owner or the owner has no messaging account
+    pub fn target_notice(&self, author: &Keypair, post: &Pubkey, target: &str) -> Result<Option<(Instruction, Pubkey)>> {
+        let Some(target_account) = self.fetch_optional::<accounts::TargetAccount>(&pda::target_account_pda(target).0)? else {
+            return Ok(None);
+        };
+        let owner = target_account.owner;
+        let Some(owner_user) = self.fetch_optional::<UserAccount>(&pda::user_pda(&owner).0)? else {
+            return Ok(None);
+        };
+
+        let keys = crypto::derive_x25519_keypair(&author.to_bytes());
+        let text = format!("New post about {target}: {post}");
+        let (encrypted, nonce) = crypto::encrypt_message(&text, &owner_user.x25519_pubkey, &keys.secret);
+        let message = pda::message_pda(&author.pubkey(), &owner, owner_user.message_count).0;
+        let ix = post::notify_post_target(
+            author.pubkey(),
+            *post,
+            target,
+            owner,
+            owner_user.message_count,
+            encrypted,
+            nonce,
+        );
+        Ok(Some((ix, message)))
+    }
+
+    /// Signs the content hash of `post` with the author's wallet and records it with `license`
+    /// (an `xray_common::license` code)
+    pub fn set_post_provenance(&self, author: &Keypair, post: &Pubkey, license: u8) -> Result<Signature> {
+        let account: Post = self.fetch(post)?;
+        let signature = author.sign_message(&post::content_hash(&account.content));
+        let signature: [u8; 64] = signature.as_ref().try_into().expect("signatures are 64 bytes");
+        let ixs = post::set_post_provenance(author.pubkey(), *post, &account.content, license, &signature);
+        self.send(&ixs, author, &[])
+    }
+
+    /// Claims `target` for `claimant` with a challenge signed by `identity`, the key the admin
+    /// attested for it; migrates an older target account first
+    pub fn prove_target_ownership(&self, claimant: &Keypair, identity: &Keypair, target: &str) -> Result<Signature> {
+        let mut instructions = Vec::new();
+        let existing = self.rpc.get_account_with_commitment(&pda::target_account_pda(target).0, self.rpc.commitment())?.value;
+        if existing.is_some_and(|account| account.data.len() < accounts::TargetAccount::SIZE) {
+            instructions.push(post::migrate_target(claimant.pubkey(), target));
+        }
+
+        let signature = identity.sign_message(post::target_challenge(target, &claimant.pubkey()).as_bytes());
+        let signature: [u8; 64] = signature.as_ref().try_into().expect("signatures are 64 bytes");
+        instructions.extend(post::prove_target_ownership(claimant.pubkey(), target, identity.pubkey(), &signature));
+        self.send(&instructions, claimant, &[])
+    }
+
+    fn create_post_builder(
+        &self,
+        author: &Keypair,
+        target: &str,
+        content: &str,
+        bid: u64,
+        price_update: Pubkey,
+    ) -> Result<post::CreatePost> {
+        let config: Config = self.fetch(&pda::config_pda().0)?;
+        let epoch = self.rpc.get_epoch_info()?.epoch;
+
+        let mut builder = post::CreatePost::new(
+            author.pubkey(),
+            target,
+            content,
+            bid,
+            price_update,
+            epoch,
+            &config,
+        );
+        if config.min_stake > 0 {
+            builder = builder.with_stake();
+        }
+        if config.receipt_tree != Pubkey::default() {
+            builder = builder.receipt_tree(config.receipt_tree);
+        }
+        Ok(builder)
+    }
+}
@@ -0,0 +1,35 @@
+//! Accounts shared by every instruction that queues an Arcium computation.
+
+use anchor_lang::prelude::*;
+use arcium_client::pda;
+use arcium_client::ARCIUM_PROGRAM_ID;
+
+/// Seed of the program's Arcium signer PDA (`SIGN_PDA_SEED` in arcium-anchor)
+pub const SIGN_PDA_SEED: &[u8] = b"ArciumSignerAccount";
+
+/// The fixed tail of a `#[queue_computation_accounts]` struct, from `sign_pda_account`
+/// to `arcium_program`, for `circuit` on the cluster at `cluster_offset`
+pub fn queue_computation_accounts(
+    program_id: &Pubkey,
+    circuit: &str,
+    cluster_offset: u32,
+    computation_offset: u64,
+) -> Vec<AccountMeta> {
+    let (sign_pda, _) = Pubkey::find_program_address(&[SIGN_PDA_SEED], program_id);
+    vec![
+        AccountMeta::new(sign_pda, false),
+        AccountMeta::new_readonly(pda::mxe_acc(program_id), false),
+        AccountMeta::new(pda::mempool_acc(cluster_offset), false),
+        AccountMeta::new(pda::execpool_acc(cluster_offset), false),
+        AccountMeta::new(pda::computation_acc(cluster_offset, computation_offset), false),
+        AccountMeta::new_readonly(
+            pda::computation_definition_acc(program_id, pda::comp_def_offset(circuit)),
+            false,
+        ),
+        AccountMeta::new(pda::cluster_acc(cluster_offset), false),
+        AccountMeta::new(pda::fee_pool_acc(), false),
+        AccountMeta::new(pda::clock_acc(), false),
+        AccountMeta::new_readonly(anchor_lang::system_program::ID, false),
+        AccountMeta::new_readonly(ARCIUM_PROGRAM_ID, false),
+    ]
+}
@@ -0,0 +1,55 @@
+//! Instruction builders. Accounts are listed in the order of the program's
+//! `#[derive(Accounts)]` struct; absent optional accounts are passed as the
+//! program ID, as Anchor expects.
+
+pub mod arcium;
+pub mod post;
+pub mod private_messages;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use sha2::{Digest, Sha256};
+use xray_common::ids::POST_MSG_PROGRAM_ID;
+use xray_common::pda;
+
+/// Anchor instruction discriminator: `sha256("global:<name>")[..8]`
+pub fn sighash(name: &str) -> [u8; 8] {
+    let hash = Sha256::digest(format!("global:{name}").as_bytes());
+    let mut out = [0u8; 8];
+    out.copy_from_slice(&hash[..8]);
+    out
+}
+
+/// Builds an Anchor instruction; `args` is the Borsh tuple of the handler arguments
+pub fn anchor_instruction(
+    program_id: Pubkey,
+    name: &str,
+    args: &impl AnchorSerialize,
+    accounts: Vec<AccountMeta>,
+) -> Instruction {
+    let mut data = sighash(name).to_vec();
+    args.serialize(&mut data).expect("serializing into a Vec cannot fail");
+    Instruction { program_id, accounts, data }
+}
+
+/// Optional account slot: the account if present, the program ID otherwise
+pub(crate) fn optional_account(program_id: Pubkey, key: Option<Pubkey>, is_writable: bool) -> AccountMeta {
+    match key {
+        Some(key) if is_writable => AccountMeta::new(key, false),
+        Some(key) => AccountMeta::new_readonly(key, false),
+        None => AccountMeta::new_readonly(program_id, false),
+    }
+}
+
+/// Appends the program's usage metrics account, so the instruction bumps its counters.
+/// Only for instructions whose accounts struct ends with the optional `metrics` account,
+/// and once `initialize_metrics` has run.
+pub fn with_metrics(mut instruction: Instruction) -> Instruction {
+    let metrics = if instruction.program_id == POST_MSG_PROGRAM_ID {
+        pda::post_metrics_pda().0
+    } else {
+        pda::private_messages_metrics_pda().0
+    };
+    instruction.accounts.push(AccountMeta::new(metrics, false));
+    instruction
+}
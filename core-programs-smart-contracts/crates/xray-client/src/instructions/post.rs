@@ -0,0 +1,723 @@
+//! `post-msg-program` instructions.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use xray_common::ids::{
+    BUBBLEGUM_PROGRAM_ID, POST_MSG_PROGRAM_ID, PRIVATE_MESSAGES_PROGRAM_ID, SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+    SPL_NOOP_PROGRAM_ID, WALLET_1, WALLET_2, WALLET_3,
+};
+use sha2::{Digest, Sha256};
+use xray_common::pda;
+
+use super::arcium::queue_computation_accounts;
+use super::{anchor_instruction, optional_account};
+use crate::accounts::Config;
+
+/// Domain separator of relayed post authorizations (`RELAYED_POST_DOMAIN` in the program)
+pub const RELAYED_POST_DOMAIN: &[u8] = b"xray-relayed-post";
+
+/// `create_post`, with the revenue split accounts and the optional referral,
+/// charity, stake and receipt accounts
+#[derive(Debug, Clone)]
+pub struct CreatePost {
+    pub author: Pubkey,
+    pub target: String,
+    pub content: String,
+    pub bid: u64,
+    /// Pyth SOL/USD `PriceUpdateV2` account
+    pub price_update: Pubkey,
+    /// Current epoch (keys the revenue snapshot)
+    pub epoch: u64,
+    /// `Config::tranche_recipient()`: WALLET_2 or the vesting vault
+    pub tranche_recipient: Pubkey,
+    /// `Config::treasury_version` (keys the treasury PDA)
+    pub treasury_version: u8,
+    /// Registered referrer wallet
+    pub referrer: Option<Pubkey>,
+    /// Registered charity wallet
+    pub charity: Option<Pubkey>,
+    /// Pass the author's stake account (required when the config has a minimum stake)
+    pub with_stake: bool,
+    /// `Config::receipt_tree`, to mint a compressed NFT receipt
+    pub receipt_tree: Option<Pubkey>,
+}
+
+impl CreatePost {
+    /// The tranche recipient and treasury version are read from `config`
+    pub fn new(
+        author: Pubkey,
+        target: impl Into<String>,
+        content: impl Into<String>,
+        bid: u64,
+        price_update: Pubkey,
+        epoch: u64,
+        config: &Config,
+    ) -> Self {
+        Self {
+            author,
+            target: target.into(),
+            content: content.into(),
+            bid,
+            price_update,
+            epoch,
+            tranche_recipient: config.tranche_recipient(),
+            treasury_version: config.treasury_version,
+            referrer: None,
+            charity: None,
+            with_stake: false,
+            receipt_tree: None,
+        }
+    }
+
+    pub fn referrer(mut self, referrer: Pubkey) -> Self {
+        self.referrer = Some(referrer);
+        self
+    }
+
+    pub fn charity(mut self, charity_wallet: Pubkey) -> Self {
+        self.charity = Some(charity_wallet);
+        self
+    }
+
+    pub fn with_stake(mut self) -> Self {
+        self.with_stake = true;
+        self
+    }
+
+    pub fn receipt_tree(mut self, tree: Pubkey) -> Self {
+        self.receipt_tree = Some(tree);
+        self
+    }
+
+    /// Address of the post this instruction creates
+    pub fn post_address(&self) -> Pubkey {
+        pda::post_pda(&self.author, &self.target).0
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        let program_id = POST_MSG_PROGRAM_ID;
+        let optional = |key, is_writable| optional_account(program_id, key, is_writable);
+        let tree_config = self
+            .receipt_tree
+            .map(|tree| Pubkey::find_program_address(&[tree.as_ref()], &BUBBLEGUM_PROGRAM_ID).0);
+        let with_receipt = |key: Pubkey| self.receipt_tree.map(|_| key);
+
+        let accounts = vec![
+            AccountMeta::new(self.author, true),
+            AccountMeta::new_readonly(pda::config_pda().0, false),
+            AccountMeta::new_readonly(self.price_update, false),
+            AccountMeta::new(pda::revenue_snapshot_pda(self.epoch).0, false),
+            AccountMeta::new(pda::treasury_pda(self.treasury_version).0, false),
+            AccountMeta::new(WALLET_1, false),
+            AccountMeta::new(self.tranche_recipient, false),
+            AccountMeta::new(WALLET_3, false),
+            AccountMeta::new(pda::author_index_pda(&self.author).0, false),
+            AccountMeta::new(self.post_address(), false),
+            optional(self.referrer, true),
+            optional(self.referrer.map(|r| pda::referral_pda(&r).0), true),
+            optional(self.charity, true),
+            optional(self.charity.map(|c| pda::charity_pda(&c).0), true),
+            AccountMeta::new(pda::author_stats_pda(&self.author).0, false),
+            optional(self.with_stake.then(|| pda::stake_pda(&self.author).0), false),
+            optional(tree_config, true),
+            optional(self.receipt_tree, true),
+            optional(with_receipt(pda::receipt_authority_pda().0), false),
+            optional(with_receipt(BUBBLEGUM_PROGRAM_ID), false),
+            optional(with_receipt(SPL_NOOP_PROGRAM_ID), false),
+            optional(with_receipt(SPL_ACCOUNT_COMPRESSION_PROGRAM_ID), false),
+            AccountMeta::new_readonly(anchor_lang::system_program::ID, false),
+        ];
+
+        anchor_instruction(program_id, "create_post", &(&self.target, &self.content, self.bid), accounts)
+    }
+}
+
+/// Message the author signs to authorize a relayed post
+/// (`relayed_post_message` in the program). `nonce` must exceed `AuthorStats::relay_nonce`.
+pub fn relayed_post_message(author: &Pubkey, target: &str, content: &str, bid: u64, nonce: u64) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(RELAYED_POST_DOMAIN);
+    hasher.update(POST_MSG_PROGRAM_ID);
+    hasher.update(author);
+    hasher.update(target.as_bytes());
+    hasher.update([0]);
+    hasher.update(content.as_bytes());
+    hasher.update(bid.to_le_bytes());
+    hasher.update(nonce.to_le_bytes());
+    hasher.finalize().into()
+}
+
+/// Message the author signs for `Post::content_signature` (`content_hash` in the program)
+pub fn content_hash(content: &str) -> [u8; 32] {
+    Sha256::digest(content.as_bytes()).into()
+}
+
+/// `set_post_provenance` preceded by the Ed25519 instruction carrying the author's
+/// `signature` over [`content_hash`] of the post's (stored) content; `license` is an
+/// `xray_common::license` code
+pub fn set_post_provenance(author: Pubkey, post: Pubkey, content: &str, license: u8, signature: &[u8; 64]) -> [Instruction; 2] {
+    let verify =
+        solana_ed25519_program::new_ed25519_instruction_with_signature(&content_hash(content), signature, &author.to_bytes());
+
+    let accounts = vec![
+        AccountMeta::new_readonly(author, true),
+        AccountMeta::new(post, false),
+        AccountMeta::new_readonly(anchor_lang::solana_program::sysvar::instructions::ID, false),
+    ];
+    let set = anchor_instruction(POST_MSG_PROGRAM_ID, "set_post_provenance", &(license,), accounts);
+
+    [verify, set]
+}
+
+/// Challenge the attested identity key signs to claim `target` for `claimant`
+/// (`target_challenge` in the program)
+pub fn target_challenge(target: &str, claimant: &Pubkey) -> String {
+    format!("X-RAY target ownership: {target} is claimed by {claimant} on {POST_MSG_PROGRAM_ID}")
+}
+
+/// `attest_target_identity` (admin only): `identity_key` belongs to the identity behind `target`
+pub fn attest_target_identity(admin: Pubkey, target: &str, identity_key: Pubkey) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(admin, true),
+        AccountMeta::new_readonly(pda::config_pda().0, false),
+        AccountMeta::new(pda::target_identity_pda(target).0, false),
+        AccountMeta::new_readonly(anchor_lang::system_program::ID, false),
+    ];
+
+    anchor_instruction(POST_MSG_PROGRAM_ID, "attest_target_identity", &(target, identity_key), accounts)
+}
+
+/// `prove_target_ownership` preceded by the Ed25519 instruction carrying the attested
+/// `identity_key`'s `signature` over [`target_challenge`]
+pub fn prove_target_ownership(claimant: Pubkey, target: &str, identity_key: Pubkey, signature: &[u8; 64]) -> [Instruction; 2] {
+    let challenge = target_challenge(target, &claimant);
+    let verify =
+        solana_ed25519_program::new_ed25519_instruction_with_signature(challenge.as_bytes(), signature, &identity_key.to_bytes());
+
+    let accounts = vec![
+        AccountMeta::new(claimant, true),
+        AccountMeta::new_readonly(pda::target_identity_pda(target).0, false),
+        AccountMeta::new(pda::target_account_pda(target).0, false),
+        AccountMeta::new_readonly(anchor_lang::solana_program::sysvar::instructions::ID, false),
+        AccountMeta::new_readonly(anchor_lang::system_program::ID, false),
+    ];
+    let prove = anchor_instruction(POST_MSG_PROGRAM_ID, "prove_target_ownership", &(target,), accounts);
+
+    [verify, prove]
+}
+
+/// `migrate_target`: grows a target account from before `identity_key` to the current layout
+pub fn migrate_target(payer: Pubkey, target: &str) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(payer, true),
+        AccountMeta::new(pda::target_account_pda(target).0, false),
+        AccountMeta::new_readonly(anchor_lang::system_program::ID, false),
+    ];
+
+    anchor_instruction(POST_MSG_PROGRAM_ID, "migrate_target", &(), accounts)
+}
+
+/// `create_post_relayed` preceded by the Ed25519 instruction carrying the author's
+/// `signature` over [`relayed_post_message`]; the relayer signs and pays the bid and rent.
+/// `with_stake` passes the author's stake account (required when the config has a minimum stake)
+#[allow(clippy::too_many_arguments)]
+pub fn create_post_relayed(
+    relayer: Pubkey,
+    author: Pubkey,
+    target: &str,
+    content: &str,
+    bid: u64,
+    nonce: u64,
+    signature: &[u8; 64],
+    price_update: Pubkey,
+    epoch: u64,
+    tranche_recipient: Pubkey,
+    treasury_version: u8,
+    with_stake: bool,
+) -> [Instruction; 2] {
+    let message = relayed_post_message(&author, target, content, bid, nonce);
+    let verify = solana_ed25519_program::new_ed25519_instruction_with_signature(&message, signature, &author.to_bytes());
+
+    let accounts = vec![
+        AccountMeta::new(relayer, true),
+        AccountMeta::new_readonly(pda::config_pda().0, false),
+        AccountMeta::new_readonly(price_update, false),
+        AccountMeta::new(pda::revenue_snapshot_pda(epoch).0, false),
+        AccountMeta::new(pda::treasury_pda(treasury_version).0, false),
+        AccountMeta::new(WALLET_1, false),
+        AccountMeta::new(tranche_recipient, false),
+        AccountMeta::new(WALLET_3, false),
+        AccountMeta::new(pda::author_index_pda(&author).0, false),
+        AccountMeta::new(pda::post_pda(&author, target).0, false),
+        AccountMeta::new_readonly(anchor_lang::solana_program::sysvar::instructions::ID, false),
+        AccountMeta::new(pda::author_stats_pda(&author).0, false),
+        optional_account(POST_MSG_PROGRAM_ID, with_stake.then(|| pda::stake_pda(&author).0), false),
+        AccountMeta::new_readonly(anchor_lang::system_program::ID, false),
+    ];
+    let create = anchor_instruction(
+        POST_MSG_PROGRAM_ID,
+        "create_post_relayed",
+        &(author, target, content, bid, nonce),
+        accounts,
+    );
+
+    [verify, create]
+}
+
+/// `upvote_post`: pays `Config::upvote_lamports` through the revenue split
+pub fn upvote_post(voter: Pubkey, post: Pubkey, tranche_recipient: Pubkey, treasury_version: u8) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(voter, true),
+        AccountMeta::new_readonly(pda::config_pda().0, false),
+        AccountMeta::new(post, false),
+        AccountMeta::new(pda::upvote_receipt_pda(&post, &voter).0, false),
+        AccountMeta::new(pda::treasury_pda(treasury_version).0, false),
+        AccountMeta::new(WALLET_1, false),
+        AccountMeta::new(tranche_recipient, false),
+        AccountMeta::new(WALLET_3, false),
+        AccountMeta::new_readonly(anchor_lang::system_program::ID, false),
+    ];
+
+    anchor_instruction(POST_MSG_PROGRAM_ID, "upvote_post", &(), accounts)
+}
+
+/// `close_post` (author only, once no escrow is pending): refunds the post rent. `with_index`
+/// passes the author's index, absent for authors who only posted before it existed
+pub fn close_post(author: Pubkey, post: Pubkey, with_index: bool) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(author, true),
+        AccountMeta::new(post, false),
+        AccountMeta::new_readonly(pda::post_escrow_pda(&post).0, false),
+        optional_account(POST_MSG_PROGRAM_ID, with_index.then(|| pda::author_index_pda(&author).0), true),
+    ];
+
+    anchor_instruction(POST_MSG_PROGRAM_ID, "close_post", &(), accounts)
+}
+
+/// `notify_post_target`: courtesy DM from the author of `post` to `target_owner`, the registered
+/// owner of its target, through private_messages (`owner_message_count` is the owner's current
+/// `UserAccount::message_count`)
+pub fn notify_post_target(
+    author: Pubkey,
+    post: Pubkey,
+    target: &str,
+    target_owner: Pubkey,
+    owner_message_count: u64,
+    encrypted_content: Vec<u8>,
+    nonce: [u8; 24],
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(author, true),
+        AccountMeta::new(post, false),
+        AccountMeta::new_readonly(pda::target_account_pda(target).0, false),
+        AccountMeta::new(pda::user_pda(&target_owner).0, false),
+        AccountMeta::new(pda::message_pda(&author, &target_owner, owner_message_count).0, false),
+        AccountMeta::new_readonly(PRIVATE_MESSAGES_PROGRAM_ID, false),
+        AccountMeta::new_readonly(anchor_lang::system_program::ID, false),
+    ];
+
+    anchor_instruction(POST_MSG_PROGRAM_ID, "notify_post_target", &(encrypted_content, nonce), accounts)
+}
+
+/// `create_auction` (admin only): opens featured-slot auction `auction_id` and queues its
+/// encrypted empty state
+#[allow(clippy::too_many_arguments)]
+pub fn create_auction(
+    admin: Pubkey,
+    cluster_offset: u32,
+    computation_offset: u64,
+    auction_id: u64,
+    deposit: u64,
+    reserve_price: u64,
+    end_time: i64,
+    featured_duration: i64,
+    mxe_nonce: u128,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(admin, true),
+        AccountMeta::new_readonly(pda::config_pda().0, false),
+        AccountMeta::new(pda::auction_pda(auction_id).0, false),
+    ];
+    accounts.extend(queue_computation_accounts(
+        &POST_MSG_PROGRAM_ID,
+        "init_sealed_auction",
+        cluster_offset,
+        computation_offset,
+    ));
+
+    anchor_instruction(
+        POST_MSG_PROGRAM_ID,
+        "create_auction",
+        &(computation_offset, auction_id, deposit, reserve_price, end_time, featured_duration, mxe_nonce),
+        accounts,
+    )
+}
+
+/// `place_sealed_bid`: escrows the auction deposit and folds `encrypted_bid` (encrypted to
+/// the MXE with `bid_pubkey` / `bid_nonce`) for `post`, one of the bidder's posts
+#[allow(clippy::too_many_arguments)]
+pub fn place_sealed_bid(
+    bidder: Pubkey,
+    auction_id: u64,
+    post: Pubkey,
+    cluster_offset: u32,
+    computation_offset: u64,
+    encrypted_bid: [u8; 32],
+    bid_pubkey: [u8; 32],
+    bid_nonce: u128,
+) -> Instruction {
+    let auction = pda::auction_pda(auction_id).0;
+    let mut accounts = vec![
+        AccountMeta::new(bidder, true),
+        AccountMeta::new(auction, false),
+        AccountMeta::new_readonly(post, false),
+        AccountMeta::new(pda::sealed_bid_pda(&auction, &bidder).0, false),
+    ];
+    accounts.extend(queue_computation_accounts(
+        &POST_MSG_PROGRAM_ID,
+        "place_sealed_bid",
+        cluster_offset,
+        computation_offset,
+    ));
+
+    anchor_instruction(
+        POST_MSG_PROGRAM_ID,
+        "place_sealed_bid",
+        &(computation_offset, encrypted_bid, bid_pubkey, bid_nonce),
+        accounts,
+    )
+}
+
+/// `resolve_auction` (permissionless, after `end_time`): queues the winner selection
+pub fn resolve_auction(payer: Pubkey, auction_id: u64, cluster_offset: u32, computation_offset: u64) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(payer, true),
+        AccountMeta::new(pda::auction_pda(auction_id).0, false),
+    ];
+    accounts.extend(queue_computation_accounts(
+        &POST_MSG_PROGRAM_ID,
+        "resolve_sealed_auction",
+        cluster_offset,
+        computation_offset,
+    ));
+
+    anchor_instruction(POST_MSG_PROGRAM_ID, "resolve_auction", &(computation_offset,), accounts)
+}
+
+/// `reset_auction_pending` (permissionless): ends the auction without a winner once its
+/// computation has gone without a callback for `PENDING_TIMEOUT_SECS`, so every bidder can
+/// withdraw their deposit
+pub fn reset_auction_pending(payer: Pubkey, auction_id: u64) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(payer, true),
+        AccountMeta::new(pda::auction_pda(auction_id).0, false),
+    ];
+
+    anchor_instruction(POST_MSG_PROGRAM_ID, "reset_auction_pending", &(), accounts)
+}
+
+/// `withdraw_sealed_bid`: refunds the bidder's deposit (minus the clearing price for the
+/// winner) and closes their bid receipt
+pub fn withdraw_sealed_bid(bidder: Pubkey, auction_id: u64) -> Instruction {
+    let auction = pda::auction_pda(auction_id).0;
+    let accounts = vec![
+        AccountMeta::new(bidder, true),
+        AccountMeta::new(auction, false),
+        AccountMeta::new(pda::sealed_bid_pda(&auction, &bidder).0, false),
+    ];
+
+    anchor_instruction(POST_MSG_PROGRAM_ID, "withdraw_sealed_bid", &(), accounts)
+}
+
+/// `initialize_vesting` (admin only): from now on the 10% tranche accrues in the vesting
+/// vault and vests linearly to WALLET_2 over `duration` seconds
+pub fn initialize_vesting(admin: Pubkey, duration: i64) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(admin, true),
+        AccountMeta::new(pda::config_pda().0, false),
+        AccountMeta::new(pda::vesting_vault_pda().0, false),
+        AccountMeta::new_readonly(anchor_lang::system_program::ID, false),
+    ];
+
+    anchor_instruction(POST_MSG_PROGRAM_ID, "initialize_vesting", &(duration,), accounts)
+}
+
+/// `claim_vested` (permissionless): pays WALLET_2 what has vested since its last claim
+pub fn claim_vested() -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(WALLET_2, false),
+        AccountMeta::new(pda::vesting_vault_pda().0, false),
+    ];
+
+    anchor_instruction(POST_MSG_PROGRAM_ID, "claim_vested", &(), accounts)
+}
+
+/// `claim_vested_token` (permissionless): pays `beneficiary_token`, WALLET_2's account for
+/// `mint`, what has vested of the token tranche accrued in `vault_token`, the vesting vault's
+/// account for `mint`
+pub fn claim_vested_token(beneficiary_token: Pubkey, vault_token: Pubkey, mint: Pubkey, token_program: Pubkey) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(beneficiary_token, false),
+        AccountMeta::new_readonly(pda::vesting_vault_pda().0, false),
+        AccountMeta::new_readonly(mint, false),
+        AccountMeta::new(pda::mint_treasury_pda(&mint).0, false),
+        AccountMeta::new(vault_token, false),
+        AccountMeta::new_readonly(token_program, false),
+    ];
+
+    anchor_instruction(POST_MSG_PROGRAM_ID, "claim_vested_token", &(), accounts)
+}
+
+/// `register_target` (admin only): `owner` may accept escrowed posts to `target`
+pub fn register_target(admin: Pubkey, target: &str, owner: Pubkey) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(admin, true),
+        AccountMeta::new_readonly(pda::config_pda().0, false),
+        AccountMeta::new(pda::target_account_pda(target).0, false),
+        AccountMeta::new_readonly(anchor_lang::system_program::ID, false),
+    ];
+
+    anchor_instruction(POST_MSG_PROGRAM_ID, "register_target", &(target, owner), accounts)
+}
+
+/// `set_escrow_params` (admin only): how long escrowed bids wait for the target owner,
+/// and the share kept when the author reclaims them
+pub fn set_escrow_params(admin: Pubkey, escrow_window_secs: i64, escrow_keep_fee_bps: u16) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(admin, true),
+        AccountMeta::new(pda::config_pda().0, false),
+    ];
+
+    anchor_instruction(
+        POST_MSG_PROGRAM_ID,
+        "set_escrow_params",
+        &(escrow_window_secs, escrow_keep_fee_bps),
+        accounts,
+    )
+}
+
+/// `create_post_escrowed`: creates the post and holds `bid` until the registered owner of
+/// `target` accepts it. `with_stake` passes the author's stake account (required when the
+/// config has a minimum stake)
+pub fn create_post_escrowed(
+    author: Pubkey,
+    target: &str,
+    content: &str,
+    bid: u64,
+    price_update: Pubkey,
+    with_stake: bool,
+) -> Instruction {
+    let post = pda::post_pda(&author, target).0;
+    let accounts = vec![
+        AccountMeta::new(author, true),
+        AccountMeta::new_readonly(pda::config_pda().0, false),
+        AccountMeta::new_readonly(price_update, false),
+        AccountMeta::new_readonly(pda::target_account_pda(target).0, false),
+        AccountMeta::new(pda::author_index_pda(&author).0, false),
+        AccountMeta::new(post, false),
+        AccountMeta::new(pda::post_escrow_pda(&post).0, false),
+        AccountMeta::new(pda::author_stats_pda(&author).0, false),
+        optional_account(POST_MSG_PROGRAM_ID, with_stake.then(|| pda::stake_pda(&author).0), false),
+        AccountMeta::new_readonly(anchor_lang::system_program::ID, false),
+    ];
+
+    anchor_instruction(POST_MSG_PROGRAM_ID, "create_post_escrowed", &(target, content, bid), accounts)
+}
+
+/// `accept_post` (registered owner of `target` only): releases the escrowed bid of `author`'s
+/// post through the revenue split; the tranche recipient and treasury version are read from
+/// `config`
+pub fn accept_post(owner: Pubkey, author: Pubkey, target: &str, epoch: u64, config: &Config) -> Instruction {
+    let post = pda::post_pda(&author, target).0;
+    let accounts = vec![
+        AccountMeta::new(owner, true),
+        AccountMeta::new_readonly(pda::config_pda().0, false),
+        AccountMeta::new_readonly(pda::target_account_pda(target).0, false),
+        AccountMeta::new(author, false),
+        AccountMeta::new(pda::post_escrow_pda(&post).0, false),
+        AccountMeta::new(pda::revenue_snapshot_pda(epoch).0, false),
+        AccountMeta::new(pda::treasury_pda(config.treasury_version).0, false),
+        AccountMeta::new(WALLET_1, false),
+        AccountMeta::new(config.tranche_recipient(), false),
+        AccountMeta::new(WALLET_3, false),
+        AccountMeta::new_readonly(anchor_lang::system_program::ID, false),
+    ];
+
+    anchor_instruction(POST_MSG_PROGRAM_ID, "accept_post", &(), accounts)
+}
+
+/// `reclaim_escrow` (author only, once the escrow window is over): refunds the bid minus
+/// the keep fee, and the escrow rent
+pub fn reclaim_escrow(author: Pubkey, post: Pubkey, treasury_version: u8) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(author, true),
+        AccountMeta::new_readonly(pda::config_pda().0, false),
+        AccountMeta::new(pda::post_escrow_pda(&post).0, false),
+        AccountMeta::new(pda::treasury_pda(treasury_version).0, false),
+    ];
+
+    anchor_instruction(POST_MSG_PROGRAM_ID, "reclaim_escrow", &(), accounts)
+}
+
+/// `create_sponsorship`: escrows `periods` bids of `bid_per_period`, paid out one per
+/// `period_secs` by `process_sponsorship_period`
+#[allow(clippy::too_many_arguments)]
+pub fn create_sponsorship(
+    sponsor: Pubkey,
+    target: &str,
+    content: &str,
+    bid_per_period: u64,
+    period_secs: i64,
+    periods: u32,
+    price_update: Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(sponsor, true),
+        AccountMeta::new_readonly(pda::config_pda().0, false),
+        AccountMeta::new_readonly(price_update, false),
+        AccountMeta::new(pda::sponsorship_pda(&sponsor, target).0, false),
+        AccountMeta::new_readonly(anchor_lang::system_program::ID, false),
+    ];
+
+    anchor_instruction(
+        POST_MSG_PROGRAM_ID,
+        "create_sponsorship",
+        &(target, content, bid_per_period, period_secs, periods),
+        accounts,
+    )
+}
+
+/// `process_sponsorship_period` (permissionless, once a period is due): pays the period's bid
+/// through the revenue split and creates or refreshes the sponsorship's post; the tranche
+/// recipient and treasury version are read from `config`. `with_stake` passes the sponsor's
+/// stake account (required when the config has a minimum stake)
+pub fn process_sponsorship_period(
+    cranker: Pubkey,
+    sponsor: Pubkey,
+    target: &str,
+    epoch: u64,
+    config: &Config,
+    with_stake: bool,
+) -> Instruction {
+    let sponsorship = pda::sponsorship_pda(&sponsor, target).0;
+    let accounts = vec![
+        AccountMeta::new(cranker, true),
+        AccountMeta::new_readonly(pda::config_pda().0, false),
+        AccountMeta::new(sponsorship, false),
+        AccountMeta::new(pda::post_pda(&sponsorship, target).0, false),
+        AccountMeta::new(pda::revenue_snapshot_pda(epoch).0, false),
+        AccountMeta::new(pda::treasury_pda(config.treasury_version).0, false),
+        AccountMeta::new(WALLET_1, false),
+        AccountMeta::new(config.tranche_recipient(), false),
+        AccountMeta::new(WALLET_3, false),
+        AccountMeta::new(pda::author_stats_pda(&sponsor).0, false),
+        optional_account(POST_MSG_PROGRAM_ID, with_stake.then(|| pda::stake_pda(&sponsor).0), false),
+        AccountMeta::new_readonly(anchor_lang::system_program::ID, false),
+    ];
+
+    anchor_instruction(POST_MSG_PROGRAM_ID, "process_sponsorship_period", &(), accounts)
+}
+
+/// `cancel_sponsorship` (sponsor only): refunds the unspent periods and the rent, and hands
+/// the sponsorship's post (if a period created it) to the sponsor
+pub fn cancel_sponsorship(sponsor: Pubkey, target: &str) -> Instruction {
+    let sponsorship = pda::sponsorship_pda(&sponsor, target).0;
+    let accounts = vec![
+        AccountMeta::new(sponsor, true),
+        AccountMeta::new(sponsorship, false),
+        AccountMeta::new(pda::post_pda(&sponsorship, target).0, false),
+    ];
+
+    anchor_instruction(POST_MSG_PROGRAM_ID, "cancel_sponsorship", &(), accounts)
+}
+
+/// `schedule_post`: escrows the bid, the crank reward and the post rent until `publish_at`.
+/// `with_stake` passes the author's stake account (required when the config has a minimum stake)
+#[allow(clippy::too_many_arguments)]
+pub fn schedule_post(
+    author: Pubkey,
+    target: &str,
+    content: &str,
+    bid: u64,
+    publish_at: i64,
+    price_update: Pubkey,
+    with_stake: bool,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(author, true),
+        AccountMeta::new_readonly(pda::config_pda().0, false),
+        AccountMeta::new_readonly(price_update, false),
+        AccountMeta::new(pda::scheduled_post_pda(&author, target).0, false),
+        AccountMeta::new(pda::author_stats_pda(&author).0, false),
+        optional_account(POST_MSG_PROGRAM_ID, with_stake.then(|| pda::stake_pda(&author).0), false),
+        AccountMeta::new_readonly(anchor_lang::system_program::ID, false),
+    ];
+
+    anchor_instruction(POST_MSG_PROGRAM_ID, "schedule_post", &(target, content, bid, publish_at), accounts)
+}
+
+/// `publish_scheduled_post` (permissionless, from `publish_at`): the cranker fronts the post
+/// rent and gets it back with `SCHEDULE_CRANK_REWARD`; the tranche recipient and treasury
+/// version are read from `config`
+pub fn publish_scheduled_post(cranker: Pubkey, author: Pubkey, target: &str, epoch: u64, config: &Config) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(cranker, true),
+        AccountMeta::new_readonly(pda::config_pda().0, false),
+        AccountMeta::new(author, false),
+        AccountMeta::new(pda::scheduled_post_pda(&author, target).0, false),
+        AccountMeta::new(pda::post_pda(&author, target).0, false),
+        AccountMeta::new(pda::author_index_pda(&author).0, false),
+        AccountMeta::new(pda::revenue_snapshot_pda(epoch).0, false),
+        AccountMeta::new(pda::treasury_pda(config.treasury_version).0, false),
+        AccountMeta::new(WALLET_1, false),
+        AccountMeta::new(config.tranche_recipient(), false),
+        AccountMeta::new(WALLET_3, false),
+        AccountMeta::new_readonly(anchor_lang::system_program::ID, false),
+    ];
+
+    anchor_instruction(POST_MSG_PROGRAM_ID, "publish_scheduled_post", &(), accounts)
+}
+
+/// `cancel_scheduled_post` (author only, before publication): refunds the whole escrow
+pub fn cancel_scheduled_post(author: Pubkey, target: &str) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(author, true),
+        AccountMeta::new(pda::scheduled_post_pda(&author, target).0, false),
+    ];
+
+    anchor_instruction(POST_MSG_PROGRAM_ID, "cancel_scheduled_post", &(), accounts)
+}
+
+/// `initialize_metrics` (permissionless, once); see [`super::with_metrics`]
+pub fn initialize_metrics(payer: Pubkey) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(payer, true),
+        AccountMeta::new(pda::post_metrics_pda().0, false),
+        AccountMeta::new_readonly(anchor_lang::system_program::ID, false),
+    ];
+
+    anchor_instruction(POST_MSG_PROGRAM_ID, "initialize_metrics", &(), accounts)
+}
+
+/// Sample post created by `devnet_seed` (bid recorded, not paid)
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct DemoPost {
+    pub author: Pubkey,
+    pub target: String,
+    pub content: String,
+    pub bid: u64,
+}
+
+/// `devnet_seed` (admin only, `devnet-tools` builds only): funds the treasury with
+/// `treasury_lamports` and creates `posts`
+pub fn devnet_seed(admin: Pubkey, treasury_version: u8, treasury_lamports: u64, posts: Vec<DemoPost>) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(admin, true),
+        AccountMeta::new_readonly(pda::config_pda().0, false),
+        AccountMeta::new(pda::treasury_pda(treasury_version).0, false),
+        AccountMeta::new_readonly(anchor_lang::system_program::ID, false),
+    ];
+    accounts.extend(posts.iter().map(|post| AccountMeta::new(pda::post_pda(&post.author, &post.target).0, false)));
+
+    anchor_instruction(POST_MSG_PROGRAM_ID, "devnet_seed", &(treasury_lamports, posts), accounts)
+}
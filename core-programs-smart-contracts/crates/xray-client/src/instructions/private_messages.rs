@@ -0,0 +1,223 @@
+//! `private_messages` instructions.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use xray_common::ids::PRIVATE_MESSAGES_PROGRAM_ID;
+use xray_common::pda;
+
+use super::anchor_instruction;
+use super::arcium::queue_computation_accounts;
+
+pub fn register_user(owner: Pubkey, x25519_pubkey: [u8; 32]) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(owner, true),
+        AccountMeta::new(pda::user_pda(&owner).0, false),
+        AccountMeta::new_readonly(anchor_lang::system_program::ID, false),
+    ];
+
+    anchor_instruction(PRIVATE_MESSAGES_PROGRAM_ID, "register_user", &(x25519_pubkey,), accounts)
+}
+
+pub fn update_user_key(owner: Pubkey, new_x25519_pubkey: [u8; 32]) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(owner, true),
+        AccountMeta::new(pda::user_pda(&owner).0, false),
+    ];
+
+    anchor_instruction(PRIVATE_MESSAGES_PROGRAM_ID, "update_user_key", &(new_x25519_pubkey,), accounts)
+}
+
+/// `send_message`; `recipient_message_count` is the recipient's current
+/// `UserAccount::message_count`, which keys the new message account
+pub fn send_message(
+    sender: Pubkey,
+    recipient: Pubkey,
+    recipient_message_count: u64,
+    encrypted_content: Vec<u8>,
+    nonce: [u8; 24],
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(sender, true),
+        AccountMeta::new(pda::user_pda(&recipient).0, false),
+        AccountMeta::new(pda::message_pda(&sender, &recipient, recipient_message_count).0, false),
+        AccountMeta::new_readonly(anchor_lang::system_program::ID, false),
+    ];
+
+    anchor_instruction(PRIVATE_MESSAGES_PROGRAM_ID, "send_message", &(encrypted_content, nonce), accounts)
+}
+
+pub fn mark_as_read(reader: Pubkey, message: Pubkey) -> Instruction {
+    let accounts = vec![AccountMeta::new_readonly(reader, true), AccountMeta::new(message, false)];
+
+    anchor_instruction(PRIVATE_MESSAGES_PROGRAM_ID, "mark_as_read", &(), accounts)
+}
+
+/// `set_notification_prefs`: `flags` combines the `sizes::NOTIFY_*` bits (0 = none)
+pub fn set_notification_prefs(owner: Pubkey, flags: u8) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(owner, true),
+        AccountMeta::new(pda::notification_prefs_pda(&owner).0, false),
+        AccountMeta::new_readonly(anchor_lang::system_program::ID, false),
+    ];
+
+    anchor_instruction(PRIVATE_MESSAGES_PROGRAM_ID, "set_notification_prefs", &(flags,), accounts)
+}
+
+/// Arguments of `send_private_message`; the identity hashes are MPC-encrypted by the caller
+#[derive(Debug, Clone)]
+pub struct PrivateMessage {
+    pub message_index: u64,
+    pub encrypted_sender_hash: [u8; 32],
+    pub encrypted_recipient_hash: [u8; 32],
+    pub encrypted_content: Vec<u8>,
+    pub nonce: [u8; 24],
+    pub mpc_pubkey: [u8; 32],
+    pub mpc_nonce: u128,
+}
+
+/// `send_private_message`; needs a send token granted by [`request_send_token`]
+pub fn send_private_message(sender: Pubkey, message: &PrivateMessage) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(sender, true),
+        AccountMeta::new(pda::private_message_counter_pda().0, false),
+        AccountMeta::new(pda::private_message_pda(&sender, message.message_index).0, false),
+        AccountMeta::new(pda::rate_bucket_pda(&sender).0, false),
+        AccountMeta::new_readonly(anchor_lang::system_program::ID, false),
+    ];
+    let args = (
+        message.message_index,
+        message.encrypted_sender_hash,
+        message.encrypted_recipient_hash,
+        &message.encrypted_content,
+        message.nonce,
+        message.mpc_pubkey,
+        message.mpc_nonce,
+    );
+
+    anchor_instruction(PRIVATE_MESSAGES_PROGRAM_ID, "send_private_message", &args, accounts)
+}
+
+/// `verify_private_message_access`: asks the MPC whether the requester is the recipient of
+/// `private_message`; the answer is encrypted back to `mpc_pubkey` (see `PrivateAccessVerified`)
+pub fn verify_private_message_access(
+    payer: Pubkey,
+    private_message: Pubkey,
+    cluster_offset: u32,
+    computation_offset: u64,
+    encrypted_requester_hash: [u8; 32],
+    mpc_pubkey: [u8; 32],
+    mpc_nonce: u128,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(payer, true),
+        AccountMeta::new_readonly(private_message, false),
+    ];
+    accounts.extend(queue_computation_accounts(
+        &PRIVATE_MESSAGES_PROGRAM_ID,
+        "verify_and_reveal_sender",
+        cluster_offset,
+        computation_offset,
+    ));
+
+    anchor_instruction(
+        PRIVATE_MESSAGES_PROGRAM_ID,
+        "verify_private_message_access",
+        &(computation_offset, encrypted_requester_hash, mpc_pubkey, mpc_nonce),
+        accounts,
+    )
+}
+
+/// `create_rate_bucket`: creates the sender's encrypted send-rate bucket
+pub fn create_rate_bucket(sender: Pubkey, cluster_offset: u32, computation_offset: u64, mxe_nonce: u128) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(sender, true),
+        AccountMeta::new(pda::rate_bucket_pda(&sender).0, false),
+    ];
+    accounts.extend(queue_computation_accounts(
+        &PRIVATE_MESSAGES_PROGRAM_ID,
+        "init_rate_bucket",
+        cluster_offset,
+        computation_offset,
+    ));
+
+    anchor_instruction(
+        PRIVATE_MESSAGES_PROGRAM_ID,
+        "create_rate_bucket",
+        &(computation_offset, mxe_nonce),
+        accounts,
+    )
+}
+
+/// `request_send_token`: asks the MPC for one send token from the sender's bucket
+pub fn request_send_token(sender: Pubkey, cluster_offset: u32, computation_offset: u64) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(sender, true),
+        AccountMeta::new(pda::rate_bucket_pda(&sender).0, false),
+    ];
+    accounts.extend(queue_computation_accounts(
+        &PRIVATE_MESSAGES_PROGRAM_ID,
+        "consume_rate_token",
+        cluster_offset,
+        computation_offset,
+    ));
+
+    anchor_instruction(PRIVATE_MESSAGES_PROGRAM_ID, "request_send_token", &(computation_offset,), accounts)
+}
+
+/// `reset_rate_bucket_pending`: unlocks the sender's bucket once its computation has gone
+/// without a callback for `PENDING_TIMEOUT_SECS` (closes the bucket if it was never initialized)
+pub fn reset_rate_bucket_pending(sender: Pubkey) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(sender, true),
+        AccountMeta::new(pda::rate_bucket_pda(&sender).0, false),
+    ];
+
+    anchor_instruction(PRIVATE_MESSAGES_PROGRAM_ID, "reset_rate_bucket_pending", &(), accounts)
+}
+
+/// `initialize_metrics` (permissionless, once); see [`super::with_metrics`]
+pub fn initialize_metrics(payer: Pubkey) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(payer, true),
+        AccountMeta::new(pda::private_messages_metrics_pda().0, false),
+        AccountMeta::new_readonly(anchor_lang::system_program::ID, false),
+    ];
+
+    anchor_instruction(PRIVATE_MESSAGES_PROGRAM_ID, "initialize_metrics", &(), accounts)
+}
+
+/// Demo user registered by `devnet_seed` (no signature needed)
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct DemoUser {
+    pub wallet: Pubkey,
+    pub x25519_pubkey: [u8; 32],
+}
+
+/// Message created by `devnet_seed` between two of its users (indices into `users`)
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct DemoMessage {
+    pub sender: u8,
+    pub recipient: u8,
+    pub encrypted_content: Vec<u8>,
+    pub nonce: [u8; 24],
+}
+
+/// `devnet_seed` (`devnet-tools` builds only): registers `users` and creates `messages`.
+/// `message_counts` are the users' current `UserAccount::message_count` (0 for new users),
+/// which key the message accounts.
+pub fn devnet_seed(payer: Pubkey, users: Vec<DemoUser>, message_counts: &[u64], messages: Vec<DemoMessage>) -> Instruction {
+    let mut counts = message_counts.to_vec();
+    let mut accounts = vec![
+        AccountMeta::new(payer, true),
+        AccountMeta::new_readonly(anchor_lang::system_program::ID, false),
+    ];
+    accounts.extend(users.iter().map(|user| AccountMeta::new(pda::user_pda(&user.wallet).0, false)));
+    for message in &messages {
+        let (sender, recipient) = (users[message.sender as usize].wallet, users[message.recipient as usize].wallet);
+        let index = &mut counts[message.recipient as usize];
+        accounts.push(AccountMeta::new(pda::message_pda(&sender, &recipient, *index).0, false));
+        *index += 1;
+    }
+
+    anchor_instruction(PRIVATE_MESSAGES_PROGRAM_ID, "devnet_seed", &(users, messages), accounts)
+}
@@ -0,0 +1,290 @@
+//! Account mirrors as JSON objects, for tools that export state (`xray-geyser`,
+//! `xray-snapshot`): addresses in base58, byte arrays in hex, u128 as strings. Bumps and
+//! encrypted MPC state are left out.
+
+use anchor_lang::prelude::Pubkey;
+use serde_json::{json, Value};
+use xray_common::ids::{POST_MSG_PROGRAM_ID, PRIVATE_MESSAGES_PROGRAM_ID};
+
+use crate::accounts::{self, *};
+
+type Normalizer = fn(&[u8]) -> Option<Value>;
+
+macro_rules! normalizers {
+    ($($name:ident),* $(,)?) => {
+        &[$((stringify!($name), |data| accounts::decode::<$name>(data).ok().map(|account| account.normalize()))),*]
+    };
+}
+
+const POST_ACCOUNTS: &[(&str, Normalizer)] = normalizers![
+    Config, Post, Referral, Charity, AuthorIndex, AuthorStats, StakeAccount, TargetAccount, TargetIdentity,
+    UpvoteReceipt, PostMetrics,
+];
+
+const PRIVATE_MESSAGES_ACCOUNTS: &[(&str, Normalizer)] = normalizers![
+    UserAccount, MessageAccount, PrivateMessageAccount, PrivateMessageCounter, RateBucket, Poll, NotificationPrefs,
+    MessagesMetrics,
+];
+
+/// Account type and fields of an account owned by `program_id`, if its discriminator is one
+/// of that program's mirrored accounts and it decodes
+pub fn decode(program_id: &Pubkey, data: &[u8]) -> Option<(&'static str, Value)> {
+    let candidates = if *program_id == POST_MSG_PROGRAM_ID {
+        POST_ACCOUNTS
+    } else if *program_id == PRIVATE_MESSAGES_PROGRAM_ID {
+        PRIVATE_MESSAGES_ACCOUNTS
+    } else {
+        return None;
+    };
+    let discriminator = data.get(..8)?;
+    let (name, normalize) = candidates.iter().find(|(name, _)| accounts::discriminator(name) == discriminator)?;
+    Some((name, normalize(data)?))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Account mirror as a JSON object
+trait Normalize {
+    fn normalize(&self) -> Value;
+}
+
+impl Normalize for Config {
+    fn normalize(&self) -> Value {
+        json!({
+            "admin": self.admin.to_string(),
+            "min_bid_usd": self.min_bid_usd,
+            "referral_bps": self.referral_bps,
+            "receipt_tree": self.receipt_tree.to_string(),
+            "rate_window_secs": self.rate_window_secs,
+            "max_posts_per_window": self.max_posts_per_window,
+            "rate_waiver_bid_usd": self.rate_waiver_bid_usd,
+            "min_stake": self.min_stake,
+            "stake_cooldown_secs": self.stake_cooldown_secs,
+            "vesting_vault": self.vesting_vault.to_string(),
+            "pending_admin": self.pending_admin.to_string(),
+            "escrow_window_secs": self.escrow_window_secs,
+            "escrow_keep_fee_bps": self.escrow_keep_fee_bps,
+            "upvote_lamports": self.upvote_lamports,
+            "treasury_version": self.treasury_version,
+            "view_attestor": self.view_attestor.to_string(),
+        })
+    }
+}
+
+impl Normalize for Post {
+    fn normalize(&self) -> Value {
+        json!({
+            "author": self.author.to_string(),
+            "target": self.target,
+            "content": self.content,
+            "bid": self.bid,
+            "timestamp": self.timestamp,
+            "version": self.version,
+            "total_boosts": self.total_boosts,
+            "rank_score": self.rank_score,
+            "rank_updated_at": self.rank_updated_at,
+            "bid_mint": self.bid_mint.to_string(),
+            "upvotes": self.upvotes,
+            "flags": self.flags,
+            "views": self.views,
+            "charity": self.charity.to_string(),
+            "charity_amount": self.charity_amount,
+            "license": self.license,
+            "content_signature": hex(&self.content_signature),
+        })
+    }
+}
+
+impl Normalize for Referral {
+    fn normalize(&self) -> Value {
+        json!({
+            "wallet": self.wallet.to_string(),
+            "total_earned": self.total_earned,
+            "referral_count": self.referral_count,
+        })
+    }
+}
+
+impl Normalize for Charity {
+    fn normalize(&self) -> Value {
+        json!({
+            "wallet": self.wallet.to_string(),
+            "share_bps": self.share_bps,
+            "total_received": self.total_received,
+        })
+    }
+}
+
+impl Normalize for AuthorIndex {
+    fn normalize(&self) -> Value {
+        json!({
+            "author": self.author.to_string(),
+            "post_count": self.post_count,
+            "recent_posts": self.recent_posts.iter().map(Pubkey::to_string).collect::<Vec<_>>(),
+        })
+    }
+}
+
+impl Normalize for AuthorStats {
+    fn normalize(&self) -> Value {
+        json!({
+            "author": self.author.to_string(),
+            "window_start": self.window_start,
+            "window_posts": self.window_posts,
+            "total_posts": self.total_posts,
+            "relay_nonce": self.relay_nonce,
+        })
+    }
+}
+
+impl Normalize for StakeAccount {
+    fn normalize(&self) -> Value {
+        json!({
+            "author": self.author.to_string(),
+            "amount": self.amount,
+            "unlock_requested_at": self.unlock_requested_at,
+        })
+    }
+}
+
+impl Normalize for TargetAccount {
+    fn normalize(&self) -> Value {
+        json!({
+            "target": self.target,
+            "owner": self.owner.to_string(),
+            "pinned_post": self.pinned_post.to_string(),
+            "pinned_until": self.pinned_until,
+            "pin_fee": self.pin_fee,
+            "identity_key": self.identity_key.to_string(),
+            "verified_at": self.verified_at,
+        })
+    }
+}
+
+impl Normalize for TargetIdentity {
+    fn normalize(&self) -> Value {
+        json!({
+            "identity_key": self.identity_key.to_string(),
+            "attested_by": self.attested_by.to_string(),
+            "attested_at": self.attested_at,
+        })
+    }
+}
+
+impl Normalize for UpvoteReceipt {
+    fn normalize(&self) -> Value {
+        json!({
+            "post": self.post.to_string(),
+            "voter": self.voter.to_string(),
+            "timestamp": self.timestamp,
+        })
+    }
+}
+
+impl Normalize for PostMetrics {
+    fn normalize(&self) -> Value {
+        json!({
+            "posts_created": self.posts_created,
+            "messages_sent": self.messages_sent,
+            "computations_queued": self.computations_queued,
+            "computations_completed": self.computations_completed,
+            "computations_failed": self.computations_failed,
+            "upvotes": self.upvotes,
+            "boosts": self.boosts,
+        })
+    }
+}
+
+impl Normalize for UserAccount {
+    fn normalize(&self) -> Value {
+        json!({
+            "wallet": self.wallet.to_string(),
+            "x25519_pubkey": hex(&self.x25519_pubkey),
+            "message_count": self.message_count,
+        })
+    }
+}
+
+impl Normalize for MessageAccount {
+    fn normalize(&self) -> Value {
+        json!({
+            "sender": self.sender.to_string(),
+            "recipient": self.recipient.to_string(),
+            "nonce": hex(&self.nonce),
+            "timestamp": self.timestamp,
+            "is_read": self.is_read,
+            "encrypted_content": hex(&self.encrypted_content),
+        })
+    }
+}
+
+impl Normalize for PrivateMessageAccount {
+    fn normalize(&self) -> Value {
+        json!({
+            "encrypted_sender_hash": hex(&self.encrypted_sender_hash),
+            "encrypted_recipient_hash": hex(&self.encrypted_recipient_hash),
+            "encrypted_content": hex(&self.encrypted_content),
+            "nonce": hex(&self.nonce),
+            "timestamp": self.timestamp,
+            "mpc_pubkey": hex(&self.mpc_pubkey),
+            "mpc_nonce": self.mpc_nonce.to_string(),
+        })
+    }
+}
+
+impl Normalize for PrivateMessageCounter {
+    fn normalize(&self) -> Value {
+        json!({ "count": self.count })
+    }
+}
+
+impl Normalize for RateBucket {
+    fn normalize(&self) -> Value {
+        json!({
+            "sender": self.sender.to_string(),
+            "send_authorized": self.send_authorized,
+            "computation_pending": self.computation_pending,
+            "pending_since": self.pending_since,
+            "pending_computation": self.pending_computation,
+        })
+    }
+}
+
+impl Normalize for Poll {
+    fn normalize(&self) -> Value {
+        json!({
+            "creator": self.creator.to_string(),
+            "membership_set": self.membership_set.to_string(),
+            "poll_id": self.poll_id,
+            "question_hash": hex(&self.question_hash),
+            "closes_at": self.closes_at,
+            "voter_count": self.voter_count,
+            "computation_pending": self.computation_pending,
+            "pending_since": self.pending_since,
+            "pending_computation": self.pending_computation,
+        })
+    }
+}
+
+impl Normalize for NotificationPrefs {
+    fn normalize(&self) -> Value {
+        json!({
+            "owner": self.owner.to_string(),
+            "flags": self.flags,
+        })
+    }
+}
+
+impl Normalize for MessagesMetrics {
+    fn normalize(&self) -> Value {
+        json!({
+            "users_registered": self.users_registered,
+            "messages_sent": self.messages_sent,
+            "computations_queued": self.computations_queued,
+            "computations_completed": self.computations_completed,
+            "computations_failed": self.computations_failed,
+        })
+    }
+}
@@ -0,0 +1,29 @@
+//! Rust client SDK for the X-RAY programs.
+//!
+//! - [`instructions`]: typed instruction builders (Anchor sighash + Borsh args, accounts in program order)
+//! - [`accounts`]: account decoding, checked against the Anchor discriminator
+//! - [`json`]: accounts as JSON objects, for state exports
+//! - [`filters`]: `getProgramAccounts` memcmp filters (messages by recipient or sender, posts by author or target)
+//! - [`crypto`]: message encryption, compatible with the web client (NaCl box over X25519)
+//! - [`mpc`]: Arcium MPC inputs and results (Rescue cipher keyed by an x25519 exchange with the MXE)
+//! - [`flows`]: blocking RPC flows (register, encrypt and send a message, create a post with its split accounts)
+//! - [`nonblocking`]: async (tokio) counterpart of the flows client, with event subscriptions
+//! - [`stream`]: typed event streams over websocket log subscriptions, with reconnection and cursor resume
+//!
+//! PDA derivation, seeds, sizes and event decoding (`xray_common::events::decode_event`) come from
+//! [`xray_common`], re-exported here.
+
+pub mod accounts;
+pub mod crypto;
+pub mod error;
+pub mod filters;
+pub mod flows;
+pub mod instructions;
+pub mod json;
+pub mod mpc;
+pub mod nonblocking;
+pub mod stream;
+
+pub use error::{ClientError, Result};
+pub use xray_common;
+pub use xray_common::pda;
@@ -0,0 +1,136 @@
+//! Client side of the Arcium MPC encryption: Rescue cipher (CTR mode over the Curve25519 base
+//! field) keyed by an x25519 exchange with the MXE, the same construction as `RescueCipher` in
+//! `@arcium-hq/client`. Used to build `Enc<Shared, _>` inputs and to read results encrypted back
+//! to the client key.
+
+use anchor_lang::prelude::Pubkey;
+use arcis_compiler::traits::FromLeBytes;
+use arcis_compiler::utils::crypto::key::{RescueKey, RESCUE_KEY_COUNT};
+use arcis_compiler::utils::crypto::rescue_cipher::RescueCipher;
+use arcis_compiler::utils::crypto::rescue_prime_hash::RescuePrimeHash;
+use arcis_compiler::utils::field::BaseField;
+use crypto_box::aead::rand_core::RngCore;
+use crypto_box::aead::OsRng;
+use x25519_dalek::{PublicKey, StaticSecret};
+
+use crate::error::{ClientError, Result};
+
+pub struct MpcCipher {
+    cipher: RescueCipher<BaseField, BaseField>,
+    /// Client x25519 public key, passed to the program as `mpc_pubkey`
+    pub public_key: [u8; 32],
+}
+
+impl MpcCipher {
+    /// Cipher under a fresh ephemeral client key
+    pub fn new(mxe_x25519_pubkey: &[u8; 32]) -> Self {
+        let mut secret = [0u8; 32];
+        OsRng.fill_bytes(&mut secret);
+        Self::from_secret(secret, mxe_x25519_pubkey)
+    }
+
+    /// Cipher under a known client key (to decrypt results later)
+    pub fn from_secret(secret: [u8; 32], mxe_x25519_pubkey: &[u8; 32]) -> Self {
+        let secret = StaticSecret::from(secret);
+        let public_key = PublicKey::from(&secret).to_bytes();
+        let shared_secret = secret.diffie_hellman(&PublicKey::from(*mxe_x25519_pubkey));
+
+        // Key derivation: Rescue-Prime hash of [1, shared secret, key length]
+        let hasher = RescuePrimeHash::<BaseField, BaseField>::new();
+        let key = hasher.digest(vec![
+            BaseField::from(1u64),
+            BaseField::from_le_bytes(shared_secret.to_bytes()),
+            BaseField::from(RESCUE_KEY_COUNT as u64),
+        ]);
+
+        Self { cipher: RescueCipher::new(RescueKey::new_from_inner(key)), public_key }
+    }
+
+    /// Encrypts field elements given as u64 words; one 32-byte ciphertext per word
+    pub fn encrypt(&self, plaintext: &[u64], nonce: u128) -> Vec<[u8; 32]> {
+        let plaintext = plaintext.iter().map(|word| BaseField::from(*word)).collect();
+        self.cipher
+            .encrypt(plaintext, nonce_to_field(nonce))
+            .iter()
+            .map(|c| c.to_le_bytes())
+            .collect()
+    }
+
+    /// Decrypts 32-byte ciphertexts; one little-endian field element per ciphertext
+    pub fn decrypt(&self, ciphertexts: &[[u8; 32]], nonce: u128) -> Vec<[u8; 32]> {
+        let ciphertexts = ciphertexts.iter().map(|c| BaseField::from_le_bytes(*c)).collect();
+        self.cipher
+            .decrypt(ciphertexts, nonce_to_field(nonce))
+            .iter()
+            .map(|p| p.to_le_bytes())
+            .collect()
+    }
+
+    /// A 32-byte identity hash in the web client's layout: four little-endian u64 words,
+    /// each encrypted, keeping the low 8 bytes of every ciphertext
+    pub fn encrypt_identity_hash(&self, hash: &[u8; 32], nonce: u128) -> [u8; 32] {
+        self.encrypt_identity_hashes(&[hash], nonce)[0]
+    }
+
+    /// Several identity hashes (layout of [`MpcCipher::encrypt_identity_hash`]) encrypted in a
+    /// single pass under `nonce`, so no two of them share keystream: the first hash reads back
+    /// as a lone [`MpcCipher::encrypt_identity_hash`] under `nonce`, the next ones continue the
+    /// counter. Never encrypt two hashes separately under the same nonce.
+    pub fn encrypt_identity_hashes(&self, hashes: &[&[u8; 32]], nonce: u128) -> Vec<[u8; 32]> {
+        let words: Vec<u64> = hashes
+            .iter()
+            .flat_map(|hash| hash.chunks_exact(8))
+            .map(|chunk| u64::from_le_bytes(chunk.try_into().unwrap()))
+            .collect();
+
+        self.encrypt(&words, nonce)
+            .chunks_exact(4)
+            .map(|ciphertexts| {
+                let mut encrypted = [0u8; 32];
+                for (out, ciphertext) in encrypted.chunks_exact_mut(8).zip(ciphertexts) {
+                    out.copy_from_slice(&ciphertext[..8]);
+                }
+                encrypted
+            })
+            .collect()
+    }
+}
+
+pub fn random_nonce() -> u128 {
+    let mut nonce = [0u8; 16];
+    OsRng.fill_bytes(&mut nonce);
+    u128::from_le_bytes(nonce)
+}
+
+/// The MXE x25519 public key from the raw `MXEAccount` data of `program_id`'s MXE
+pub fn mxe_x25519_pubkey(program_id: &Pubkey, data: &[u8]) -> Result<[u8; 32]> {
+    let not_ready = || ClientError::MxeKeyUnavailable(*program_id);
+
+    // Anchor discriminator, then cluster: Option<u32>
+    let mut offset = 8;
+    offset += match data.get(offset) {
+        Some(0) => 1,
+        Some(1) => 5,
+        _ => return Err(not_ready()),
+    };
+    // keygen_offset, key_recovery_init_offset, mxe_program_id, then authority: Option<Pubkey>
+    offset += 8 + 8 + 32;
+    offset += match data.get(offset) {
+        Some(0) => 1,
+        Some(1) => 33,
+        _ => return Err(not_ready()),
+    };
+    // utility_pubkeys: SetUnset<UtilityPubkeys>, x25519_pubkey first; Unset until keygen completes
+    if data.get(offset) != Some(&0) {
+        return Err(not_ready());
+    }
+    data.get(offset + 1..offset + 33)
+        .map(|key| key.try_into().unwrap())
+        .ok_or_else(not_ready)
+}
+
+fn nonce_to_field(nonce: u128) -> BaseField {
+    let mut bytes = [0u8; 32];
+    bytes[..16].copy_from_slice(&nonce.to_le_bytes());
+    BaseField::from_le_bytes(bytes)
+}
@@ -0,0 +1,98 @@
+//! Async (tokio) client: account fetching and transaction sending over the nonblocking
+//! `RpcClient`, plus event subscriptions on the same endpoints (see [`crate::stream`]).
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::Event;
+use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_sdk::transaction::Transaction;
+
+use xray_common::ids::PRIVATE_MESSAGES_PROGRAM_ID;
+
+use crate::accounts::{self, NotificationPrefs, XrayAccount};
+use crate::error::{ClientError, Result};
+use crate::filters;
+use crate::stream::{self, EventStream, EventStreamConfig};
+
+pub struct AsyncXrayClient {
+    rpc: RpcClient,
+    ws_url: String,
+}
+
+impl AsyncXrayClient {
+    /// `confirmed` commitment, the lowest one event backfill supports
+    pub fn new(rpc_url: impl Into<String>, ws_url: impl Into<String>) -> Self {
+        Self::with_commitment(rpc_url, ws_url, CommitmentConfig::confirmed())
+    }
+
+    pub fn with_commitment(rpc_url: impl Into<String>, ws_url: impl Into<String>, commitment: CommitmentConfig) -> Self {
+        Self {
+            rpc: RpcClient::new_with_commitment(rpc_url.into(), commitment),
+            ws_url: ws_url.into(),
+        }
+    }
+
+    pub fn rpc(&self) -> &RpcClient {
+        &self.rpc
+    }
+
+    /// Fetches and decodes an account, `None` if it does not exist
+    pub async fn fetch_optional<T: XrayAccount>(&self, address: &Pubkey) -> Result<Option<T>> {
+        let account = self
+            .rpc
+            .get_account_with_commitment(address, self.rpc.commitment())
+            .await?
+            .value;
+        account.map(|account| accounts::decode(&account.data)).transpose()
+    }
+
+    pub async fn fetch<T: XrayAccount>(&self, address: &Pubkey) -> Result<T> {
+        self.fetch_optional(address)
+            .await?
+            .ok_or(ClientError::AccountNotFound(*address))
+    }
+
+    /// Signs `instructions` with `payer` (fee payer) and `signers`, sends and confirms
+    pub async fn send(&self, instructions: &[Instruction], payer: &Keypair, signers: &[&Keypair]) -> Result<Signature> {
+        let mut all_signers: Vec<&Keypair> = vec![payer];
+        all_signers.extend(signers.iter().filter(|s| s.pubkey() != payer.pubkey()));
+
+        let blockhash = self.rpc.get_latest_blockhash().await?;
+        let tx = Transaction::new_signed_with_payer(instructions, Some(&payer.pubkey()), &all_signers, blockhash);
+        Ok(self.rpc.send_and_confirm_transaction(&tx).await?)
+    }
+
+    /// Every wallet's notification preferences
+    pub async fn notification_prefs(&self) -> Result<Vec<NotificationPrefs>> {
+        let filters = vec![filters::account::<NotificationPrefs>()];
+        let config = filters::program_accounts_config(filters, self.rpc.commitment());
+
+        self.rpc
+            .get_program_accounts_with_config(&PRIVATE_MESSAGES_PROGRAM_ID, config)
+            .await?
+            .into_iter()
+            .map(|(_, account)| accounts::decode(&account.data))
+            .collect()
+    }
+
+    // ------------------------------------------------------------------------
+    // Events
+    // ------------------------------------------------------------------------
+
+    /// Stream configuration on this client's endpoints and commitment (both programs, from the tip)
+    pub fn event_config(&self) -> EventStreamConfig {
+        EventStreamConfig::new(self.rpc.url(), self.ws_url.clone()).commitment(self.rpc.commitment())
+    }
+
+    /// Live events of type `E` from both programs
+    pub fn subscribe<E: Event + Send + 'static>(&self) -> EventStream<E> {
+        stream::subscribe(self.event_config())
+    }
+
+    /// Live undecoded events from both programs
+    pub fn subscribe_raw(&self) -> EventStream<Vec<u8>> {
+        stream::subscribe_raw(self.event_config())
+    }
+}
@@ -0,0 +1,394 @@
+//! Typed event streams over websocket log subscriptions.
+//!
+//! One `logsSubscribe` (mentions filter) runs per program. Every delivered event carries an
+//! [`EventCursor`] (slot + signature of its transaction); persist the last one and pass it back
+//! with [`EventStreamConfig::resume_from`] to pick up where a previous run stopped. The same
+//! cursor is used internally after a dropped connection: transactions missed while disconnected
+//! are backfilled over RPC (`getSignaturesForAddress` + `getTransaction`), oldest first, before
+//! live notifications resume.
+//!
+//! Without a cursor the stream starts at the tip; backfill only covers gaps after the first
+//! delivered event.
+
+use std::collections::HashSet;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::Event;
+use base64::Engine;
+use futures_util::{Stream, StreamExt};
+use solana_pubsub_client::nonblocking::pubsub_client::PubsubClient;
+use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+use solana_rpc_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+use solana_rpc_client_api::config::{RpcTransactionConfig, RpcTransactionLogsConfig, RpcTransactionLogsFilter};
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::Signature;
+use solana_transaction_status_client_types::UiTransactionEncoding;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use xray_common::events::XrayEvent;
+use xray_common::{POST_MSG_PROGRAM_ID, PRIVATE_MESSAGES_PROGRAM_ID};
+
+use crate::error::{ClientError, Result};
+
+// getSignaturesForAddress page size (RPC maximum)
+const SIGNATURES_PAGE: usize = 1000;
+
+/// Position of a delivered event: the transaction it was emitted in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventCursor {
+    pub slot: u64,
+    pub signature: Signature,
+}
+
+/// An event with the program that emitted it and its cursor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventEnvelope<T> {
+    pub program_id: Pubkey,
+    pub slot: u64,
+    pub signature: Signature,
+    pub event: T,
+}
+
+/// Undecoded event: Anchor discriminator followed by the Borsh payload
+pub type RawEvent = EventEnvelope<Vec<u8>>;
+
+impl<T> EventEnvelope<T> {
+    pub fn cursor(&self) -> EventCursor {
+        EventCursor { slot: self.slot, signature: self.signature }
+    }
+
+    fn map<U>(self, event: U) -> EventEnvelope<U> {
+        EventEnvelope { program_id: self.program_id, slot: self.slot, signature: self.signature, event }
+    }
+}
+
+impl RawEvent {
+    /// Decodes the payload as `E`, `None` if the discriminator belongs to another event
+    pub fn decode<E: Event>(&self) -> Result<Option<E>> {
+        match self.event.strip_prefix(E::DISCRIMINATOR) {
+            Some(mut payload) => Ok(Some(E::deserialize(&mut payload)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Decodes the payload as whichever event of either program its discriminator names
+    pub fn decode_any(&self) -> Result<Option<XrayEvent>> {
+        Ok(XrayEvent::from_bytes(&self.event)?)
+    }
+}
+
+/// Extracts the `Program data:` payloads emitted directly by one of `programs`, following the
+/// invoke stack so CPI'd programs are attributed correctly
+pub fn parse_logs(logs: &[String], programs: &[Pubkey]) -> Vec<(Pubkey, Vec<u8>)> {
+    let mut stack: Vec<Pubkey> = Vec::new();
+    let mut events = Vec::new();
+
+    for line in logs {
+        if let Some(data) = line.strip_prefix("Program data: ") {
+            let Some(program_id) = stack.last() else { continue };
+            if !programs.contains(program_id) {
+                continue;
+            }
+            if let Ok(bytes) = base64::engine::general_purpose::STANDARD.decode(data) {
+                events.push((*program_id, bytes));
+            }
+        } else if let Some(rest) = line.strip_prefix("Program ") {
+            let mut words = rest.split_whitespace();
+            let (Some(id), Some(action)) = (words.next(), words.next()) else { continue };
+            match action {
+                "invoke" => {
+                    if let Ok(program_id) = Pubkey::from_str(id) {
+                        stack.push(program_id);
+                    }
+                }
+                "success" | "failed:" => {
+                    stack.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+    events
+}
+
+pub struct EventStreamConfig {
+    rpc_url: String,
+    ws_url: String,
+    commitment: CommitmentConfig,
+    programs: Vec<(Pubkey, Option<EventCursor>)>,
+    min_reconnect_delay: Duration,
+    max_reconnect_delay: Duration,
+    channel_capacity: usize,
+}
+
+impl EventStreamConfig {
+    /// Both programs, from the tip, at `confirmed` (the lowest commitment backfill supports)
+    pub fn new(rpc_url: impl Into<String>, ws_url: impl Into<String>) -> Self {
+        Self {
+            rpc_url: rpc_url.into(),
+            ws_url: ws_url.into(),
+            commitment: CommitmentConfig::confirmed(),
+            programs: vec![(POST_MSG_PROGRAM_ID, None), (PRIVATE_MESSAGES_PROGRAM_ID, None)],
+            min_reconnect_delay: Duration::from_millis(500),
+            max_reconnect_delay: Duration::from_secs(30),
+            channel_capacity: 1024,
+        }
+    }
+
+    /// Restricts the stream to `program_ids`
+    pub fn programs(mut self, program_ids: &[Pubkey]) -> Self {
+        self.programs = program_ids.iter().map(|id| (*id, None)).collect();
+        self
+    }
+
+    /// Resumes `program_id` after `cursor` (the last event processed in a previous run)
+    pub fn resume_from(mut self, program_id: Pubkey, cursor: EventCursor) -> Self {
+        match self.programs.iter_mut().find(|(id, _)| *id == program_id) {
+            Some(entry) => entry.1 = Some(cursor),
+            None => self.programs.push((program_id, Some(cursor))),
+        }
+        self
+    }
+
+    pub fn commitment(mut self, commitment: CommitmentConfig) -> Self {
+        self.commitment = commitment;
+        self
+    }
+
+    /// Exponential backoff bounds between reconnection attempts
+    pub fn reconnect_delay(mut self, min: Duration, max: Duration) -> Self {
+        self.min_reconnect_delay = min;
+        self.max_reconnect_delay = max.max(min);
+        self
+    }
+
+    pub fn channel_capacity(mut self, capacity: usize) -> Self {
+        self.channel_capacity = capacity.max(1);
+        self
+    }
+}
+
+/// Stream of events; connection errors are yielded as `Err` items and the stream reconnects.
+/// The background tasks stop when the stream is dropped.
+pub struct EventStream<T> {
+    receiver: mpsc::Receiver<Result<EventEnvelope<T>>>,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl<T> EventStream<T> {
+    pub async fn recv(&mut self) -> Option<Result<EventEnvelope<T>>> {
+        self.receiver.recv().await
+    }
+}
+
+impl<T> Stream for EventStream<T> {
+    type Item = Result<EventEnvelope<T>>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_recv(cx)
+    }
+}
+
+impl<T> Drop for EventStream<T> {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+/// Every event of the configured programs, undecoded. Must be called within a tokio runtime.
+pub fn subscribe_raw(config: EventStreamConfig) -> EventStream<Vec<u8>> {
+    spawn(config, |raw| Some(Ok(raw)))
+}
+
+/// Events of type `E` only; a payload with `E`'s discriminator that fails to decode is yielded
+/// as an `Err` item (layout drift between the program and the client mirror).
+/// Must be called within a tokio runtime.
+pub fn subscribe<E: Event + Send + 'static>(config: EventStreamConfig) -> EventStream<E> {
+    spawn(config, |raw: RawEvent| match raw.decode::<E>() {
+        Ok(Some(event)) => Some(Ok(raw.map(event))),
+        Ok(None) => None,
+        Err(err) => Some(Err(err)),
+    })
+}
+
+type Decoder<T> = dyn Fn(RawEvent) -> Option<Result<EventEnvelope<T>>> + Send + Sync;
+
+fn spawn<T, F>(config: EventStreamConfig, decode: F) -> EventStream<T>
+where
+    T: Send + 'static,
+    F: Fn(RawEvent) -> Option<Result<EventEnvelope<T>>> + Send + Sync + 'static,
+{
+    let (sender, receiver) = mpsc::channel(config.channel_capacity);
+    let decode: Arc<Decoder<T>> = Arc::new(decode);
+    let config = Arc::new(config);
+
+    let tasks = config
+        .programs
+        .iter()
+        .map(|(program_id, cursor)| {
+            let source = ProgramSource {
+                config: config.clone(),
+                program_id: *program_id,
+                cursor: *cursor,
+                sender: sender.clone(),
+                decode: decode.clone(),
+            };
+            tokio::spawn(source.run())
+        })
+        .collect();
+
+    EventStream { receiver, tasks }
+}
+
+// Receiver dropped: the stream is gone, stop the task
+struct Closed;
+
+struct ProgramSource<T> {
+    config: Arc<EventStreamConfig>,
+    program_id: Pubkey,
+    cursor: Option<EventCursor>,
+    sender: mpsc::Sender<Result<EventEnvelope<T>>>,
+    decode: Arc<Decoder<T>>,
+}
+
+impl<T: Send + 'static> ProgramSource<T> {
+    async fn run(mut self) {
+        let rpc = RpcClient::new_with_commitment(self.config.rpc_url.clone(), self.config.commitment);
+        let mut delay = self.config.min_reconnect_delay;
+
+        loop {
+            let err = match self.connect(&rpc, &mut delay).await {
+                Ok(Ok(())) => ClientError::SubscriptionClosed(self.program_id),
+                Ok(Err(err)) => err,
+                Err(Closed) => return,
+            };
+            if self.sender.send(Err(err)).await.is_err() {
+                return;
+            }
+            tokio::time::sleep(delay).await;
+            delay = (delay * 2).min(self.config.max_reconnect_delay);
+        }
+    }
+
+    // Subscribes, backfills from the cursor, then forwards live notifications until the
+    // subscription ends. The outer result is `Err` only once the stream has been dropped.
+    async fn connect(&mut self, rpc: &RpcClient, delay: &mut Duration) -> std::result::Result<Result<()>, Closed> {
+        let pubsub = match PubsubClient::new(&self.config.ws_url).await {
+            Ok(pubsub) => pubsub,
+            Err(err) => return Ok(Err(err.into())),
+        };
+        let filter = RpcTransactionLogsFilter::Mentions(vec![self.program_id.to_string()]);
+        let logs_config = RpcTransactionLogsConfig { commitment: Some(self.config.commitment) };
+        let (mut notifications, unsubscribe) = match pubsub.logs_subscribe(filter, logs_config).await {
+            Ok(subscription) => subscription,
+            Err(err) => return Ok(Err(err.into())),
+        };
+        *delay = self.config.min_reconnect_delay;
+
+        // Subscribed before backfilling so nothing falls in between; live notifications for
+        // backfilled transactions are skipped below
+        let backfilled = match self.backfill(rpc).await? {
+            Ok(backfilled) => backfilled,
+            Err(err) => return Ok(Err(err)),
+        };
+
+        while let Some(notification) = notifications.next().await {
+            if notification.value.err.is_some() {
+                continue;
+            }
+            let Ok(signature) = Signature::from_str(&notification.value.signature) else { continue };
+            if backfilled.contains(&signature) || self.is_delivered(notification.context.slot, &signature) {
+                continue;
+            }
+            self.deliver(notification.context.slot, signature, &notification.value.logs).await?;
+        }
+
+        drop(notifications);
+        unsubscribe().await;
+        Ok(Ok(()))
+    }
+
+    // Delivers the successful transactions after the cursor, oldest first
+    async fn backfill(&mut self, rpc: &RpcClient) -> std::result::Result<Result<HashSet<Signature>>, Closed> {
+        let mut backfilled = HashSet::new();
+        let Some(cursor) = self.cursor else { return Ok(Ok(backfilled)) };
+
+        let statuses = match self.signatures_after(rpc, &cursor).await {
+            Ok(statuses) => statuses,
+            Err(err) => return Ok(Err(err)),
+        };
+        for (slot, signature) in statuses {
+            let config = RpcTransactionConfig {
+                encoding: Some(UiTransactionEncoding::Base64),
+                commitment: Some(self.config.commitment),
+                max_supported_transaction_version: Some(0),
+            };
+            let transaction = match rpc.get_transaction_with_config(&signature, config).await {
+                Ok(transaction) => transaction,
+                Err(err) => return Ok(Err(err.into())),
+            };
+            let logs: Vec<String> = transaction
+                .transaction
+                .meta
+                .and_then(|meta| meta.log_messages.into())
+                .unwrap_or_default();
+
+            self.deliver(slot, signature, &logs).await?;
+            backfilled.insert(signature);
+        }
+        Ok(Ok(backfilled))
+    }
+
+    async fn signatures_after(&self, rpc: &RpcClient, cursor: &EventCursor) -> Result<Vec<(u64, Signature)>> {
+        let mut signatures = Vec::new();
+        let mut before = None;
+        loop {
+            let config = GetConfirmedSignaturesForAddress2Config {
+                before,
+                until: Some(cursor.signature),
+                limit: Some(SIGNATURES_PAGE),
+                commitment: Some(self.config.commitment),
+            };
+            let page = rpc.get_signatures_for_address_with_config(&self.program_id, config).await?;
+            let full_page = page.len() == SIGNATURES_PAGE;
+
+            for status in page {
+                let signature = Signature::from_str(&status.signature)
+                    .map_err(|_| ClientError::InvalidSignature(status.signature.clone()))?;
+                before = Some(signature);
+                if status.err.is_none() {
+                    signatures.push((status.slot, signature));
+                }
+            }
+            if !full_page {
+                break;
+            }
+        }
+        // Newest first from the RPC
+        signatures.reverse();
+        Ok(signatures)
+    }
+
+    fn is_delivered(&self, slot: u64, signature: &Signature) -> bool {
+        self.cursor
+            .is_some_and(|cursor| slot < cursor.slot || cursor.signature == *signature)
+    }
+
+    async fn deliver(&mut self, slot: u64, signature: Signature, logs: &[String]) -> std::result::Result<(), Closed> {
+        for (program_id, data) in parse_logs(logs, &[self.program_id]) {
+            let raw = EventEnvelope { program_id, slot, signature, event: data };
+            if let Some(item) = (self.decode)(raw) {
+                self.sender.send(item).await.map_err(|_| Closed)?;
+            }
+        }
+        self.cursor = Some(EventCursor { slot, signature });
+        Ok(())
+    }
+}
@@ -0,0 +1,150 @@
+//! Registry of the programs' custom error codes.
+//!
+//! Each program numbers its errors from Anchor's default offset (6000) over a range of
+//! [`RANGE_LEN`] codes. Codes are the range offset plus the variant index, so new errors go
+//! last and existing ones are never reordered or removed (both programs are deployed with
+//! these codes); the programs' tests check their enums against this table.
+//!
+//! The two ranges overlap, so a code is only meaningful with the program that returned it:
+//! look errors up with [`lookup_in`]. The codes also tag the `InstructionFailed` events
+//! emitted on validation failures, which carry their program (see
+//! [`crate::events::shared::InstructionFailed`]). Anchor's own errors (below 6000) and
+//! Arcium's callback errors are not in the registry.
+
+use anchor_lang::prelude::Pubkey;
+
+use crate::ids::{POST_MSG_PROGRAM_ID, PRIVATE_MESSAGES_PROGRAM_ID};
+
+/// Codes per program
+pub const RANGE_LEN: u32 = 1000;
+/// First `post-msg-program` code (`PostError`)
+pub const POST_ERROR_OFFSET: u32 = 6000;
+/// First `private_messages` code (`ErrorCode`)
+pub const PRIVATE_MESSAGES_ERROR_OFFSET: u32 = 6000;
+
+/// A registered error
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ErrorInfo {
+    pub code: u32,
+    pub program_id: Pubkey,
+    /// Variant name, as in the program
+    pub name: &'static str,
+    /// `#[msg]` of the variant
+    pub msg: &'static str,
+}
+
+/// A program's range and its errors, in code order
+pub struct ErrorRange {
+    pub program_id: Pubkey,
+    pub offset: u32,
+    pub errors: &'static [(&'static str, &'static str)],
+}
+
+impl ErrorRange {
+    pub fn contains(&self, code: u32) -> bool {
+        (self.offset..self.offset + RANGE_LEN).contains(&code)
+    }
+
+    pub fn get(&self, code: u32) -> Option<ErrorInfo> {
+        let index = code.checked_sub(self.offset)? as usize;
+        let (name, msg) = *self.errors.get(index)?;
+        Some(ErrorInfo { code, program_id: self.program_id, name, msg })
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = ErrorInfo> + '_ {
+        (self.offset..).zip(self.errors).map(|(code, &(name, msg))| ErrorInfo { code, program_id: self.program_id, name, msg })
+    }
+}
+
+pub const POST_ERRORS: ErrorRange = ErrorRange {
+    program_id: POST_MSG_PROGRAM_ID,
+    offset: POST_ERROR_OFFSET,
+    errors: &[
+        ("BidTooLow", "Bid is below the USD-pegged minimum"),
+        ("TargetTooLong", "Target too long (max 32 bytes)"),
+        ("ContentTooLong", "Content too long (max 512 bytes)"),
+        ("InvalidWallet", "Invalid wallet address"),
+        ("InvalidPrice", "Invalid oracle price"),
+        ("InvalidConfig", "Invalid config value"),
+        ("Unauthorized", "Unauthorized"),
+        ("InvalidReferrer", "Invalid referrer"),
+        ("InvalidSignature", "Missing or invalid Ed25519 signature"),
+        ("InvalidRecipient", "Recipient is not the post author's messaging account"),
+        ("AbortedComputation", "The computation was aborted"),
+        ("ClusterNotSet", "Cluster not set"),
+        ("AuctionNotOpen", "Auction is not open for bids"),
+        ("AuctionStillOpen", "Auction bidding period has not ended"),
+        ("AuctionBusy", "Auction has a computation in progress"),
+        ("AuctionFull", "Auction has reached the maximum number of bids"),
+        ("AuctionNotResolved", "Auction is not resolved"),
+        ("AuctionAlreadySettled", "Auction already settled"),
+        ("AlreadyRevealed", "Content already revealed"),
+        ("ComputationPending", "A computation is already pending"),
+        ("ThresholdNotReached", "Boost threshold not reached"),
+        ("InvalidReceiptTree", "Invalid or incomplete receipt tree accounts"),
+        ("RateLimited", "Posting rate limit reached, retry later or bid higher"),
+        ("StakeRequired", "A locked stake of at least the minimum is required to post"),
+        ("InvalidStakeAmount", "Invalid stake amount"),
+        ("StakeLocked", "Stake is still locked"),
+        ("InvalidPost", "Account is not a post"),
+        ("AlreadyMigrated", "Post is already at the current version"),
+        ("TargetEmpty", "Target cannot be empty"),
+        ("ContentEmpty", "Content cannot be empty or whitespace only"),
+        ("TargetNotNormalized", "Target must be lowercase"),
+        ("InvalidCharacters", "Control or whitespace characters are not allowed"),
+        ("PostNotMigrated", "Post must be migrated to the current version first"),
+        ("NothingToClaim", "Nothing to claim yet"),
+        ("InvalidGovernance", "Governance account is not owned by the governance program"),
+        ("EscrowStillOpen", "Escrow acceptance window is still open"),
+        ("SponsorshipExhausted", "Sponsorship has no periods left"),
+        ("PeriodNotDue", "Sponsorship period is not due yet"),
+        ("NotYetPublishable", "Scheduled post cannot be published yet"),
+        ("InvalidCharity", "Charity wallet does not match the registry entry"),
+        ("TargetAlreadyNotified", "Post target was already notified"),
+        ("RefundsVaulted", "Deposits were moved to the refund vault, use claim_refund"),
+        ("RefundExpired", "Refund claim window has expired"),
+        ("RefundNotExpired", "Refund claim window is still open"),
+        ("InvalidLicense", "Unknown license code"),
+        ("ProvenanceAlreadySet", "Post provenance is already set"),
+        ("TargetProofRequired", "Handle targets must be claimed with an ownership proof"),
+        ("NoComputationPending", "No computation is pending"),
+        ("PendingNotExpired", "The pending computation has not timed out yet"),
+        ("PostEscrowed", "Post has a live escrow, accept or reclaim it first"),
+        ("RelayNonceUsed", "Relayed post nonce already used"),
+        ("DepositBelowClearingPrice", "Winning deposit is below the clearing price"),
+        ("StaleCallback", "Callback does not match the pending computation"),
+        ("EscrowExpired", "Escrow deadline has passed, the offer can no longer be accepted"),
+    ],
+};
+
+pub const PRIVATE_MESSAGES_ERRORS: ErrorRange = ErrorRange {
+    program_id: PRIVATE_MESSAGES_PROGRAM_ID,
+    offset: PRIVATE_MESSAGES_ERROR_OFFSET,
+    errors: &[
+        ("AbortedComputation", "The computation was aborted"),
+        ("ClusterNotSet", "Cluster not set"),
+        ("MessageTooLong", "Message content exceeds maximum size"),
+        ("Unauthorized", "Unauthorized action"),
+        ("ComputationPending", "A computation is already pending"),
+        ("MemberSetFull", "Membership set is full"),
+        ("RateLimited", "Send rate limit reached, request a send token first"),
+        ("SendAlreadyAuthorized", "A send token is already authorized"),
+        ("InvalidPollDeadline", "Poll deadline must be in the future"),
+        ("PollClosed", "Poll is closed"),
+        ("PollStillOpen", "Poll is still open"),
+        ("InvalidNotificationFlags", "Unknown notification flags"),
+        ("NoComputationPending", "No computation is pending"),
+        ("PendingNotExpired", "The pending computation has not timed out yet"),
+        ("AlreadyVoted", "Ballot already counted"),
+        ("StaleCallback", "Callback does not match the pending computation"),
+    ],
+};
+
+/// Both programs' ranges
+pub const RANGES: [ErrorRange; 2] = [POST_ERRORS, PRIVATE_MESSAGES_ERRORS];
+
+/// Registered error of `program_id` with this code (`None` for codes outside its range,
+/// such as Anchor's or Arcium's)
+pub fn lookup_in(program_id: &Pubkey, code: u32) -> Option<ErrorInfo> {
+    RANGES.iter().find(|range| range.program_id == *program_id)?.get(code)
+}
@@ -0,0 +1,525 @@
+//! Client-side mirrors of the programs' `#[event]` structs.
+//!
+//! Anchor event discriminators are derived from the struct name, so these decode
+//! the `Program data:` logs emitted by the programs. Keep them field-for-field in
+//! sync with the program definitions (new fields go last).
+//!
+//! [`shared`] holds the events both programs emit with the same layout.
+//!
+//! [`decode_event`] turns any `Program data:` log of either program into an
+//! [`XrayEvent`], looked up by discriminator.
+
+use std::io;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use base64::Engine;
+
+/// Events of `post-msg-program`
+pub mod post {
+    use anchor_lang::prelude::*;
+
+    #[event]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct PostCreated {
+        pub post: Pubkey,
+        pub author: Pubkey,
+        pub target: String,
+        pub bid: u64,
+        pub bid_mint: Pubkey,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct PrivateReplySent {
+        pub post: Pubkey,
+        pub message: Pubkey,
+        pub sender: Pubkey,
+        pub recipient: Pubkey,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct PostTargetNotified {
+        pub post: Pubkey,
+        pub message: Pubkey,
+        pub author: Pubkey,
+        pub target_owner: Pubkey,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct PostProvenanceSet {
+        pub post: Pubkey,
+        pub author: Pubkey,
+        pub license: u8,
+        pub content_hash: [u8; 32],
+    }
+
+    #[event]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct TargetOwnershipProven {
+        pub target_account: Pubkey,
+        pub owner: Pubkey,
+        pub previous_owner: Pubkey,
+        pub identity_key: Pubkey,
+    }
+
+    #[event]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct SealedBidPlaced {
+        pub auction: Pubkey,
+        pub bid_count: u8,
+    }
+
+    #[event]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct FeaturedPostSelected {
+        pub auction: Pubkey,
+        pub post: Pubkey,
+        pub clearing_price: u64,
+        pub featured_until: i64,
+    }
+
+    #[event]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct RefundVaultOpened {
+        pub auction: Pubkey,
+        pub amount: u64,
+        pub stream_end: i64,
+        pub expires_at: i64,
+    }
+
+    #[event]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct RefundClaimed {
+        pub auction: Pubkey,
+        pub bidder: Pubkey,
+        pub amount: u64,
+        pub remaining: u64,
+    }
+
+    #[event]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct RefundsExpired {
+        pub auction: Pubkey,
+        pub amount: u64,
+    }
+
+    #[event]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct PostBoosted {
+        pub post: Pubkey,
+        pub booster: Pubkey,
+        pub amount: u64,
+    }
+
+    #[event]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct PostContentUnlocked {
+        pub post: Pubkey,
+        pub content_key: [u8; 32],
+    }
+
+    #[event]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct PostReceiptMinted {
+        pub post: Pubkey,
+        pub author: Pubkey,
+        pub tree: Pubkey,
+        pub target: String,
+        pub content_hash: [u8; 32],
+        pub bid: u64,
+    }
+
+    #[event]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct TreasurySwept {
+        pub admin: Pubkey,
+        pub amount: u64,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct StakeSlashed {
+        pub author: Pubkey,
+        pub post: Pubkey,
+        pub moderator: Pubkey,
+        pub amount: u64,
+        pub remaining: u64,
+    }
+
+    #[event]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct PostMigrated {
+        pub post: Pubkey,
+        pub from_version: u8,
+        pub to_version: u8,
+    }
+
+    #[event]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct VestedClaimed {
+        pub beneficiary: Pubkey,
+        pub amount: u64,
+        pub total_claimed: u64,
+    }
+
+    #[event]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct VestedTokenClaimed {
+        pub beneficiary: Pubkey,
+        pub mint: Pubkey,
+        pub amount: u64,
+        pub total_claimed: u64,
+    }
+
+    #[event]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct AdminChanged {
+        pub previous_admin: Pubkey,
+        pub new_admin: Pubkey,
+    }
+
+    #[event]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct PostAccepted {
+        pub post: Pubkey,
+        pub owner: Pubkey,
+        pub amount: u64,
+    }
+
+    #[event]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct EscrowReclaimed {
+        pub post: Pubkey,
+        pub author: Pubkey,
+        pub refunded: u64,
+        pub fee: u64,
+    }
+
+    #[event]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct PostUpvoted {
+        pub post: Pubkey,
+        pub voter: Pubkey,
+        pub amount: u64,
+        pub upvotes: u64,
+    }
+
+    #[event]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct TreasuryMigrated {
+        pub admin: Pubkey,
+        pub from: Pubkey,
+        pub to: Pubkey,
+        pub version: u8,
+        pub amount: u64,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct OfficialReplyPosted {
+        pub post: Pubkey,
+        pub reply: Pubkey,
+        pub owner: Pubkey,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct PostPinned {
+        pub target: Pubkey,
+        pub post: Pubkey,
+        pub fee: u64,
+        pub pinned_until: i64,
+    }
+
+    #[event]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct SponsorshipPeriodProcessed {
+        pub sponsorship: Pubkey,
+        pub post: Pubkey,
+        pub amount: u64,
+        pub periods_remaining: u32,
+    }
+
+    #[event]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct ViewsRecorded {
+        pub attestor: Pubkey,
+        pub posts: u32,
+        pub total_views: u64,
+        pub timestamp: i64,
+    }
+}
+
+/// Events of `private_messages`
+pub mod private_messages {
+    use anchor_lang::prelude::*;
+
+    #[event]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct UserRegistered {
+        pub wallet: Pubkey,
+        pub x25519_pubkey: [u8; 32],
+    }
+
+    #[event]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct UserKeyUpdated {
+        pub wallet: Pubkey,
+        pub new_x25519_pubkey: [u8; 32],
+    }
+
+    #[event]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct MessageSent {
+        pub sender: Pubkey,
+        pub recipient: Pubkey,
+        pub timestamp: i64,
+        /// Recipient's `message_count` after this message, one past the index in its PDA seeds
+        pub message_index: u64,
+    }
+
+    impl MessageSent {
+        /// Index of the message in its PDA seeds, `None` for a malformed event (`message_index` 0)
+        pub fn seed_index(&self) -> Option<u64> {
+            self.message_index.checked_sub(1)
+        }
+
+        /// Address of the message account, `None` for a malformed event (see [`Self::seed_index`])
+        pub fn message_address(&self) -> Option<Pubkey> {
+            let index = self.seed_index()?;
+            Some(crate::pda::message_pda(&self.sender, &self.recipient, index).0)
+        }
+    }
+
+    #[event]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct MessageRead {
+        pub sender: Pubkey,
+        pub recipient: Pubkey,
+        pub timestamp: i64,
+        pub message: Pubkey,
+    }
+
+    #[event]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct TestAddResult {
+        pub result: [u8; 32],
+        pub nonce: [u8; 16],
+    }
+
+    /// Event émis quand un message privé est envoyé
+    /// Note: on n'émet PAS sender/recipient car c'est ce qu'on cache!
+    #[event]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct PrivateMessageSent {
+        pub message_index: u64,
+        pub timestamp: i64,
+    }
+
+    /// Event émis après vérification d'accès via MPC
+    /// Le résultat est chiffré - seul le requester peut le déchiffrer
+    #[event]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct PrivateAccessVerified {
+        /// Résultat chiffré (is_authorized + sender_hash si autorisé)
+        pub encrypted_result: [u8; 32],
+        pub nonce: [u8; 16],
+    }
+
+    #[event]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct NotificationPrefsUpdated {
+        pub owner: Pubkey,
+        pub flags: u8,
+    }
+
+    #[event]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct SetMembershipChecked {
+        pub membership_set: Pubkey,
+        /// Bit d'appartenance chiffré pour le demandeur
+        pub encrypted_result: [u8; 32],
+        pub nonce: [u8; 16],
+    }
+
+    #[event]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct PollCreated {
+        pub poll: Pubkey,
+        pub creator: Pubkey,
+        pub question_hash: [u8; 32],
+        pub closes_at: i64,
+    }
+
+    #[event]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct PollTallyRevealed {
+        pub poll: Pubkey,
+        pub voter_count: u32,
+        /// Totaux chiffrés pour le créateur (oui, non, abstention)
+        pub encrypted_totals: [[u8; 32]; 3],
+        pub nonce: [u8; 16],
+    }
+}
+
+/// Events emitted by both programs with the same layout
+pub mod shared {
+    use anchor_lang::prelude::*;
+
+    /// A validation failure, emitted just before the instruction fails when the program is
+    /// built with its `telemetry` feature (it stays in the failed transaction's logs)
+    #[event]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct InstructionFailed {
+        /// Error code, see [`crate::errors`]
+        pub code: u32,
+        /// Program that failed (codes are only unique within a program)
+        pub program: Pubkey,
+        /// Signer of the failed instruction
+        pub signer: Pubkey,
+        /// Value that failed the check (bid, length, ...), 0 if none
+        pub context: u64,
+        pub timestamp: i64,
+    }
+
+    impl InstructionFailed {
+        /// Registered error of the code
+        pub fn error(&self) -> Option<crate::errors::ErrorInfo> {
+            crate::errors::lookup_in(&self.program, self.code)
+        }
+    }
+}
+
+macro_rules! xray_events {
+    (
+        shared { $($shared:ident),* $(,)? }
+        $($module:ident ($program_id:path) { $($name:ident),* $(,)? })*
+    ) => {
+        /// Any event of either program
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub enum XrayEvent {
+            $($($name($module::$name),)*)*
+            $($shared(shared::$shared),)*
+        }
+
+        impl XrayEvent {
+            /// Decodes an event's bytes (discriminator, then Borsh payload): `None` if the
+            /// discriminator is not one of ours, an error if the payload does not match it
+            pub fn from_bytes(data: &[u8]) -> io::Result<Option<Self>> {
+                $($(
+                    if let Some(mut payload) = data.strip_prefix($module::$name::DISCRIMINATOR) {
+                        return $module::$name::deserialize(&mut payload).map(|event| Some(Self::$name(event)));
+                    }
+                )*)*
+                $(
+                    if let Some(mut payload) = data.strip_prefix(shared::$shared::DISCRIMINATOR) {
+                        return shared::$shared::deserialize(&mut payload).map(|event| Some(Self::$shared(event)));
+                    }
+                )*
+                Ok(None)
+            }
+
+            /// Struct name of the event, as in the program
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $($(Self::$name(_) => stringify!($name),)*)*
+                    $(Self::$shared(_) => stringify!($shared),)*
+                }
+            }
+
+            /// Program that emits the event
+            pub fn program_id(&self) -> Pubkey {
+                match self {
+                    $($(Self::$name(_) => $program_id,)*)*
+                    $(Self::$shared(event) => event.program,)*
+                }
+            }
+        }
+
+        $($(
+            impl From<$module::$name> for XrayEvent {
+                fn from(event: $module::$name) -> Self {
+                    Self::$name(event)
+                }
+            }
+        )*)*
+        $(
+            impl From<shared::$shared> for XrayEvent {
+                fn from(event: shared::$shared) -> Self {
+                    Self::$shared(event)
+                }
+            }
+        )*
+    };
+}
+
+xray_events! {
+    shared {
+        InstructionFailed,
+    }
+    post (crate::POST_MSG_PROGRAM_ID) {
+        PostCreated,
+        PrivateReplySent,
+        PostTargetNotified,
+        PostProvenanceSet,
+        TargetOwnershipProven,
+        SealedBidPlaced,
+        FeaturedPostSelected,
+        RefundVaultOpened,
+        RefundClaimed,
+        RefundsExpired,
+        PostBoosted,
+        PostContentUnlocked,
+        PostReceiptMinted,
+        TreasurySwept,
+        StakeSlashed,
+        PostMigrated,
+        VestedClaimed,
+        VestedTokenClaimed,
+        AdminChanged,
+        PostAccepted,
+        EscrowReclaimed,
+        PostUpvoted,
+        TreasuryMigrated,
+        OfficialReplyPosted,
+        PostPinned,
+        SponsorshipPeriodProcessed,
+        ViewsRecorded,
+    }
+    private_messages (crate::PRIVATE_MESSAGES_PROGRAM_ID) {
+        UserRegistered,
+        UserKeyUpdated,
+        MessageSent,
+        MessageRead,
+        TestAddResult,
+        PrivateMessageSent,
+        PrivateAccessVerified,
+        NotificationPrefsUpdated,
+        SetMembershipChecked,
+        PollCreated,
+        PollTallyRevealed,
+    }
+}
+
+/// Decodes a `Program data:` log line (the prefix is optional) into an event of either
+/// program: `None` if the discriminator is unknown, an error if the data is not base64 or
+/// the payload does not match its discriminator.
+///
+/// Log lines carry no program id: use `xray-client`'s `stream::parse_logs` to keep only
+/// the events emitted by the X-RAY programs themselves.
+pub fn decode_event(log_data: &str) -> io::Result<Option<XrayEvent>> {
+    let data = log_data.strip_prefix("Program data: ").unwrap_or(log_data).trim();
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(data)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    XrayEvent::from_bytes(&bytes)
+}
@@ -0,0 +1,13 @@
+use anchor_lang::prelude::*;
+
+pub use xray_crypto::ids::{POST_MSG_PROGRAM_ID, PRIVATE_MESSAGES_PROGRAM_ID};
+
+/// Revenue split wallets of `post-msg-program` (45% / 10% / 45%)
+pub const WALLET_1: Pubkey = pubkey!("69TwH2GJiBSA8Eo3DunPGsXGWjNFY267zRrpHptYWCuC");
+pub const WALLET_2: Pubkey = pubkey!("EbhZhYumUZyHQCPbeaLLt57SS2obHiFdp7TMLjUBBqcD");
+pub const WALLET_3: Pubkey = pubkey!("HxtzFZhjNCsQb9ZqEyK8xYftqv6j6AM2MAT6uwWG3KYd");
+
+/// Metaplex Bubblegum and its dependencies (compressed NFT post receipts)
+pub const BUBBLEGUM_PROGRAM_ID: Pubkey = pubkey!("BGUMAp9Gq7iTEuizy4pqaxsTyUCBK68MDfK752saRPUY");
+pub const SPL_NOOP_PROGRAM_ID: Pubkey = pubkey!("noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV");
+pub const SPL_ACCOUNT_COMPRESSION_PROGRAM_ID: Pubkey = pubkey!("cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK");
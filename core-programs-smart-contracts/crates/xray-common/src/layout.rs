@@ -0,0 +1,25 @@
+//! Field offsets the programs commit to keeping stable, Anchor discriminator included.
+//!
+//! Only fields before the first variable-length one have a fixed offset: `MessageAccount`
+//! is fixed up to its `recipient` (its content comes next), and `Post` up to its `target`.
+//! Fields are never reordered, so existing accounts keep these offsets. Checked against
+//! the programs' Borsh layout by the testkit's `*_layouts.rs` tests.
+
+use crate::sizes::DISCRIMINATOR;
+
+// ----------------------------------------------------------------------------
+// post-msg-program
+// ----------------------------------------------------------------------------
+
+pub const POST_AUTHOR_OFFSET: usize = DISCRIMINATOR;
+/// `target` as Borsh: u32 length (LE), then the UTF-8 bytes
+pub const POST_TARGET_OFFSET: usize = POST_AUTHOR_OFFSET + 32;
+
+// ----------------------------------------------------------------------------
+// private_messages
+// ----------------------------------------------------------------------------
+
+pub const MESSAGE_SENDER_OFFSET: usize = DISCRIMINATOR;
+pub const MESSAGE_RECIPIENT_OFFSET: usize = MESSAGE_SENDER_OFFSET + 32;
+/// `encrypted_content` as Borsh: u32 length (LE), then the bytes
+pub const MESSAGE_CONTENT_OFFSET: usize = MESSAGE_RECIPIENT_OFFSET + 32;
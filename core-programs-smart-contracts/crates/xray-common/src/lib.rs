@@ -0,0 +1,25 @@
+//! Constants and types shared by the X-RAY programs, their tests and off-chain clients.
+//!
+//! - [`ids`]: program IDs
+//! - [`seeds`]: PDA seed prefixes (from `xray-crypto`)
+//! - [`pda`]: PDA derivation helpers (from `xray-crypto`)
+//! - [`sizes`]: field limits and account space
+//! - [`layout`]: fixed field offsets, for `getProgramAccounts` memcmp filters
+//! - [`license`]: content license codes of posts
+//! - [`errors`]: registry of both programs' error codes, by stable range
+//! - [`events`]: client-side mirrors of the programs' events, and [`events::decode_event`]
+
+pub mod errors;
+pub mod events;
+pub mod ids;
+pub mod layout;
+pub mod license;
+pub mod sizes;
+
+pub use xray_crypto::{pda, seeds};
+
+// `pda` moved to `xray-crypto` unchanged: its helpers must keep returning anchor's
+// `Pubkey`, so programs and clients can use them as before
+const _: fn() -> (anchor_lang::prelude::Pubkey, u8) = pda::config_pda;
+
+pub use ids::{POST_MSG_PROGRAM_ID, PRIVATE_MESSAGES_PROGRAM_ID};
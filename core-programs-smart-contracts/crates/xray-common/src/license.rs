@@ -0,0 +1,30 @@
+//! Content license codes of `Post::license`.
+//!
+//! The codes are stored on-chain, so existing ones are never renumbered; new licenses go
+//! last and raise [`MAX_LICENSE`].
+
+/// No license declared
+pub const LICENSE_NONE: u8 = 0;
+pub const LICENSE_ALL_RIGHTS_RESERVED: u8 = 1;
+pub const LICENSE_CC0: u8 = 2;
+pub const LICENSE_CC_BY: u8 = 3;
+pub const LICENSE_CC_BY_SA: u8 = 4;
+pub const LICENSE_CC_BY_NC: u8 = 5;
+pub const LICENSE_CC_BY_ND: u8 = 6;
+
+/// Highest known code (the program rejects anything above)
+pub const MAX_LICENSE: u8 = LICENSE_CC_BY_ND;
+
+/// Short names, indexed by code
+pub const LICENSE_NAMES: [&str; MAX_LICENSE as usize + 1] =
+    ["none", "all-rights-reserved", "cc0", "cc-by", "cc-by-sa", "cc-by-nc", "cc-by-nd"];
+
+/// Short name of a license code
+pub fn name(code: u8) -> Option<&'static str> {
+    LICENSE_NAMES.get(code as usize).copied()
+}
+
+/// License code of a short name (as in [`LICENSE_NAMES`])
+pub fn from_name(name: &str) -> Option<u8> {
+    LICENSE_NAMES.iter().position(|known| *known == name).map(|code| code as u8)
+}
@@ -0,0 +1,101 @@
+//! Field limits and account space (`space = ...`), Anchor discriminator included.
+
+/// Anchor account discriminator
+pub const DISCRIMINATOR: usize = 8;
+
+/// Space of a Borsh `String` holding at most `max_bytes` UTF-8 bytes
+pub const fn string_space(max_bytes: usize) -> usize {
+    4 + max_bytes
+}
+
+/// Space of a Borsh `Vec` holding at most `max_items` items of `item_size` bytes
+pub const fn vec_space(max_items: usize, item_size: usize) -> usize {
+    4 + max_items * item_size
+}
+
+// ----------------------------------------------------------------------------
+// post-msg-program
+// ----------------------------------------------------------------------------
+
+/// Post field limits, in UTF-8 bytes. A target is used as is in the post and scheduled post
+/// PDA seeds, so it is capped at the 32-byte seed limit.
+pub const MAX_TARGET_LEN: usize = 32;
+pub const MAX_CONTENT_LEN: usize = 512;
+
+/// Space reserved for a target in account layouts (set before targets were capped at
+/// [`MAX_TARGET_LEN`], kept so existing accounts keep their size)
+pub const TARGET_SPACE: usize = 64;
+
+/// Number of recent post addresses kept in an AuthorIndex
+pub const AUTHOR_INDEX_RECENT: usize = 16;
+
+/// Maximum number of sealed bids per featured-slot auction
+pub const MAX_SEALED_BIDS: usize = 16;
+
+pub const CONFIG_SIZE: usize = DISCRIMINATOR + 32 + 8 + 2 + 32 + 8 + 2 + 8 + 8 + 8 + 32 + 32 + 8 + 2 + 8 + 1 + 32 + 1;
+pub const REFERRAL_SIZE: usize = DISCRIMINATOR + 32 + 8 + 8 + 1;
+pub const CHARITY_SIZE: usize = DISCRIMINATOR + 32 + 2 + 8 + 1;
+pub const AUTHOR_INDEX_SIZE: usize = DISCRIMINATOR + 32 + 8 + vec_space(AUTHOR_INDEX_RECENT, 32) + 1;
+pub const AUTHOR_STATS_SIZE: usize = DISCRIMINATOR + 32 + 8 + 2 + 8 + 8 + 1;
+pub const STAKE_ACCOUNT_SIZE: usize = DISCRIMINATOR + 32 + 8 + 8 + 1;
+pub const REVENUE_SNAPSHOT_SIZE: usize = DISCRIMINATOR + 8 + 8 + 8 + 8 + 1;
+pub const VESTING_VAULT_SIZE: usize = DISCRIMINATOR + 32 + 8 + 8 + 8 + 1;
+pub const TARGET_ACCOUNT_SIZE: usize = DISCRIMINATOR + string_space(TARGET_SPACE) + 32 + 32 + 8 + 8 + 1
+    + 32 + 8; // v2: identity_key, verified_at
+pub const TARGET_IDENTITY_SIZE: usize = DISCRIMINATOR + 32 + 32 + 8 + 1;
+pub const OFFICIAL_REPLY_SIZE: usize = DISCRIMINATOR + 32 + 32 + string_space(MAX_CONTENT_LEN) + 8 + 1;
+pub const POST_ESCROW_SIZE: usize = DISCRIMINATOR + 32 + 32 + 32 + 8 + 8 + 1;
+pub const MINT_TREASURY_SIZE: usize = DISCRIMINATOR + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 1;
+pub const UPVOTE_RECEIPT_SIZE: usize = DISCRIMINATOR + 32 + 32 + 8 + 1;
+pub const SPONSORSHIP_SIZE: usize =
+    DISCRIMINATOR + 32 + string_space(TARGET_SPACE) + string_space(MAX_CONTENT_LEN) + 8 + 8 + 4 + 8 + 1;
+pub const SCHEDULED_POST_SIZE: usize =
+    DISCRIMINATOR + 32 + string_space(TARGET_SPACE) + string_space(MAX_CONTENT_LEN) + 8 + 8 + 1;
+pub const POST_SIZE: usize = DISCRIMINATOR
+    + 32
+    + string_space(TARGET_SPACE)
+    + string_space(MAX_CONTENT_LEN)
+    + 8 + 8 + 1 + 1 // bid, timestamp, bump, version
+    + 8 + 8 + 8 // v2: total_boosts, rank_score, rank_updated_at
+    + 32 // v3: bid_mint
+    + 8 // v4: upvotes
+    + 1 // v5: flags
+    + 8 // v6: views
+    + 32 + 8 // v7: charity, charity_amount
+    + 1 + 64; // v8: license, content_signature
+pub const AUCTION_SIZE: usize =
+    DISCRIMINATOR + 32 * 3 + 16 + 8 + 32 + 8 + 8 + 8 + 8 + 1 + 1 + 8 + 8 + 1 + 32 * MAX_SEALED_BIDS + 1 + 8 + 32 + 8 + 1 + 1;
+pub const SEALED_BID_SIZE: usize = DISCRIMINATOR + 32 + 32 + 32 + 1 + 8 + 1;
+pub const REFUND_VAULT_SIZE: usize = DISCRIMINATOR + 32 + 8 + 1 + 8 + 8 + 8 * MAX_SEALED_BIDS + 8 + 8 + 8 + 1;
+pub const SEALED_CONTENT_SIZE: usize = DISCRIMINATOR + 32 + 32 * 2 + 32 + 16 + 8 + 8 + 1 + 8 + 1 + 32 + 1;
+pub const POST_METRICS_SIZE: usize = DISCRIMINATOR + 8 * 7 + 1;
+
+// ----------------------------------------------------------------------------
+// private_messages
+// ----------------------------------------------------------------------------
+
+/// Maximum encrypted message content, in bytes (~170 characters once encrypted)
+pub const MAX_MESSAGE_SIZE: usize = 256;
+
+/// Slots in an encrypted membership set (must match the MemberSet circuit)
+pub const MEMBER_SET_SLOTS: usize = 32;
+
+/// `NotificationPrefs::flags`: new plain messages received
+pub const NOTIFY_MESSAGES: u8 = 1 << 0;
+/// `NotificationPrefs::flags`: results of the wallet's private message access checks
+pub const NOTIFY_ACCESS_VERIFIED: u8 = 1 << 1;
+/// `NotificationPrefs::flags`: posts on a target the wallet owns
+pub const NOTIFY_POSTS: u8 = 1 << 2;
+pub const NOTIFY_ALL: u8 = NOTIFY_MESSAGES | NOTIFY_ACCESS_VERIFIED | NOTIFY_POSTS;
+
+pub const USER_ACCOUNT_SIZE: usize = DISCRIMINATOR + 32 + 32 + 8 + 1;
+pub const MESSAGE_ACCOUNT_SIZE: usize = DISCRIMINATOR + 32 + 32 + vec_space(MAX_MESSAGE_SIZE, 1) + 24 + 8 + 1 + 1;
+pub const PRIVATE_MESSAGE_ACCOUNT_SIZE: usize =
+    DISCRIMINATOR + 32 + 32 + vec_space(MAX_MESSAGE_SIZE, 1) + 24 + 8 + 32 + 16 + 1;
+pub const PRIVATE_MESSAGE_COUNTER_SIZE: usize = DISCRIMINATOR + 8 + 1;
+pub const MEMBERSHIP_SET_SIZE: usize = DISCRIMINATOR + 32 * MEMBER_SET_SLOTS + 16 + 32 + 8 + 1 + 1 + 8 + 8 + 1;
+pub const RATE_BUCKET_SIZE: usize = DISCRIMINATOR + 32 * 2 + 16 + 32 + 1 + 1 + 8 + 8 + 1;
+pub const POLL_SIZE: usize = DISCRIMINATOR + 32 * 3 + 16 + 32 + 32 + 8 + 32 + 8 + 4 + 1 + 8 + 8 + 1;
+pub const POLL_BALLOT_SIZE: usize = DISCRIMINATOR + 32 + 32 + 1 + 1;
+pub const NOTIFICATION_PREFS_SIZE: usize = DISCRIMINATOR + 32 + 1 + 1;
+pub const MESSAGES_METRICS_SIZE: usize = DISCRIMINATOR + 8 * 5 + 1;
@@ -0,0 +1,7 @@
+use solana_pubkey::{pubkey, Pubkey};
+
+/// Posts, bids and revenue split (`post-msg-program`)
+pub const POST_MSG_PROGRAM_ID: Pubkey = pubkey!("5gPGpcXTq1R2chrEP9qPaFw4i1ge5ZgG2n7xnrUGZHPk");
+
+/// Encrypted direct messages (`private_messages`)
+pub const PRIVATE_MESSAGES_PROGRAM_ID: Pubkey = pubkey!("A8r4vLoD79gtdwvyHBY7bXzRSXjFNBbuXic9cPHUJa2s");
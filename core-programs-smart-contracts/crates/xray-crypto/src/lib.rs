@@ -0,0 +1,32 @@
+//! `no_std` core of the X-RAY message format, shared by the Rust SDK and (compiled to WASM)
+//! the browser frontends, so both encrypt, hash and derive addresses the same way.
+//!
+//! - [`ids`]: program IDs
+//! - [`seeds`]: PDA seed prefixes
+//! - [`pda`]: PDA derivation helpers
+//! - [`message`]: message encryption (NaCl box: X25519 + XSalsa20-Poly1305), messaging key
+//!   derivation and the identity hashes of private message metadata (`message` feature)
+//!
+//! Only `ids`, `seeds` and `pda` are available without default features, which is how the
+//! programs use it through `xray-common`.
+//!
+//! WASM build, exports in `wasm.rs`:
+//!
+//! ```sh
+//! cargo rustc -p xray-crypto --release --features wasm --target wasm32-unknown-unknown --crate-type cdylib
+//! wasm-bindgen --target web --out-dir pkg target/wasm32-unknown-unknown/release/xray_crypto.wasm
+//! ```
+
+#![no_std]
+
+extern crate alloc;
+
+pub mod ids;
+#[cfg(feature = "message")]
+pub mod message;
+pub mod pda;
+pub mod seeds;
+#[cfg(feature = "wasm")]
+mod wasm;
+
+pub use solana_pubkey::Pubkey;
@@ -0,0 +1,101 @@
+//! Message encryption shared with the web client (`front/lib/shadow/privateMessages.ts`):
+//! NaCl box (X25519 + XSalsa20-Poly1305) with a 24-byte random nonce, and a one-byte
+//! prefix telling whether the copy was encrypted for the recipient or for the sender.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crypto_box::aead::Aead;
+use crypto_box::{PublicKey, SalsaBox, SecretKey};
+use sha2::{Digest, Sha256};
+use solana_pubkey::Pubkey;
+
+/// Prefix of a message encrypted for its recipient
+pub const MSG_PREFIX_TO_RECIPIENT: u8 = 0x01;
+/// Prefix of the sender's own copy of a message
+pub const MSG_PREFIX_TO_SENDER: u8 = 0x02;
+/// NaCl box nonce length
+pub const NONCE_LEN: usize = 24;
+
+#[derive(Clone)]
+pub struct X25519Keypair {
+    pub public: [u8; 32],
+    pub secret: [u8; 32],
+}
+
+impl X25519Keypair {
+    pub fn from_secret(secret: [u8; 32]) -> Self {
+        let public = *SecretKey::from(secret).public_key().as_bytes();
+        Self { public, secret }
+    }
+}
+
+/// Authentication failed, or the plaintext is not UTF-8
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecryptError;
+
+impl fmt::Display for DecryptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("decryption failed")
+    }
+}
+
+/// Messaging key derived from a wallet's 64-byte secret key (`sha256(secret_key)`),
+/// the same derivation as the web client so both read the same inbox
+pub fn derive_x25519_keypair(wallet_secret_key: &[u8]) -> X25519Keypair {
+    X25519Keypair::from_secret(Sha256::digest(wallet_secret_key).into())
+}
+
+/// Encrypts `message` for `recipient_pubkey` under `nonce`, which must never be reused
+/// for the same pair of keys
+pub fn encrypt_message_with_nonce(
+    message: &str,
+    recipient_pubkey: &[u8; 32],
+    sender_secret: &[u8; 32],
+    nonce: &[u8; NONCE_LEN],
+) -> Vec<u8> {
+    let salsa_box = SalsaBox::new(&PublicKey::from(*recipient_pubkey), &SecretKey::from(*sender_secret));
+
+    let mut plaintext = Vec::with_capacity(message.len() + 1);
+    plaintext.push(MSG_PREFIX_TO_RECIPIENT);
+    plaintext.extend_from_slice(message.as_bytes());
+
+    salsa_box
+        .encrypt(nonce.into(), plaintext.as_slice())
+        .expect("encryption into a Vec cannot fail")
+}
+
+/// Encrypts `message` for `recipient_pubkey` under a random nonce, returning
+/// `(encrypted_content, nonce)`
+#[cfg(feature = "random-nonce")]
+pub fn encrypt_message(message: &str, recipient_pubkey: &[u8; 32], sender_secret: &[u8; 32]) -> (Vec<u8>, [u8; NONCE_LEN]) {
+    use crypto_box::aead::{AeadCore, OsRng};
+
+    let nonce: [u8; NONCE_LEN] = SalsaBox::generate_nonce(&mut OsRng).into();
+    (encrypt_message_with_nonce(message, recipient_pubkey, sender_secret, &nonce), nonce)
+}
+
+/// Decrypts a message exchanged with `other_party_pubkey` (either direction)
+pub fn decrypt_message(
+    encrypted_content: &[u8],
+    nonce: &[u8; NONCE_LEN],
+    other_party_pubkey: &[u8; 32],
+    my_secret: &[u8; 32],
+) -> Result<String, DecryptError> {
+    let salsa_box = SalsaBox::new(&PublicKey::from(*other_party_pubkey), &SecretKey::from(*my_secret));
+    let plaintext = salsa_box
+        .decrypt(nonce.into(), encrypted_content)
+        .map_err(|_| DecryptError)?;
+
+    let text = match plaintext.first() {
+        Some(&MSG_PREFIX_TO_RECIPIENT) | Some(&MSG_PREFIX_TO_SENDER) => &plaintext[1..],
+        _ => &plaintext[..],
+    };
+    String::from_utf8(text.to_vec()).map_err(|_| DecryptError)
+}
+
+/// Identity hash hidden in private message metadata (`sha256(pubkey)`), before MPC encryption
+pub fn identity_hash(wallet: &Pubkey) -> [u8; 32] {
+    Sha256::digest(wallet.as_ref()).into()
+}
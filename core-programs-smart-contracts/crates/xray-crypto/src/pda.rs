@@ -0,0 +1,170 @@
+//! PDA derivation, returning `(address, bump)` like `Pubkey::find_program_address`.
+
+use solana_pubkey::Pubkey;
+use solana_sha256_hasher::hashv;
+
+use crate::ids::{POST_MSG_PROGRAM_ID, PRIVATE_MESSAGES_PROGRAM_ID};
+use crate::seeds::*;
+
+/// Fixed-size seed for a target handle (targets can exceed the 32-byte seed limit)
+pub fn target_seed(target: &str) -> [u8; 32] {
+    hashv(&[target.as_bytes()]).to_bytes()
+}
+
+fn post_program_pda(seeds: &[&[u8]]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(seeds, &POST_MSG_PROGRAM_ID)
+}
+
+fn private_messages_pda(seeds: &[&[u8]]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(seeds, &PRIVATE_MESSAGES_PROGRAM_ID)
+}
+
+// ----------------------------------------------------------------------------
+// post-msg-program
+// ----------------------------------------------------------------------------
+
+pub fn config_pda() -> (Pubkey, u8) {
+    post_program_pda(&[CONFIG_SEED])
+}
+
+/// Treasury holding the SOL bids: version 1 is `["treasury"]`, later versions `["treasury", version]`
+pub fn treasury_pda(version: u8) -> (Pubkey, u8) {
+    post_program_pda(&[TREASURY_SEED, treasury_version_seed(&version)])
+}
+
+pub fn post_pda(author: &Pubkey, target: &str) -> (Pubkey, u8) {
+    post_program_pda(&[POST_SEED, author.as_ref(), target.as_bytes()])
+}
+
+pub fn referral_pda(wallet: &Pubkey) -> (Pubkey, u8) {
+    post_program_pda(&[REFERRAL_SEED, wallet.as_ref()])
+}
+
+pub fn charity_pda(wallet: &Pubkey) -> (Pubkey, u8) {
+    post_program_pda(&[CHARITY_SEED, wallet.as_ref()])
+}
+
+pub fn author_index_pda(author: &Pubkey) -> (Pubkey, u8) {
+    post_program_pda(&[AUTHOR_INDEX_SEED, author.as_ref()])
+}
+
+pub fn author_stats_pda(author: &Pubkey) -> (Pubkey, u8) {
+    post_program_pda(&[AUTHOR_STATS_SEED, author.as_ref()])
+}
+
+pub fn stake_pda(author: &Pubkey) -> (Pubkey, u8) {
+    post_program_pda(&[STAKE_SEED, author.as_ref()])
+}
+
+pub fn revenue_snapshot_pda(epoch: u64) -> (Pubkey, u8) {
+    post_program_pda(&[REVENUE_SEED, &epoch.to_le_bytes()])
+}
+
+pub fn vesting_vault_pda() -> (Pubkey, u8) {
+    post_program_pda(&[VESTING_VAULT_SEED])
+}
+
+pub fn target_account_pda(target: &str) -> (Pubkey, u8) {
+    post_program_pda(&[TARGET_SEED, &target_seed(target)])
+}
+
+pub fn target_identity_pda(target: &str) -> (Pubkey, u8) {
+    post_program_pda(&[TARGET_IDENTITY_SEED, &target_seed(target)])
+}
+
+pub fn official_reply_pda(post: &Pubkey) -> (Pubkey, u8) {
+    post_program_pda(&[OFFICIAL_REPLY_SEED, post.as_ref()])
+}
+
+pub fn post_escrow_pda(post: &Pubkey) -> (Pubkey, u8) {
+    post_program_pda(&[ESCROW_SEED, post.as_ref()])
+}
+
+pub fn mint_treasury_pda(mint: &Pubkey) -> (Pubkey, u8) {
+    post_program_pda(&[MINT_TREASURY_SEED, mint.as_ref()])
+}
+
+pub fn mint_vault_pda(mint: &Pubkey) -> (Pubkey, u8) {
+    post_program_pda(&[MINT_VAULT_SEED, mint.as_ref()])
+}
+
+pub fn upvote_receipt_pda(post: &Pubkey, voter: &Pubkey) -> (Pubkey, u8) {
+    post_program_pda(&[UPVOTE_SEED, post.as_ref(), voter.as_ref()])
+}
+
+pub fn sponsorship_pda(sponsor: &Pubkey, target: &str) -> (Pubkey, u8) {
+    post_program_pda(&[SPONSORSHIP_SEED, sponsor.as_ref(), &target_seed(target)])
+}
+
+pub fn scheduled_post_pda(author: &Pubkey, target: &str) -> (Pubkey, u8) {
+    post_program_pda(&[SCHEDULED_SEED, author.as_ref(), target.as_bytes()])
+}
+
+pub fn receipt_authority_pda() -> (Pubkey, u8) {
+    post_program_pda(&[RECEIPT_AUTHORITY_SEED])
+}
+
+pub fn auction_pda(auction_id: u64) -> (Pubkey, u8) {
+    post_program_pda(&[AUCTION_SEED, &auction_id.to_le_bytes()])
+}
+
+pub fn sealed_bid_pda(auction: &Pubkey, bidder: &Pubkey) -> (Pubkey, u8) {
+    post_program_pda(&[SEALED_BID_SEED, auction.as_ref(), bidder.as_ref()])
+}
+
+pub fn refund_vault_pda(auction: &Pubkey) -> (Pubkey, u8) {
+    post_program_pda(&[REFUND_VAULT_SEED, auction.as_ref()])
+}
+
+pub fn sealed_content_pda(post: &Pubkey) -> (Pubkey, u8) {
+    post_program_pda(&[SEALED_CONTENT_SEED, post.as_ref()])
+}
+
+pub fn post_metrics_pda() -> (Pubkey, u8) {
+    post_program_pda(&[METRICS_SEED])
+}
+
+// ----------------------------------------------------------------------------
+// private_messages
+// ----------------------------------------------------------------------------
+
+pub fn user_pda(wallet: &Pubkey) -> (Pubkey, u8) {
+    private_messages_pda(&[USER_SEED, wallet.as_ref()])
+}
+
+/// Plain message; `message_index` is the recipient's `message_count` when it was sent
+pub fn message_pda(sender: &Pubkey, recipient: &Pubkey, message_index: u64) -> (Pubkey, u8) {
+    private_messages_pda(&[MESSAGE_SEED, sender.as_ref(), recipient.as_ref(), &message_index.to_le_bytes()])
+}
+
+pub fn private_message_counter_pda() -> (Pubkey, u8) {
+    private_messages_pda(&[PRIVATE_MESSAGE_COUNTER_SEED])
+}
+
+pub fn private_message_pda(sender: &Pubkey, message_index: u64) -> (Pubkey, u8) {
+    private_messages_pda(&[PRIVATE_MESSAGE_SEED, sender.as_ref(), &message_index.to_le_bytes()])
+}
+
+pub fn membership_set_pda(authority: &Pubkey, set_id: u64) -> (Pubkey, u8) {
+    private_messages_pda(&[MEMBERSHIP_SET_SEED, authority.as_ref(), &set_id.to_le_bytes()])
+}
+
+pub fn rate_bucket_pda(sender: &Pubkey) -> (Pubkey, u8) {
+    private_messages_pda(&[RATE_BUCKET_SEED, sender.as_ref()])
+}
+
+pub fn poll_pda(creator: &Pubkey, poll_id: u64) -> (Pubkey, u8) {
+    private_messages_pda(&[POLL_SEED, creator.as_ref(), &poll_id.to_le_bytes()])
+}
+
+pub fn poll_ballot_pda(poll: &Pubkey, voter: &Pubkey) -> (Pubkey, u8) {
+    private_messages_pda(&[POLL_BALLOT_SEED, poll.as_ref(), voter.as_ref()])
+}
+
+pub fn notification_prefs_pda(wallet: &Pubkey) -> (Pubkey, u8) {
+    private_messages_pda(&[NOTIFICATION_PREFS_SEED, wallet.as_ref()])
+}
+
+pub fn private_messages_metrics_pda() -> (Pubkey, u8) {
+    private_messages_pda(&[METRICS_SEED])
+}
@@ -0,0 +1,89 @@
+//! PDA seed prefixes. The remaining seeds of each PDA are listed next to the prefix.
+
+// ----------------------------------------------------------------------------
+// post-msg-program
+// ----------------------------------------------------------------------------
+
+/// `[CONFIG_SEED]`
+pub const CONFIG_SEED: &[u8] = b"config";
+/// `[TREASURY_SEED, treasury_version_seed(&version)]`
+pub const TREASURY_SEED: &[u8] = b"treasury";
+/// `[POST_SEED, author, target bytes]`
+pub const POST_SEED: &[u8] = b"post";
+/// `[REFERRAL_SEED, wallet]`
+pub const REFERRAL_SEED: &[u8] = b"referral";
+/// `[CHARITY_SEED, wallet]`
+pub const CHARITY_SEED: &[u8] = b"charity";
+/// `[AUTHOR_INDEX_SEED, author]`
+pub const AUTHOR_INDEX_SEED: &[u8] = b"author_index";
+/// `[AUTHOR_STATS_SEED, author]`
+pub const AUTHOR_STATS_SEED: &[u8] = b"author_stats";
+/// `[STAKE_SEED, author]`
+pub const STAKE_SEED: &[u8] = b"stake";
+/// `[REVENUE_SEED, epoch le bytes]`
+pub const REVENUE_SEED: &[u8] = b"revenue";
+/// `[VESTING_VAULT_SEED]`
+pub const VESTING_VAULT_SEED: &[u8] = b"vesting_vault";
+/// `[TARGET_SEED, target_seed(target)]`
+pub const TARGET_SEED: &[u8] = b"target";
+/// `[TARGET_IDENTITY_SEED, target_seed(target)]`
+pub const TARGET_IDENTITY_SEED: &[u8] = b"target_identity";
+/// `[OFFICIAL_REPLY_SEED, post]`
+pub const OFFICIAL_REPLY_SEED: &[u8] = b"official_reply";
+/// `[ESCROW_SEED, post]`
+pub const ESCROW_SEED: &[u8] = b"escrow";
+/// `[MINT_TREASURY_SEED, mint]`
+pub const MINT_TREASURY_SEED: &[u8] = b"mint_treasury";
+/// `[MINT_VAULT_SEED, mint]`
+pub const MINT_VAULT_SEED: &[u8] = b"mint_vault";
+/// `[UPVOTE_SEED, post, voter]`
+pub const UPVOTE_SEED: &[u8] = b"upvote";
+/// `[SPONSORSHIP_SEED, sponsor, target_seed(target)]`
+pub const SPONSORSHIP_SEED: &[u8] = b"sponsorship";
+/// `[SCHEDULED_SEED, author, target bytes]`
+pub const SCHEDULED_SEED: &[u8] = b"scheduled";
+/// `[RECEIPT_AUTHORITY_SEED]`
+pub const RECEIPT_AUTHORITY_SEED: &[u8] = b"receipt_authority";
+/// `[AUCTION_SEED, auction id le bytes]`
+pub const AUCTION_SEED: &[u8] = b"auction";
+/// `[SEALED_BID_SEED, auction, bidder]`
+pub const SEALED_BID_SEED: &[u8] = b"sealed_bid";
+/// `[REFUND_VAULT_SEED, auction]`
+pub const REFUND_VAULT_SEED: &[u8] = b"refund_vault";
+/// `[SEALED_CONTENT_SEED, post]`
+pub const SEALED_CONTENT_SEED: &[u8] = b"sealed_content";
+/// `[METRICS_SEED]`, in both programs
+pub const METRICS_SEED: &[u8] = b"metrics";
+
+// ----------------------------------------------------------------------------
+// private_messages
+// ----------------------------------------------------------------------------
+
+/// `[USER_SEED, wallet]`
+pub const USER_SEED: &[u8] = b"user";
+/// `[MESSAGE_SEED, sender, recipient, recipient message_count le bytes]`
+pub const MESSAGE_SEED: &[u8] = b"message";
+/// `[PRIVATE_MESSAGE_COUNTER_SEED]`
+pub const PRIVATE_MESSAGE_COUNTER_SEED: &[u8] = b"private_message_counter";
+/// `[PRIVATE_MESSAGE_SEED, sender, message index le bytes]`
+pub const PRIVATE_MESSAGE_SEED: &[u8] = b"private_message";
+/// `[MEMBERSHIP_SET_SEED, authority, set_id le bytes]`
+pub const MEMBERSHIP_SET_SEED: &[u8] = b"membership_set";
+/// `[RATE_BUCKET_SEED, sender]`
+pub const RATE_BUCKET_SEED: &[u8] = b"rate_bucket";
+/// `[POLL_SEED, creator, poll_id le bytes]`
+pub const POLL_SEED: &[u8] = b"poll";
+/// `[POLL_BALLOT_SEED, poll, voter]`
+pub const POLL_BALLOT_SEED: &[u8] = b"poll_ballot";
+/// `[NOTIFICATION_PREFS_SEED, wallet]`
+pub const NOTIFICATION_PREFS_SEED: &[u8] = b"notification_prefs";
+
+/// Second treasury seed of `Config::treasury_version`: empty for version 1 (the original
+/// `["treasury"]` PDA, an empty seed does not change the address), `[version]` afterwards
+pub fn treasury_version_seed(version: &u8) -> &[u8] {
+    if *version <= 1 {
+        &[]
+    } else {
+        core::slice::from_ref(version)
+    }
+}
@@ -0,0 +1,111 @@
+//! wasm-bindgen exports. Keys and ciphertexts are byte arrays (`Uint8Array`), addresses
+//! base58 strings, message indexes `bigint`.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::str::FromStr;
+
+use solana_pubkey::Pubkey;
+use wasm_bindgen::prelude::*;
+
+use crate::{message, pda};
+
+fn pubkey(value: &str) -> Result<Pubkey, JsError> {
+    Pubkey::from_str(value).map_err(|_| JsError::new("invalid pubkey"))
+}
+
+fn key(bytes: &[u8]) -> Result<[u8; 32], JsError> {
+    bytes.try_into().map_err(|_| JsError::new("keys are 32 bytes"))
+}
+
+#[wasm_bindgen]
+pub struct MessagingKeypair {
+    public: [u8; 32],
+    secret: [u8; 32],
+}
+
+#[wasm_bindgen]
+impl MessagingKeypair {
+    #[wasm_bindgen(getter, js_name = publicKey)]
+    pub fn public_key(&self) -> Vec<u8> {
+        self.public.to_vec()
+    }
+
+    #[wasm_bindgen(getter, js_name = secretKey)]
+    pub fn secret_key(&self) -> Vec<u8> {
+        self.secret.to_vec()
+    }
+}
+
+#[wasm_bindgen]
+pub struct EncryptedMessage {
+    content: Vec<u8>,
+    nonce: [u8; message::NONCE_LEN],
+}
+
+#[wasm_bindgen]
+impl EncryptedMessage {
+    #[wasm_bindgen(getter)]
+    pub fn content(&self) -> Vec<u8> {
+        self.content.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn nonce(&self) -> Vec<u8> {
+        self.nonce.to_vec()
+    }
+}
+
+#[wasm_bindgen(js_name = deriveMessagingKeypair)]
+pub fn derive_messaging_keypair(wallet_secret_key: &[u8]) -> MessagingKeypair {
+    let keys = message::derive_x25519_keypair(wallet_secret_key);
+    MessagingKeypair { public: keys.public, secret: keys.secret }
+}
+
+#[wasm_bindgen(js_name = encryptMessage)]
+pub fn encrypt_message(text: &str, recipient_pubkey: &[u8], sender_secret: &[u8]) -> Result<EncryptedMessage, JsError> {
+    let (content, nonce) = message::encrypt_message(text, &key(recipient_pubkey)?, &key(sender_secret)?);
+    Ok(EncryptedMessage { content, nonce })
+}
+
+#[wasm_bindgen(js_name = decryptMessage)]
+pub fn decrypt_message(
+    encrypted_content: &[u8],
+    nonce: &[u8],
+    other_party_pubkey: &[u8],
+    my_secret: &[u8],
+) -> Result<String, JsError> {
+    let nonce: &[u8; message::NONCE_LEN] = nonce.try_into().map_err(|_| JsError::new("nonces are 24 bytes"))?;
+    message::decrypt_message(encrypted_content, nonce, &key(other_party_pubkey)?, &key(my_secret)?)
+        .map_err(|err| JsError::new(&err.to_string()))
+}
+
+#[wasm_bindgen(js_name = identityHash)]
+pub fn identity_hash(wallet: &str) -> Result<Vec<u8>, JsError> {
+    Ok(message::identity_hash(&pubkey(wallet)?).to_vec())
+}
+
+#[wasm_bindgen(js_name = userPda)]
+pub fn user_pda(wallet: &str) -> Result<String, JsError> {
+    Ok(pda::user_pda(&pubkey(wallet)?).0.to_string())
+}
+
+#[wasm_bindgen(js_name = messagePda)]
+pub fn message_pda(sender: &str, recipient: &str, message_index: u64) -> Result<String, JsError> {
+    Ok(pda::message_pda(&pubkey(sender)?, &pubkey(recipient)?, message_index).0.to_string())
+}
+
+#[wasm_bindgen(js_name = privateMessagePda)]
+pub fn private_message_pda(sender: &str, message_index: u64) -> Result<String, JsError> {
+    Ok(pda::private_message_pda(&pubkey(sender)?, message_index).0.to_string())
+}
+
+#[wasm_bindgen(js_name = postPda)]
+pub fn post_pda(author: &str, target: &str) -> Result<String, JsError> {
+    Ok(pda::post_pda(&pubkey(author)?, target).0.to_string())
+}
+
+#[wasm_bindgen(js_name = targetAccountPda)]
+pub fn target_account_pda(target: &str) -> String {
+    pda::target_account_pda(target).0.to_string()
+}
@@ -0,0 +1,78 @@
+//! Plugin configuration: the JSON file the validator passes with `--geyser-plugin-config`
+//! (see `config.example.json`).
+
+use serde::Deserialize;
+use solana_sdk::pubkey::Pubkey;
+use xray_client::xray_common::{POST_MSG_PROGRAM_ID, PRIVATE_MESSAGES_PROGRAM_ID};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Program {
+    Post,
+    PrivateMessages,
+}
+
+impl Program {
+    pub fn id(self) -> Pubkey {
+        match self {
+            Self::Post => POST_MSG_PROGRAM_ID,
+            Self::PrivateMessages => PRIVATE_MESSAGES_PROGRAM_ID,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Post => "post",
+            Self::PrivateMessages => "private_messages",
+        }
+    }
+
+    pub fn from_owner(owner: &[u8]) -> Option<Self> {
+        [Self::Post, Self::PrivateMessages].into_iter().find(|program| program.id().as_ref() == owner)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SinkConfig {
+    /// Records keyed by account address (slot records by slot), so a compacted topic keeps the
+    /// latest state of each account
+    Kafka {
+        brokers: String,
+        topic: String,
+        /// Extra librdkafka settings (`compression.type`, `acks`, ...)
+        #[serde(default)]
+        producer: std::collections::BTreeMap<String, String>,
+    },
+    /// Records on `<subject>.<program>.<account type>` (slot records on `<subject>.slot`)
+    Nats { url: String, subject: String },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Config {
+    pub sink: SinkConfig,
+    /// Programs whose accounts are streamed (both by default)
+    #[serde(default = "all_programs")]
+    pub programs: Vec<Program>,
+    /// Also stream the accounts loaded from the snapshot at startup
+    #[serde(default = "yes")]
+    pub startup_accounts: bool,
+    /// Also stream slot status changes (to tell processed updates from rooted ones)
+    #[serde(default = "yes")]
+    pub slot_updates: bool,
+    /// Records buffered for the sink; updates are dropped (and counted) when it is full
+    #[serde(default = "default_queue_capacity")]
+    pub queue_capacity: usize,
+}
+
+fn all_programs() -> Vec<Program> {
+    vec![Program::Post, Program::PrivateMessages]
+}
+
+fn yes() -> bool {
+    true
+}
+
+fn default_queue_capacity() -> usize {
+    100_000
+}
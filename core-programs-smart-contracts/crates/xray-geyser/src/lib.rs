@@ -0,0 +1,136 @@
+//! `xray-geyser`: Geyser plugin for a validator (or RPC node) that keeps only the account
+//! updates of the two X-RAY programs, decodes them with the `xray-client` mirrors and streams
+//! normalized JSON records to Kafka or NATS. Unlike `xray-indexer` it sees every state
+//! change, including the ones no event describes, without polling or websocket gaps.
+//!
+//! Build it against the validator's exact agave version (bump `agave-geyser-plugin-interface`
+//! to match), then point the validator at a config file:
+//!
+//! ```text
+//! cargo build --release -p xray-geyser                                     # Kafka
+//! cargo build --release -p xray-geyser --no-default-features --features nats
+//! agave-validator ... --geyser-plugin-config config.json
+//! ```
+//!
+//! See `config.example.json` and [`config::Config`] for the options, and [`record`] for the
+//! record format.
+
+pub mod config;
+pub mod record;
+mod sink;
+
+use agave_geyser_plugin_interface::geyser_plugin_interface::{
+    GeyserPlugin, GeyserPluginError, ReplicaAccountInfoVersions, Result, SlotStatus,
+};
+
+use crate::config::{Config, Program};
+use crate::record::{AccountRecord, AccountUpdate, Record, SlotRecord};
+use crate::sink::Sink;
+
+#[derive(Default)]
+pub struct XrayGeyserPlugin {
+    programs: Vec<Program>,
+    startup_accounts: bool,
+    slot_updates: bool,
+    sink: Option<Sink>,
+}
+
+impl std::fmt::Debug for XrayGeyserPlugin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("XrayGeyserPlugin").field("programs", &self.programs).finish_non_exhaustive()
+    }
+}
+
+impl XrayGeyserPlugin {
+    fn send(&self, record: Record) -> std::result::Result<(), String> {
+        match &self.sink {
+            Some(sink) => sink.send(&record),
+            None => Err("plugin not loaded".to_string()),
+        }
+    }
+}
+
+impl GeyserPlugin for XrayGeyserPlugin {
+    fn name(&self) -> &'static str {
+        "xray-geyser"
+    }
+
+    fn setup_logger(&self, logger: &'static dyn log::Log, level: log::LevelFilter) -> Result<()> {
+        log::set_max_level(level);
+        let _ = log::set_logger(logger);
+        Ok(())
+    }
+
+    fn on_load(&mut self, config_file: &str, _is_reload: bool) -> Result<()> {
+        let contents = std::fs::read_to_string(config_file).map_err(GeyserPluginError::ConfigFileOpenError)?;
+        let config: Config = serde_json::from_str(&contents)
+            .map_err(|err| GeyserPluginError::ConfigFileReadError { msg: err.to_string() })?;
+
+        let sink = Sink::start(config.sink, config.queue_capacity)
+            .map_err(|msg| GeyserPluginError::ConfigFileReadError { msg })?;
+        self.programs = config.programs;
+        self.startup_accounts = config.startup_accounts;
+        self.slot_updates = config.slot_updates;
+        self.sink = Some(sink);
+        log::info!("xray-geyser: streaming {:?}", self.programs);
+        Ok(())
+    }
+
+    fn on_unload(&mut self) {
+        if let Some(mut sink) = self.sink.take() {
+            sink.stop();
+        }
+    }
+
+    fn update_account(&self, account: ReplicaAccountInfoVersions, slot: u64, is_startup: bool) -> Result<()> {
+        if is_startup && !self.startup_accounts {
+            return Ok(());
+        }
+        let (address, owner, lamports, data, write_version) = match account {
+            ReplicaAccountInfoVersions::V0_0_1(info) => {
+                (info.pubkey, info.owner, info.lamports, info.data, info.write_version)
+            }
+            ReplicaAccountInfoVersions::V0_0_2(info) => {
+                (info.pubkey, info.owner, info.lamports, info.data, info.write_version)
+            }
+            ReplicaAccountInfoVersions::V0_0_3(info) => {
+                (info.pubkey, info.owner, info.lamports, info.data, info.write_version)
+            }
+        };
+        let Some(program) = Program::from_owner(owner).filter(|program| self.programs.contains(program)) else {
+            return Ok(());
+        };
+
+        let update = AccountUpdate { program, address, lamports, data, slot, write_version, is_startup };
+        self.send(Record::Account(AccountRecord::new(&update)))
+            .map_err(|msg| GeyserPluginError::AccountsUpdateError { msg })
+    }
+
+    fn update_slot_status(&self, slot: u64, parent: Option<u64>, status: &SlotStatus) -> Result<()> {
+        if !self.slot_updates {
+            return Ok(());
+        }
+        let record = SlotRecord { slot, parent, status: status.as_str().to_string() };
+        self.send(Record::Slot(record)).map_err(|msg| GeyserPluginError::SlotStatusUpdateError { msg })
+    }
+
+    fn account_data_notifications_enabled(&self) -> bool {
+        true
+    }
+
+    fn transaction_notifications_enabled(&self) -> bool {
+        false
+    }
+}
+
+/// Entry point looked up by the validator
+///
+/// # Safety
+///
+/// The validator takes ownership of the returned pointer.
+#[no_mangle]
+#[allow(improper_ctypes_definitions)]
+pub unsafe extern "C" fn _create_plugin() -> *mut dyn GeyserPlugin {
+    let plugin: Box<dyn GeyserPlugin> = Box::new(XrayGeyserPlugin::default());
+    Box::into_raw(plugin)
+}
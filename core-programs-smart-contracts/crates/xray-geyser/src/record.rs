@@ -0,0 +1,92 @@
+//! Normalized records: account updates decoded into JSON with `xray_client::json`, plus slot
+//! status changes.
+//!
+//! An account whose discriminator is unknown (or that no longer decodes) is still streamed,
+//! with `account_type` null and only its raw data.
+
+use base64::Engine;
+use serde::Serialize;
+use serde_json::Value;
+use solana_sdk::pubkey::Pubkey;
+use xray_client::json;
+
+use crate::config::Program;
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "record", rename_all = "snake_case")]
+pub enum Record {
+    Account(AccountRecord),
+    Slot(SlotRecord),
+}
+
+#[derive(Debug, Serialize)]
+pub struct AccountRecord {
+    pub program: &'static str,
+    /// Anchor account name (`Post`, `MessageAccount`, ...)
+    pub account_type: Option<&'static str>,
+    pub address: String,
+    pub slot: u64,
+    /// Orders updates of the same account within a slot
+    pub write_version: u64,
+    pub lamports: u64,
+    /// Closed accounts are sent once with no lamports and no data
+    pub closed: bool,
+    pub is_startup: bool,
+    pub fields: Option<Value>,
+    /// Raw account data, base64
+    pub data: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SlotRecord {
+    pub slot: u64,
+    pub parent: Option<u64>,
+    /// `processed`, `confirmed`, `rooted`, ...
+    pub status: String,
+}
+
+impl Record {
+    /// Partition / deduplication key
+    pub fn key(&self) -> String {
+        match self {
+            Self::Account(account) => account.address.clone(),
+            Self::Slot(slot) => slot.slot.to_string(),
+        }
+    }
+
+    /// Subject suffix on NATS
+    pub fn topic_suffix(&self) -> String {
+        match self {
+            Self::Account(account) => format!("{}.{}", account.program, account.account_type.unwrap_or("unknown")),
+            Self::Slot(_) => "slot".to_string(),
+        }
+    }
+}
+
+pub struct AccountUpdate<'a> {
+    pub program: Program,
+    pub address: &'a [u8],
+    pub lamports: u64,
+    pub data: &'a [u8],
+    pub slot: u64,
+    pub write_version: u64,
+    pub is_startup: bool,
+}
+
+impl AccountRecord {
+    pub fn new(update: &AccountUpdate) -> Self {
+        let decoded = json::decode(&update.program.id(), update.data);
+        Self {
+            program: update.program.name(),
+            account_type: decoded.as_ref().map(|(name, _)| *name),
+            address: Pubkey::try_from(update.address).map(|address| address.to_string()).unwrap_or_default(),
+            slot: update.slot,
+            write_version: update.write_version,
+            lamports: update.lamports,
+            closed: update.lamports == 0,
+            is_startup: update.is_startup,
+            fields: decoded.map(|(_, fields)| fields),
+            data: base64::engine::general_purpose::STANDARD.encode(update.data),
+        }
+    }
+}
@@ -0,0 +1,145 @@
+//! Sink worker: a thread draining the record queue into Kafka or NATS, so the validator's
+//! notification threads only ever serialize and enqueue.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
+use crate::config::SinkConfig;
+use crate::record::Record;
+
+/// Encoded record: key, subject suffix, JSON payload
+#[allow(dead_code)] // the key is only read by Kafka, the suffix by NATS
+struct Message {
+    key: String,
+    suffix: String,
+    payload: Vec<u8>,
+}
+
+pub struct Sink {
+    queue: Option<SyncSender<Message>>,
+    worker: Option<JoinHandle<()>>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl Sink {
+    pub fn start(config: SinkConfig, capacity: usize) -> Result<Self, String> {
+        let (queue, receiver) = mpsc::sync_channel(capacity);
+        let publisher = Publisher::connect(config)?;
+        let worker = std::thread::Builder::new()
+            .name("xray-geyser-sink".to_string())
+            .spawn(move || publisher.run(receiver))
+            .map_err(|err| err.to_string())?;
+        Ok(Self { queue: Some(queue), worker: Some(worker), dropped: Arc::default() })
+    }
+
+    /// Enqueues `record` without blocking; a full queue drops it
+    pub fn send(&self, record: &Record) -> Result<(), String> {
+        let Some(queue) = &self.queue else {
+            return Err("sink is stopped".to_string());
+        };
+        let payload = serde_json::to_vec(record).map_err(|err| err.to_string())?;
+        let message = Message { key: record.key(), suffix: record.topic_suffix(), payload };
+        match queue.try_send(message) {
+            Ok(()) => Ok(()),
+            Err(TrySendError::Full(_)) => {
+                let dropped = self.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+                if dropped.is_power_of_two() {
+                    log::warn!("xray-geyser: sink queue full, {dropped} records dropped so far");
+                }
+                Ok(())
+            }
+            Err(TrySendError::Disconnected(_)) => Err("sink worker exited".to_string()),
+        }
+    }
+
+    /// Closes the queue and waits for the worker to flush what is left
+    pub fn stop(&mut self) {
+        self.queue.take();
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+impl Drop for Sink {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+enum Publisher {
+    #[cfg(feature = "kafka")]
+    Kafka { producer: rdkafka::producer::BaseProducer, topic: String },
+    #[cfg(feature = "nats")]
+    Nats { runtime: tokio::runtime::Runtime, client: async_nats::Client, subject: String },
+}
+
+impl Publisher {
+    fn connect(config: SinkConfig) -> Result<Self, String> {
+        match config {
+            #[cfg(feature = "kafka")]
+            SinkConfig::Kafka { brokers, topic, producer } => {
+                let mut client_config = rdkafka::ClientConfig::new();
+                client_config.set("bootstrap.servers", brokers);
+                for (key, value) in producer {
+                    client_config.set(key, value);
+                }
+                let producer = client_config.create().map_err(|err| err.to_string())?;
+                Ok(Self::Kafka { producer, topic })
+            }
+            #[cfg(feature = "nats")]
+            SinkConfig::Nats { url, subject } => {
+                let runtime = tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                    .map_err(|err| err.to_string())?;
+                let client = runtime.block_on(async_nats::connect(url)).map_err(|err| err.to_string())?;
+                Ok(Self::Nats { runtime, client, subject })
+            }
+            #[allow(unreachable_patterns)]
+            sink => Err(format!("{sink:?}: sink not compiled in (enable its cargo feature)")),
+        }
+    }
+
+    fn run(self, receiver: Receiver<Message>) {
+        match self {
+            #[cfg(feature = "kafka")]
+            Self::Kafka { producer, topic } => {
+                use std::time::Duration;
+
+                use rdkafka::producer::{BaseRecord, Producer};
+
+                for message in receiver {
+                    let mut record = BaseRecord::to(&topic).key(&message.key).payload(&message.payload);
+                    // Queue full on the librdkafka side: serve delivery reports and retry
+                    while let Err((err, returned)) = producer.send(record) {
+                        if err.rdkafka_error_code() != Some(rdkafka::types::RDKafkaErrorCode::QueueFull) {
+                            log::error!("xray-geyser: kafka send failed: {err}");
+                            break;
+                        }
+                        producer.poll(Duration::from_millis(100));
+                        record = returned;
+                    }
+                    producer.poll(Duration::ZERO);
+                }
+                if let Err(err) = producer.flush(Duration::from_secs(10)) {
+                    log::error!("xray-geyser: kafka flush failed: {err}");
+                }
+            }
+            #[cfg(feature = "nats")]
+            Self::Nats { runtime, client, subject } => {
+                for message in receiver {
+                    let subject = format!("{subject}.{}", message.suffix);
+                    if let Err(err) = runtime.block_on(client.publish(subject, message.payload.into())) {
+                        log::error!("xray-geyser: nats publish failed: {err}");
+                    }
+                }
+                if let Err(err) = runtime.block_on(client.flush()) {
+                    log::error!("xray-geyser: nats flush failed: {err}");
+                }
+            }
+        }
+    }
+}
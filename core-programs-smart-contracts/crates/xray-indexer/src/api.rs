@@ -0,0 +1,153 @@
+//! Read-only HTTP API over the indexed views.
+//!
+//! - `GET /health`: stream cursor (slot) per program
+//! - `GET /users/{wallet}`: registration and messaging key
+//! - `GET /inbox/{wallet}?unread=true&before=&limit=`: received messages, newest first
+//! - `GET /outbox/{wallet}?before=&limit=`: sent messages, newest first
+//! - `GET /posts?target=&author=&before=&limit=`: posts, newest first
+//! - `GET /posts/{address}`: one post
+//!
+//! `before` is a unix timestamp (exclusive) for paging; `limit` defaults to 50, at most 500.
+
+use std::str::FromStr;
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::store::{Page, Store};
+
+const DEFAULT_LIMIT: u32 = 50;
+const MAX_LIMIT: u32 = 500;
+
+pub fn router(store: Arc<Store>) -> Router {
+    Router::new()
+        .route("/health", get(health))
+        .route("/users/{wallet}", get(user))
+        .route("/inbox/{wallet}", get(inbox))
+        .route("/outbox/{wallet}", get(outbox))
+        .route("/posts", get(posts))
+        .route("/posts/{address}", get(post))
+        .with_state(store)
+}
+
+pub enum ApiError {
+    BadRequest(String),
+    NotFound,
+    Internal(anyhow::Error),
+}
+
+impl From<anyhow::Error> for ApiError {
+    fn from(err: anyhow::Error) -> Self {
+        ApiError::Internal(err)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, message) = match self {
+            ApiError::BadRequest(message) => (StatusCode::BAD_REQUEST, message),
+            ApiError::NotFound => (StatusCode::NOT_FOUND, "not found".to_string()),
+            ApiError::Internal(err) => {
+                eprintln!("request failed: {err:#}");
+                (StatusCode::INTERNAL_SERVER_ERROR, "internal error".to_string())
+            }
+        };
+        (status, Json(ErrorBody { error: message })).into_response()
+    }
+}
+
+#[derive(Serialize)]
+struct ErrorBody {
+    error: String,
+}
+
+type ApiResult<T> = Result<Json<T>, ApiError>;
+
+#[derive(Deserialize)]
+struct PageQuery {
+    before: Option<i64>,
+    limit: Option<u32>,
+    #[serde(default)]
+    unread: bool,
+}
+
+impl PageQuery {
+    fn page(&self) -> Page {
+        page(self.before, self.limit)
+    }
+}
+
+// Not flattened into PageQuery: serde_urlencoded cannot parse numbers inside #[serde(flatten)]
+#[derive(Deserialize)]
+struct PostsQuery {
+    target: Option<String>,
+    author: Option<String>,
+    before: Option<i64>,
+    limit: Option<u32>,
+}
+
+fn page(before: Option<i64>, limit: Option<u32>) -> Page {
+    Page {
+        before,
+        limit: limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT),
+    }
+}
+
+fn pubkey(value: &str) -> Result<Pubkey, ApiError> {
+    Pubkey::from_str(value).map_err(|_| ApiError::BadRequest(format!("invalid pubkey {value}")))
+}
+
+#[derive(Serialize)]
+struct Health {
+    cursors: Vec<ProgramCursor>,
+}
+
+#[derive(Serialize)]
+struct ProgramCursor {
+    program_id: String,
+    slot: u64,
+}
+
+async fn health(State(store): State<Arc<Store>>) -> ApiResult<Health> {
+    let cursors = store
+        .cursors()?
+        .into_iter()
+        .map(|(program_id, slot)| ProgramCursor { program_id, slot })
+        .collect();
+    Ok(Json(Health { cursors }))
+}
+
+async fn user(State(store): State<Arc<Store>>, Path(wallet): Path<String>) -> ApiResult<impl Serialize> {
+    store.user(&pubkey(&wallet)?)?.map(Json).ok_or(ApiError::NotFound)
+}
+
+async fn inbox(
+    State(store): State<Arc<Store>>,
+    Path(wallet): Path<String>,
+    Query(query): Query<PageQuery>,
+) -> ApiResult<impl Serialize> {
+    Ok(Json(store.inbox(&pubkey(&wallet)?, query.unread, query.page())?))
+}
+
+async fn outbox(
+    State(store): State<Arc<Store>>,
+    Path(wallet): Path<String>,
+    Query(query): Query<PageQuery>,
+) -> ApiResult<impl Serialize> {
+    Ok(Json(store.outbox(&pubkey(&wallet)?, query.page())?))
+}
+
+async fn posts(State(store): State<Arc<Store>>, Query(query): Query<PostsQuery>) -> ApiResult<impl Serialize> {
+    let author = query.author.as_deref().map(pubkey).transpose()?;
+    Ok(Json(store.posts(query.target.as_deref(), author.as_ref(), page(query.before, query.limit))?))
+}
+
+async fn post(State(store): State<Arc<Store>>, Path(address): Path<String>) -> ApiResult<impl Serialize> {
+    store.post(&pubkey(&address)?)?.map(Json).ok_or(ApiError::NotFound)
+}
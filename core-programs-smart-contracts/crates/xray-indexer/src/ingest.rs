@@ -0,0 +1,120 @@
+//! Event ingestion: raw program events from the log stream, applied to the SQLite views.
+//! Nothing is decrypted; rows only hold what the events make public.
+
+use std::sync::Arc;
+
+use anyhow::{bail, Result};
+use futures_util::StreamExt;
+use rusqlite::{params, Transaction};
+use xray_client::stream::{self, EventStreamConfig, RawEvent};
+use xray_client::xray_common::events::post::{
+    EscrowReclaimed, PostAccepted, PostBoosted, PostCreated, PostPinned, PostUpvoted,
+};
+use xray_client::xray_common::events::private_messages::{
+    MessageRead, MessageSent, PrivateMessageSent, UserKeyUpdated, UserRegistered,
+};
+
+use crate::store::Store;
+
+/// Indexes events until the stream ends; stream and write errors are logged and skipped
+pub async fn run(store: Arc<Store>, config: EventStreamConfig) {
+    let mut events = stream::subscribe_raw(config);
+    while let Some(item) = events.next().await {
+        match item {
+            Ok(raw) => {
+                if let Err(err) = store.apply(&raw) {
+                    eprintln!("failed to index event from {}: {err:#}", raw.signature);
+                }
+            }
+            Err(err) => eprintln!("event stream: {err}"),
+        }
+    }
+}
+
+pub fn apply_event(tx: &Transaction, raw: &RawEvent) -> Result<()> {
+    let slot = raw.slot;
+    let signature = raw.signature.to_string();
+
+    // private_messages
+    if let Some(event) = raw.decode::<UserRegistered>()? {
+        tx.execute(
+            "INSERT INTO users (wallet, x25519_pubkey, registered_slot) VALUES (?1, ?2, ?3)
+             ON CONFLICT (wallet) DO UPDATE SET x25519_pubkey = excluded.x25519_pubkey",
+            params![event.wallet.to_string(), hex(&event.x25519_pubkey), slot],
+        )?;
+    } else if let Some(event) = raw.decode::<UserKeyUpdated>()? {
+        tx.execute(
+            "UPDATE users SET x25519_pubkey = ?2 WHERE wallet = ?1",
+            params![event.wallet.to_string(), hex(&event.new_x25519_pubkey)],
+        )?;
+    } else if let Some(event) = raw.decode::<MessageSent>()? {
+        let (Some(index), Some(address)) = (event.seed_index(), event.message_address()) else {
+            bail!("MessageSent with message_index 0");
+        };
+        tx.execute(
+            "INSERT INTO messages (address, sender, recipient, message_index, sent_at, slot, signature)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7) ON CONFLICT (address) DO NOTHING",
+            params![
+                address.to_string(),
+                event.sender.to_string(),
+                event.recipient.to_string(),
+                index,
+                event.timestamp,
+                slot,
+                signature
+            ],
+        )?;
+    } else if let Some(event) = raw.decode::<MessageRead>()? {
+        tx.execute("UPDATE messages SET is_read = 1 WHERE address = ?1", [event.message.to_string()])?;
+    } else if let Some(event) = raw.decode::<PrivateMessageSent>()? {
+        tx.execute(
+            "INSERT INTO private_messages (message_index, sent_at, slot, signature) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT (message_index) DO NOTHING",
+            params![event.message_index, event.timestamp, slot, signature],
+        )?;
+    }
+    // post-msg-program
+    else if let Some(event) = raw.decode::<PostCreated>()? {
+        // Sponsorship periods re-create the same post address: the latest period wins
+        tx.execute(
+            "INSERT INTO posts (address, author, target, bid, bid_mint, created_at, slot, signature)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+             ON CONFLICT (address) DO UPDATE SET bid = excluded.bid, created_at = excluded.created_at,
+                 slot = excluded.slot, signature = excluded.signature",
+            params![
+                event.post.to_string(),
+                event.author.to_string(),
+                event.target,
+                event.bid,
+                event.bid_mint.to_string(),
+                event.timestamp,
+                slot,
+                signature
+            ],
+        )?;
+    } else if let Some(event) = raw.decode::<PostUpvoted>()? {
+        tx.execute(
+            "UPDATE posts SET upvotes = ?2 WHERE address = ?1",
+            params![event.post.to_string(), event.upvotes],
+        )?;
+    } else if let Some(event) = raw.decode::<PostBoosted>()? {
+        tx.execute(
+            "UPDATE posts SET total_boosts = total_boosts + ?2 WHERE address = ?1",
+            params![event.post.to_string(), event.amount],
+        )?;
+    } else if let Some(event) = raw.decode::<PostPinned>()? {
+        tx.execute(
+            "UPDATE posts SET pinned_until = ?2 WHERE address = ?1",
+            params![event.post.to_string(), event.pinned_until],
+        )?;
+    } else if let Some(event) = raw.decode::<PostAccepted>()? {
+        tx.execute("UPDATE posts SET accepted = 1 WHERE address = ?1", [event.post.to_string()])?;
+    } else if let Some(event) = raw.decode::<EscrowReclaimed>()? {
+        tx.execute("UPDATE posts SET reclaimed = 1 WHERE address = ?1", [event.post.to_string()])?;
+    }
+    Ok(())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
@@ -0,0 +1,60 @@
+//! `xray-indexer`: consumes the programs' events from websocket log subscriptions and
+//! materializes inbox and post views into SQLite, served read-only over HTTP so frontends
+//! don't need `getProgramAccounts` scans.
+//!
+//! Progress is checkpointed per program (slot + signature of the last indexed transaction);
+//! on restart the stream backfills everything after the checkpoint before going live.
+
+mod api;
+mod ingest;
+mod store;
+
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::Result;
+use clap::Parser;
+use xray_client::stream::EventStreamConfig;
+use xray_client::xray_common::{POST_MSG_PROGRAM_ID, PRIVATE_MESSAGES_PROGRAM_ID};
+
+use crate::store::Store;
+
+#[derive(Parser)]
+#[command(name = "xray-indexer", version, about)]
+struct Args {
+    /// RPC endpoint (backfill after a disconnect or restart)
+    #[arg(long, env = "XRAY_RPC_URL", default_value = "https://api.devnet.solana.com")]
+    rpc_url: String,
+
+    /// Websocket endpoint (log subscriptions)
+    #[arg(long, env = "XRAY_WS_URL", default_value = "wss://api.devnet.solana.com")]
+    ws_url: String,
+
+    /// SQLite database file
+    #[arg(long, env = "XRAY_INDEXER_DB", default_value = "xray-indexer.sqlite")]
+    db: PathBuf,
+
+    /// HTTP API listen address
+    #[arg(long, env = "XRAY_INDEXER_LISTEN", default_value = "127.0.0.1:8080")]
+    listen: SocketAddr,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = Args::parse();
+    let store = Arc::new(Store::open(&args.db)?);
+
+    let mut config = EventStreamConfig::new(args.rpc_url, args.ws_url);
+    for program_id in [POST_MSG_PROGRAM_ID, PRIVATE_MESSAGES_PROGRAM_ID] {
+        if let Some(cursor) = store.cursor(&program_id)? {
+            config = config.resume_from(program_id, cursor);
+        }
+    }
+    tokio::spawn(ingest::run(store.clone(), config));
+
+    let listener = tokio::net::TcpListener::bind(args.listen).await?;
+    eprintln!("serving on {}", args.listen);
+    axum::serve(listener, api::router(store)).await?;
+    Ok(())
+}
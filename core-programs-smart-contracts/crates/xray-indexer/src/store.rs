@@ -0,0 +1,258 @@
+//! SQLite views (users, inboxes, private message index, posts) and the per-program stream cursor.
+//! Pubkeys and signatures are stored base58-encoded, amounts as INTEGER.
+
+use std::path::Path;
+use std::sync::Mutex;
+
+use anyhow::Result;
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use xray_client::stream::{EventCursor, RawEvent};
+
+use crate::ingest;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS cursors (
+    program_id TEXT PRIMARY KEY,
+    slot INTEGER NOT NULL,
+    signature TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS users (
+    wallet TEXT PRIMARY KEY,
+    x25519_pubkey TEXT NOT NULL,
+    registered_slot INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS messages (
+    address TEXT PRIMARY KEY,
+    sender TEXT NOT NULL,
+    recipient TEXT NOT NULL,
+    message_index INTEGER NOT NULL,
+    sent_at INTEGER NOT NULL,
+    is_read INTEGER NOT NULL DEFAULT 0,
+    slot INTEGER NOT NULL,
+    signature TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS messages_recipient ON messages (recipient, sent_at);
+CREATE INDEX IF NOT EXISTS messages_sender ON messages (sender, sent_at);
+CREATE TABLE IF NOT EXISTS private_messages (
+    message_index INTEGER PRIMARY KEY,
+    sent_at INTEGER NOT NULL,
+    slot INTEGER NOT NULL,
+    signature TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS posts (
+    address TEXT PRIMARY KEY,
+    author TEXT NOT NULL,
+    target TEXT NOT NULL,
+    bid INTEGER NOT NULL,
+    bid_mint TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    upvotes INTEGER NOT NULL DEFAULT 0,
+    total_boosts INTEGER NOT NULL DEFAULT 0,
+    pinned_until INTEGER NOT NULL DEFAULT 0,
+    accepted INTEGER NOT NULL DEFAULT 0,
+    reclaimed INTEGER NOT NULL DEFAULT 0,
+    slot INTEGER NOT NULL,
+    signature TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS posts_target ON posts (target, created_at);
+CREATE INDEX IF NOT EXISTS posts_author ON posts (author, created_at);
+";
+
+#[derive(Debug, Serialize)]
+pub struct UserRow {
+    pub wallet: String,
+    pub x25519_pubkey: String,
+    pub registered_slot: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MessageRow {
+    pub address: String,
+    pub sender: String,
+    pub recipient: String,
+    pub message_index: u64,
+    pub sent_at: i64,
+    pub is_read: bool,
+    pub slot: u64,
+    pub signature: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PostRow {
+    pub address: String,
+    pub author: String,
+    pub target: String,
+    pub bid: u64,
+    pub bid_mint: String,
+    pub created_at: i64,
+    pub upvotes: u64,
+    pub total_boosts: u64,
+    pub pinned_until: i64,
+    pub accepted: bool,
+    pub reclaimed: bool,
+    pub slot: u64,
+    pub signature: String,
+}
+
+/// Page of rows older than `before` (unix timestamp), newest first
+#[derive(Debug, Clone, Copy)]
+pub struct Page {
+    pub before: Option<i64>,
+    pub limit: u32,
+}
+
+pub struct Store {
+    conn: Mutex<Connection>,
+}
+
+impl Store {
+    pub fn open(path: &Path) -> Result<Self> {
+        let conn = Connection::open(path)?;
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self { conn: Mutex::new(conn) })
+    }
+
+    pub fn cursor(&self, program_id: &Pubkey) -> Result<Option<EventCursor>> {
+        let conn = self.conn.lock().unwrap();
+        let row: Option<(u64, String)> = conn
+            .query_row(
+                "SELECT slot, signature FROM cursors WHERE program_id = ?1",
+                [program_id.to_string()],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        row.map(|(slot, signature)| Ok(EventCursor { slot, signature: signature.parse()? }))
+            .transpose()
+    }
+
+    /// Applies an event and advances its program's cursor in one transaction
+    pub fn apply(&self, raw: &RawEvent) -> Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        ingest::apply_event(&tx, raw)?;
+        save_cursor(&tx, &raw.program_id, &raw.cursor())?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn user(&self, wallet: &Pubkey) -> Result<Option<UserRow>> {
+        let conn = self.conn.lock().unwrap();
+        Ok(conn
+            .query_row(
+                "SELECT wallet, x25519_pubkey, registered_slot FROM users WHERE wallet = ?1",
+                [wallet.to_string()],
+                |row| {
+                    Ok(UserRow {
+                        wallet: row.get(0)?,
+                        x25519_pubkey: row.get(1)?,
+                        registered_slot: row.get(2)?,
+                    })
+                },
+            )
+            .optional()?)
+    }
+
+    /// Messages received by `recipient`
+    pub fn inbox(&self, recipient: &Pubkey, unread_only: bool, page: Page) -> Result<Vec<MessageRow>> {
+        self.messages("recipient", recipient, unread_only, page)
+    }
+
+    /// Messages sent by `sender`
+    pub fn outbox(&self, sender: &Pubkey, page: Page) -> Result<Vec<MessageRow>> {
+        self.messages("sender", sender, false, page)
+    }
+
+    fn messages(&self, party: &str, wallet: &Pubkey, unread_only: bool, page: Page) -> Result<Vec<MessageRow>> {
+        let conn = self.conn.lock().unwrap();
+        let sql = format!(
+            "SELECT address, sender, recipient, message_index, sent_at, is_read, slot, signature FROM messages
+             WHERE {party} = ?1 AND (?2 = 0 OR is_read = 0) AND sent_at < ?3
+             ORDER BY sent_at DESC LIMIT ?4"
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(
+            params![wallet.to_string(), unread_only, page.before.unwrap_or(i64::MAX), page.limit],
+            |row| {
+                Ok(MessageRow {
+                    address: row.get(0)?,
+                    sender: row.get(1)?,
+                    recipient: row.get(2)?,
+                    message_index: row.get(3)?,
+                    sent_at: row.get(4)?,
+                    is_read: row.get(5)?,
+                    slot: row.get(6)?,
+                    signature: row.get(7)?,
+                })
+            },
+        )?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// Posts, optionally restricted to a target and/or an author
+    pub fn posts(&self, target: Option<&str>, author: Option<&Pubkey>, page: Page) -> Result<Vec<PostRow>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!(
+            "{POST_COLUMNS} WHERE (?1 IS NULL OR target = ?1) AND (?2 IS NULL OR author = ?2) AND created_at < ?3
+             ORDER BY created_at DESC LIMIT ?4"
+        ))?;
+        let rows = stmt.query_map(
+            params![
+                target,
+                author.map(|a| a.to_string()),
+                page.before.unwrap_or(i64::MAX),
+                page.limit
+            ],
+            post_row,
+        )?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    pub fn post(&self, address: &Pubkey) -> Result<Option<PostRow>> {
+        let conn = self.conn.lock().unwrap();
+        Ok(conn
+            .query_row(&format!("{POST_COLUMNS} WHERE address = ?1"), [address.to_string()], post_row)
+            .optional()?)
+    }
+
+    /// Stream position of every program indexed so far
+    pub fn cursors(&self) -> Result<Vec<(String, u64)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT program_id, slot FROM cursors ORDER BY program_id")?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+}
+
+const POST_COLUMNS: &str = "SELECT address, author, target, bid, bid_mint, created_at, upvotes, total_boosts,
+    pinned_until, accepted, reclaimed, slot, signature FROM posts";
+
+fn post_row(row: &rusqlite::Row) -> rusqlite::Result<PostRow> {
+    Ok(PostRow {
+        address: row.get(0)?,
+        author: row.get(1)?,
+        target: row.get(2)?,
+        bid: row.get(3)?,
+        bid_mint: row.get(4)?,
+        created_at: row.get(5)?,
+        upvotes: row.get(6)?,
+        total_boosts: row.get(7)?,
+        pinned_until: row.get(8)?,
+        accepted: row.get(9)?,
+        reclaimed: row.get(10)?,
+        slot: row.get(11)?,
+        signature: row.get(12)?,
+    })
+}
+
+fn save_cursor(tx: &Transaction, program_id: &Pubkey, cursor: &EventCursor) -> Result<()> {
+    tx.execute(
+        "INSERT INTO cursors (program_id, slot, signature) VALUES (?1, ?2, ?3)
+         ON CONFLICT (program_id) DO UPDATE SET slot = excluded.slot, signature = excluded.signature",
+        params![program_id.to_string(), cursor.slot, cursor.signature.to_string()],
+    )?;
+    Ok(())
+}
+
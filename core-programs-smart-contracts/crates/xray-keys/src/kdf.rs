@@ -0,0 +1,82 @@
+//! Passphrase → 32-byte wrapping key. Parameters and salt are stored in the keystore, so
+//! files written with older defaults keep opening.
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::OsRng;
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+use crate::{hex, KeystoreError, Result};
+
+pub const SALT_LEN: usize = 16;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "name", rename_all = "lowercase")]
+pub enum Kdf {
+    Scrypt {
+        log_n: u8,
+        r: u32,
+        p: u32,
+        #[serde(with = "hex")]
+        salt: [u8; SALT_LEN],
+    },
+    Argon2id {
+        /// Memory, in KiB
+        m_cost: u32,
+        t_cost: u32,
+        p_cost: u32,
+        #[serde(with = "hex")]
+        salt: [u8; SALT_LEN],
+    },
+}
+
+impl Default for Kdf {
+    fn default() -> Self {
+        Self::scrypt()
+    }
+}
+
+impl Kdf {
+    /// scrypt N = 2^15, r = 8, p = 1 (32 MiB), with a fresh salt
+    pub fn scrypt() -> Self {
+        Self::Scrypt { log_n: 15, r: 8, p: 1, salt: random_salt() }
+    }
+
+    /// Argon2id with 64 MiB, 3 passes, 1 lane, with a fresh salt
+    pub fn argon2id() -> Self {
+        Self::Argon2id { m_cost: 64 * 1024, t_cost: 3, p_cost: 1, salt: random_salt() }
+    }
+
+    /// Same algorithm and cost, new salt (every write of a keystore gets one)
+    pub fn with_fresh_salt(&self) -> Self {
+        let mut kdf = self.clone();
+        match &mut kdf {
+            Self::Scrypt { salt, .. } | Self::Argon2id { salt, .. } => *salt = random_salt(),
+        }
+        kdf
+    }
+
+    pub fn derive_key(&self, passphrase: &[u8]) -> Result<Zeroizing<[u8; 32]>> {
+        let mut key = Zeroizing::new([0u8; 32]);
+        match self {
+            Self::Scrypt { log_n, r, p, salt } => {
+                let params = scrypt::Params::new(*log_n, *r, *p, 32).map_err(|_| KeystoreError::InvalidKdf)?;
+                scrypt::scrypt(passphrase, salt, &params, &mut key[..]).map_err(|_| KeystoreError::InvalidKdf)?;
+            }
+            Self::Argon2id { m_cost, t_cost, p_cost, salt } => {
+                let params = Params::new(*m_cost, *t_cost, *p_cost, Some(32)).map_err(|_| KeystoreError::InvalidKdf)?;
+                Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+                    .hash_password_into(passphrase, salt, &mut key[..])
+                    .map_err(|_| KeystoreError::InvalidKdf)?;
+            }
+        }
+        Ok(key)
+    }
+}
+
+fn random_salt() -> [u8; SALT_LEN] {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    salt
+}
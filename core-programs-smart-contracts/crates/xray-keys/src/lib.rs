@@ -0,0 +1,259 @@
+//! Encrypted keystore for an X-RAY wallet: its Ed25519 keypair and its X25519 messaging key,
+//! wrapped under a passphrase. Used by `xray-cli` (`xray keys ...`, `--keystore`) and
+//! `xray-relayer` (`--keystore`) instead of a plaintext Solana keypair file.
+//!
+//! File format (JSON, `version` 1):
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "wallet": "<base58 address>",
+//!   "x25519_public": "<hex>",
+//!   "kdf": { "name": "scrypt", "log_n": 15, "r": 8, "p": 1, "salt": "<hex>" },
+//!   "nonce": "<hex, 24 bytes>",
+//!   "ciphertext": "<hex>"
+//! }
+//! ```
+//!
+//! `kdf` is either `scrypt` or `argon2id` (`m_cost`, `t_cost`, `p_cost`, `salt`). The
+//! ciphertext is XChaCha20-Poly1305 under the derived key, over the 64-byte Ed25519 keypair
+//! followed by the 32-byte X25519 secret; the public fields are authenticated as associated
+//! data, so the address and messaging key shown without the passphrase can be trusted once
+//! it unlocks.
+//!
+//! The X25519 key defaults to the one derived from the wallet (same as the web client, so
+//! both read the same inbox).
+
+mod kdf;
+
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::str::FromStr;
+
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, KeyInit, OsRng, Payload};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{read_keypair_file, write_keypair_file, Keypair, Signer};
+use xray_crypto::message::{derive_x25519_keypair, X25519Keypair};
+use zeroize::Zeroizing;
+
+pub use kdf::{Kdf, SALT_LEN};
+
+/// Keystore format written by this crate
+pub const VERSION: u32 = 1;
+
+const NONCE_LEN: usize = 24;
+const SECRETS_LEN: usize = 64 + 32;
+
+pub type Result<T> = std::result::Result<T, KeystoreError>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum KeystoreError {
+    #[error("keystore i/o: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("invalid keystore file: {0}")]
+    Format(#[from] serde_json::Error),
+    #[error("unsupported keystore version {0}")]
+    UnsupportedVersion(u32),
+    #[error("invalid KDF parameters")]
+    InvalidKdf,
+    #[error("wrong passphrase or corrupted keystore")]
+    Decrypt,
+    #[error("keystore secrets do not match its public keys")]
+    Mismatch,
+    #[error("invalid keypair: {0}")]
+    Keypair(String),
+}
+
+/// Unlocked keys of a keystore
+pub struct Keys {
+    pub wallet: Keypair,
+    pub x25519: X25519Keypair,
+}
+
+impl Keys {
+    /// New wallet, with its derived messaging key
+    pub fn generate() -> Self {
+        Self::from_wallet(Keypair::new())
+    }
+
+    /// `wallet` with the messaging key derived from it (the web client's derivation)
+    pub fn from_wallet(wallet: Keypair) -> Self {
+        let x25519 = derive_x25519_keypair(&wallet.to_bytes());
+        Self { wallet, x25519 }
+    }
+
+    fn secrets(&self) -> Zeroizing<Vec<u8>> {
+        let mut secrets = Zeroizing::new(Vec::with_capacity(SECRETS_LEN));
+        secrets.extend_from_slice(&self.wallet.to_bytes());
+        secrets.extend_from_slice(&self.x25519.secret);
+        secrets
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Keystore {
+    pub version: u32,
+    /// Wallet address, readable without the passphrase
+    pub wallet: String,
+    /// Messaging key to register on-chain, readable without the passphrase
+    #[serde(with = "hex")]
+    pub x25519_public: [u8; 32],
+    pub kdf: Kdf,
+    #[serde(with = "hex")]
+    nonce: [u8; NONCE_LEN],
+    #[serde(with = "hex")]
+    ciphertext: Vec<u8>,
+}
+
+impl Keystore {
+    /// Keystore for a new wallet
+    pub fn create(passphrase: &str, kdf: Kdf) -> Result<(Self, Keys)> {
+        let keys = Keys::generate();
+        Ok((Self::seal(&keys, passphrase, kdf)?, keys))
+    }
+
+    /// Keystore for existing keys
+    pub fn seal(keys: &Keys, passphrase: &str, kdf: Kdf) -> Result<Self> {
+        let mut keystore = Self {
+            version: VERSION,
+            wallet: keys.wallet.pubkey().to_string(),
+            x25519_public: keys.x25519.public,
+            kdf: kdf.with_fresh_salt(),
+            nonce: [0; NONCE_LEN],
+            ciphertext: Vec::new(),
+        };
+        OsRng.fill_bytes(&mut keystore.nonce);
+
+        let key = keystore.kdf.derive_key(passphrase.as_bytes())?;
+        let secrets = keys.secrets();
+        let aad = keystore.associated_data();
+        let payload = Payload { msg: &secrets[..], aad: &aad };
+        keystore.ciphertext = XChaCha20Poly1305::new(Key::from_slice(&key[..]))
+            .encrypt(XNonce::from_slice(&keystore.nonce), payload)
+            .map_err(|_| KeystoreError::Decrypt)?;
+        Ok(keystore)
+    }
+
+    /// Keystore for a Solana CLI keypair file (`solana-keygen` JSON)
+    pub fn import_keypair_file(path: impl AsRef<Path>, passphrase: &str, kdf: Kdf) -> Result<Self> {
+        let wallet = read_keypair_file(path).map_err(|err| KeystoreError::Keypair(err.to_string()))?;
+        Self::seal(&Keys::from_wallet(wallet), passphrase, kdf)
+    }
+
+    /// Decrypts the keys and checks them against the public fields
+    pub fn unlock(&self, passphrase: &str) -> Result<Keys> {
+        if self.version != VERSION {
+            return Err(KeystoreError::UnsupportedVersion(self.version));
+        }
+
+        let key = self.kdf.derive_key(passphrase.as_bytes())?;
+        let aad = self.associated_data();
+        let payload = Payload { msg: &self.ciphertext[..], aad: &aad };
+        let secrets = Zeroizing::new(
+            XChaCha20Poly1305::new(Key::from_slice(&key[..]))
+                .decrypt(XNonce::from_slice(&self.nonce), payload)
+                .map_err(|_| KeystoreError::Decrypt)?,
+        );
+        if secrets.len() != SECRETS_LEN {
+            return Err(KeystoreError::Mismatch);
+        }
+
+        let wallet = Keypair::try_from(&secrets[..64]).map_err(|err| KeystoreError::Keypair(err.to_string()))?;
+        let mut x25519_secret = [0u8; 32];
+        x25519_secret.copy_from_slice(&secrets[64..]);
+        let keys = Keys { wallet, x25519: X25519Keypair::from_secret(x25519_secret) };
+        zeroize::Zeroize::zeroize(&mut x25519_secret);
+
+        if keys.wallet.pubkey() != self.pubkey()? || keys.x25519.public != self.x25519_public {
+            return Err(KeystoreError::Mismatch);
+        }
+        Ok(keys)
+    }
+
+    /// Writes the wallet as a plaintext Solana CLI keypair file
+    pub fn export_keypair_file(&self, passphrase: &str, path: impl AsRef<Path>) -> Result<()> {
+        let keys = self.unlock(passphrase)?;
+        write_keypair_file(&keys.wallet, path).map_err(|err| KeystoreError::Keypair(err.to_string()))?;
+        Ok(())
+    }
+
+    /// Same keys re-wrapped under `new_passphrase`, with a new salt and nonce (and `kdf`'s
+    /// algorithm and cost if given)
+    pub fn rotate(&self, passphrase: &str, new_passphrase: &str, kdf: Option<Kdf>) -> Result<Self> {
+        let keys = self.unlock(passphrase)?;
+        Self::seal(&keys, new_passphrase, kdf.unwrap_or_else(|| self.kdf.clone()))
+    }
+
+    /// Wallet address
+    pub fn pubkey(&self) -> Result<Pubkey> {
+        Pubkey::from_str(&self.wallet).map_err(|err| KeystoreError::Keypair(err.to_string()))
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(serde_json::from_slice(&fs::read(path)?)?)
+    }
+
+    /// Writes the keystore through a temporary file (owner-only on Unix), then renames it
+    /// over `path`
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let tmp = path.with_extension("tmp");
+
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        let mut file = options.open(&tmp)?;
+        file.write_all(&serde_json::to_vec_pretty(self)?)?;
+        file.sync_all()?;
+
+        fs::rename(tmp, path)?;
+        Ok(())
+    }
+
+    fn associated_data(&self) -> Vec<u8> {
+        format!("xray-keys:{}:{}:{}", self.version, self.wallet, hex::encode(&self.x25519_public)).into_bytes()
+    }
+}
+
+/// Reads a passphrase from `env_var`, else prompts for it on the terminal
+pub fn read_passphrase(env_var: &str, prompt: &str) -> Result<Zeroizing<String>> {
+    match std::env::var(env_var) {
+        Ok(passphrase) => Ok(Zeroizing::new(passphrase)),
+        Err(_) => Ok(Zeroizing::new(rpassword::prompt_password(prompt)?)),
+    }
+}
+
+/// Hex (de)serialization of the byte fields
+mod hex {
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn encode(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    pub fn serialize<S: Serializer, T: AsRef<[u8]>>(bytes: &T, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&encode(bytes.as_ref()))
+    }
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: TryFrom<Vec<u8>>,
+    {
+        let hex = String::deserialize(deserializer)?;
+        if hex.len() % 2 != 0 || !hex.is_ascii() {
+            return Err(de::Error::custom("invalid hex"));
+        }
+        let bytes = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16))
+            .collect::<Result<Vec<u8>, _>>()
+            .map_err(de::Error::custom)?;
+        T::try_from(bytes).map_err(|_| de::Error::custom("unexpected length"))
+    }
+}
@@ -0,0 +1,91 @@
+//! Keystore round trips, with cheap KDF parameters.
+
+use solana_sdk::signature::{read_keypair_file, write_keypair_file, Keypair, Signer};
+use xray_crypto::message::derive_x25519_keypair;
+use xray_keys::{Kdf, Keys, Keystore, KeystoreError, SALT_LEN};
+
+fn fast_scrypt() -> Kdf {
+    Kdf::Scrypt { log_n: 4, r: 8, p: 1, salt: [0; SALT_LEN] }
+}
+
+fn fast_argon2id() -> Kdf {
+    Kdf::Argon2id { m_cost: 64, t_cost: 1, p_cost: 1, salt: [0; SALT_LEN] }
+}
+
+#[test]
+fn create_then_unlock() {
+    for kdf in [fast_scrypt(), fast_argon2id()] {
+        let (keystore, keys) = Keystore::create("correct horse", kdf).unwrap();
+        let unlocked = keystore.unlock("correct horse").unwrap();
+
+        assert_eq!(unlocked.wallet.to_bytes(), keys.wallet.to_bytes());
+        assert_eq!(unlocked.x25519.secret, keys.x25519.secret);
+        assert_eq!(keystore.pubkey().unwrap(), keys.wallet.pubkey());
+        assert_eq!(keystore.x25519_public, keys.x25519.public);
+    }
+}
+
+#[test]
+fn messaging_key_is_derived_from_the_wallet() {
+    let (_, keys) = Keystore::create("pass", fast_scrypt()).unwrap();
+    assert_eq!(keys.x25519.public, derive_x25519_keypair(&keys.wallet.to_bytes()).public);
+}
+
+#[test]
+fn wrong_passphrase_is_rejected() {
+    let (keystore, _) = Keystore::create("pass", fast_scrypt()).unwrap();
+    assert!(matches!(keystore.unlock("other"), Err(KeystoreError::Decrypt)));
+}
+
+#[test]
+fn public_fields_are_authenticated() {
+    let (mut keystore, _) = Keystore::create("pass", fast_scrypt()).unwrap();
+    keystore.wallet = Keypair::new().pubkey().to_string();
+    assert!(matches!(keystore.unlock("pass"), Err(KeystoreError::Decrypt)));
+}
+
+#[test]
+fn salts_and_nonces_are_fresh() {
+    let keys = Keys::generate();
+    let first = Keystore::seal(&keys, "pass", fast_scrypt()).unwrap();
+    let second = Keystore::seal(&keys, "pass", fast_scrypt()).unwrap();
+    assert_ne!(first.kdf, second.kdf);
+    assert_ne!(first, second);
+}
+
+#[test]
+fn rotate_rewraps_the_same_keys() {
+    let (keystore, keys) = Keystore::create("old", fast_scrypt()).unwrap();
+    let rotated = keystore.rotate("old", "new", Some(fast_argon2id())).unwrap();
+
+    assert!(matches!(rotated.kdf, Kdf::Argon2id { .. }));
+    assert!(matches!(rotated.unlock("old"), Err(KeystoreError::Decrypt)));
+    assert_eq!(rotated.unlock("new").unwrap().wallet.to_bytes(), keys.wallet.to_bytes());
+    assert_eq!(rotated.x25519_public, keystore.x25519_public);
+}
+
+#[test]
+fn save_load_import_export() {
+    let dir = tempfile::tempdir().unwrap();
+    let wallet = Keypair::new();
+    let keypair_path = dir.path().join("id.json");
+    write_keypair_file(&wallet, &keypair_path).unwrap();
+
+    let keystore = Keystore::import_keypair_file(&keypair_path, "pass", fast_scrypt()).unwrap();
+    let keystore_path = dir.path().join("wallet.keystore.json");
+    keystore.save(&keystore_path).unwrap();
+
+    let loaded = Keystore::load(&keystore_path).unwrap();
+    assert_eq!(loaded, keystore);
+
+    let exported_path = dir.path().join("exported.json");
+    loaded.export_keypair_file("pass", &exported_path).unwrap();
+    assert_eq!(read_keypair_file(&exported_path).unwrap().to_bytes(), wallet.to_bytes());
+}
+
+#[test]
+fn newer_versions_are_refused() {
+    let (mut keystore, _) = Keystore::create("pass", fast_scrypt()).unwrap();
+    keystore.version = 2;
+    assert!(matches!(keystore.unlock("pass"), Err(KeystoreError::UnsupportedVersion(2))));
+}
@@ -0,0 +1,56 @@
+//! Stream position per program, persisted as JSON so a restart resumes after the last
+//! handled event instead of skipping what happened while down (or notifying it twice).
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use xray_client::stream::EventCursor;
+
+#[derive(Serialize, Deserialize)]
+struct StoredCursor {
+    slot: u64,
+    signature: String,
+}
+
+pub struct Cursors {
+    path: PathBuf,
+    cursors: Mutex<HashMap<String, StoredCursor>>,
+}
+
+impl Cursors {
+    /// Starts empty if `path` does not exist yet
+    pub fn open(path: PathBuf) -> Result<Self> {
+        let cursors = match std::fs::read(&path) {
+            Ok(data) => serde_json::from_slice(&data).with_context(|| format!("invalid cursor file {}", path.display()))?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+            Err(err) => return Err(err).with_context(|| format!("cannot read {}", path.display())),
+        };
+        Ok(Self { path, cursors: Mutex::new(cursors) })
+    }
+
+    pub fn get(&self, program_id: &Pubkey) -> Result<Option<EventCursor>> {
+        let cursors = self.cursors.lock().unwrap();
+        cursors
+            .get(&program_id.to_string())
+            .map(|cursor| Ok(EventCursor { slot: cursor.slot, signature: cursor.signature.parse()? }))
+            .transpose()
+    }
+
+    /// Records the cursor and rewrites the file (through a rename, never left half-written)
+    pub fn save(&self, program_id: Pubkey, cursor: EventCursor) -> Result<()> {
+        let mut cursors = self.cursors.lock().unwrap();
+        cursors.insert(
+            program_id.to_string(),
+            StoredCursor { slot: cursor.slot, signature: cursor.signature.to_string() },
+        );
+
+        let tmp = self.path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_vec_pretty(&*cursors)?)?;
+        std::fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+}
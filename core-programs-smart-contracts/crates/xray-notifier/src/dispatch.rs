@@ -0,0 +1,78 @@
+//! Webhook delivery: one JSON POST per notification, retried with backoff in the background
+//! so a slow endpoint never holds up the event stream.
+//!
+//! Payloads only carry what the events already make public; message contents and access
+//! results stay encrypted.
+
+use std::time::Duration;
+
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+
+const ATTEMPTS: u32 = 3;
+const FIRST_RETRY_DELAY: Duration = Duration::from_secs(1);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Notification {
+    /// A plain message was sent to the wallet
+    Message { message: String, sender: String, timestamp: i64 },
+    /// The MPC answered one of the wallet's private message access checks (encrypted for its
+    /// ephemeral key, see `verify_private_message_access`)
+    AccessVerified { encrypted_result: String, nonce: String },
+    /// A post was created on a target the wallet owns
+    Post { post: String, author: String, target: String, bid: u64, bid_mint: String, timestamp: i64 },
+}
+
+#[derive(Serialize)]
+struct Delivery<'a> {
+    wallet: String,
+    /// Transaction that emitted the event
+    signature: String,
+    #[serde(flatten)]
+    notification: &'a Notification,
+}
+
+#[derive(Clone)]
+pub struct Dispatcher {
+    http: reqwest::Client,
+}
+
+impl Dispatcher {
+    pub fn new() -> reqwest::Result<Self> {
+        Ok(Self { http: reqwest::Client::builder().timeout(REQUEST_TIMEOUT).build()? })
+    }
+
+    /// Queues the delivery; failures are logged once the retries are exhausted
+    pub fn send(&self, endpoint: String, wallet: Pubkey, signature: Signature, notification: Notification) {
+        let http = self.http.clone();
+        tokio::spawn(async move {
+            let delivery = Delivery {
+                wallet: wallet.to_string(),
+                signature: signature.to_string(),
+                notification: &notification,
+            };
+            let mut delay = FIRST_RETRY_DELAY;
+            for attempt in 1..=ATTEMPTS {
+                let result = http
+                    .post(&endpoint)
+                    .json(&delivery)
+                    .send()
+                    .await
+                    .and_then(|response| response.error_for_status());
+                match result {
+                    Ok(_) => return,
+                    Err(err) if attempt == ATTEMPTS => {
+                        eprintln!("notification for {wallet} to {endpoint} failed: {err}");
+                    }
+                    Err(_) => {
+                        tokio::time::sleep(delay).await;
+                        delay *= 4;
+                    }
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,73 @@
+//! `xray-notifier`: watches the programs' events and pushes a webhook notification to the
+//! wallets they concern, so recipients learn about new encrypted messages (and access check
+//! results, and posts on their targets) without polling.
+//!
+//! Wallets opt in on-chain with `set_notification_prefs`; where to deliver is local
+//! configuration (see [`subscribers::Destinations`]).
+
+mod cursors;
+mod dispatch;
+mod resolve;
+mod subscribers;
+mod watch;
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::Result;
+use clap::Parser;
+use xray_client::nonblocking::AsyncXrayClient;
+use xray_client::xray_common::{POST_MSG_PROGRAM_ID, PRIVATE_MESSAGES_PROGRAM_ID};
+
+use crate::cursors::Cursors;
+use crate::dispatch::Dispatcher;
+use crate::subscribers::{Destinations, Subscribers};
+use crate::watch::Notifier;
+
+#[derive(Parser)]
+#[command(name = "xray-notifier", version, about)]
+struct Args {
+    /// RPC endpoint (preferences, lookups, backfill after a disconnect or restart)
+    #[arg(long, env = "XRAY_RPC_URL", default_value = "https://api.devnet.solana.com")]
+    rpc_url: String,
+
+    /// Websocket endpoint (log subscriptions)
+    #[arg(long, env = "XRAY_WS_URL", default_value = "wss://api.devnet.solana.com")]
+    ws_url: String,
+
+    /// Destinations file (JSON: `default` endpoint and/or per-wallet `wallets` endpoints)
+    #[arg(long, env = "XRAY_NOTIFIER_DESTINATIONS")]
+    destinations: PathBuf,
+
+    /// Stream cursor file
+    #[arg(long, env = "XRAY_NOTIFIER_CURSORS", default_value = "xray-notifier-cursors.json")]
+    cursors: PathBuf,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = Args::parse();
+    let destinations = Destinations::load(&args.destinations)?;
+    let client = AsyncXrayClient::new(args.rpc_url, args.ws_url);
+    let cursors = Cursors::open(args.cursors)?;
+
+    let mut config = client.event_config();
+    for program_id in [POST_MSG_PROGRAM_ID, PRIVATE_MESSAGES_PROGRAM_ID] {
+        if let Some(cursor) = cursors.get(&program_id)? {
+            config = config.resume_from(program_id, cursor);
+        }
+    }
+
+    // Loaded after the cursors: updates since then replay from the stream
+    let prefs = client.notification_prefs().await?;
+    eprintln!("{} wallets with notification preferences", prefs.len());
+
+    let notifier = Arc::new(Notifier {
+        client,
+        subscribers: Subscribers::new(destinations, prefs),
+        dispatcher: Dispatcher::new()?,
+        cursors,
+    });
+    watch::run(notifier, config).await;
+    Ok(())
+}
@@ -0,0 +1,108 @@
+//! Attributes a `PrivateAccessVerified` event to the wallet that asked for the check.
+//!
+//! The event itself is anonymous (only an encrypted result), so the requester is recovered
+//! from the chain: the callback instruction names the computation account, whose first
+//! transaction is the one that queued it, paid for by the requester.
+
+use anyhow::{anyhow, Context, Result};
+use solana_rpc_client_api::config::RpcTransactionConfig;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::VersionedTransaction;
+use solana_transaction_status_client_types::{
+    UiInnerInstructions, UiInstruction, UiLoadedAddresses, UiTransactionEncoding,
+};
+use xray_client::instructions::sighash;
+use xray_client::nonblocking::AsyncXrayClient;
+use xray_client::xray_common::PRIVATE_MESSAGES_PROGRAM_ID;
+
+// VerifyAndRevealSenderCallback: arcium_program, comp_def_account, mxe_account, computation_account
+const CALLBACK_COMPUTATION_INDEX: usize = 3;
+
+/// Fee payer of the transaction that queued the computation answered in `callback`
+pub async fn access_requester(client: &AsyncXrayClient, callback: &Signature) -> Result<Pubkey> {
+    let computation = callback_computation(client, callback).await?;
+
+    // Newest first: the queueing transaction is the last one
+    let signatures = client.rpc().get_signatures_for_address(&computation).await?;
+    let queued = signatures
+        .last()
+        .context("computation account has no transactions")?
+        .signature
+        .parse::<Signature>()?;
+    let (tx, _) = transaction(client, &queued).await?;
+    tx.message
+        .static_account_keys()
+        .first()
+        .copied()
+        .context("transaction has no fee payer")
+}
+
+async fn callback_computation(client: &AsyncXrayClient, callback: &Signature) -> Result<Pubkey> {
+    let (tx, loaded) = transaction(client, callback).await?;
+    let keys: Vec<Pubkey> = tx.message.static_account_keys().iter().copied().chain(loaded.keys).collect();
+    let discriminator = sighash("verify_and_reveal_sender_callback");
+
+    // Usually invoked by the Arcium program, so look at the inner instructions too
+    let top_level = tx
+        .message
+        .instructions()
+        .iter()
+        .map(|ix| (ix.program_id_index, ix.accounts.clone(), ix.data.clone()));
+    let inner = loaded.inner.into_iter().filter_map(|ix| {
+        let UiInstruction::Compiled(ix) = ix else { return None };
+        Some((ix.program_id_index, ix.accounts, bs58::decode(ix.data).into_vec().ok()?))
+    });
+
+    for (program_index, accounts, data) in top_level.chain(inner) {
+        if keys.get(program_index as usize) == Some(&PRIVATE_MESSAGES_PROGRAM_ID) && data.starts_with(&discriminator) {
+            return accounts
+                .get(CALLBACK_COMPUTATION_INDEX)
+                .and_then(|&index| keys.get(index as usize))
+                .copied()
+                .context("callback has no computation account");
+        }
+    }
+    Err(anyhow!("{callback} is not a verify_and_reveal_sender callback"))
+}
+
+/// What the transaction metadata adds to the message: address lookup table keys and inner instructions
+struct Loaded {
+    keys: Vec<Pubkey>,
+    inner: Vec<UiInstruction>,
+}
+
+async fn transaction(client: &AsyncXrayClient, signature: &Signature) -> Result<(VersionedTransaction, Loaded)> {
+    let config = RpcTransactionConfig {
+        encoding: Some(UiTransactionEncoding::Base64),
+        commitment: Some(CommitmentConfig::confirmed()),
+        max_supported_transaction_version: Some(0),
+    };
+    let fetched = client.rpc().get_transaction_with_config(signature, config).await?;
+    let tx = fetched
+        .transaction
+        .transaction
+        .decode()
+        .with_context(|| format!("cannot decode transaction {signature}"))?;
+
+    let meta = fetched.transaction.meta;
+    let keys = meta
+        .as_ref()
+        .and_then(|meta| Option::<UiLoadedAddresses>::from(meta.loaded_addresses.clone()))
+        .map(|loaded| {
+            loaded
+                .writable
+                .iter()
+                .chain(&loaded.readonly)
+                .filter_map(|key| key.parse().ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    let inner = meta
+        .and_then(|meta| Option::<Vec<UiInnerInstructions>>::from(meta.inner_instructions))
+        .map(|inner| inner.into_iter().flat_map(|set| set.instructions).collect())
+        .unwrap_or_default();
+
+    Ok((tx, Loaded { keys, inner }))
+}
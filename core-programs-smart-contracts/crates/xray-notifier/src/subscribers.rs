@@ -0,0 +1,89 @@
+//! Who gets notified of what: on-chain `NotificationPrefs` (which events a wallet opted into)
+//! joined with the local destinations file (where to deliver them).
+//!
+//! Destinations stay off-chain on purpose: a webhook or push-gateway URL on-chain would link
+//! the wallet to its device or service for anyone to see.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::RwLock;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use solana_sdk::pubkey::Pubkey;
+use xray_client::accounts::NotificationPrefs;
+
+/// Destinations file:
+///
+/// ```json
+/// { "default": "https://push.example/notify", "wallets": { "<wallet>": "https://hooks.example/..." } }
+/// ```
+///
+/// `default` (optional) receives the notifications of wallets without their own entry.
+#[derive(Debug, Default)]
+pub struct Destinations {
+    default: Option<String>,
+    wallets: HashMap<Pubkey, String>,
+}
+
+#[derive(Deserialize)]
+struct DestinationsFile {
+    #[serde(default)]
+    default: Option<String>,
+    #[serde(default)]
+    wallets: HashMap<String, String>,
+}
+
+impl Destinations {
+    pub fn load(path: &Path) -> Result<Self> {
+        let data = std::fs::read(path).with_context(|| format!("cannot read {}", path.display()))?;
+        let file: DestinationsFile =
+            serde_json::from_slice(&data).with_context(|| format!("invalid destinations file {}", path.display()))?;
+
+        let wallets = file
+            .wallets
+            .into_iter()
+            .map(|(wallet, url)| {
+                let wallet = Pubkey::from_str(&wallet).with_context(|| format!("invalid wallet {wallet}"))?;
+                Ok((wallet, url))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { default: file.default, wallets })
+    }
+
+    fn endpoint(&self, wallet: &Pubkey) -> Option<&str> {
+        self.wallets.get(wallet).or(self.default.as_ref()).map(String::as_str)
+    }
+}
+
+pub struct Subscribers {
+    destinations: Destinations,
+    flags: RwLock<HashMap<Pubkey, u8>>,
+}
+
+impl Subscribers {
+    pub fn new(destinations: Destinations, prefs: Vec<NotificationPrefs>) -> Self {
+        let flags = prefs.into_iter().map(|prefs| (prefs.owner, prefs.flags)).collect();
+        Self { destinations, flags: RwLock::new(flags) }
+    }
+
+    /// Applies a `NotificationPrefsUpdated` event
+    pub fn update(&self, wallet: Pubkey, flags: u8) {
+        self.flags.write().unwrap().insert(wallet, flags);
+    }
+
+    /// Endpoint for `wallet` if it opted into `flag` and has somewhere to deliver it
+    pub fn endpoint(&self, wallet: &Pubkey, flag: u8) -> Option<String> {
+        let flags = self.flags.read().unwrap().get(wallet).copied().unwrap_or(0);
+        if flags & flag == 0 {
+            return None;
+        }
+        self.destinations.endpoint(wallet).map(str::to_string)
+    }
+
+    /// Whether any wallet opted into `flag`, to skip RPC lookups nobody would be notified of
+    pub fn any(&self, flag: u8) -> bool {
+        self.flags.read().unwrap().values().any(|flags| flags & flag != 0)
+    }
+}
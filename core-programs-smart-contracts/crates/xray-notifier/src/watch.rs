@@ -0,0 +1,109 @@
+//! Event handling: which wallet each event concerns, checked against its preferences.
+//!
+//! - `MessageSent`: the recipient (`NOTIFY_MESSAGES`)
+//! - `PrivateAccessVerified`: the requester of the check (`NOTIFY_ACCESS_VERIFIED`)
+//! - `PostCreated`: the registered owner of the target, unless they wrote it (`NOTIFY_POSTS`)
+//! - `NotificationPrefsUpdated`: keeps the preferences current
+
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use futures_util::StreamExt;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use xray_client::accounts::TargetAccount;
+use xray_client::nonblocking::AsyncXrayClient;
+use xray_client::pda;
+use xray_client::stream::{self, EventStreamConfig, RawEvent};
+use xray_client::xray_common::events::post::PostCreated;
+use xray_client::xray_common::events::private_messages::{
+    MessageSent, NotificationPrefsUpdated, PrivateAccessVerified,
+};
+use xray_client::xray_common::sizes::{NOTIFY_ACCESS_VERIFIED, NOTIFY_MESSAGES, NOTIFY_POSTS};
+
+use crate::cursors::Cursors;
+use crate::dispatch::{Dispatcher, Notification};
+use crate::resolve;
+use crate::subscribers::Subscribers;
+
+pub struct Notifier {
+    pub client: AsyncXrayClient,
+    pub subscribers: Subscribers,
+    pub dispatcher: Dispatcher,
+    pub cursors: Cursors,
+}
+
+/// Handles events until the stream ends; stream and handling errors are logged and skipped
+pub async fn run(notifier: Arc<Notifier>, config: EventStreamConfig) {
+    let mut events = stream::subscribe_raw(config);
+    while let Some(item) = events.next().await {
+        match item {
+            Ok(raw) => {
+                if let Err(err) = notifier.handle(&raw).await {
+                    eprintln!("failed to handle event from {}: {err:#}", raw.signature);
+                }
+                if let Err(err) = notifier.cursors.save(raw.program_id, raw.cursor()) {
+                    eprintln!("failed to save cursor: {err:#}");
+                }
+            }
+            Err(err) => eprintln!("event stream: {err}"),
+        }
+    }
+}
+
+impl Notifier {
+    async fn handle(&self, raw: &RawEvent) -> Result<()> {
+        if let Some(event) = raw.decode::<NotificationPrefsUpdated>()? {
+            self.subscribers.update(event.owner, event.flags);
+        } else if let Some(event) = raw.decode::<MessageSent>()? {
+            let message = event.message_address().ok_or_else(|| anyhow!("MessageSent with message_index 0"))?;
+            let notification = Notification::Message {
+                message: message.to_string(),
+                sender: event.sender.to_string(),
+                timestamp: event.timestamp,
+            };
+            self.notify(event.recipient, NOTIFY_MESSAGES, raw.signature, notification);
+        } else if let Some(event) = raw.decode::<PrivateAccessVerified>()? {
+            if !self.subscribers.any(NOTIFY_ACCESS_VERIFIED) {
+                return Ok(());
+            }
+            let requester = resolve::access_requester(&self.client, &raw.signature).await?;
+            let notification = Notification::AccessVerified {
+                encrypted_result: hex(&event.encrypted_result),
+                nonce: hex(&event.nonce),
+            };
+            self.notify(requester, NOTIFY_ACCESS_VERIFIED, raw.signature, notification);
+        } else if let Some(event) = raw.decode::<PostCreated>()? {
+            if !self.subscribers.any(NOTIFY_POSTS) {
+                return Ok(());
+            }
+            let target_account = pda::target_account_pda(&event.target).0;
+            let Some(target) = self.client.fetch_optional::<TargetAccount>(&target_account).await? else {
+                return Ok(());
+            };
+            if target.owner == event.author {
+                return Ok(());
+            }
+            let notification = Notification::Post {
+                post: event.post.to_string(),
+                author: event.author.to_string(),
+                target: event.target,
+                bid: event.bid,
+                bid_mint: event.bid_mint.to_string(),
+                timestamp: event.timestamp,
+            };
+            self.notify(target.owner, NOTIFY_POSTS, raw.signature, notification);
+        }
+        Ok(())
+    }
+
+    fn notify(&self, wallet: Pubkey, flag: u8, signature: Signature, notification: Notification) {
+        if let Some(endpoint) = self.subscribers.endpoint(&wallet, flag) {
+            self.dispatcher.send(endpoint, wallet, signature, notification);
+        }
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
@@ -0,0 +1,136 @@
+//! HTTP API.
+//!
+//! - `GET /health`: relayer address
+//! - `POST /v1/posts` `{author, target, content, bid, nonce, signature}`: relayed post;
+//!   `signature` is the author's Ed25519 signature (base58) over `relayed_post_message`, and
+//!   `nonce` must exceed the last one the program consumed for the author
+//! - `POST /v1/transactions` `{transaction}`: sponsored private_messages transaction,
+//!   bincode then base64, with the relayer as fee payer and every other signature present
+
+use std::str::FromStr;
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use base64::prelude::{Engine, BASE64_STANDARD};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::Transaction;
+
+use crate::relay::{RelayError, RelayedPost, Relayer};
+
+pub fn router(relayer: Arc<Relayer>) -> Router {
+    Router::new()
+        .route("/health", get(health))
+        .route("/v1/posts", post(relay_post))
+        .route("/v1/transactions", post(sponsor_transaction))
+        .with_state(relayer)
+}
+
+pub enum ApiError {
+    BadRequest(String),
+    TooManyRequests(&'static str),
+    Rejected(String),
+}
+
+impl From<RelayError> for ApiError {
+    fn from(err: RelayError) -> Self {
+        match err {
+            RelayError::Invalid(message) => ApiError::BadRequest(message),
+            RelayError::QuotaExceeded => ApiError::TooManyRequests("request quota exceeded, retry later"),
+            RelayError::BudgetExhausted => ApiError::TooManyRequests("relayer budget exhausted, retry later"),
+            // Mostly simulation failures (bid too low, post already exists), worth returning as is
+            RelayError::Client(err) => ApiError::Rejected(err.to_string()),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, message) = match self {
+            ApiError::BadRequest(message) => (StatusCode::BAD_REQUEST, message),
+            ApiError::TooManyRequests(message) => (StatusCode::TOO_MANY_REQUESTS, message.to_string()),
+            ApiError::Rejected(message) => {
+                eprintln!("submission failed: {message}");
+                (StatusCode::BAD_GATEWAY, message)
+            }
+        };
+        (status, Json(ErrorBody { error: message })).into_response()
+    }
+}
+
+#[derive(Serialize)]
+struct ErrorBody {
+    error: String,
+}
+
+type ApiResult<T> = Result<Json<T>, ApiError>;
+
+fn parse<T: FromStr>(what: &str, value: &str) -> Result<T, ApiError> {
+    T::from_str(value).map_err(|_| ApiError::BadRequest(format!("invalid {what} {value}")))
+}
+
+#[derive(Serialize)]
+struct Health {
+    relayer: String,
+}
+
+async fn health(State(relayer): State<Arc<Relayer>>) -> Json<Health> {
+    Json(Health { relayer: relayer.pubkey().to_string() })
+}
+
+#[derive(Deserialize)]
+struct PostRequest {
+    author: String,
+    target: String,
+    content: String,
+    bid: u64,
+    nonce: u64,
+    signature: String,
+}
+
+#[derive(Serialize)]
+struct PostResponse {
+    post: String,
+    signature: String,
+}
+
+async fn relay_post(State(relayer): State<Arc<Relayer>>, Json(request): Json<PostRequest>) -> ApiResult<PostResponse> {
+    let request = RelayedPost {
+        author: parse::<Pubkey>("pubkey", &request.author)?,
+        target: request.target,
+        content: request.content,
+        bid: request.bid,
+        nonce: request.nonce,
+        signature: parse::<Signature>("signature", &request.signature)?,
+    };
+    let (signature, post) = relayer.relay_post(request).await?;
+    Ok(Json(PostResponse { post: post.to_string(), signature: signature.to_string() }))
+}
+
+#[derive(Deserialize)]
+struct TransactionRequest {
+    transaction: String,
+}
+
+#[derive(Serialize)]
+struct TransactionResponse {
+    signature: String,
+}
+
+async fn sponsor_transaction(
+    State(relayer): State<Arc<Relayer>>,
+    Json(request): Json<TransactionRequest>,
+) -> ApiResult<TransactionResponse> {
+    let bytes = BASE64_STANDARD
+        .decode(&request.transaction)
+        .map_err(|_| ApiError::BadRequest("transaction is not base64".to_string()))?;
+    let tx: Transaction =
+        bincode::deserialize(&bytes).map_err(|_| ApiError::BadRequest("malformed transaction".to_string()))?;
+    let signature = relayer.sponsor_transaction(tx).await?;
+    Ok(Json(TransactionResponse { signature: signature.to_string() }))
+}
@@ -0,0 +1,96 @@
+//! `xray-relayer`: submits signed payloads on behalf of wallets without SOL (relayed posts,
+//! fee-sponsored messaging), within a per-wallet request quota and a global lamport budget.
+//!
+//! Everything is checked before the relayer signs: payload signatures, sizes and bid cap for
+//! posts, and for sponsored transactions the instruction allowlist and that the relayer is
+//! only the fee payer.
+
+mod api;
+mod quota;
+mod relay;
+
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use clap::Parser;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::read_keypair_file;
+use xray_client::nonblocking::AsyncXrayClient;
+use xray_keys::Keystore;
+
+use crate::relay::{Limits, Relayer};
+
+#[derive(Parser)]
+#[command(name = "xray-relayer", version, about)]
+struct Args {
+    /// RPC endpoint
+    #[arg(long, env = "XRAY_RPC_URL", default_value = "https://api.devnet.solana.com")]
+    rpc_url: String,
+
+    /// Relayer keypair file (pays fees, bids and rent)
+    #[arg(long, env = "XRAY_RELAYER_KEYPAIR", required_unless_present = "keystore")]
+    keypair: Option<PathBuf>,
+
+    /// Encrypted keystore holding the relayer wallet, instead of a keypair file
+    /// (passphrase from `XRAY_RELAYER_KEYSTORE_PASSPHRASE`, else prompted)
+    #[arg(long, env = "XRAY_RELAYER_KEYSTORE", conflicts_with = "keypair")]
+    keystore: Option<PathBuf>,
+
+    /// Pyth SOL/USD price update account (minimum bid check of relayed posts)
+    #[arg(long, env = "XRAY_PRICE_UPDATE")]
+    price_update: Pubkey,
+
+    /// HTTP API listen address
+    #[arg(long, env = "XRAY_RELAYER_LISTEN", default_value = "127.0.0.1:8081")]
+    listen: SocketAddr,
+
+    /// Requests per wallet within the quota window
+    #[arg(long, default_value_t = 20)]
+    requests_per_wallet: usize,
+
+    /// Lamports the relayer may spend within the quota window
+    #[arg(long, default_value_t = 10_000_000_000)]
+    budget: u64,
+
+    /// Quota window, in seconds
+    #[arg(long, default_value_t = 86_400)]
+    window_secs: u64,
+
+    /// Highest bid paid for a relayed post, in lamports
+    #[arg(long, default_value_t = 100_000_000)]
+    max_bid: u64,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = Args::parse();
+    let keypair = match (&args.keystore, &args.keypair) {
+        (Some(path), _) => {
+            let keystore = Keystore::load(path)?;
+            let passphrase = xray_keys::read_passphrase("XRAY_RELAYER_KEYSTORE_PASSPHRASE", "keystore passphrase: ")?;
+            keystore.unlock(&passphrase)?.wallet
+        }
+        (None, Some(path)) => read_keypair_file(path)
+            .map_err(|err| anyhow::anyhow!("cannot read keypair {}: {err}", path.display()))?,
+        (None, None) => unreachable!("clap requires --keypair or --keystore"),
+    };
+
+    // Websocket endpoint unused: the relayer does not subscribe to events
+    let client = AsyncXrayClient::new(args.rpc_url, String::new());
+    let limits = Limits {
+        requests_per_wallet: args.requests_per_wallet,
+        budget: args.budget,
+        window: Duration::from_secs(args.window_secs),
+        max_bid: args.max_bid,
+    };
+    let relayer = Arc::new(Relayer::new(client, keypair, args.price_update, limits));
+    eprintln!("relayer {}", relayer.pubkey());
+
+    let listener = tokio::net::TcpListener::bind(args.listen).await?;
+    eprintln!("serving on {}", args.listen);
+    axum::serve(listener, api::router(relayer)).await?;
+    Ok(())
+}
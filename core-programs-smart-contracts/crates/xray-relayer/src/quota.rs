@@ -0,0 +1,91 @@
+//! Anti-abuse limits: a sliding-window request quota per wallet and a global lamport budget
+//! for everything the relayer pays (bids and fees).
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use solana_sdk::pubkey::Pubkey;
+
+/// At most `limit` requests per wallet within `window`
+pub struct RateLimiter {
+    window: Duration,
+    limit: usize,
+    requests: Mutex<HashMap<Pubkey, VecDeque<Instant>>>,
+}
+
+impl RateLimiter {
+    pub fn new(limit: usize, window: Duration) -> Self {
+        Self { window, limit, requests: Mutex::new(HashMap::new()) }
+    }
+
+    /// Records a request from `wallet`, `false` if its quota is exhausted
+    pub fn try_acquire(&self, wallet: &Pubkey) -> bool {
+        let now = Instant::now();
+        let mut requests = self.requests.lock().unwrap();
+        // Forget idle wallets so the map does not grow with every wallet ever seen
+        requests.retain(|_, times| {
+            expire(times, now, self.window);
+            !times.is_empty()
+        });
+
+        let times = requests.entry(*wallet).or_default();
+        if times.len() >= self.limit {
+            return false;
+        }
+        times.push_back(now);
+        true
+    }
+}
+
+fn expire(times: &mut VecDeque<Instant>, now: Instant, window: Duration) {
+    while times.front().is_some_and(|&t| now.duration_since(t) >= window) {
+        times.pop_front();
+    }
+}
+
+/// At most `limit` lamports spent within `window`, across all wallets
+pub struct SpendBudget {
+    window: Duration,
+    limit: u64,
+    spent: Mutex<VecDeque<(Instant, u64)>>,
+}
+
+/// Lamports reserved from a [`SpendBudget`], released with [`SpendBudget::refund`] if the
+/// transaction never lands
+pub struct Reservation {
+    at: Instant,
+    lamports: u64,
+}
+
+impl SpendBudget {
+    pub fn new(limit: u64, window: Duration) -> Self {
+        Self { window, limit, spent: Mutex::new(VecDeque::new()) }
+    }
+
+    /// Reserves `lamports`, `None` if that would exceed the budget
+    pub fn try_reserve(&self, lamports: u64) -> Option<Reservation> {
+        let now = Instant::now();
+        let mut spent = self.spent.lock().unwrap();
+        while spent.front().is_some_and(|&(t, _)| now.duration_since(t) >= self.window) {
+            spent.pop_front();
+        }
+
+        let total: u64 = spent.iter().map(|&(_, lamports)| lamports).sum();
+        if total.saturating_add(lamports) > self.limit {
+            return None;
+        }
+        spent.push_back((now, lamports));
+        Some(Reservation { at: now, lamports })
+    }
+
+    pub fn refund(&self, reservation: Reservation) {
+        let mut spent = self.spent.lock().unwrap();
+        if let Some(index) = spent
+            .iter()
+            .position(|&(t, lamports)| t == reservation.at && lamports == reservation.lamports)
+        {
+            spent.remove(index);
+        }
+    }
+}
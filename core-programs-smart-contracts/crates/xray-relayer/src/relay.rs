@@ -0,0 +1,222 @@
+//! Validation and submission of the two relayed payload kinds:
+//!
+//! - relayed posts: the author signs [`post::relayed_post_message`] off-chain, the relayer
+//!   submits `create_post_relayed` and pays the bid and rent, so the author's wallet never
+//!   holds SOL or appears as a signer
+//! - sponsored messaging: a private_messages transaction signed by the user with the relayer
+//!   as fee payer; the relayer only co-signs for the fee (rent stays with the user, the
+//!   program has no relayer-paid messaging instruction)
+
+use std::time::Duration;
+
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_sdk::transaction::Transaction;
+use xray_client::accounts::Config;
+use xray_client::instructions::{post, sighash};
+use xray_client::nonblocking::AsyncXrayClient;
+use xray_client::xray_common::sizes::{MAX_CONTENT_LEN, MAX_TARGET_LEN, POST_SIZE};
+use xray_client::xray_common::PRIVATE_MESSAGES_PROGRAM_ID;
+use xray_client::{pda, ClientError};
+
+use crate::quota::{RateLimiter, SpendBudget};
+
+/// Base fee per signature (the Ed25519 precompile's signatures are charged too)
+const SIGNATURE_FEE: u64 = 5_000;
+
+/// private_messages instructions the relayer sponsors
+const SPONSORED_INSTRUCTIONS: [&str; 4] = ["register_user", "update_user_key", "send_message", "mark_as_read"];
+
+/// `SetComputeUnitLimit`, the only compute budget instruction allowed (no priority fees at our expense)
+const SET_COMPUTE_UNIT_LIMIT: u8 = 2;
+
+pub enum RelayError {
+    Invalid(String),
+    QuotaExceeded,
+    BudgetExhausted,
+    Client(ClientError),
+}
+
+impl From<ClientError> for RelayError {
+    fn from(err: ClientError) -> Self {
+        RelayError::Client(err)
+    }
+}
+
+fn invalid(message: impl Into<String>) -> RelayError {
+    RelayError::Invalid(message.into())
+}
+
+pub struct Limits {
+    /// Requests per wallet (post author or transaction signer) within `window`
+    pub requests_per_wallet: usize,
+    /// Lamports the relayer spends within `window`, bids and fees included
+    pub budget: u64,
+    pub window: Duration,
+    /// Highest bid the relayer pays for a single post
+    pub max_bid: u64,
+}
+
+pub struct RelayedPost {
+    pub author: Pubkey,
+    pub target: String,
+    pub content: String,
+    pub bid: u64,
+    pub nonce: u64,
+    pub signature: Signature,
+}
+
+pub struct Relayer {
+    client: AsyncXrayClient,
+    keypair: Keypair,
+    price_update: Pubkey,
+    max_bid: u64,
+    rate_limiter: RateLimiter,
+    budget: SpendBudget,
+}
+
+impl Relayer {
+    pub fn new(client: AsyncXrayClient, keypair: Keypair, price_update: Pubkey, limits: Limits) -> Self {
+        Self {
+            client,
+            keypair,
+            price_update,
+            max_bid: limits.max_bid,
+            rate_limiter: RateLimiter::new(limits.requests_per_wallet, limits.window),
+            budget: SpendBudget::new(limits.budget, limits.window),
+        }
+    }
+
+    pub fn pubkey(&self) -> Pubkey {
+        self.keypair.pubkey()
+    }
+
+    /// Submits `create_post_relayed`, returns the transaction signature and post address
+    pub async fn relay_post(&self, request: RelayedPost) -> Result<(Signature, Pubkey), RelayError> {
+        let RelayedPost { author, target, content, bid, nonce, signature } = request;
+
+        if target.is_empty() || target.len() > MAX_TARGET_LEN {
+            return Err(invalid(format!("target must be 1 to {MAX_TARGET_LEN} bytes")));
+        }
+        let trimmed = content.trim();
+        if trimmed.is_empty() || trimmed.len() > MAX_CONTENT_LEN {
+            return Err(invalid(format!("content must be 1 to {MAX_CONTENT_LEN} bytes")));
+        }
+        if bid > self.max_bid {
+            return Err(invalid(format!("bid above the relayer maximum of {} lamports", self.max_bid)));
+        }
+        // Checked here too so bad signatures never cost a fee
+        let message = post::relayed_post_message(&author, &target, &content, bid, nonce);
+        if !signature.verify(author.as_ref(), &message) {
+            return Err(invalid("signature does not match the author and payload"));
+        }
+
+        if !self.rate_limiter.try_acquire(&author) {
+            return Err(RelayError::QuotaExceeded);
+        }
+        let rent = self
+            .client
+            .rpc()
+            .get_minimum_balance_for_rent_exemption(POST_SIZE)
+            .await
+            .map_err(ClientError::from)?;
+        let cost = bid + rent + 2 * SIGNATURE_FEE;
+        let reservation = self.budget.try_reserve(cost).ok_or(RelayError::BudgetExhausted)?;
+
+        let result = self.submit_post(author, &target, &content, bid, nonce, &signature).await;
+        if result.is_err() {
+            self.budget.refund(reservation);
+        }
+        result
+    }
+
+    async fn submit_post(
+        &self,
+        author: Pubkey,
+        target: &str,
+        content: &str,
+        bid: u64,
+        nonce: u64,
+        signature: &Signature,
+    ) -> Result<(Signature, Pubkey), RelayError> {
+        let config: Config = self.client.fetch(&pda::config_pda().0).await?;
+        let epoch = self.client.rpc().get_epoch_info().await.map_err(ClientError::from)?.epoch;
+
+        let signature: [u8; 64] = signature.as_ref().try_into().expect("signatures are 64 bytes");
+        let instructions = post::create_post_relayed(
+            self.pubkey(),
+            author,
+            target,
+            content,
+            bid,
+            nonce,
+            &signature,
+            self.price_update,
+            epoch,
+            config.tranche_recipient(),
+            config.treasury_version,
+            config.min_stake > 0,
+        );
+        let tx_signature = self.client.send(&instructions, &self.keypair, &[]).await?;
+        Ok((tx_signature, pda::post_pda(&author, target).0))
+    }
+
+    /// Co-signs a user-signed private_messages transaction as fee payer and submits it
+    pub async fn sponsor_transaction(&self, mut tx: Transaction) -> Result<Signature, RelayError> {
+        let relayer = self.pubkey();
+        let message = &tx.message;
+
+        if message.account_keys.first() != Some(&relayer) {
+            return Err(invalid(format!("fee payer must be the relayer {relayer}")));
+        }
+        let signer_count = message.header.num_required_signatures as usize;
+        if signer_count < 2 || tx.signatures.len() != signer_count {
+            return Err(invalid("transaction must be signed by the user"));
+        }
+        for ix in &message.instructions {
+            // The relayer signs only as fee payer: it must not be a rent payer or any other account
+            if ix.accounts.contains(&0) {
+                return Err(invalid("the relayer cannot be an instruction account"));
+            }
+            let program_id = message.account_keys.get(ix.program_id_index as usize);
+            let sponsored = match program_id {
+                Some(id) if *id == PRIVATE_MESSAGES_PROGRAM_ID => SPONSORED_INSTRUCTIONS
+                    .iter()
+                    .any(|name| ix.data.get(..8) == Some(&sighash(name)[..])),
+                Some(id) if *id == solana_sdk_ids::compute_budget::ID => ix.data.first() == Some(&SET_COMPUTE_UNIT_LIMIT),
+                _ => false,
+            };
+            if !sponsored {
+                return Err(invalid("transaction contains an instruction the relayer does not sponsor"));
+            }
+        }
+        // Every signature but the relayer's (still empty) must already be valid
+        if !tx.verify_with_results().iter().skip(1).all(|&valid| valid) {
+            return Err(invalid("invalid user signature"));
+        }
+
+        let user = message.account_keys[1];
+        if !self.rate_limiter.try_acquire(&user) {
+            return Err(RelayError::QuotaExceeded);
+        }
+        let reservation = self
+            .budget
+            .try_reserve(signer_count as u64 * SIGNATURE_FEE)
+            .ok_or(RelayError::BudgetExhausted)?;
+
+        let blockhash = tx.message.recent_blockhash;
+        let result = match tx.try_partial_sign(&[&self.keypair], blockhash) {
+            Ok(()) => self
+                .client
+                .rpc()
+                .send_and_confirm_transaction(&tx)
+                .await
+                .map_err(|err| RelayError::Client(err.into())),
+            Err(err) => Err(invalid(format!("cannot sign: {err}"))),
+        };
+        if result.is_err() {
+            self.budget.refund(reservation);
+        }
+        result
+    }
+}
@@ -0,0 +1,95 @@
+//! Account-level diff of two snapshots: accounts added, removed, and changed (lamports, raw
+//! data, and which decoded fields moved).
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::snapshot::{Snapshot, SnapshotAccount};
+
+#[derive(Debug, Default, Serialize)]
+pub struct Diff {
+    pub added: Vec<SnapshotAccount>,
+    pub removed: Vec<SnapshotAccount>,
+    pub changed: Vec<Change>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Change {
+    pub address: String,
+    pub account_type: Option<String>,
+    /// Before and after, if the balance moved
+    pub lamports: Option<(u64, u64)>,
+    /// Decoded fields that differ, before and after (a field missing on one side is null)
+    pub fields: BTreeMap<String, (Value, Value)>,
+    /// Raw data differs (also set when only undecoded bytes moved)
+    pub data_changed: bool,
+}
+
+pub fn diff(before: &Snapshot, after: &Snapshot) -> Diff {
+    let before: BTreeMap<_, _> = before.accounts.iter().map(|account| (&account.address, account)).collect();
+    let after: BTreeMap<_, _> = after.accounts.iter().map(|account| (&account.address, account)).collect();
+
+    let mut diff = Diff::default();
+    for (address, old) in &before {
+        match after.get(address) {
+            None => diff.removed.push((*old).clone()),
+            Some(new) if old != new => diff.changed.push(change(old, new)),
+            Some(_) => {}
+        }
+    }
+    diff.added = after.iter().filter(|(address, _)| !before.contains_key(*address)).map(|(_, new)| (*new).clone()).collect();
+    diff
+}
+
+fn change(old: &SnapshotAccount, new: &SnapshotAccount) -> Change {
+    let mut fields = BTreeMap::new();
+    if let (Some(Value::Object(old_fields)), Some(Value::Object(new_fields))) = (&old.fields, &new.fields) {
+        for key in old_fields.keys().chain(new_fields.keys()) {
+            let (old_value, new_value) = (old_fields.get(key), new_fields.get(key));
+            if old_value != new_value {
+                fields.insert(
+                    key.clone(),
+                    (old_value.cloned().unwrap_or(Value::Null), new_value.cloned().unwrap_or(Value::Null)),
+                );
+            }
+        }
+    }
+    Change {
+        address: new.address.clone(),
+        account_type: new.account_type.clone().or_else(|| old.account_type.clone()),
+        lamports: (old.lamports != new.lamports).then_some((old.lamports, new.lamports)),
+        fields,
+        data_changed: old.data != new.data,
+    }
+}
+
+fn describe(account: &SnapshotAccount) -> String {
+    format!("{} {} ({})", account.address, account.account_type.as_deref().unwrap_or("unknown"), account.program)
+}
+
+impl fmt::Display for Diff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for account in &self.added {
+            writeln!(f, "+ {}", describe(account))?;
+        }
+        for account in &self.removed {
+            writeln!(f, "- {}", describe(account))?;
+        }
+        for change in &self.changed {
+            writeln!(f, "~ {} {}", change.address, change.account_type.as_deref().unwrap_or("unknown"))?;
+            if let Some((old, new)) = change.lamports {
+                writeln!(f, "    lamports: {old} -> {new}")?;
+            }
+            for (field, (old, new)) in &change.fields {
+                writeln!(f, "    {field}: {old} -> {new}")?;
+            }
+            if change.data_changed && change.fields.is_empty() {
+                writeln!(f, "    data changed")?;
+            }
+        }
+        write!(f, "{} added, {} removed, {} changed", self.added.len(), self.removed.len(), self.changed.len())
+    }
+}
@@ -0,0 +1,94 @@
+//! Parquet snapshots: one row per account, fields as a JSON string column, header in the file
+//! metadata.
+
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use arrow_array::cast::AsArray;
+use arrow_array::types::UInt64Type;
+use arrow_array::{Array, ArrayRef, RecordBatch, StringArray, UInt64Array};
+use arrow_schema::{DataType, Field, Schema};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::arrow::ArrowWriter;
+use parquet::file::metadata::KeyValue;
+use parquet::file::properties::WriterProperties;
+
+use crate::snapshot::{Header, Snapshot, SnapshotAccount};
+
+const HEADER_KEY: &str = "xray_snapshot";
+
+fn schema() -> Schema {
+    Schema::new(vec![
+        Field::new("address", DataType::Utf8, false),
+        Field::new("program", DataType::Utf8, false),
+        Field::new("account_type", DataType::Utf8, true),
+        Field::new("lamports", DataType::UInt64, false),
+        Field::new("fields", DataType::Utf8, true),
+        Field::new("data", DataType::Utf8, false),
+    ])
+}
+
+pub fn write(snapshot: &Snapshot, path: &Path) -> Result<()> {
+    let schema = Arc::new(schema());
+    let accounts = &snapshot.accounts;
+    let fields = accounts
+        .iter()
+        .map(|account| account.fields.as_ref().map(serde_json::to_string).transpose())
+        .collect::<serde_json::Result<Vec<_>>>()?;
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(StringArray::from_iter_values(accounts.iter().map(|account| &account.address))),
+        Arc::new(StringArray::from_iter_values(accounts.iter().map(|account| &account.program))),
+        Arc::new(accounts.iter().map(|account| account.account_type.as_deref()).collect::<StringArray>()),
+        Arc::new(UInt64Array::from_iter_values(accounts.iter().map(|account| account.lamports))),
+        Arc::new(fields.iter().map(Option::as_deref).collect::<StringArray>()),
+        Arc::new(StringArray::from_iter_values(accounts.iter().map(|account| &account.data))),
+    ];
+    let batch = RecordBatch::try_new(schema.clone(), columns)?;
+
+    let header = serde_json::to_string(&snapshot.header)?;
+    let properties = WriterProperties::builder()
+        .set_key_value_metadata(Some(vec![KeyValue::new(HEADER_KEY.to_string(), header)]))
+        .build();
+    let mut writer = ArrowWriter::try_new(File::create(path)?, schema, Some(properties))?;
+    writer.write(&batch)?;
+    writer.close()?;
+    Ok(())
+}
+
+pub fn read(path: &Path) -> Result<Snapshot> {
+    let builder = ParquetRecordBatchReaderBuilder::try_new(File::open(path)?)?;
+    let header = builder
+        .metadata()
+        .file_metadata()
+        .key_value_metadata()
+        .and_then(|metadata| metadata.iter().find(|kv| kv.key == HEADER_KEY))
+        .and_then(|kv| kv.value.as_deref())
+        .with_context(|| format!("{}: no {HEADER_KEY} metadata", path.display()))?;
+    let header: Header = serde_json::from_str(header)?;
+
+    let mut accounts = Vec::new();
+    for batch in builder.build()? {
+        let batch = batch?;
+        let column = |name: &str| batch.column_by_name(name).with_context(|| format!("missing column {name}"));
+        let address = column("address")?.as_string::<i32>();
+        let program = column("program")?.as_string::<i32>();
+        let account_type = column("account_type")?.as_string::<i32>();
+        let lamports = column("lamports")?.as_primitive::<UInt64Type>();
+        let fields = column("fields")?.as_string::<i32>();
+        let data = column("data")?.as_string::<i32>();
+
+        for row in 0..batch.num_rows() {
+            accounts.push(SnapshotAccount {
+                address: address.value(row).to_string(),
+                program: program.value(row).to_string(),
+                account_type: account_type.is_valid(row).then(|| account_type.value(row).to_string()),
+                lamports: lamports.value(row),
+                fields: fields.is_valid(row).then(|| serde_json::from_str(fields.value(row))).transpose()?,
+                data: data.value(row).to_string(),
+            });
+        }
+    }
+    Ok(Snapshot { header, accounts })
+}
@@ -0,0 +1,132 @@
+//! Snapshot format and capture.
+//!
+//! A snapshot is a header (format version, cluster genesis hash, capture time, slot served
+//! per program) and every account of both programs sorted by address, each with its raw data
+//! (base64) and, when it decodes, its type and fields (`xray_client::json`). The raw data is
+//! enough to recreate the accounts; the fields are for reading and diffing.
+//!
+//! `.json` files hold the whole snapshot; `.parquet` files hold one row per account with the
+//! header as JSON in the `xray_snapshot` key-value metadata.
+
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use solana_sdk::pubkey::Pubkey;
+use xray_client::flows::XrayClient;
+use xray_client::json;
+use xray_client::xray_common::{POST_MSG_PROGRAM_ID, PRIVATE_MESSAGES_PROGRAM_ID};
+
+/// Snapshot format written by this tool
+pub const FORMAT_VERSION: u32 = 1;
+
+pub const PROGRAMS: [(&str, Pubkey); 2] =
+    [("post", POST_MSG_PROGRAM_ID), ("private_messages", PRIVATE_MESSAGES_PROGRAM_ID)];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Header {
+    pub version: u32,
+    pub genesis_hash: String,
+    /// Unix time of the capture
+    pub taken_at: u64,
+    /// Slot each program's accounts were served at, by program name
+    pub slots: std::collections::BTreeMap<String, u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotAccount {
+    pub address: String,
+    pub program: String,
+    pub account_type: Option<String>,
+    pub lamports: u64,
+    pub fields: Option<Value>,
+    /// Raw account data, base64
+    pub data: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    #[serde(flatten)]
+    pub header: Header,
+    pub accounts: Vec<SnapshotAccount>,
+}
+
+impl Snapshot {
+    /// Every account of both programs. RPC nodes only serve current state: the post program
+    /// is read first (at `min_slot` or later), then private_messages no earlier than that.
+    pub fn take(client: &XrayClient, min_slot: Option<u64>) -> Result<Self> {
+        let mut slots = std::collections::BTreeMap::new();
+        let mut accounts = Vec::new();
+        let mut min_context_slot = min_slot;
+        for (program, program_id) in PROGRAMS {
+            let (slot, program_accounts) = client
+                .program_state(&program_id, min_context_slot)
+                .with_context(|| format!("reading {program} accounts"))?;
+            slots.insert(program.to_string(), slot);
+            min_context_slot = Some(slot);
+
+            accounts.extend(program_accounts.into_iter().map(|(address, account)| {
+                let decoded = json::decode(&program_id, &account.data);
+                SnapshotAccount {
+                    address: address.to_string(),
+                    program: program.to_string(),
+                    account_type: decoded.as_ref().map(|(name, _)| name.to_string()),
+                    lamports: account.lamports,
+                    fields: decoded.map(|(_, fields)| fields),
+                    data: base64::engine::general_purpose::STANDARD.encode(&account.data),
+                }
+            }));
+        }
+        accounts.sort_by(|a, b| a.address.cmp(&b.address));
+
+        let header = Header {
+            version: FORMAT_VERSION,
+            genesis_hash: client.rpc().get_genesis_hash()?.to_string(),
+            taken_at: std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)?.as_secs(),
+            slots,
+        };
+        Ok(Self { header, accounts })
+    }
+
+    /// Reads a `.json` or `.parquet` snapshot
+    pub fn load(path: &Path) -> Result<Self> {
+        let snapshot = match Format::of(path)? {
+            Format::Json => serde_json::from_slice::<Self>(&std::fs::read(path)?)?,
+            #[cfg(feature = "parquet")]
+            Format::Parquet => crate::parquet::read(path)?,
+        };
+        if snapshot.header.version > FORMAT_VERSION {
+            bail!("{} is snapshot format {}, this tool reads up to {FORMAT_VERSION}", path.display(), snapshot.header.version);
+        }
+        Ok(snapshot)
+    }
+
+    /// Writes the snapshot as `.json` or `.parquet`, from `path`'s extension
+    pub fn save(&self, path: &Path) -> Result<()> {
+        match Format::of(path)? {
+            Format::Json => std::fs::write(path, serde_json::to_vec_pretty(self)?)?,
+            #[cfg(feature = "parquet")]
+            Format::Parquet => crate::parquet::write(self, path)?,
+        }
+        Ok(())
+    }
+}
+
+enum Format {
+    Json,
+    #[cfg(feature = "parquet")]
+    Parquet,
+}
+
+impl Format {
+    fn of(path: &Path) -> Result<Self> {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Ok(Self::Json),
+            #[cfg(feature = "parquet")]
+            Some("parquet") => Ok(Self::Parquet),
+            _ => bail!("{}: unsupported snapshot extension (.json or .parquet)", path.display()),
+        }
+    }
+}
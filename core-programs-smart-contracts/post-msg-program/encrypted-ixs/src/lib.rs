@@ -0,0 +1,93 @@
+use arcis::*;
+
+#[encrypted]
+mod circuits {
+    use arcis::*;
+
+    // ============================================================================
+    // SEALED-BID FEATURED-SLOT AUCTION
+    // ============================================================================
+    //
+    // Bids are encrypted by each bidder for the MXE. The running auction state is
+    // kept encrypted under the MXE key inside the Auction account, so losing bids
+    // are never revealed. Resolution reveals only the winner index and the
+    // clearing price (second-highest valid bid, Vickrey style).
+    // ============================================================================
+
+    /// Encrypted running state of a sealed-bid auction
+    pub struct SealedAuctionState {
+        /// Highest valid bid so far
+        highest_bid: u64,
+        /// Second-highest valid bid so far (clearing price)
+        second_bid: u64,
+        /// Index of the highest bidder in the Auction account
+        winner_index: u8,
+    }
+
+    /// Empty auction state, encrypted for the MXE
+    #[instruction]
+    pub fn init_sealed_auction(mxe: Mxe) -> Enc<Mxe, SealedAuctionState> {
+        mxe.from_arcis(SealedAuctionState {
+            highest_bid: 0,
+            second_bid: 0,
+            winner_index: 0,
+        })
+    }
+
+    /// Folds one encrypted bid into the auction state.
+    /// Bids below the reserve price or above the bidder's public deposit count as 0.
+    #[instruction]
+    pub fn place_sealed_bid(
+        bid_ctxt: Enc<Shared, u64>,
+        bidder_index: u8,
+        reserve_price: u64,
+        deposit: u64,
+        state_ctxt: Enc<Mxe, SealedAuctionState>,
+    ) -> Enc<Mxe, SealedAuctionState> {
+        let bid = bid_ctxt.to_arcis();
+        let mut state = state_ctxt.to_arcis();
+
+        let amount = if bid < reserve_price || bid > deposit { 0 } else { bid };
+
+        if amount > state.highest_bid {
+            state.second_bid = state.highest_bid;
+            state.highest_bid = amount;
+            state.winner_index = bidder_index;
+        } else if amount > state.second_bid {
+            state.second_bid = amount;
+        }
+
+        state_ctxt.owner.from_arcis(state)
+    }
+
+    /// Reveals whether there is a winner, its index and the clearing price
+    #[instruction]
+    pub fn resolve_sealed_auction(state_ctxt: Enc<Mxe, SealedAuctionState>) -> (bool, u8, u64) {
+        let state = state_ctxt.to_arcis();
+        let has_winner = state.highest_bid > 0;
+
+        (has_winner.reveal(), state.winner_index.reveal(), state.second_bid.reveal())
+    }
+
+    // ============================================================================
+    // THRESHOLD REVEAL OF ENCRYPTED POSTS
+    // ============================================================================
+    //
+    // The post content is encrypted client-side with a symmetric key. That key is
+    // encrypted for the MXE and stored on-chain; once cumulative boosts cross the
+    // unlock threshold, this circuit reveals it so anyone can read the post.
+    // ============================================================================
+
+    /// 256-bit symmetric content key split in two halves
+    pub struct ContentKey {
+        lo: u128,
+        hi: u128,
+    }
+
+    /// Reveals the content key (lo, hi)
+    #[instruction]
+    pub fn reveal_content_key(key_ctxt: Enc<Shared, ContentKey>) -> (u128, u128) {
+        let key = key_ctxt.to_arcis();
+        (key.lo.reveal(), key.hi.reveal())
+    }
+}
@@ -0,0 +1,136 @@
+// Devnet tools - feature `devnet-tools`
+//
+// One-call seeding of demo environments: funds the treasury and creates sample posts
+// for any authors, without oracle price, bid payment or author signatures. Bids are
+// recorded on the posts but not paid.
+//
+// Localnet / devnet builds only: a devnet-tools build must never be deployed to mainnet.
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{create_account, transfer, CreateAccount, Transfer};
+use xray_common::seeds::*;
+
+use crate::{validate_post_fields, Config, Post, PostCreated, PostError, POST_VERSION};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct DemoPost {
+    pub author: Pubkey,
+    pub target: String,
+    pub content: String,
+    pub bid: u64,
+}
+
+#[derive(Accounts)]
+pub struct DevnetSeed<'info>
+{
+    #[account(
+        mut,
+        constraint = admin.key() == config.admin @ PostError::Unauthorized
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: PDA treasury - program controlled
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED, treasury_version_seed(&config.treasury_version)],
+        bump
+    )]
+    pub treasury: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+    // remaining_accounts: one post PDA per DemoPost, in order
+}
+
+pub(crate) fn seed<'info>(
+    ctx: Context<'_, '_, 'info, 'info, DevnetSeed<'info>>,
+    treasury_lamports: u64,
+    posts: Vec<DemoPost>,
+) -> Result<()> {
+    require!(ctx.remaining_accounts.len() == posts.len(), PostError::InvalidConfig);
+
+    if treasury_lamports > 0 {
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.admin.to_account_info(),
+                    to: ctx.accounts.treasury.to_account_info(),
+                },
+            ),
+            treasury_lamports,
+        )?;
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    for (demo, account) in posts.into_iter().zip(ctx.remaining_accounts) {
+        let content = validate_post_fields(&demo.target, &demo.content)?;
+        let (address, bump) =
+            Pubkey::find_program_address(&[POST_SEED, demo.author.as_ref(), demo.target.as_bytes()], &crate::ID);
+        require_keys_eq!(account.key(), address, PostError::InvalidPost);
+        let signer_seeds: &[&[u8]] = &[POST_SEED, demo.author.as_ref(), demo.target.as_bytes(), &[bump]];
+        create_pda(&ctx.accounts.admin, &ctx.accounts.system_program, account, signer_seeds, Post::SIZE)?;
+
+        let mut post = Post {
+            author: demo.author,
+            target: demo.target,
+            content,
+            bid: demo.bid,
+            timestamp: now,
+            bump,
+            version: POST_VERSION,
+            total_boosts: 0,
+            rank_score: 0,
+            rank_updated_at: 0,
+            bid_mint: Pubkey::default(),
+            upvotes: 0,
+            flags: 0,
+            views: 0,
+            charity: Pubkey::default(),
+            charity_amount: 0,
+            license: 0,
+            content_signature: [0; 64],
+        };
+        post.refresh_rank(now);
+        post.try_serialize(&mut &mut account.try_borrow_mut_data()?[..])?;
+
+        emit!(PostCreated {
+            post: address,
+            author: post.author,
+            target: post.target,
+            bid: post.bid,
+            bid_mint: post.bid_mint,
+            timestamp: now,
+        });
+    }
+
+    Ok(())
+}
+
+// Allocate a program-owned PDA of `space` bytes, paid by `payer`
+fn create_pda<'info>(
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    account: &AccountInfo<'info>,
+    signer_seeds: &[&[u8]],
+    space: usize,
+) -> Result<()> {
+    create_account(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            CreateAccount {
+                from: payer.to_account_info(),
+                to: account.clone(),
+            },
+            &[signer_seeds],
+        ),
+        Rent::get()?.minimum_balance(space),
+        space as u64,
+        &crate::ID,
+    )
+}
@@ -1,31 +1,192 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::{invoke, invoke_signed};
 use anchor_lang::system_program::{transfer, Transfer};
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+use arcium_anchor::prelude::*;
+use arcium_client::idl::arcium::types::CallbackAccount;
+use pyth_solana_receiver_sdk::price_update::{get_feed_id_from_hex, PriceUpdateV2};
+use solana_instructions_sysvar::{load_current_index_checked, load_instruction_at_checked};
+use solana_sdk_ids::{ed25519_program, sysvar};
+use solana_sha256_hasher::hashv;
+use xray_common::license::MAX_LICENSE;
+use xray_common::seeds::*;
+use xray_common::sizes;
+
+#[cfg(feature = "devnet-tools")]
+mod devnet_tools;
+#[cfg(feature = "devnet-tools")]
+pub use devnet_tools::*;
+
+// Shared with the other programs and the clients (see xray-common)
+pub use xray_common::ids::{
+    BUBBLEGUM_PROGRAM_ID, PRIVATE_MESSAGES_PROGRAM_ID, SPL_ACCOUNT_COMPRESSION_PROGRAM_ID, SPL_NOOP_PROGRAM_ID,
+    WALLET_1, WALLET_2, WALLET_3,
+};
+pub use xray_common::pda::target_seed;
+pub use xray_common::sizes::{AUTHOR_INDEX_RECENT, MAX_CONTENT_LEN, MAX_SEALED_BIDS, MAX_TARGET_LEN};
+
+// Arcium computation definition offsets
+const COMP_DEF_OFFSET_INIT_SEALED_AUCTION: u32 = comp_def_offset("init_sealed_auction");
+const COMP_DEF_OFFSET_PLACE_SEALED_BID: u32 = comp_def_offset("place_sealed_bid");
+const COMP_DEF_OFFSET_RESOLVE_SEALED_AUCTION: u32 = comp_def_offset("resolve_sealed_auction");
+const COMP_DEF_OFFSET_REVEAL_CONTENT_KEY: u32 = comp_def_offset("reveal_content_key");
 
 declare_id!("5gPGpcXTq1R2chrEP9qPaFw4i1ge5ZgG2n7xnrUGZHPk");
 
-// Revenue split wallets (45% / 10% / 45%)
-pub const WALLET_1: Pubkey = pubkey!("69TwH2GJiBSA8Eo3DunPGsXGWjNFY267zRrpHptYWCuC"); // 45%
-pub const WALLET_2: Pubkey = pubkey!("EbhZhYumUZyHQCPbeaLLt57SS2obHiFdp7TMLjUBBqcD"); // 10%
-pub const WALLET_3: Pubkey = pubkey!("HxtzFZhjNCsQb9ZqEyK8xYftqv6j6AM2MAT6uwWG3KYd"); // 45%
+// Arcium macros expect an `ErrorCode::ClusterNotSet` in scope
+use PostError as ErrorCode;
 
 // Minimum lamports to keep in treasury PDA (rent-exempt for 0 bytes = ~890_880 lamports ≈ 0.00089 SOL)
 pub const TREASURY_MIN_BALANCE: u64 = 890_880;
 
-// Minimum bid required (0.007 SOL = amount received after Privacy Cash fees from 0.015 SOL deposit)
-pub const MIN_BID: u64 = 7_000_000;
+// Privacy Cash pool program (anonymous funding source for create_post_private)
+pub const PRIVACY_CASH_PROGRAM_ID: Pubkey = pubkey!("9fhQBbumKEFuXtMBDw8AaQyAjCorLGJQiS3skWZdQyQD");
+
+// Rank scores halve every RANK_HALF_LIFE seconds of post age (24 hours)
+pub const RANK_HALF_LIFE: i64 = 86_400;
+
+// Escrowed posts: default acceptance window (7 days) and fee kept on reclaim (5%)
+pub const DEFAULT_ESCROW_WINDOW: i64 = 7 * 24 * 3_600;
+pub const DEFAULT_ESCROW_KEEP_FEE_BPS: u16 = 500;
+
+// Default upvote price (0.0001 SOL)
+pub const DEFAULT_UPVOTE_LAMPORTS: u64 = 100_000;
+
+// Reward paid to whoever publishes a scheduled post (0.0001 SOL)
+pub const SCHEDULE_CRANK_REWARD: u64 = 100_000;
+
+// Delay after which a computation that never got its callback (aborted by the
+// cluster) can be cleared by reset_sealed_post_pending / reset_auction_pending (1 hour)
+pub const PENDING_TIMEOUT_SECS: i64 = 3_600;
+
+// Current Post account layout version (bump when adding fields, see migrate_post)
+pub const POST_VERSION: u8 = 8;
+
+// Post flags
+pub const POST_FLAG_OFFICIAL_REPLY: u8 = 1 << 0;
+pub const POST_FLAG_TARGET_NOTIFIED: u8 = 1 << 1;
+
+// Default rolling window for per-author rate limits (1 hour)
+pub const DEFAULT_RATE_WINDOW: i64 = 3_600;
+
+// Default cooldown before an unstaked deposit can be withdrawn (7 days)
+pub const DEFAULT_STAKE_COOLDOWN: i64 = 7 * 24 * 3_600;
+
+// Post receipt metadata (Bubblegum limits: name 32 bytes, symbol 10, uri 200)
+pub const RECEIPT_NAME_PREFIX: &str = "X-RAY: ";
+pub const RECEIPT_SYMBOL: &str = "XRAY";
+pub const RECEIPT_URI_BASE: &str = "https://x-ray.one/receipt";
+
+// Domain separator for relayed post authorizations signed by burner keys
+pub const RELAYED_POST_DOMAIN: &[u8] = b"xray-relayed-post";
+
+// Pyth SOL/USD feed used to peg the minimum bid to a USD amount
+pub const SOL_USD_FEED_ID: &str = "ef0d8b6fda2ceba41da15d4095d1da392a0d2f8ed0c6c7bc0f4cfac8c280b56d";
+
+// Reject SOL/USD prices older than this (seconds)
+pub const MAX_PRICE_AGE: u64 = 60;
 
-#[program]
+// USD amounts in config are expressed with 6 decimals (1_000_000 = $1.00)
+pub const USD_DECIMALS: u32 = 6;
+
+// Basis points denominator for configurable shares
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+// Maximum share of a bid that can be redirected to a referrer (20%)
+pub const MAX_REFERRAL_BPS: u16 = 2_000;
+
+// Maximum share of a bid that can go to a designated charity (50%)
+pub const MAX_CHARITY_BPS: u16 = 5_000;
+
+// Fail with `$err` like `require!`, reporting it first (see `report_failure`).
+// Every check a signer can trip with its own input, timing or accounts is reported, and
+// shared helpers are wrapped in `reported` at the call site. Plain `require!` is kept for
+// admin-only instructions (operator mistakes, not user failures), for permissionless
+// instructions without a signer to attribute (settle_auction, expire_refunds, refresh_rank,
+// claim_vested) and inside the helpers themselves.
+macro_rules! require_reported {
+    ($cond:expr, $err:expr, $signer:expr, $context:expr $(,)?) => {
+        if !($cond) {
+            report_failure(u32::from($err), $signer, $context);
+            return Err($err.into());
+        }
+    };
+}
+
+#[arcium_program]
 pub mod post_msg_program {
     use super::*;
 
     pub fn create_post(ctx: Context<CreatePost>, target: String, content: String, bid: u64) -> Result<()>
     {
         // Validation
-        require!(bid >= MIN_BID, PostError::BidTooLow);
-        require!(target.len() <= 64, PostError::TargetTooLong);
-        require!(content.len() <= 512, PostError::ContentTooLong);
+        let author = ctx.accounts.author.key();
+        let min_bid = min_bid_lamports(&ctx.accounts.config, &ctx.accounts.price_update)?;
+        require_reported!(bid >= min_bid, PostError::BidTooLow, author, bid);
+        let content = reported(validate_post_fields(&target, &content), author, content.len() as u64)?;
+
+        let waived = rate_waived(&ctx.accounts.config, bid, &ctx.accounts.price_update)?;
+        enforce_author_policy(
+            &ctx.accounts.config,
+            &ctx.accounts.stake_account,
+            &mut ctx.accounts.author_stats,
+            ctx.bumps.author_stats,
+            author,
+            waived,
+        )?;
+
+        // Pay the referrer's slice directly from the author (optional)
+        let mut referral_amount = 0;
+        if let Some(referral) = ctx.accounts.referral.as_mut() {
+            let referrer = ctx.accounts.referrer.as_ref().ok_or(PostError::InvalidReferrer)?;
+            require_reported!(referrer.key() == referral.wallet, PostError::InvalidReferrer, author, 0);
+            require_reported!(referrer.key() != author, PostError::InvalidReferrer, author, 0);
+
+            referral_amount = bps_share(bid, ctx.accounts.config.referral_bps);
+            if referral_amount > 0 {
+                transfer(
+                    CpiContext::new(
+                        ctx.accounts.system_program.to_account_info(),
+                        Transfer {
+                            from: ctx.accounts.author.to_account_info(),
+                            to: referrer.to_account_info(),
+                        },
+                    ),
+                    referral_amount,
+                )?;
+            }
+
+            referral.total_earned += referral_amount;
+            referral.referral_count += 1;
+        }
+
+        // Pay the designated charity's share directly from the author (optional)
+        let mut charity_amount = 0;
+        let mut charity_wallet_key = Pubkey::default();
+        if let Some(charity) = ctx.accounts.charity.as_mut() {
+            let charity_wallet = ctx.accounts.charity_wallet.as_ref().ok_or(PostError::InvalidCharity)?;
+            require_reported!(charity_wallet.key() == charity.wallet, PostError::InvalidCharity, author, 0);
+
+            charity_amount = bps_share(bid, charity.share_bps);
+            if charity_amount > 0 {
+                transfer(
+                    CpiContext::new(
+                        ctx.accounts.system_program.to_account_info(),
+                        Transfer {
+                            from: ctx.accounts.author.to_account_info(),
+                            to: charity_wallet.to_account_info(),
+                        },
+                    ),
+                    charity_amount,
+                )?;
+            }
+
+            charity.total_received += charity_amount;
+            charity_wallet_key = charity.wallet;
+        }
 
-        // Transfer bid from author to PDA treasury
+        // Transfer the rest of the bid from author to PDA treasury
         transfer(
             CpiContext::new(
                 ctx.accounts.system_program.to_account_info(),
@@ -34,64 +195,163 @@ pub mod post_msg_program {
                     to: ctx.accounts.treasury.to_account_info(),
                 },
             ),
-            bid,
+            bid - referral_amount - charity_amount,
         )?;
 
-        // Calculate distributable amount (keep rent-exempt minimum in treasury)
-        let treasury_balance = ctx.accounts.treasury.lamports();
-        let distributable = treasury_balance.saturating_sub(TREASURY_MIN_BALANCE);
+        // Split everything above the rent-exempt minimum between the revenue wallets
+        let distributed = distribute_treasury(
+            &ctx.accounts.treasury,
+            &ctx.accounts.wallet_1,
+            &ctx.accounts.wallet_2,
+            &ctx.accounts.wallet_3,
+            &ctx.accounts.system_program,
+            ctx.bumps.treasury,
+            ctx.accounts.config.treasury_version,
+        )?;
 
-        // Only distribute if there's enough to split (skip if treasury is building up minimum)
-        if distributable > 0 {
-            // Calculate split amounts (45% / 10% / 45%)
-            let amount_1 = distributable * 45 / 100;  // 45%
-            let amount_2 = distributable * 10 / 100;  // 10%
-            let amount_3 = distributable - amount_1 - amount_2;  // Remaining (handles rounding)
+        ctx.accounts.revenue_snapshot.record(ctx.bumps.revenue_snapshot, bid, distributed)?;
 
-            let treasury_bump = ctx.bumps.treasury;
-            let seeds = &[b"treasury".as_ref(), &[treasury_bump]];
-            let signer_seeds = &[&seeds[..]];
+        // Create the post
+        ctx.accounts.post.author = ctx.accounts.author.key();
+        ctx.accounts.post.target = target;
+        ctx.accounts.post.content = content;
+        ctx.accounts.post.bid = bid;
+        ctx.accounts.post.charity = charity_wallet_key;
+        ctx.accounts.post.charity_amount = charity_amount;
 
-            // Transfer from PDA treasury to wallet 1 (45%)
-            transfer(
-                CpiContext::new_with_signer(
-                    ctx.accounts.system_program.to_account_info(),
-                    Transfer {
-                        from: ctx.accounts.treasury.to_account_info(),
-                        to: ctx.accounts.wallet_1.to_account_info(),
-                    },
-                    signer_seeds,
-                ),
-                amount_1,
-            )?;
+        let clock = Clock::get()?;
+        ctx.accounts.post.timestamp = clock.unix_timestamp;
 
-            // Transfer from PDA treasury to wallet 2 (10%)
-            transfer(
-                CpiContext::new_with_signer(
-                    ctx.accounts.system_program.to_account_info(),
-                    Transfer {
-                        from: ctx.accounts.treasury.to_account_info(),
-                        to: ctx.accounts.wallet_2.to_account_info(),
-                    },
-                    signer_seeds,
-                ),
-                amount_2,
-            )?;
+        ctx.accounts.post.bump = ctx.bumps.post;
+        ctx.accounts.post.version = POST_VERSION;
+        ctx.accounts.post.refresh_rank(clock.unix_timestamp);
 
-            // Transfer from PDA treasury to wallet 3 (45%)
-            transfer(
-                CpiContext::new_with_signer(
-                    ctx.accounts.system_program.to_account_info(),
-                    Transfer {
-                        from: ctx.accounts.treasury.to_account_info(),
-                        to: ctx.accounts.wallet_3.to_account_info(),
-                    },
-                    signer_seeds,
-                ),
-                amount_3,
+        let post_key = ctx.accounts.post.key();
+        ctx.accounts.author_index.record_post(ctx.accounts.post.author, post_key, ctx.bumps.author_index);
+        emit!(PostCreated {
+            post: post_key,
+            author: ctx.accounts.post.author,
+            target: ctx.accounts.post.target.clone(),
+            bid: ctx.accounts.post.bid,
+            bid_mint: ctx.accounts.post.bid_mint,
+            timestamp: ctx.accounts.post.timestamp,
+        });
+
+        // Mint a compressed NFT receipt to the author (optional)
+        if let Some(merkle_tree) = ctx.accounts.receipt_tree.as_ref() {
+            require_reported!(
+                merkle_tree.key() == ctx.accounts.config.receipt_tree,
+                PostError::InvalidReceiptTree,
+                author,
+                0
+            );
+            let (Some(tree_config), Some(receipt_authority), Some(bubblegum_program), Some(log_wrapper), Some(compression_program)) = (
+                ctx.accounts.receipt_tree_config.as_ref(),
+                ctx.accounts.receipt_authority.as_ref(),
+                ctx.accounts.bubblegum_program.as_ref(),
+                ctx.accounts.log_wrapper.as_ref(),
+                ctx.accounts.compression_program.as_ref(),
+            ) else {
+                return err!(PostError::InvalidReceiptTree);
+            };
+
+            let post = &ctx.accounts.post;
+            let content_hash = content_hash(&post.content);
+            mint_post_receipt(
+                &ctx.accounts.author,
+                tree_config,
+                merkle_tree,
+                receipt_authority,
+                bubblegum_program,
+                log_wrapper,
+                compression_program,
+                &ctx.accounts.system_program,
+                ctx.bumps.receipt_authority.ok_or(PostError::InvalidReceiptTree)?,
+                &post.key(),
+                &post.target,
+                &content_hash,
+                post.bid,
             )?;
+
+            emit!(PostReceiptMinted {
+                post: post.key(),
+                author: post.author,
+                tree: merkle_tree.key(),
+                target: post.target.clone(),
+                content_hash,
+                bid: post.bid,
+            });
         }
 
+        count(&mut ctx.accounts.metrics, |metrics| &mut metrics.posts_created);
+        Ok(())
+    }
+
+    // Create a post whose bid is paid atomically by a Privacy Cash withdrawal to the treasury.
+    // `withdraw_data` is the Privacy Cash withdraw instruction data (proof + ext data) built off-chain
+    // with the treasury as recipient; its accounts are passed as remaining accounts.
+    pub fn create_post_private<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreatePostPrivate<'info>>,
+        target: String,
+        content: String,
+        withdraw_data: Vec<u8>,
+    ) -> Result<()>
+    {
+        let author = ctx.accounts.author.key();
+        let content = reported(validate_post_fields(&target, &content), author, content.len() as u64)?;
+
+        // Relay the withdrawal to Privacy Cash
+        let balance_before = ctx.accounts.treasury.lamports();
+
+        let accounts = ctx
+            .remaining_accounts
+            .iter()
+            .map(|account| {
+                if account.is_writable {
+                    AccountMeta::new(account.key(), account.is_signer)
+                } else {
+                    AccountMeta::new_readonly(account.key(), account.is_signer)
+                }
+            })
+            .collect();
+        let mut account_infos = ctx.remaining_accounts.to_vec();
+        account_infos.push(ctx.accounts.privacy_cash_program.to_account_info());
+
+        invoke(
+            &Instruction {
+                program_id: PRIVACY_CASH_PROGRAM_ID,
+                accounts,
+                data: withdraw_data,
+            },
+            &account_infos,
+        )?;
+
+        // The bid is whatever the withdrawal delivered to the treasury
+        let bid = ctx.accounts.treasury.lamports().saturating_sub(balance_before);
+        let min_bid = min_bid_lamports(&ctx.accounts.config, &ctx.accounts.price_update)?;
+        require_reported!(bid >= min_bid, PostError::BidTooLow, author, bid);
+        let waived = rate_waived(&ctx.accounts.config, bid, &ctx.accounts.price_update)?;
+        enforce_author_policy(
+            &ctx.accounts.config,
+            &ctx.accounts.stake_account,
+            &mut ctx.accounts.author_stats,
+            ctx.bumps.author_stats,
+            author,
+            waived,
+        )?;
+
+        let distributed = distribute_treasury(
+            &ctx.accounts.treasury,
+            &ctx.accounts.wallet_1,
+            &ctx.accounts.wallet_2,
+            &ctx.accounts.wallet_3,
+            &ctx.accounts.system_program,
+            ctx.bumps.treasury,
+            ctx.accounts.config.treasury_version,
+        )?;
+
+        ctx.accounts.revenue_snapshot.record(ctx.bumps.revenue_snapshot, bid, distributed)?;
+
         // Create the post
         ctx.accounts.post.author = ctx.accounts.author.key();
         ctx.accounts.post.target = target;
@@ -102,10 +362,213 @@ pub mod post_msg_program {
         ctx.accounts.post.timestamp = clock.unix_timestamp;
 
         ctx.accounts.post.bump = ctx.bumps.post;
+        ctx.accounts.post.version = POST_VERSION;
+        ctx.accounts.post.refresh_rank(clock.unix_timestamp);
+
+        let post_key = ctx.accounts.post.key();
+        ctx.accounts.author_index.record_post(ctx.accounts.post.author, post_key, ctx.bumps.author_index);
+        emit!(PostCreated {
+            post: post_key,
+            author: ctx.accounts.post.author,
+            target: ctx.accounts.post.target.clone(),
+            bid: ctx.accounts.post.bid,
+            bid_mint: ctx.accounts.post.bid_mint,
+            timestamp: ctx.accounts.post.timestamp,
+        });
+
+        count(&mut ctx.accounts.metrics, |metrics| &mut metrics.posts_created);
+        Ok(())
+    }
+
+    // Create a post on behalf of a pseudonymous author key. The author authorizes target, content,
+    // bid and nonce with an Ed25519 signature (verified by the preceding Ed25519 program instruction)
+    // while the relayer signs, pays the bid and the rent, so the author key has no funding trail.
+    // The nonce must exceed the last one consumed for the author, so a signature can't be replayed
+    // after the post is closed.
+    pub fn create_post_relayed(
+        ctx: Context<CreatePostRelayed>,
+        author: Pubkey,
+        target: String,
+        content: String,
+        bid: u64,
+        nonce: u64,
+    ) -> Result<()>
+    {
+        // Validation
+        let relayer = ctx.accounts.relayer.key();
+        let min_bid = min_bid_lamports(&ctx.accounts.config, &ctx.accounts.price_update)?;
+        require_reported!(bid >= min_bid, PostError::BidTooLow, relayer, bid);
+        let message = relayed_post_message(&author, &target, &content, bid, nonce);
+        reported(verify_ed25519_ix(&ctx.accounts.instructions_sysvar, &author, &message), relayer, 0)?;
+        require_reported!(nonce > ctx.accounts.author_stats.relay_nonce, PostError::RelayNonceUsed, relayer, nonce);
+        ctx.accounts.author_stats.relay_nonce = nonce;
+        let content = reported(validate_post_fields(&target, &content), relayer, content.len() as u64)?;
+        let waived = rate_waived(&ctx.accounts.config, bid, &ctx.accounts.price_update)?;
+        enforce_author_policy(
+            &ctx.accounts.config,
+            &ctx.accounts.stake_account,
+            &mut ctx.accounts.author_stats,
+            ctx.bumps.author_stats,
+            author,
+            waived,
+        )?;
+
+        // Transfer bid from relayer to PDA treasury
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.relayer.to_account_info(),
+                    to: ctx.accounts.treasury.to_account_info(),
+                },
+            ),
+            bid,
+        )?;
+
+        let distributed = distribute_treasury(
+            &ctx.accounts.treasury,
+            &ctx.accounts.wallet_1,
+            &ctx.accounts.wallet_2,
+            &ctx.accounts.wallet_3,
+            &ctx.accounts.system_program,
+            ctx.bumps.treasury,
+            ctx.accounts.config.treasury_version,
+        )?;
+
+        ctx.accounts.revenue_snapshot.record(ctx.bumps.revenue_snapshot, bid, distributed)?;
+
+        // Create the post
+        ctx.accounts.post.author = author;
+        ctx.accounts.post.target = target;
+        ctx.accounts.post.content = content;
+        ctx.accounts.post.bid = bid;
+
+        let clock = Clock::get()?;
+        ctx.accounts.post.timestamp = clock.unix_timestamp;
+
+        ctx.accounts.post.bump = ctx.bumps.post;
+        ctx.accounts.post.version = POST_VERSION;
+        ctx.accounts.post.refresh_rank(clock.unix_timestamp);
+
+        let post_key = ctx.accounts.post.key();
+        ctx.accounts.author_index.record_post(ctx.accounts.post.author, post_key, ctx.bumps.author_index);
+        emit!(PostCreated {
+            post: post_key,
+            author: ctx.accounts.post.author,
+            target: ctx.accounts.post.target.clone(),
+            bid: ctx.accounts.post.bid,
+            bid_mint: ctx.accounts.post.bid_mint,
+            timestamp: ctx.accounts.post.timestamp,
+        });
+        count(&mut ctx.accounts.metrics, |metrics| &mut metrics.posts_created);
+        Ok(())
+    }
+
+    // Reply to a post with an encrypted DM to its author, sent through private_messages::send_message.
+    // The content is encrypted client-side with the author's registered X25519 key.
+    pub fn reply_privately_to_post(
+        ctx: Context<ReplyPrivatelyToPost>,
+        encrypted_content: Vec<u8>,
+        nonce: [u8; 24],
+    ) -> Result<()>
+    {
+        // The recipient must be the messaging account of the post author
+        let sender = ctx.accounts.sender.key();
+        let recipient = reported(messaging_user_wallet(&ctx.accounts.recipient_user), sender, 0)?;
+        require_reported!(recipient == ctx.accounts.post.author, PostError::InvalidRecipient, sender, 0);
+
+        send_message_cpi(
+            &ctx.accounts.sender,
+            &ctx.accounts.recipient_user,
+            &ctx.accounts.message_account,
+            &ctx.accounts.system_program,
+            &ctx.accounts.private_messages_program,
+            encrypted_content,
+            nonce,
+        )?;
+
+        emit!(PrivateReplySent {
+            post: ctx.accounts.post.key(),
+            message: ctx.accounts.message_account.key(),
+            sender: ctx.accounts.sender.key(),
+            recipient,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        count(&mut ctx.accounts.metrics, |metrics| &mut metrics.messages_sent);
+        Ok(())
+    }
+
+    // Cross-post: notify the owner of the post's target with a courtesy DM, sent through
+    // private_messages::send_message. Only when the target is registered and its owner has a
+    // messaging account; the content (encrypted client-side with the owner's X25519 key) carries
+    // the post pubkey. Sent once per post, usually in the same transaction as the post.
+    pub fn notify_post_target(
+        ctx: Context<NotifyPostTarget>,
+        encrypted_content: Vec<u8>,
+        nonce: [u8; 24],
+    ) -> Result<()>
+    {
+        let author = ctx.accounts.author.key();
+        let recipient = reported(messaging_user_wallet(&ctx.accounts.recipient_user), author, 0)?;
+        require_reported!(recipient == ctx.accounts.target_account.owner, PostError::InvalidRecipient, author, 0);
+        require_reported!(
+            ctx.accounts.post.flags & POST_FLAG_TARGET_NOTIFIED == 0,
+            PostError::TargetAlreadyNotified,
+            author,
+            0
+        );
+
+        send_message_cpi(
+            &ctx.accounts.author,
+            &ctx.accounts.recipient_user,
+            &ctx.accounts.message_account,
+            &ctx.accounts.system_program,
+            &ctx.accounts.private_messages_program,
+            encrypted_content,
+            nonce,
+        )?;
+        ctx.accounts.post.flags |= POST_FLAG_TARGET_NOTIFIED;
+
+        emit!(PostTargetNotified {
+            post: ctx.accounts.post.key(),
+            message: ctx.accounts.message_account.key(),
+            author: ctx.accounts.author.key(),
+            target_owner: recipient,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        count(&mut ctx.accounts.metrics, |metrics| &mut metrics.messages_sent);
+        Ok(())
+    }
+
+    // Record the post's license and the author's Ed25519 signature over content_hash(content),
+    // checked against the Ed25519 instruction right before this one. Set once, so reusers can
+    // rely on both from the account alone.
+    pub fn set_post_provenance(ctx: Context<SetPostProvenance>, license: u8) -> Result<()> {
+        let author = ctx.accounts.author.key();
+        let post = &mut ctx.accounts.post;
+        require_reported!(post.version >= POST_VERSION, PostError::PostNotMigrated, author, post.version as u64);
+        require_reported!(license <= MAX_LICENSE, PostError::InvalidLicense, author, license as u64);
+        require_reported!(post.content_signature == [0; 64], PostError::ProvenanceAlreadySet, author, 0);
+
+        let content_hash = content_hash(&post.content);
+        post.content_signature =
+            reported(verify_ed25519_ix(&ctx.accounts.instructions_sysvar, &post.author, &content_hash), author, 0)?;
+        post.license = license;
+
+        emit!(PostProvenanceSet {
+            post: post.key(),
+            author: post.author,
+            license,
+            content_hash,
+        });
+
         Ok(())
     }
 
-    // Initialize treasury PDA with rent-exempt minimum (call once)
+    // Initialize treasury PDA with rent-exempt minimum (call once). Version 1 only: later
+    // treasuries receive the whole balance, rent included, from migrate_treasury.
     pub fn initialize_treasury(ctx: Context<InitializeTreasury>) -> Result<()> {
         transfer(
             CpiContext::new(
@@ -119,92 +582,5883 @@ pub mod post_msg_program {
         )?;
         Ok(())
     }
-}
 
-#[derive(Accounts)]
-#[instruction(target: String)]
-pub struct CreatePost<'info>
-{
-    #[account(mut)]
-    pub author: Signer<'info>,
+    // Upgrade a legacy post to the current layout, growing the account if needed.
+    // Permissionless: the payer covers any extra rent.
+    pub fn migrate_post(ctx: Context<MigratePost>) -> Result<()> {
+        let payer = ctx.accounts.payer.key();
+        let post_info = ctx.accounts.post.to_account_info();
+        require_keys_eq!(*post_info.owner, crate::ID, PostError::InvalidPost);
+        require_reported!(
+            post_info.data_len() >= 8 && post_info.try_borrow_data()?[..8] == *Post::DISCRIMINATOR,
+            PostError::InvalidPost,
+            payer,
+            post_info.data_len() as u64,
+        );
 
-    /// CHECK: PDA treasury - program controlled
-    #[account(
-        mut,
-        seeds = [b"treasury"],
-        bump
-    )]
-    pub treasury: AccountInfo<'info>,
+        // Grow the account to the current size (new bytes are zeroed, i.e. version 0)
+        if post_info.data_len() < Post::SIZE {
+            let rent = Rent::get()?.minimum_balance(Post::SIZE);
+            let top_up = rent.saturating_sub(post_info.lamports());
+            if top_up > 0 {
+                transfer(
+                    CpiContext::new(
+                        ctx.accounts.system_program.to_account_info(),
+                        Transfer {
+                            from: ctx.accounts.payer.to_account_info(),
+                            to: post_info.clone(),
+                        },
+                    ),
+                    top_up,
+                )?;
+            }
+            post_info.resize(Post::SIZE)?;
+        }
 
-    /// CHECK: Revenue wallet 1 (45%) - verified against hardcoded address
-    #[account(
-        mut,
-        constraint = wallet_1.key() == WALLET_1 @ PostError::InvalidWallet
-    )]
-    pub wallet_1: AccountInfo<'info>,
+        let mut post = Post::try_deserialize(&mut &post_info.try_borrow_data()?[..])?;
+        let from_version = post.version;
+        require_reported!(from_version < POST_VERSION, PostError::AlreadyMigrated, payer, from_version as u64);
 
-    /// CHECK: Revenue wallet 2 (10%) - verified against hardcoded address
-    #[account(
-        mut,
-        constraint = wallet_2.key() == WALLET_2 @ PostError::InvalidWallet
-    )]
-    pub wallet_2: AccountInfo<'info>,
+        post.version = POST_VERSION;
+        post.refresh_rank(Clock::get()?.unix_timestamp);
+        post.try_serialize(&mut &mut post_info.try_borrow_mut_data()?[..])?;
 
-    /// CHECK: Revenue wallet 3 (45%) - verified against hardcoded address
-    #[account(
-        mut,
-        constraint = wallet_3.key() == WALLET_3 @ PostError::InvalidWallet
-    )]
-    pub wallet_3: AccountInfo<'info>,
+        emit!(PostMigrated {
+            post: post_info.key(),
+            from_version,
+            to_version: POST_VERSION,
+        });
 
-    #[account(
-        init,
-        payer = author,
-        space = 8 + 32 + 4 + 64 + 4 + 512 + 8 + 8 + 1,
-        seeds = [b"post", author.key().as_ref(), target.as_bytes()],
-        bump
-    )]
-    pub post: Account<'info, Post>,
+        Ok(())
+    }
 
-    pub system_program: Program<'info, System>,
-}
+    // Distribute any residual treasury balance above the rent-exempt minimum (admin only)
+    pub fn sweep_treasury(ctx: Context<SweepTreasury>) -> Result<()> {
+        let amount = distribute_treasury(
+            &ctx.accounts.treasury,
+            &ctx.accounts.wallet_1,
+            &ctx.accounts.wallet_2,
+            &ctx.accounts.wallet_3,
+            &ctx.accounts.system_program,
+            ctx.bumps.treasury,
+            ctx.accounts.config.treasury_version,
+        )?;
 
-#[derive(Accounts)]
-pub struct InitializeTreasury<'info>
-{
-    #[account(mut)]
-    pub payer: Signer<'info>,
+        emit!(TreasurySwept {
+            admin: ctx.accounts.admin.key(),
+            amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
 
-    /// CHECK: PDA treasury - program controlled
-    #[account(
-        mut,
-        seeds = [b"treasury"],
-        bump
-    )]
-    pub treasury: AccountInfo<'info>,
+        Ok(())
+    }
 
-    pub system_program: Program<'info, System>,
-}
+    // Move the whole treasury balance to the PDA of a newer treasury version (admin only).
+    // The destination must be the program PDA ["treasury", version]; the move is logged on-chain.
+    pub fn migrate_treasury(ctx: Context<MigrateTreasury>, version: u8) -> Result<()> {
+        let current = ctx.accounts.config.treasury_version;
+        require!(version > current, PostError::InvalidConfig);
+        let (expected, _) =
+            Pubkey::find_program_address(&[TREASURY_SEED, treasury_version_seed(&version)], &crate::ID);
+        require_keys_eq!(ctx.accounts.destination.key(), expected, PostError::InvalidWallet);
 
-#[account]
-pub struct Post
-{
-    pub author: Pubkey,
-    pub target: String,
-    pub content: String,
-    pub bid: u64,
-    pub timestamp: i64,
-    pub bump: u8,
+        let amount = ctx.accounts.treasury.lamports();
+        let seeds = &[TREASURY_SEED, treasury_version_seed(&current), &[ctx.bumps.treasury]];
+        transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.treasury.to_account_info(),
+                    to: ctx.accounts.destination.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            amount,
+        )?;
+
+        ctx.accounts.config.treasury_version = version;
+
+        emit!(TreasuryMigrated {
+            admin: ctx.accounts.admin.key(),
+            from: ctx.accounts.treasury.key(),
+            to: expected,
+            version,
+            amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // Initialize the program config (call once, upgrade authority only, becomes admin)
+    pub fn initialize_config(ctx: Context<InitializeConfig>, min_bid_usd: u64) -> Result<()> {
+        require!(min_bid_usd > 0, PostError::InvalidConfig);
+
+        let config = &mut ctx.accounts.config;
+        config.admin = ctx.accounts.admin.key();
+        config.min_bid_usd = min_bid_usd;
+        config.referral_bps = 0;
+        config.receipt_tree = Pubkey::default();
+        config.rate_window_secs = DEFAULT_RATE_WINDOW;
+        config.max_posts_per_window = 0;
+        config.rate_waiver_bid_usd = 0;
+        config.min_stake = 0;
+        config.stake_cooldown_secs = DEFAULT_STAKE_COOLDOWN;
+        config.vesting_vault = Pubkey::default();
+        config.pending_admin = Pubkey::default();
+        config.escrow_window_secs = DEFAULT_ESCROW_WINDOW;
+        config.escrow_keep_fee_bps = DEFAULT_ESCROW_KEEP_FEE_BPS;
+        config.upvote_lamports = DEFAULT_UPVOTE_LAMPORTS;
+        config.treasury_version = 1;
+        config.view_attestor = Pubkey::default();
+        config.bump = ctx.bumps.config;
+        Ok(())
+    }
+
+    // Update the USD-pegged minimum bid (admin only)
+    pub fn set_min_bid_usd(ctx: Context<UpdateConfig>, min_bid_usd: u64) -> Result<()> {
+        require!(min_bid_usd > 0, PostError::InvalidConfig);
+
+        ctx.accounts.config.min_bid_usd = min_bid_usd;
+        Ok(())
+    }
+
+    // Update the share of each bid redirected to referrers (admin only)
+    pub fn set_referral_bps(ctx: Context<UpdateConfig>, referral_bps: u16) -> Result<()> {
+        require!(referral_bps <= MAX_REFERRAL_BPS, PostError::InvalidConfig);
+
+        ctx.accounts.config.referral_bps = referral_bps;
+        Ok(())
+    }
+
+    // Configure per-author rate limits (admin only). max_posts_per_window = 0 disables the cap,
+    // rate_waiver_bid_usd = 0 disables the high-bid waiver.
+    pub fn set_rate_limit(
+        ctx: Context<UpdateConfig>,
+        rate_window_secs: i64,
+        max_posts_per_window: u16,
+        rate_waiver_bid_usd: u64,
+    ) -> Result<()> {
+        require!(rate_window_secs > 0, PostError::InvalidConfig);
+
+        let config = &mut ctx.accounts.config;
+        config.rate_window_secs = rate_window_secs;
+        config.max_posts_per_window = max_posts_per_window;
+        config.rate_waiver_bid_usd = rate_waiver_bid_usd;
+        Ok(())
+    }
+
+    // Configure stake-to-post (admin only). min_stake = 0 disables the requirement.
+    pub fn set_stake_requirement(ctx: Context<UpdateConfig>, min_stake: u64, stake_cooldown_secs: i64) -> Result<()> {
+        require!(stake_cooldown_secs >= 0, PostError::InvalidConfig);
+
+        let config = &mut ctx.accounts.config;
+        config.min_stake = min_stake;
+        config.stake_cooldown_secs = stake_cooldown_secs;
+        Ok(())
+    }
+
+    // Configure escrowed posts (admin only): acceptance window and fee kept on reclaim
+    pub fn set_escrow_params(ctx: Context<UpdateConfig>, escrow_window_secs: i64, escrow_keep_fee_bps: u16) -> Result<()> {
+        require!(escrow_window_secs > 0, PostError::InvalidConfig);
+        require!(escrow_keep_fee_bps as u64 <= BPS_DENOMINATOR, PostError::InvalidConfig);
+
+        let config = &mut ctx.accounts.config;
+        config.escrow_window_secs = escrow_window_secs;
+        config.escrow_keep_fee_bps = escrow_keep_fee_bps;
+        Ok(())
+    }
+
+    // Set the authority allowed to attest post views (admin only, default = disabled)
+    pub fn set_view_attestor(ctx: Context<UpdateConfig>, view_attestor: Pubkey) -> Result<()> {
+        ctx.accounts.config.view_attestor = view_attestor;
+        Ok(())
+    }
+
+    // Set the price of an upvote in lamports (admin only)
+    pub fn set_upvote_price(ctx: Context<UpdateConfig>, upvote_lamports: u64) -> Result<()> {
+        require!(upvote_lamports > 0, PostError::InvalidConfig);
+
+        ctx.accounts.config.upvote_lamports = upvote_lamports;
+        Ok(())
+    }
+
+    // Set the Bubblegum tree used for post receipts (admin only).
+    // The tree delegate must be the program's receipt_authority PDA.
+    pub fn set_receipt_tree(ctx: Context<UpdateConfig>, receipt_tree: Pubkey) -> Result<()> {
+        ctx.accounts.config.receipt_tree = receipt_tree;
+        Ok(())
+    }
+
+    // Apply several economic parameters at once (admin only). A single instruction keeps
+    // a governance proposal to one transaction; `None` leaves a parameter unchanged.
+    pub fn update_economics(ctx: Context<UpdateConfig>, params: EconomicsParams) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        if let Some(min_bid_usd) = params.min_bid_usd {
+            require!(min_bid_usd > 0, PostError::InvalidConfig);
+            config.min_bid_usd = min_bid_usd;
+        }
+        if let Some(referral_bps) = params.referral_bps {
+            require!(referral_bps <= MAX_REFERRAL_BPS, PostError::InvalidConfig);
+            config.referral_bps = referral_bps;
+        }
+        if let Some(rate_window_secs) = params.rate_window_secs {
+            require!(rate_window_secs > 0, PostError::InvalidConfig);
+            config.rate_window_secs = rate_window_secs;
+        }
+        if let Some(max_posts_per_window) = params.max_posts_per_window {
+            config.max_posts_per_window = max_posts_per_window;
+        }
+        if let Some(rate_waiver_bid_usd) = params.rate_waiver_bid_usd {
+            config.rate_waiver_bid_usd = rate_waiver_bid_usd;
+        }
+        if let Some(min_stake) = params.min_stake {
+            config.min_stake = min_stake;
+        }
+        if let Some(stake_cooldown_secs) = params.stake_cooldown_secs {
+            require!(stake_cooldown_secs >= 0, PostError::InvalidConfig);
+            config.stake_cooldown_secs = stake_cooldown_secs;
+        }
+        if let Some(escrow_window_secs) = params.escrow_window_secs {
+            require!(escrow_window_secs > 0, PostError::InvalidConfig);
+            config.escrow_window_secs = escrow_window_secs;
+        }
+        if let Some(escrow_keep_fee_bps) = params.escrow_keep_fee_bps {
+            require!(escrow_keep_fee_bps as u64 <= BPS_DENOMINATOR, PostError::InvalidConfig);
+            config.escrow_keep_fee_bps = escrow_keep_fee_bps;
+        }
+        if let Some(upvote_lamports) = params.upvote_lamports {
+            require!(upvote_lamports > 0, PostError::InvalidConfig);
+            config.upvote_lamports = upvote_lamports;
+        }
+        Ok(())
+    }
+
+    // Start an admin handover (admin only); the new admin must call accept_admin
+    pub fn propose_admin(ctx: Context<UpdateConfig>, new_admin: Pubkey) -> Result<()> {
+        ctx.accounts.config.pending_admin = new_admin;
+        Ok(())
+    }
+
+    // Start a handover to an SPL Governance (Realms) governance: the pending admin is the
+    // governance's native treasury PDA, which signs when a passed proposal is executed.
+    pub fn propose_governance_admin(ctx: Context<ProposeGovernanceAdmin>) -> Result<()> {
+        let (native_treasury, _) = Pubkey::find_program_address(
+            &[b"native-treasury", ctx.accounts.governance.key().as_ref()],
+            &ctx.accounts.governance_program.key(),
+        );
+        ctx.accounts.config.pending_admin = native_treasury;
+        Ok(())
+    }
+
+    // Complete an admin handover (signed by the pending admin, e.g. via governance execution)
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let previous_admin = config.admin;
+        config.admin = config.pending_admin;
+        config.pending_admin = Pubkey::default();
+
+        emit!(AdminChanged {
+            previous_admin,
+            new_admin: config.admin,
+        });
+
+        Ok(())
+    }
+
+    // Register a referral partner (admin only)
+    pub fn register_referrer(ctx: Context<RegisterReferrer>) -> Result<()> {
+        let referral = &mut ctx.accounts.referral;
+        referral.wallet = ctx.accounts.referrer.key();
+        referral.total_earned = 0;
+        referral.referral_count = 0;
+        referral.bump = ctx.bumps.referral;
+        Ok(())
+    }
+
+    // Add a charity to the curated registry (admin only). Authors who designate it
+    // give it share_bps of their bid.
+    pub fn register_charity(ctx: Context<RegisterCharity>, share_bps: u16) -> Result<()> {
+        require!(share_bps > 0 && share_bps <= MAX_CHARITY_BPS, PostError::InvalidConfig);
+
+        let charity = &mut ctx.accounts.charity;
+        charity.wallet = ctx.accounts.charity_wallet.key();
+        charity.share_bps = share_bps;
+        charity.total_received = 0;
+        charity.bump = ctx.bumps.charity;
+        Ok(())
+    }
+
+    // Remove a charity from the registry (admin only)
+    pub fn remove_charity(_ctx: Context<RemoveCharity>) -> Result<()> {
+        Ok(())
+    }
+
+    // ========================================================================
+    // SEALED-BID FEATURED-SLOT AUCTION (via Arcium MPC)
+    // ========================================================================
+    //
+    // Bidders escrow a public, uniform deposit and submit an encrypted bid. The MPC
+    // folds each bid into an encrypted auction state, then reveals only the winner
+    // and the clearing price (second-highest bid), so losing bids stay private and
+    // nobody can snipe or copy-bid.
+
+    pub fn init_sealed_auction_comp_def(ctx: Context<InitSealedAuctionCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_place_sealed_bid_comp_def(ctx: Context<InitPlaceSealedBidCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_resolve_sealed_auction_comp_def(ctx: Context<InitResolveSealedAuctionCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    // Open a featured-slot auction (admin only) and queue the encrypted empty state
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_auction(
+        ctx: Context<CreateAuction>,
+        computation_offset: u64,
+        auction_id: u64,
+        deposit: u64,
+        reserve_price: u64,
+        end_time: i64,
+        featured_duration: i64,
+        mxe_nonce: u128,
+    ) -> Result<()> {
+        require!(reserve_price <= deposit, PostError::InvalidConfig);
+        require!(end_time > Clock::get()?.unix_timestamp, PostError::InvalidConfig);
+        require!(featured_duration > 0, PostError::InvalidConfig);
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let auction = &mut ctx.accounts.auction;
+        auction.id = auction_id;
+        auction.authority = ctx.accounts.admin.key();
+        auction.deposit = deposit;
+        auction.reserve_price = reserve_price;
+        auction.end_time = end_time;
+        auction.featured_duration = featured_duration;
+        auction.status = AuctionStatus::Initializing;
+        auction.computation_pending = true;
+        auction.pending_since = Clock::get()?.unix_timestamp;
+        auction.pending_computation = computation_offset;
+        auction.bump = ctx.bumps.auction;
+
+        let args = ArgBuilder::new().plaintext_u128(mxe_nonce).build();
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![InitSealedAuctionCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &with_metrics(
+                    vec![CallbackAccount {
+                        pubkey: ctx.accounts.auction.key(),
+                        is_writable: true,
+                    }],
+                    &ctx.accounts.metrics,
+                ),
+            )?],
+            1,
+            0,
+        )?;
+
+        count(&mut ctx.accounts.metrics, |metrics| &mut metrics.computations_queued);
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "init_sealed_auction")]
+    pub fn init_sealed_auction_callback(
+        ctx: Context<InitSealedAuctionCallback>,
+        output: SignedComputationOutputs<InitSealedAuctionOutput>,
+    ) -> Result<()> {
+        require_current_computation(
+            &ctx.accounts.auction,
+            &ctx.accounts.computation_account,
+            &ctx.accounts.mxe_account,
+        )?;
+
+        let state = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(InitSealedAuctionOutput { field_0 }) => field_0,
+            Err(_) => return Err(PostError::AbortedComputation.into()),
+        };
+        count(&mut ctx.accounts.metrics, |metrics| &mut metrics.computations_completed);
+
+        let auction = &mut ctx.accounts.auction;
+        auction.encrypted_state = state.ciphertexts;
+        auction.state_nonce = state.nonce;
+        auction.status = AuctionStatus::Open;
+        auction.computation_pending = false;
+
+        Ok(())
+    }
+
+    // Escrow the auction deposit and fold an encrypted bid for one of the bidder's posts
+    pub fn place_sealed_bid(
+        ctx: Context<PlaceSealedBid>,
+        computation_offset: u64,
+        encrypted_bid: [u8; 32],
+        bid_pubkey: [u8; 32],
+        bid_nonce: u128,
+    ) -> Result<()> {
+        let bidder = ctx.accounts.bidder.key();
+        let auction = &ctx.accounts.auction;
+        require_reported!(auction.status == AuctionStatus::Open, PostError::AuctionNotOpen, bidder, 0);
+        require_reported!(Clock::get()?.unix_timestamp < auction.end_time, PostError::AuctionNotOpen, bidder, 0);
+        require_reported!(!auction.computation_pending, PostError::AuctionBusy, bidder, 0);
+        require_reported!(
+            (auction.bid_count as usize) < MAX_SEALED_BIDS,
+            PostError::AuctionFull,
+            bidder,
+            auction.bid_count as u64,
+        );
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        // Escrow the uniform deposit in the auction account
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.bidder.to_account_info(),
+                    to: ctx.accounts.auction.to_account_info(),
+                },
+            ),
+            auction.deposit,
+        )?;
+
+        let auction = &mut ctx.accounts.auction;
+        let bidder_index = auction.bid_count;
+        auction.bid_posts[bidder_index as usize] = ctx.accounts.post.key();
+        auction.bid_count += 1;
+        auction.computation_pending = true;
+        auction.pending_since = Clock::get()?.unix_timestamp;
+        auction.pending_computation = computation_offset;
+
+        let sealed_bid = &mut ctx.accounts.sealed_bid;
+        sealed_bid.auction = auction.key();
+        sealed_bid.bidder = ctx.accounts.bidder.key();
+        sealed_bid.post = ctx.accounts.post.key();
+        sealed_bid.index = bidder_index;
+        sealed_bid.deposit = auction.deposit;
+        sealed_bid.bump = ctx.bumps.sealed_bid;
+
+        let args = ArgBuilder::new()
+            .x25519_pubkey(bid_pubkey)
+            .plaintext_u128(bid_nonce)
+            .encrypted_u64(encrypted_bid)
+            .plaintext_u8(bidder_index)
+            .plaintext_u64(auction.reserve_price)
+            .plaintext_u64(auction.deposit)
+            .plaintext_u128(auction.state_nonce)
+            .account(auction.key(), Auction::ENCRYPTED_STATE_OFFSET, Auction::ENCRYPTED_STATE_LEN)
+            .build();
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![PlaceSealedBidCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &with_metrics(
+                    vec![CallbackAccount {
+                        pubkey: ctx.accounts.auction.key(),
+                        is_writable: true,
+                    }],
+                    &ctx.accounts.metrics,
+                ),
+            )?],
+            1,
+            0,
+        )?;
+
+        count(&mut ctx.accounts.metrics, |metrics| &mut metrics.computations_queued);
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "place_sealed_bid")]
+    pub fn place_sealed_bid_callback(
+        ctx: Context<PlaceSealedBidCallback>,
+        output: SignedComputationOutputs<PlaceSealedBidOutput>,
+    ) -> Result<()> {
+        require_current_computation(
+            &ctx.accounts.auction,
+            &ctx.accounts.computation_account,
+            &ctx.accounts.mxe_account,
+        )?;
+
+        let state = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(PlaceSealedBidOutput { field_0 }) => field_0,
+            Err(_) => return Err(PostError::AbortedComputation.into()),
+        };
+        count(&mut ctx.accounts.metrics, |metrics| &mut metrics.computations_completed);
+
+        let auction = &mut ctx.accounts.auction;
+        auction.encrypted_state = state.ciphertexts;
+        auction.state_nonce = state.nonce;
+        auction.computation_pending = false;
+
+        emit!(SealedBidPlaced {
+            auction: auction.key(),
+            bid_count: auction.bid_count,
+        });
+
+        Ok(())
+    }
+
+    // Close bidding and queue the winner selection (permissionless, after end_time)
+    pub fn resolve_auction(ctx: Context<ResolveAuction>, computation_offset: u64) -> Result<()> {
+        let payer = ctx.accounts.payer.key();
+        let auction = &ctx.accounts.auction;
+        require_reported!(auction.status == AuctionStatus::Open, PostError::AuctionNotOpen, payer, 0);
+        require_reported!(Clock::get()?.unix_timestamp >= auction.end_time, PostError::AuctionStillOpen, payer, 0);
+        require_reported!(!auction.computation_pending, PostError::AuctionBusy, payer, 0);
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let auction = &mut ctx.accounts.auction;
+        auction.status = AuctionStatus::Resolving;
+        auction.computation_pending = true;
+        auction.pending_since = Clock::get()?.unix_timestamp;
+        auction.pending_computation = computation_offset;
+
+        let args = ArgBuilder::new()
+            .plaintext_u128(auction.state_nonce)
+            .account(auction.key(), Auction::ENCRYPTED_STATE_OFFSET, Auction::ENCRYPTED_STATE_LEN)
+            .build();
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ResolveSealedAuctionCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &with_metrics(
+                    vec![CallbackAccount {
+                        pubkey: ctx.accounts.auction.key(),
+                        is_writable: true,
+                    }],
+                    &ctx.accounts.metrics,
+                ),
+            )?],
+            1,
+            0,
+        )?;
+
+        count(&mut ctx.accounts.metrics, |metrics| &mut metrics.computations_queued);
+        Ok(())
+    }
+
+    // Finalizes the featured post from the revealed winner and clearing price
+    #[arcium_callback(encrypted_ix = "resolve_sealed_auction")]
+    pub fn resolve_sealed_auction_callback(
+        ctx: Context<ResolveSealedAuctionCallback>,
+        output: SignedComputationOutputs<ResolveSealedAuctionOutput>,
+    ) -> Result<()> {
+        require_current_computation(
+            &ctx.accounts.auction,
+            &ctx.accounts.computation_account,
+            &ctx.accounts.mxe_account,
+        )?;
+
+        let result = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(ResolveSealedAuctionOutput { field_0 }) => field_0,
+            Err(_) => return Err(PostError::AbortedComputation.into()),
+        };
+        count(&mut ctx.accounts.metrics, |metrics| &mut metrics.computations_completed);
+        let (has_winner, winner_index, second_bid) = (result.field_0, result.field_1, result.field_2);
+
+        let auction = &mut ctx.accounts.auction;
+        auction.computation_pending = false;
+
+        if !has_winner || winner_index >= auction.bid_count {
+            auction.status = AuctionStatus::NoWinner;
+            return Ok(());
+        }
+
+        // Vickrey: the winner pays the second-highest valid bid, at least the reserve price
+        let now = Clock::get()?.unix_timestamp;
+        auction.status = AuctionStatus::Resolved;
+        auction.winner_index = winner_index;
+        auction.clearing_price = second_bid.max(auction.reserve_price);
+        auction.featured_post = auction.bid_posts[winner_index as usize];
+        auction.featured_until = now + auction.featured_duration;
+
+        emit!(FeaturedPostSelected {
+            auction: auction.key(),
+            post: auction.featured_post,
+            clearing_price: auction.clearing_price,
+            featured_until: auction.featured_until,
+        });
+
+        Ok(())
+    }
+
+    // Cancel an auction whose computation never got its callback (aborted) after
+    // PENDING_TIMEOUT_SECS (permissionless). The encrypted state may be missing a bid,
+    // so the auction ends without a winner and every bidder withdraws their deposit.
+    pub fn reset_auction_pending(ctx: Context<ResetAuctionPending>) -> Result<()> {
+        let payer = ctx.accounts.payer.key();
+        let auction = &mut ctx.accounts.auction;
+        require_reported!(auction.computation_pending, PostError::NoComputationPending, payer, 0);
+        require_reported!(
+            Clock::get()?.unix_timestamp >= auction.pending_since.saturating_add(PENDING_TIMEOUT_SECS),
+            PostError::PendingNotExpired,
+            payer,
+            0,
+        );
+        auction.computation_pending = false;
+        auction.status = AuctionStatus::NoWinner;
+
+        count(&mut ctx.accounts.metrics, |metrics| &mut metrics.computations_failed);
+        Ok(())
+    }
+
+    // Move the winner's clearing price from escrow to the revenue split (permissionless)
+    pub fn settle_auction(ctx: Context<SettleAuction>) -> Result<()> {
+        let auction = &mut ctx.accounts.auction;
+        require!(auction.status == AuctionStatus::Resolved, PostError::AuctionNotResolved);
+        require!(!auction.settled, PostError::AuctionAlreadySettled);
+        auction.settled = true;
+
+        let clearing_price = auction.clearing_price;
+        **auction.to_account_info().try_borrow_mut_lamports()? -= clearing_price;
+        **ctx.accounts.treasury.try_borrow_mut_lamports()? += clearing_price;
+
+        distribute_treasury(
+            &ctx.accounts.treasury,
+            &ctx.accounts.wallet_1,
+            &ctx.accounts.wallet_2,
+            &ctx.accounts.wallet_3,
+            &ctx.accounts.system_program,
+            ctx.bumps.treasury,
+            ctx.accounts.config.treasury_version,
+        )?;
+
+        Ok(())
+    }
+
+    // Refund a bidder's deposit (minus the clearing price for the winner) and close the receipt
+    pub fn withdraw_sealed_bid(ctx: Context<WithdrawSealedBid>) -> Result<()> {
+        let bidder = ctx.accounts.bidder.key();
+        let auction = &ctx.accounts.auction;
+        let sealed_bid = &ctx.accounts.sealed_bid;
+        require_reported!(auction.status != AuctionStatus::Refunding, PostError::RefundsVaulted, bidder, 0);
+
+        let is_winner = match auction.status {
+            AuctionStatus::Resolved => sealed_bid.index == auction.winner_index,
+            AuctionStatus::NoWinner => false,
+            _ => return err!(PostError::AuctionNotResolved),
+        };
+        require_reported!(!is_winner || auction.settled, PostError::AuctionNotResolved, bidder, 0);
+
+        let refund = if is_winner {
+            sealed_bid
+                .deposit
+                .checked_sub(auction.clearing_price)
+                .ok_or(PostError::DepositBelowClearingPrice)?
+        } else {
+            sealed_bid.deposit
+        };
+
+        **ctx.accounts.auction.to_account_info().try_borrow_mut_lamports()? -= refund;
+        **ctx.accounts.bidder.try_borrow_mut_lamports()? += refund;
+
+        Ok(())
+    }
+
+    // Move the remaining deposits of a resolved (and settled) auction into a refund vault (admin
+    // only). Bidders then claim with claim_refund instead of withdraw_sealed_bid; refunds are
+    // released linearly over `stream_duration` (0 = at once) and whatever is unclaimed after
+    // `claim_window` goes to the treasury.
+    pub fn open_refund_vault(ctx: Context<OpenRefundVault>, stream_duration: i64, claim_window: i64) -> Result<()> {
+        require!(stream_duration >= 0 && claim_window > stream_duration, PostError::InvalidConfig);
+
+        let auction = &ctx.accounts.auction;
+        let winner_index = match auction.status {
+            AuctionStatus::Resolved => {
+                require!(auction.settled, PostError::AuctionNotResolved);
+                auction.winner_index
+            }
+            AuctionStatus::NoWinner => RefundVault::NO_WINNER,
+            _ => return err!(PostError::AuctionNotResolved),
+        };
+
+        // Everything above the auction's rent-exempt minimum is still owed to bidders
+        let auction_info = ctx.accounts.auction.to_account_info();
+        let amount = auction_info.lamports().saturating_sub(Rent::get()?.minimum_balance(Auction::SIZE));
+        **auction_info.try_borrow_mut_lamports()? -= amount;
+        **ctx.accounts.refund_vault.to_account_info().try_borrow_mut_lamports()? += amount;
+
+        let now = Clock::get()?.unix_timestamp;
+        let auction = &mut ctx.accounts.auction;
+        auction.status = AuctionStatus::Refunding;
+
+        let vault = &mut ctx.accounts.refund_vault;
+        vault.auction = auction.key();
+        vault.deposit = auction.deposit;
+        vault.winner_index = winner_index;
+        vault.clearing_price = auction.clearing_price;
+        vault.amount = amount;
+        vault.claimed = [0; MAX_SEALED_BIDS];
+        vault.stream_start = now;
+        vault.stream_duration = stream_duration;
+        vault.expires_at = now + claim_window;
+        vault.bump = ctx.bumps.refund_vault;
+
+        emit!(RefundVaultOpened {
+            auction: vault.auction,
+            amount,
+            stream_end: now + stream_duration,
+            expires_at: vault.expires_at,
+        });
+
+        Ok(())
+    }
+
+    // Claim the released part of a bid's refund; the receipt is closed once it is fully refunded
+    pub fn claim_refund(ctx: Context<ClaimRefund>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let bidder = ctx.accounts.bidder.key();
+        let vault = &mut ctx.accounts.refund_vault;
+        require_reported!(now < vault.expires_at, PostError::RefundExpired, bidder, 0);
+
+        let index = ctx.accounts.sealed_bid.index as usize;
+        let refund = vault.refund_of(ctx.accounts.sealed_bid.index, ctx.accounts.sealed_bid.deposit);
+        let released = released_refund(refund, vault.stream_start, vault.stream_duration, now);
+        let amount = released.saturating_sub(vault.claimed[index]);
+        require_reported!(amount > 0 || refund == 0, PostError::NothingToClaim, bidder, refund);
+
+        vault.claimed[index] += amount;
+        **vault.to_account_info().try_borrow_mut_lamports()? -= amount;
+        **ctx.accounts.bidder.try_borrow_mut_lamports()? += amount;
+
+        emit!(RefundClaimed {
+            auction: vault.auction,
+            bidder: ctx.accounts.bidder.key(),
+            amount,
+            remaining: refund - vault.claimed[index],
+        });
+
+        if vault.claimed[index] == refund {
+            ctx.accounts.sealed_bid.close(ctx.accounts.bidder.to_account_info())?;
+        }
+
+        Ok(())
+    }
+
+    // Send unclaimed refunds to the treasury once the claim window is over (permissionless)
+    pub fn expire_refunds(ctx: Context<ExpireRefunds>) -> Result<()> {
+        let vault = &ctx.accounts.refund_vault;
+        require!(Clock::get()?.unix_timestamp >= vault.expires_at, PostError::RefundNotExpired);
+
+        emit!(RefundsExpired {
+            auction: vault.auction,
+            amount: vault.to_account_info().lamports(),
+        });
+
+        Ok(())
+    }
+
+    // ========================================================================
+    // BOOSTS & ENCRYPTED POSTS WITH THRESHOLD REVEAL (via Arcium MPC)
+    // ========================================================================
+    //
+    // An author can publish encrypted content and seal its symmetric key for the
+    // MXE. Boosts accumulate on the sealed post; once they cross the author's
+    // threshold anyone can queue the reveal and the key is published on-chain.
+
+    // Boost a post, paying through the revenue split
+    pub fn boost_post(ctx: Context<BoostPost>, amount: u64) -> Result<()> {
+        let booster = ctx.accounts.booster.key();
+        require_reported!(amount > 0, PostError::BidTooLow, booster, amount);
+        require_reported!(ctx.accounts.post.version >= POST_VERSION, PostError::PostNotMigrated, booster, 0);
+
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.booster.to_account_info(),
+                    to: ctx.accounts.treasury.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        distribute_treasury(
+            &ctx.accounts.treasury,
+            &ctx.accounts.wallet_1,
+            &ctx.accounts.wallet_2,
+            &ctx.accounts.wallet_3,
+            &ctx.accounts.system_program,
+            ctx.bumps.treasury,
+            ctx.accounts.config.treasury_version,
+        )?;
+
+        // Boosts add to the post's ranking weight
+        let post = &mut ctx.accounts.post;
+        post.total_boosts += amount;
+        post.refresh_rank(Clock::get()?.unix_timestamp);
+
+        // Boosts count towards unlocking sealed content
+        if let Some(sealed) = ctx.accounts.sealed_content.as_mut() {
+            sealed.total_boosts += amount;
+        }
+
+        emit!(PostBoosted {
+            post: ctx.accounts.post.key(),
+            booster: ctx.accounts.booster.key(),
+            amount,
+        });
+
+        count(&mut ctx.accounts.metrics, |metrics| &mut metrics.boosts);
+        Ok(())
+    }
+
+    // Close a post and reclaim its rent (author only).
+    // An escrowed post stays open until the escrow is accepted or reclaimed.
+    // Sponsorship posts are authored by the sponsorship PDA and can't be closed here until
+    // cancel_sponsorship hands them to the sponsor.
+    pub fn close_post(ctx: Context<ClosePost>) -> Result<()> {
+        require_reported!(ctx.accounts.escrow.data_is_empty(), PostError::PostEscrowed, ctx.accounts.author.key(), 0);
+        // Posts from before the author index have no entry to update
+        if let Some(author_index) = &mut ctx.accounts.author_index {
+            author_index.remove_post(&ctx.accounts.post.key());
+        }
+        Ok(())
+    }
+
+    // Upvote a post for a small fixed payment, split like any other revenue.
+    // The voter's receipt PDA makes a second upvote on the same post fail.
+    pub fn upvote_post(ctx: Context<UpvotePost>) -> Result<()> {
+        require_reported!(ctx.accounts.post.version >= POST_VERSION, PostError::PostNotMigrated, ctx.accounts.voter.key(), 0);
+
+        let price = ctx.accounts.config.upvote_lamports;
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.voter.to_account_info(),
+                    to: ctx.accounts.treasury.to_account_info(),
+                },
+            ),
+            price,
+        )?;
+
+        distribute_treasury(
+            &ctx.accounts.treasury,
+            &ctx.accounts.wallet_1,
+            &ctx.accounts.wallet_2,
+            &ctx.accounts.wallet_3,
+            &ctx.accounts.system_program,
+            ctx.bumps.treasury,
+            ctx.accounts.config.treasury_version,
+        )?;
+
+        let now = Clock::get()?.unix_timestamp;
+        let receipt = &mut ctx.accounts.upvote_receipt;
+        receipt.post = ctx.accounts.post.key();
+        receipt.voter = ctx.accounts.voter.key();
+        receipt.timestamp = now;
+        receipt.bump = ctx.bumps.upvote_receipt;
+
+        let post = &mut ctx.accounts.post;
+        post.upvotes += 1;
+
+        emit!(PostUpvoted {
+            post: post.key(),
+            voter: ctx.accounts.voter.key(),
+            amount: price,
+            upvotes: post.upvotes,
+        });
+
+        count(&mut ctx.accounts.metrics, |metrics| &mut metrics.upvotes);
+        Ok(())
+    }
+
+    // Add attested off-chain impressions to a batch of posts (view attestor only).
+    // The posts are passed as writable remaining accounts, `views[i]` applying to the i-th one.
+    pub fn record_views<'info>(ctx: Context<'_, '_, 'info, 'info, RecordViews<'info>>, views: Vec<u64>) -> Result<()> {
+        let attestor = ctx.accounts.attestor.key();
+        require_reported!(
+            views.len() == ctx.remaining_accounts.len(),
+            PostError::InvalidConfig,
+            attestor,
+            views.len() as u64,
+        );
+
+        let mut total_views = 0u64;
+        for (post_info, count) in ctx.remaining_accounts.iter().zip(views) {
+            require_reported!(post_info.is_writable, PostError::InvalidPost, attestor, 0);
+            let mut post: Account<Post> = Account::try_from(post_info)?;
+            require_reported!(post.version >= POST_VERSION, PostError::PostNotMigrated, attestor, 0);
+
+            post.views += count;
+            post.exit(&crate::ID)?;
+            total_views += count;
+        }
+
+        emit!(ViewsRecorded {
+            attestor: ctx.accounts.attestor.key(),
+            posts: ctx.remaining_accounts.len() as u32,
+            total_views,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // Recompute a post's time-decayed rank score (permissionless crank)
+    pub fn refresh_rank(ctx: Context<RefreshRank>) -> Result<()> {
+        let post = &mut ctx.accounts.post;
+        require!(post.version >= POST_VERSION, PostError::PostNotMigrated);
+        post.refresh_rank(Clock::get()?.unix_timestamp);
+        Ok(())
+    }
+
+    pub fn init_reveal_content_key_comp_def(ctx: Context<InitRevealContentKeyCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    // Seal the content key of an encrypted post (author only).
+    // The key is encrypted for the MXE with an ephemeral X25519 key (Enc<Shared, ContentKey>).
+    pub fn seal_post_content(
+        ctx: Context<SealPostContent>,
+        encrypted_key: [[u8; 32]; 2],
+        key_pubkey: [u8; 32],
+        key_nonce: u128,
+        unlock_threshold: u64,
+    ) -> Result<()> {
+        require_reported!(unlock_threshold > 0, PostError::InvalidConfig, ctx.accounts.author.key(), 0);
+
+        let sealed = &mut ctx.accounts.sealed_content;
+        sealed.post = ctx.accounts.post.key();
+        sealed.encrypted_key = encrypted_key;
+        sealed.key_pubkey = key_pubkey;
+        sealed.key_nonce = key_nonce;
+        sealed.unlock_threshold = unlock_threshold;
+        sealed.total_boosts = 0;
+        sealed.computation_pending = false;
+        sealed.pending_since = 0;
+        sealed.revealed = false;
+        sealed.content_key = [0; 32];
+        sealed.bump = ctx.bumps.sealed_content;
+        Ok(())
+    }
+
+    // Queue the key reveal once boosts crossed the threshold (permissionless)
+    pub fn reveal_sealed_post(ctx: Context<RevealSealedPost>, computation_offset: u64) -> Result<()> {
+        let payer = ctx.accounts.payer.key();
+        let sealed = &ctx.accounts.sealed_content;
+        require_reported!(!sealed.revealed, PostError::AlreadyRevealed, payer, 0);
+        require_reported!(!sealed.computation_pending, PostError::ComputationPending, payer, 0);
+        require_reported!(
+            sealed.total_boosts >= sealed.unlock_threshold,
+            PostError::ThresholdNotReached,
+            payer,
+            sealed.total_boosts,
+        );
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let args = ArgBuilder::new()
+            .x25519_pubkey(sealed.key_pubkey)
+            .plaintext_u128(sealed.key_nonce)
+            .encrypted_u128(sealed.encrypted_key[0])
+            .encrypted_u128(sealed.encrypted_key[1])
+            .build();
+
+        ctx.accounts.sealed_content.computation_pending = true;
+        ctx.accounts.sealed_content.pending_since = Clock::get()?.unix_timestamp;
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![RevealContentKeyCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &with_metrics(
+                    vec![CallbackAccount {
+                        pubkey: ctx.accounts.sealed_content.key(),
+                        is_writable: true,
+                    }],
+                    &ctx.accounts.metrics,
+                ),
+            )?],
+            1,
+            0,
+        )?;
+
+        count(&mut ctx.accounts.metrics, |metrics| &mut metrics.computations_queued);
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "reveal_content_key")]
+    pub fn reveal_content_key_callback(
+        ctx: Context<RevealContentKeyCallback>,
+        output: SignedComputationOutputs<RevealContentKeyOutput>,
+    ) -> Result<()> {
+        let key = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(RevealContentKeyOutput { field_0 }) => field_0,
+            Err(_) => return Err(PostError::AbortedComputation.into()),
+        };
+        count(&mut ctx.accounts.metrics, |metrics| &mut metrics.computations_completed);
+
+        let sealed = &mut ctx.accounts.sealed_content;
+        sealed.content_key[..16].copy_from_slice(&key.field_0.to_le_bytes());
+        sealed.content_key[16..].copy_from_slice(&key.field_1.to_le_bytes());
+        sealed.revealed = true;
+        sealed.computation_pending = false;
+
+        emit!(PostContentUnlocked {
+            post: sealed.post,
+            content_key: sealed.content_key,
+        });
+
+        Ok(())
+    }
+
+    // Clear a reveal that never got its callback (aborted) after PENDING_TIMEOUT_SECS,
+    // so reveal_sealed_post can be queued again (permissionless)
+    pub fn reset_sealed_post_pending(ctx: Context<ResetSealedPostPending>) -> Result<()> {
+        let payer = ctx.accounts.payer.key();
+        let sealed = &mut ctx.accounts.sealed_content;
+        require_reported!(sealed.computation_pending, PostError::NoComputationPending, payer, 0);
+        require_reported!(
+            Clock::get()?.unix_timestamp >= sealed.pending_since.saturating_add(PENDING_TIMEOUT_SECS),
+            PostError::PendingNotExpired,
+            payer,
+            0,
+        );
+        sealed.computation_pending = false;
+
+        count(&mut ctx.accounts.metrics, |metrics| &mut metrics.computations_failed);
+        Ok(())
+    }
+
+    // ========================================================================
+    // STAKE-TO-POST
+    // ========================================================================
+
+    // Lock lamports in the author's stake account (cancels a pending unstake)
+    pub fn deposit_stake(ctx: Context<DepositStake>, amount: u64) -> Result<()> {
+        require_reported!(amount > 0, PostError::InvalidStakeAmount, ctx.accounts.author.key(), amount);
+
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.author.to_account_info(),
+                    to: ctx.accounts.stake_account.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        let stake = &mut ctx.accounts.stake_account;
+        stake.author = ctx.accounts.author.key();
+        stake.amount += amount;
+        stake.unlock_requested_at = 0;
+        stake.bump = ctx.bumps.stake_account;
+        Ok(())
+    }
+
+    // Start the unstake cooldown; the stake no longer qualifies for posting
+    pub fn request_unstake(ctx: Context<RequestUnstake>) -> Result<()> {
+        ctx.accounts.stake_account.unlock_requested_at = Clock::get()?.unix_timestamp;
+        Ok(())
+    }
+
+    // Withdraw the whole stake once the cooldown has elapsed and close the account
+    pub fn withdraw_stake(ctx: Context<WithdrawStake>) -> Result<()> {
+        let stake = &ctx.accounts.stake_account;
+        require_reported!(stake.unlock_requested_at != 0, PostError::StakeLocked, stake.author, stake.amount);
+        require_reported!(
+            Clock::get()?.unix_timestamp >= stake.unlock_requested_at + ctx.accounts.config.stake_cooldown_secs,
+            PostError::StakeLocked,
+            stake.author,
+            stake.amount,
+        );
+        Ok(())
+    }
+
+    // Moderation: slash part of an author's stake for an abusive post (admin only).
+    // Slashed lamports go to the treasury and are distributed with the next split.
+    // The post is identified by its PDA, so closing it doesn't escape the slash.
+    pub fn slash_stake(ctx: Context<SlashStake>, _target: String, amount: u64) -> Result<()> {
+        let stake = &mut ctx.accounts.stake_account;
+        require!(amount > 0 && amount <= stake.amount, PostError::InvalidStakeAmount);
+
+        stake.amount -= amount;
+        **stake.to_account_info().try_borrow_mut_lamports()? -= amount;
+        **ctx.accounts.treasury.try_borrow_mut_lamports()? += amount;
+
+        emit!(StakeSlashed {
+            author: stake.author,
+            post: ctx.accounts.post.key(),
+            moderator: ctx.accounts.admin.key(),
+            amount,
+            remaining: stake.amount,
+        });
+
+        Ok(())
+    }
+
+    // ========================================================================
+    // VESTING (10% tranche)
+    // ========================================================================
+
+    // Route the 10% tranche to a vesting vault released linearly over `duration` seconds (admin only)
+    pub fn initialize_vesting(ctx: Context<InitializeVesting>, duration: i64) -> Result<()> {
+        require!(duration > 0, PostError::InvalidConfig);
+
+        let vault = &mut ctx.accounts.vesting_vault;
+        vault.beneficiary = WALLET_2;
+        vault.start = Clock::get()?.unix_timestamp;
+        vault.duration = duration;
+        vault.total_claimed = 0;
+        vault.bump = ctx.bumps.vesting_vault;
+
+        ctx.accounts.config.vesting_vault = vault.key();
+        Ok(())
+    }
+
+    // Release the vested part of everything the vault has received so far to the beneficiary.
+    // The schedule is fixed at initialize_vesting: every lamport received vests on the same
+    // `start` / `duration`, and each claim pays what has vested minus what was already claimed.
+    pub fn claim_vested(ctx: Context<ClaimVested>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let rent = Rent::get()?.minimum_balance(VestingVault::SIZE);
+        let vault_info = ctx.accounts.vesting_vault.to_account_info();
+        let vault = &mut ctx.accounts.vesting_vault;
+
+        let received = vault_info.lamports().saturating_sub(rent) + vault.total_claimed;
+        let amount = vault.claimable(received, now);
+        require!(amount > 0, PostError::NothingToClaim);
+
+        vault.total_claimed += amount;
+        **vault_info.try_borrow_mut_lamports()? -= amount;
+        **ctx.accounts.beneficiary.try_borrow_mut_lamports()? += amount;
+
+        emit!(VestedClaimed {
+            beneficiary: vault.beneficiary,
+            amount,
+            total_claimed: vault.total_claimed,
+        });
+
+        Ok(())
+    }
+
+    // Token bids pay their 10% tranche into a token account of the vesting vault. It vests on
+    // the same schedule as the lamports, claims being tracked per mint by its sub-treasury.
+    pub fn claim_vested_token(ctx: Context<ClaimVestedToken>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let mint_treasury = &mut ctx.accounts.mint_treasury;
+
+        let received = ctx.accounts.vault_token.amount + mint_treasury.vested_claimed;
+        let amount = ctx
+            .accounts
+            .vesting_vault
+            .vested_at(received, now)
+            .saturating_sub(mint_treasury.vested_claimed);
+        require!(amount > 0, PostError::NothingToClaim);
+        mint_treasury.vested_claimed += amount;
+
+        let seeds = &[VESTING_VAULT_SEED, &[ctx.accounts.vesting_vault.bump]];
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.vault_token.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.beneficiary_token.to_account_info(),
+                    authority: ctx.accounts.vesting_vault.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            amount,
+            ctx.accounts.mint.decimals,
+        )?;
+
+        emit!(VestedTokenClaimed {
+            beneficiary: ctx.accounts.vesting_vault.beneficiary,
+            mint: ctx.accounts.mint.key(),
+            amount,
+            total_claimed: ctx.accounts.mint_treasury.vested_claimed,
+        });
+
+        Ok(())
+    }
+
+    // ========================================================================
+    // TARGET REGISTRY & ESCROWED POSTS
+    // ========================================================================
+
+    // Record who owns a target (admin only, after off-chain verification). Handle targets
+    // ("@name") can only be claimed with prove_target_ownership.
+    pub fn register_target(ctx: Context<RegisterTarget>, target: String, owner: Pubkey) -> Result<()> {
+        validate_target(&target)?;
+        require!(!is_handle_target(&target), PostError::TargetProofRequired);
+
+        let target_account = &mut ctx.accounts.target_account;
+        target_account.target = target;
+        target_account.owner = owner;
+        target_account.pinned_post = Pubkey::default();
+        target_account.pinned_until = 0;
+        target_account.pin_fee = 0;
+        target_account.bump = ctx.bumps.target_account;
+        Ok(())
+    }
+
+    // Attest that `identity_key` belongs to the identity behind a handle target (admin only,
+    // after off-chain verification); replaces any previous attestation
+    pub fn attest_target_identity(ctx: Context<AttestTargetIdentity>, target: String, identity_key: Pubkey) -> Result<()> {
+        validate_target(&target)?;
+
+        let identity = &mut ctx.accounts.target_identity;
+        identity.identity_key = identity_key;
+        identity.attested_by = ctx.accounts.admin.key();
+        identity.attested_at = Clock::get()?.unix_timestamp;
+        identity.bump = ctx.bumps.target_identity;
+        Ok(())
+    }
+
+    // Claim a target for the signer with the attested identity key's Ed25519 signature over
+    // target_challenge(target, claimant), in the instruction right before this one. Takes the
+    // target over from any previous owner, so squatters can't keep someone else's name.
+    pub fn prove_target_ownership(ctx: Context<ProveTargetOwnership>, target: String) -> Result<()> {
+        let claimant = ctx.accounts.claimant.key();
+        reported(validate_target(&target), claimant, target.len() as u64)?;
+        let identity_key = ctx.accounts.target_identity.identity_key;
+        let challenge = target_challenge(&target, &claimant);
+        reported(
+            verify_ed25519_ix(&ctx.accounts.instructions_sysvar, &identity_key, challenge.as_bytes()),
+            claimant,
+            0,
+        )?;
+
+        let target_account = &mut ctx.accounts.target_account;
+        let previous_owner = target_account.owner;
+        if previous_owner != claimant {
+            // New owner: pins and pin fees set by a previous owner don't carry over
+            target_account.pinned_post = Pubkey::default();
+            target_account.pinned_until = 0;
+            target_account.pin_fee = 0;
+        }
+        target_account.target = target;
+        target_account.owner = claimant;
+        target_account.identity_key = identity_key;
+        target_account.verified_at = Clock::get()?.unix_timestamp;
+        target_account.bump = ctx.bumps.target_account;
+
+        emit!(TargetOwnershipProven {
+            target_account: target_account.key(),
+            owner: claimant,
+            previous_owner,
+            identity_key,
+        });
+
+        Ok(())
+    }
+
+    // Grow a target account to the current layout (permissionless, the payer covers the rent)
+    pub fn migrate_target(ctx: Context<MigrateTarget>) -> Result<()> {
+        let payer = ctx.accounts.payer.key();
+        let target_info = ctx.accounts.target_account.to_account_info();
+        require_keys_eq!(*target_info.owner, crate::ID, PostError::InvalidPost);
+        require_reported!(
+            target_info.data_len() >= 8 && target_info.try_borrow_data()?[..8] == *TargetAccount::DISCRIMINATOR,
+            PostError::InvalidPost,
+            payer,
+            target_info.data_len() as u64,
+        );
+        require_reported!(
+            target_info.data_len() < TargetAccount::SIZE,
+            PostError::AlreadyMigrated,
+            payer,
+            target_info.data_len() as u64,
+        );
+
+        // New bytes are zeroed: unverified
+        let rent = Rent::get()?.minimum_balance(TargetAccount::SIZE);
+        let top_up = rent.saturating_sub(target_info.lamports());
+        if top_up > 0 {
+            transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.payer.to_account_info(),
+                        to: target_info.clone(),
+                    },
+                ),
+                top_up,
+            )?;
+        }
+        target_info.resize(TargetAccount::SIZE)?;
+        Ok(())
+    }
+
+    // Create a post whose bid sits in escrow until the target owner accepts it.
+    // If it isn't accepted within the escrow window the author can reclaim the bid minus the keep fee.
+    pub fn create_post_escrowed(ctx: Context<CreatePostEscrowed>, target: String, content: String, bid: u64) -> Result<()> {
+        let author = ctx.accounts.author.key();
+        let min_bid = min_bid_lamports(&ctx.accounts.config, &ctx.accounts.price_update)?;
+        require_reported!(bid >= min_bid, PostError::BidTooLow, author, bid);
+        let content = reported(validate_post_fields(&target, &content), author, content.len() as u64)?;
+        let waived = rate_waived(&ctx.accounts.config, bid, &ctx.accounts.price_update)?;
+        enforce_author_policy(
+            &ctx.accounts.config,
+            &ctx.accounts.stake_account,
+            &mut ctx.accounts.author_stats,
+            ctx.bumps.author_stats,
+            author,
+            waived,
+        )?;
+
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.author.to_account_info(),
+                    to: ctx.accounts.escrow.to_account_info(),
+                },
+            ),
+            bid,
+        )?;
+
+        let now = Clock::get()?.unix_timestamp;
+
+        let escrow = &mut ctx.accounts.escrow;
+        escrow.post = ctx.accounts.post.key();
+        escrow.author = ctx.accounts.author.key();
+        escrow.target_account = ctx.accounts.target_account.key();
+        escrow.amount = bid;
+        escrow.deadline = now + ctx.accounts.config.escrow_window_secs;
+        escrow.bump = ctx.bumps.escrow;
+
+        let post = &mut ctx.accounts.post;
+        post.author = ctx.accounts.author.key();
+        post.target = target;
+        post.content = content;
+        post.bid = bid;
+        post.timestamp = now;
+        post.bump = ctx.bumps.post;
+        post.version = POST_VERSION;
+        post.refresh_rank(now);
+
+        let post_key = ctx.accounts.post.key();
+        ctx.accounts.author_index.record_post(ctx.accounts.author.key(), post_key, ctx.bumps.author_index);
+        emit!(PostCreated {
+            post: post_key,
+            author: ctx.accounts.post.author,
+            target: ctx.accounts.post.target.clone(),
+            bid: ctx.accounts.post.bid,
+            bid_mint: ctx.accounts.post.bid_mint,
+            timestamp: ctx.accounts.post.timestamp,
+        });
+
+        count(&mut ctx.accounts.metrics, |metrics| &mut metrics.posts_created);
+        Ok(())
+    }
+
+    // Accept an escrowed post (target owner only) before its deadline: the bid is released to
+    // the treasury and split. From the deadline on the offer has lapsed and only the author can
+    // reclaim it.
+    pub fn accept_post(ctx: Context<AcceptPost>) -> Result<()> {
+        let amount = ctx.accounts.escrow.amount;
+        require_reported!(
+            Clock::get()?.unix_timestamp < ctx.accounts.escrow.deadline,
+            PostError::EscrowExpired,
+            ctx.accounts.owner.key(),
+            amount,
+        );
+
+        **ctx.accounts.escrow.to_account_info().try_borrow_mut_lamports()? -= amount;
+        **ctx.accounts.treasury.try_borrow_mut_lamports()? += amount;
+
+        let distributed = distribute_treasury(
+            &ctx.accounts.treasury,
+            &ctx.accounts.wallet_1,
+            &ctx.accounts.wallet_2,
+            &ctx.accounts.wallet_3,
+            &ctx.accounts.system_program,
+            ctx.bumps.treasury,
+            ctx.accounts.config.treasury_version,
+        )?;
+
+        ctx.accounts.revenue_snapshot.record(ctx.bumps.revenue_snapshot, amount, distributed)?;
+
+        emit!(PostAccepted {
+            post: ctx.accounts.escrow.post,
+            owner: ctx.accounts.owner.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+
+    // Reclaim an unaccepted escrowed bid after the deadline (author only), minus the keep fee
+    pub fn reclaim_escrow(ctx: Context<ReclaimEscrow>) -> Result<()> {
+        let escrow = &ctx.accounts.escrow;
+        require_reported!(
+            Clock::get()?.unix_timestamp >= escrow.deadline,
+            PostError::EscrowStillOpen,
+            ctx.accounts.author.key(),
+            escrow.amount,
+        );
+
+        let fee = bps_share(escrow.amount, ctx.accounts.config.escrow_keep_fee_bps);
+        **ctx.accounts.escrow.to_account_info().try_borrow_mut_lamports()? -= fee;
+        **ctx.accounts.treasury.try_borrow_mut_lamports()? += fee;
+
+        emit!(EscrowReclaimed {
+            post: ctx.accounts.escrow.post,
+            author: ctx.accounts.author.key(),
+            refunded: ctx.accounts.escrow.amount - fee,
+            fee,
+        });
+
+        // The rest of the bid and the escrow rent go back to the author on close
+        Ok(())
+    }
+
+    // Reply to a post as the verified owner of its target (one official reply per post)
+    pub fn post_official_reply(ctx: Context<PostOfficialReply>, content: String) -> Result<()> {
+        let owner = ctx.accounts.owner.key();
+        require_reported!(ctx.accounts.post.version >= POST_VERSION, PostError::PostNotMigrated, owner, 0);
+        let content = reported(validate_post_fields(&ctx.accounts.post.target, &content), owner, content.len() as u64)?;
+        let now = Clock::get()?.unix_timestamp;
+
+        let reply = &mut ctx.accounts.official_reply;
+        reply.post = ctx.accounts.post.key();
+        reply.owner = ctx.accounts.owner.key();
+        reply.content = content;
+        reply.timestamp = now;
+        reply.bump = ctx.bumps.official_reply;
+
+        ctx.accounts.post.flags |= POST_FLAG_OFFICIAL_REPLY;
+
+        emit!(OfficialReplyPosted {
+            post: reply.post,
+            reply: reply.key(),
+            owner: reply.owner,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    // Set the fee charged to an author whose post gets pinned (target owner only, 0 = free)
+    pub fn set_pin_fee(ctx: Context<UpdateTarget>, pin_fee: u64) -> Result<()> {
+        ctx.accounts.target_account.pin_fee = pin_fee;
+        Ok(())
+    }
+
+    // Pin a post on its target page (target owner only), replacing any current pin.
+    // When the target charges a pin fee, the post author co-signs and pays it through the split.
+    // duration = 0 pins until unpinned.
+    pub fn pin_post(ctx: Context<PinPost>, duration: i64) -> Result<()> {
+        require_reported!(duration >= 0, PostError::InvalidConfig, ctx.accounts.owner.key(), 0);
+
+        let fee = ctx.accounts.target_account.pin_fee;
+        if fee > 0 {
+            let author = ctx.accounts.author.as_ref().ok_or(PostError::Unauthorized)?;
+            require_keys_eq!(author.key(), ctx.accounts.post.author, PostError::Unauthorized);
+
+            transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    Transfer {
+                        from: author.to_account_info(),
+                        to: ctx.accounts.treasury.to_account_info(),
+                    },
+                ),
+                fee,
+            )?;
+
+            distribute_treasury(
+                &ctx.accounts.treasury,
+                &ctx.accounts.wallet_1,
+                &ctx.accounts.wallet_2,
+                &ctx.accounts.wallet_3,
+                &ctx.accounts.system_program,
+                ctx.bumps.treasury,
+                ctx.accounts.config.treasury_version,
+            )?;
+        }
+
+        let now = Clock::get()?.unix_timestamp;
+        let target_account = &mut ctx.accounts.target_account;
+        target_account.pinned_post = ctx.accounts.post.key();
+        target_account.pinned_until = if duration == 0 { 0 } else { now + duration };
+
+        emit!(PostPinned {
+            target: target_account.key(),
+            post: target_account.pinned_post,
+            fee,
+            pinned_until: target_account.pinned_until,
+        });
+
+        Ok(())
+    }
+
+    // Clear the pin: the target owner at any time, anyone once it has expired
+    pub fn unpin_post(ctx: Context<UnpinPost>) -> Result<()> {
+        let target_account = &mut ctx.accounts.target_account;
+        let expired = target_account.pinned_until != 0
+            && Clock::get()?.unix_timestamp >= target_account.pinned_until;
+        let caller = ctx.accounts.caller.key();
+        require_reported!(expired || caller == target_account.owner, PostError::Unauthorized, caller, 0);
+
+        target_account.pinned_post = Pubkey::default();
+        target_account.pinned_until = 0;
+        Ok(())
+    }
+
+    // ========================================================================
+    // MULTI-MINT TREASURIES (wSOL, stablecoins)
+    // ========================================================================
+
+    // Accept bids in a new mint through its own sub-treasury (admin only).
+    // min_bid is in the mint's base units.
+    pub fn initialize_mint_treasury(ctx: Context<InitializeMintTreasury>, min_bid: u64) -> Result<()> {
+        require!(min_bid > 0, PostError::InvalidConfig);
+
+        let mint_treasury = &mut ctx.accounts.mint_treasury;
+        mint_treasury.mint = ctx.accounts.mint.key();
+        mint_treasury.vault = ctx.accounts.vault.key();
+        mint_treasury.min_bid = min_bid;
+        mint_treasury.total_received = 0;
+        mint_treasury.total_distributed = 0;
+        mint_treasury.post_count = 0;
+        mint_treasury.vested_claimed = 0;
+        mint_treasury.bump = ctx.bumps.mint_treasury;
+        Ok(())
+    }
+
+    // Update a sub-treasury's minimum bid (admin only)
+    pub fn set_mint_min_bid(ctx: Context<UpdateMintTreasury>, min_bid: u64) -> Result<()> {
+        require!(min_bid > 0, PostError::InvalidConfig);
+
+        ctx.accounts.mint_treasury.min_bid = min_bid;
+        Ok(())
+    }
+
+    // Create a post paid in an SPL token; the bid goes through the mint's sub-treasury split
+    pub fn create_post_token(ctx: Context<CreatePostToken>, target: String, content: String, bid: u64) -> Result<()> {
+        let author = ctx.accounts.author.key();
+        require_reported!(bid >= ctx.accounts.mint_treasury.min_bid, PostError::BidTooLow, author, bid);
+        let content = reported(validate_post_fields(&target, &content), author, content.len() as u64)?;
+        // Token bids have no USD peg, so they never waive the rate limit
+        enforce_author_policy(
+            &ctx.accounts.config,
+            &ctx.accounts.stake_account,
+            &mut ctx.accounts.author_stats,
+            ctx.bumps.author_stats,
+            author,
+            false,
+        )?;
+
+        token_interface::transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.author_token.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                    authority: ctx.accounts.author.to_account_info(),
+                },
+            ),
+            bid,
+            ctx.accounts.mint.decimals,
+        )?;
+        ctx.accounts.vault.reload()?;
+
+        let distributed = distribute_mint_treasury(
+            &ctx.accounts.mint_treasury,
+            &ctx.accounts.vault,
+            &ctx.accounts.mint,
+            &ctx.accounts.wallet_1,
+            &ctx.accounts.wallet_2,
+            &ctx.accounts.wallet_3,
+            &ctx.accounts.token_program,
+        )?;
+
+        let mint_treasury = &mut ctx.accounts.mint_treasury;
+        mint_treasury.total_received += bid;
+        mint_treasury.total_distributed += distributed;
+        mint_treasury.post_count += 1;
+
+        let now = Clock::get()?.unix_timestamp;
+        let post = &mut ctx.accounts.post;
+        post.author = ctx.accounts.author.key();
+        post.target = target;
+        post.content = content;
+        post.bid = bid;
+        post.timestamp = now;
+        post.bump = ctx.bumps.post;
+        post.version = POST_VERSION;
+        post.bid_mint = ctx.accounts.mint.key();
+        post.refresh_rank(now);
+
+        let post_key = ctx.accounts.post.key();
+        ctx.accounts.author_index.record_post(ctx.accounts.author.key(), post_key, ctx.bumps.author_index);
+        emit!(PostCreated {
+            post: post_key,
+            author: ctx.accounts.post.author,
+            target: ctx.accounts.post.target.clone(),
+            bid: ctx.accounts.post.bid,
+            bid_mint: ctx.accounts.post.bid_mint,
+            timestamp: ctx.accounts.post.timestamp,
+        });
+
+        count(&mut ctx.accounts.metrics, |metrics| &mut metrics.posts_created);
+        Ok(())
+    }
+
+    // ========================================================================
+    // RECURRING SPONSORSHIPS
+    // ========================================================================
+
+    // Pre-fund `periods` periods of a promoted post on `target`. The post is authored by the
+    // sponsorship PDA and created/refreshed by process_sponsorship_period.
+    pub fn create_sponsorship(
+        ctx: Context<CreateSponsorship>,
+        target: String,
+        content: String,
+        bid_per_period: u64,
+        period_secs: i64,
+        periods: u32,
+    ) -> Result<()> {
+        let min_bid = min_bid_lamports(&ctx.accounts.config, &ctx.accounts.price_update)?;
+        let sponsor = ctx.accounts.sponsor.key();
+        require_reported!(bid_per_period >= min_bid, PostError::BidTooLow, sponsor, bid_per_period);
+        require_reported!(period_secs > 0 && periods > 0, PostError::InvalidConfig, sponsor, periods as u64);
+        let content = reported(validate_post_fields(&target, &content), sponsor, content.len() as u64)?;
+
+        let total = bid_per_period.checked_mul(periods as u64).ok_or(PostError::InvalidConfig)?;
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.sponsor.to_account_info(),
+                    to: ctx.accounts.sponsorship.to_account_info(),
+                },
+            ),
+            total,
+        )?;
+
+        let sponsorship = &mut ctx.accounts.sponsorship;
+        sponsorship.sponsor = ctx.accounts.sponsor.key();
+        sponsorship.target = target;
+        sponsorship.content = content;
+        sponsorship.bid_per_period = bid_per_period;
+        sponsorship.period_secs = period_secs;
+        sponsorship.periods_remaining = periods;
+        sponsorship.next_period_at = Clock::get()?.unix_timestamp;
+        sponsorship.bump = ctx.bumps.sponsorship;
+        Ok(())
+    }
+
+    // Pay out the current period of a sponsorship and create/refresh its post (permissionless crank)
+    pub fn process_sponsorship_period(ctx: Context<ProcessSponsorshipPeriod>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let cranker = ctx.accounts.cranker.key();
+        let sponsorship = &mut ctx.accounts.sponsorship;
+        require_reported!(sponsorship.periods_remaining > 0, PostError::SponsorshipExhausted, cranker, 0);
+        require_reported!(now >= sponsorship.next_period_at, PostError::PeriodNotDue, cranker, 0);
+        // The sponsor is the real author of every period's post (no price feed, so no waiver)
+        enforce_author_policy(
+            &ctx.accounts.config,
+            &ctx.accounts.stake_account,
+            &mut ctx.accounts.author_stats,
+            ctx.bumps.author_stats,
+            sponsorship.sponsor,
+            false,
+        )?;
+
+        let bid = sponsorship.bid_per_period;
+        sponsorship.periods_remaining -= 1;
+        sponsorship.next_period_at = now + sponsorship.period_secs;
+
+        **sponsorship.to_account_info().try_borrow_mut_lamports()? -= bid;
+        **ctx.accounts.treasury.try_borrow_mut_lamports()? += bid;
+
+        let distributed = distribute_treasury(
+            &ctx.accounts.treasury,
+            &ctx.accounts.wallet_1,
+            &ctx.accounts.wallet_2,
+            &ctx.accounts.wallet_3,
+            &ctx.accounts.system_program,
+            ctx.bumps.treasury,
+            ctx.accounts.config.treasury_version,
+        )?;
+
+        ctx.accounts.revenue_snapshot.record(ctx.bumps.revenue_snapshot, bid, distributed)?;
+
+        // Each period refreshes the sponsorship's single post: the engagement counters restart so
+        // the new period ranks on its own bid. Flags are kept, they mirror per-post records (like
+        // the official reply) that outlive the period, and upvote receipts still allow one upvote
+        // per voter over the whole sponsorship.
+        let sponsorship = &ctx.accounts.sponsorship;
+        let post = &mut ctx.accounts.post;
+        post.author = sponsorship.key();
+        post.target = sponsorship.target.clone();
+        post.content = sponsorship.content.clone();
+        post.bid = bid;
+        post.timestamp = now;
+        post.bump = ctx.bumps.post;
+        post.version = POST_VERSION;
+        post.total_boosts = 0;
+        post.upvotes = 0;
+        post.views = 0;
+        post.refresh_rank(now);
+
+        emit!(PostCreated {
+            post: post.key(),
+            author: post.author,
+            target: post.target.clone(),
+            bid,
+            bid_mint: post.bid_mint,
+            timestamp: now,
+        });
+
+        emit!(SponsorshipPeriodProcessed {
+            sponsorship: sponsorship.key(),
+            post: post.key(),
+            amount: bid,
+            periods_remaining: sponsorship.periods_remaining,
+        });
+
+        count(&mut ctx.accounts.metrics, |metrics| &mut metrics.posts_created);
+        Ok(())
+    }
+
+    // Cancel a sponsorship (sponsor only): unspent periods and rent are refunded on close.
+    // Its post, once a period has created it, would keep pointing at the closed sponsorship:
+    // it is handed to the sponsor, who can then close it.
+    pub fn cancel_sponsorship(ctx: Context<CancelSponsorship>) -> Result<()> {
+        let post_info = &ctx.accounts.post;
+        if !post_info.data_is_empty() {
+            let mut post = Post::try_deserialize(&mut &post_info.try_borrow_data()?[..])?;
+            post.author = ctx.accounts.sponsor.key();
+            post.try_serialize(&mut &mut post_info.try_borrow_mut_data()?[..])?;
+        }
+        Ok(())
+    }
+
+    // ========================================================================
+    // SCHEDULED POSTS
+    // ========================================================================
+
+    // Queue a post for publication at `publish_at`. The bid, the crank reward and the post rent
+    // are escrowed in the scheduled post account until publish_scheduled_post runs.
+    pub fn schedule_post(
+        ctx: Context<SchedulePost>,
+        target: String,
+        content: String,
+        bid: u64,
+        publish_at: i64,
+    ) -> Result<()> {
+        let min_bid = min_bid_lamports(&ctx.accounts.config, &ctx.accounts.price_update)?;
+        let author = ctx.accounts.author.key();
+        require_reported!(bid >= min_bid, PostError::BidTooLow, author, bid);
+        require_reported!(publish_at > Clock::get()?.unix_timestamp, PostError::InvalidConfig, author, 0);
+        let content = reported(validate_post_fields(&target, &content), author, content.len() as u64)?;
+        // Checked when the post is queued, while the author signs
+        let waived = rate_waived(&ctx.accounts.config, bid, &ctx.accounts.price_update)?;
+        enforce_author_policy(
+            &ctx.accounts.config,
+            &ctx.accounts.stake_account,
+            &mut ctx.accounts.author_stats,
+            ctx.bumps.author_stats,
+            author,
+            waived,
+        )?;
+
+        let post_rent = Rent::get()?.minimum_balance(Post::SIZE);
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.author.to_account_info(),
+                    to: ctx.accounts.scheduled_post.to_account_info(),
+                },
+            ),
+            bid + SCHEDULE_CRANK_REWARD + post_rent,
+        )?;
+
+        let scheduled = &mut ctx.accounts.scheduled_post;
+        scheduled.author = ctx.accounts.author.key();
+        scheduled.target = target;
+        scheduled.content = content;
+        scheduled.bid = bid;
+        scheduled.publish_at = publish_at;
+        scheduled.bump = ctx.bumps.scheduled_post;
+        Ok(())
+    }
+
+    // Publish a scheduled post once its time has come (permissionless crank, rewarded).
+    // The escrow pays the bid, the post rent and the reward, then closes back to the author.
+    pub fn publish_scheduled_post(ctx: Context<PublishScheduledPost>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let scheduled = &ctx.accounts.scheduled_post;
+        require_reported!(now >= scheduled.publish_at, PostError::NotYetPublishable, ctx.accounts.cranker.key(), 0);
+
+        let bid = scheduled.bid;
+        let post_rent = ctx.accounts.post.to_account_info().lamports();
+        // An index that never recorded a post was just created by the cranker. Its rent comes out
+        // of the escrow's own rent (a ScheduledPost is larger than an AuthorIndex).
+        let index_rent = if ctx.accounts.author_index.author == Pubkey::default() {
+            ctx.accounts.author_index.to_account_info().lamports()
+        } else {
+            0
+        };
+        let fronted = post_rent + index_rent;
+        let escrow_info = ctx.accounts.scheduled_post.to_account_info();
+        **escrow_info.try_borrow_mut_lamports()? -= bid + fronted + SCHEDULE_CRANK_REWARD;
+        **ctx.accounts.treasury.try_borrow_mut_lamports()? += bid;
+        // Reimburse the rent the cranker fronted for the post (and index) and pay the reward
+        **ctx.accounts.cranker.to_account_info().try_borrow_mut_lamports()? += fronted + SCHEDULE_CRANK_REWARD;
+
+        let distributed = distribute_treasury(
+            &ctx.accounts.treasury,
+            &ctx.accounts.wallet_1,
+            &ctx.accounts.wallet_2,
+            &ctx.accounts.wallet_3,
+            &ctx.accounts.system_program,
+            ctx.bumps.treasury,
+            ctx.accounts.config.treasury_version,
+        )?;
+
+        ctx.accounts.revenue_snapshot.record(ctx.bumps.revenue_snapshot, bid, distributed)?;
+
+        let scheduled = &ctx.accounts.scheduled_post;
+        let post = &mut ctx.accounts.post;
+        post.author = scheduled.author;
+        post.target = scheduled.target.clone();
+        post.content = scheduled.content.clone();
+        post.bid = bid;
+        post.timestamp = now;
+        post.bump = ctx.bumps.post;
+        post.version = POST_VERSION;
+        post.refresh_rank(now);
+
+        emit!(PostCreated {
+            post: post.key(),
+            author: post.author,
+            target: post.target.clone(),
+            bid,
+            bid_mint: post.bid_mint,
+            timestamp: now,
+        });
+
+        let (author, post_key) = (post.author, post.key());
+        ctx.accounts.author_index.record_post(author, post_key, ctx.bumps.author_index);
+
+        count(&mut ctx.accounts.metrics, |metrics| &mut metrics.posts_created);
+        Ok(())
+    }
+
+    // Cancel a scheduled post before publication (author only); everything is refunded on close
+    pub fn cancel_scheduled_post(_ctx: Context<CancelScheduledPost>) -> Result<()> {
+        Ok(())
+    }
+
+    // ========================================================================
+    // USAGE METRICS
+    // ========================================================================
+
+    // Create the global usage counters (permissionless, once). Instructions that get the
+    // metrics account as their trailing optional account bump its counters.
+    pub fn initialize_metrics(ctx: Context<InitializeMetrics>) -> Result<()> {
+        ctx.accounts.metrics.bump = ctx.bumps.metrics;
+        Ok(())
+    }
+
+    // Read-only view of the counters (simulate the transaction to get the return data)
+    pub fn get_metrics(ctx: Context<GetMetrics>) -> Result<MetricsView> {
+        Ok(MetricsView::of(&ctx.accounts.metrics))
+    }
+
+    // Devnet tools: fund the treasury and create sample posts in one call (admin only,
+    // `devnet-tools` builds only)
+    #[cfg(feature = "devnet-tools")]
+    pub fn devnet_seed<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DevnetSeed<'info>>,
+        treasury_lamports: u64,
+        posts: Vec<DemoPost>,
+    ) -> Result<()> {
+        devnet_tools::seed(ctx, treasury_lamports, posts)
+    }
+}
+
+// Targets are part of PDA seeds, so they must already be in canonical form
+// (trimmed, lowercase, no whitespace or control characters); limits are in bytes.
+pub fn validate_target(target: &str) -> Result<()> {
+    require!(!target.is_empty(), PostError::TargetEmpty);
+    require!(target.len() <= MAX_TARGET_LEN, PostError::TargetTooLong);
+    require!(
+        !target.chars().any(|c| c.is_whitespace() || c.is_control()),
+        PostError::InvalidCharacters
+    );
+    require!(target == target.to_lowercase(), PostError::TargetNotNormalized);
+    Ok(())
+}
+
+// Handle-like targets ("@name") name an identity and need an ownership proof
+pub fn is_handle_target(target: &str) -> bool {
+    target.starts_with('@')
+}
+
+// Challenge the attested identity key signs to claim `target` for `claimant`
+pub fn target_challenge(target: &str, claimant: &Pubkey) -> String {
+    format!("X-RAY target ownership: {target} is claimed by {claimant} on {}", crate::ID)
+}
+
+// With the `telemetry` feature, emit InstructionFailed before a validation failure so
+// operators can follow failure rates by cause (the event stays in the failed
+// transaction's logs); codes are registered in xray_common::errors
+fn report_failure(code: u32, signer: Pubkey, context: u64) {
+    #[cfg(feature = "telemetry")]
+    emit!(InstructionFailed {
+        code,
+        program: crate::ID,
+        signer,
+        context,
+        timestamp: Clock::get().map_or(0, |clock| clock.unix_timestamp),
+    });
+    #[cfg(not(feature = "telemetry"))]
+    let _ = (code, signer, context);
+}
+
+// Pass `result` through, reporting its error (see report_failure)
+fn reported<T>(result: Result<T>, signer: Pubkey, context: u64) -> Result<T> {
+    if let Err(Error::AnchorError(err)) = &result {
+        report_failure(err.error_code_number, signer, context);
+    }
+    result
+}
+
+// Bump one usage counter, when the caller passed the metrics account
+fn count(metrics: &mut Option<Account<PostMetrics>>, counter: impl FnOnce(&mut PostMetrics) -> &mut u64) {
+    if let Some(metrics) = metrics {
+        let value = counter(metrics);
+        *value = value.saturating_add(1);
+    }
+}
+
+// Callback accounts of a queued computation, plus the metrics account when the caller
+// passed it, so the callback can count the completion
+// An auction callback only applies to the computation the auction is waiting on: a late
+// callback (after reset_auction_pending) or one for an earlier computation is rejected
+fn require_current_computation(
+    auction: &Auction,
+    computation_account: &AccountInfo,
+    mxe_account: &MXEAccount,
+) -> Result<()> {
+    require!(
+        auction.computation_pending
+            && computation_account.key()
+                == derive_comp_pda!(auction.pending_computation, mxe_account, PostError::ClusterNotSet),
+        PostError::StaleCallback
+    );
+    Ok(())
+}
+
+fn with_metrics(mut accounts: Vec<CallbackAccount>, metrics: &Option<Account<PostMetrics>>) -> Vec<CallbackAccount> {
+    if let Some(metrics) = metrics {
+        accounts.push(CallbackAccount {
+            pubkey: metrics.key(),
+            is_writable: true,
+        });
+    }
+    accounts
+}
+
+// Validate a post's target and content, returning the trimmed content
+pub fn validate_post_fields(target: &str, content: &str) -> Result<String> {
+    validate_target(target)?;
+
+    let content = content.trim();
+    require!(!content.is_empty(), PostError::ContentEmpty);
+    require!(content.len() <= MAX_CONTENT_LEN, PostError::ContentTooLong);
+    require!(
+        !content.chars().any(|c| c.is_control() && c != '\n' && c != '\t'),
+        PostError::InvalidCharacters
+    );
+
+    Ok(content.to_string())
+}
+
+// Whether a bid clears the USD rate limit waiver
+pub fn rate_waived(config: &Config, bid: u64, price_update: &PriceUpdateV2) -> Result<bool> {
+    Ok(config.rate_waiver_bid_usd > 0 && bid >= pegged_lamports(config.rate_waiver_bid_usd, price_update)?)
+}
+
+// Author checks shared by every post creation path: the per-author rate limit (skipped when
+// `waived`) and, in stake-to-post mode, a locked stake. Records the post in the author's stats.
+pub fn enforce_author_policy(
+    config: &Config,
+    stake: &Option<Box<Account<StakeAccount>>>,
+    stats: &mut AuthorStats,
+    stats_bump: u8,
+    author: Pubkey,
+    waived: bool,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    stats.author = author;
+    stats.bump = stats_bump;
+    if now - stats.window_start >= config.rate_window_secs {
+        stats.window_start = now;
+        stats.window_posts = 0;
+    }
+    require_reported!(
+        config.max_posts_per_window == 0 || waived || stats.window_posts < config.max_posts_per_window,
+        PostError::RateLimited,
+        author,
+        stats.window_posts as u64,
+    );
+    stats.window_posts += 1;
+    stats.total_posts += 1;
+
+    if config.min_stake > 0 {
+        let stake = stake.as_deref();
+        let locked = stake.is_some_and(|stake| {
+            stake.author == author && stake.amount >= config.min_stake && stake.unlock_requested_at == 0
+        });
+        require_reported!(locked, PostError::StakeRequired, author, stake.map_or(0, |stake| stake.amount));
+    }
+    Ok(())
+}
+
+// Time-decayed ranking score: the weight (bid + boosts, in lamports) halves every
+// RANK_HALF_LIFE seconds, interpolated linearly within each half-life.
+pub fn rank_score(weight: u64, age: i64) -> u64 {
+    let age = age.max(0);
+    let halvings = age / RANK_HALF_LIFE;
+    if halvings >= 64 {
+        return 0;
+    }
+    let base = weight >> halvings;
+    let elapsed = (age % RANK_HALF_LIFE) as u128;
+    let decay = (base as u128 / 2) * elapsed / RANK_HALF_LIFE as u128;
+    base - decay as u64
+}
+
+// Part of `refund` released `now` by a linear stream from `start` over `duration` (0 = all at once)
+pub fn released_refund(refund: u64, start: i64, duration: i64, now: i64) -> u64 {
+    if duration <= 0 {
+        return refund;
+    }
+    let elapsed = now.saturating_sub(start).clamp(0, duration) as u128;
+    (refund as u128 * elapsed / duration as u128) as u64
+}
+
+// Minimum bid in lamports, pegged to USD via the Pyth SOL/USD price
+pub fn min_bid_lamports(config: &Config, price_update: &PriceUpdateV2) -> Result<u64> {
+    pegged_lamports(config.min_bid_usd, price_update)
+}
+
+// Convert a micro-USD amount to lamports at the current Pyth SOL/USD price
+pub fn pegged_lamports(usd: u64, price_update: &PriceUpdateV2) -> Result<u64> {
+    let feed_id = get_feed_id_from_hex(SOL_USD_FEED_ID)?;
+    let price = price_update.get_price_no_older_than(&Clock::get()?, MAX_PRICE_AGE, &feed_id)?;
+    usd_to_lamports(usd, price.price, price.exponent)
+}
+
+// Convert a micro-USD amount to lamports using a Pyth price (price * 10^exponent USD per SOL)
+pub fn usd_to_lamports(usd: u64, price: i64, exponent: i32) -> Result<u64> {
+    require!(price > 0, PostError::InvalidPrice);
+
+    // lamports = usd / 10^USD_DECIMALS / (price * 10^exponent) * 10^9
+    let mut numerator = usd as u128 * 1_000_000_000;
+    let mut denominator = price as u128 * 10u128.pow(USD_DECIMALS);
+    if exponent < 0 {
+        numerator = numerator
+            .checked_mul(10u128.pow(exponent.unsigned_abs()))
+            .ok_or(PostError::InvalidPrice)?;
+    } else {
+        denominator = denominator
+            .checked_mul(10u128.pow(exponent as u32))
+            .ok_or(PostError::InvalidPrice)?;
+    }
+
+    u64::try_from(numerator / denominator).map_err(|_| PostError::InvalidPrice.into())
+}
+
+// Message a burner key signs to authorize a relayed post:
+// sha256(domain || program id || author || target || 0x00 || content || bid || nonce)
+pub fn relayed_post_message(author: &Pubkey, target: &str, content: &str, bid: u64, nonce: u64) -> [u8; 32] {
+    hashv(&[
+        RELAYED_POST_DOMAIN,
+        crate::ID.as_ref(),
+        author.as_ref(),
+        target.as_bytes(),
+        &[0],
+        content.as_bytes(),
+        &bid.to_le_bytes(),
+        &nonce.to_le_bytes(),
+    ])
+    .to_bytes()
+}
+
+// Check that the instruction right before this one is an Ed25519 program instruction
+// verifying exactly one signature by `signer` over `message`, with all data inline.
+// Returns the verified signature.
+pub fn verify_ed25519_ix(instructions_sysvar: &AccountInfo, signer: &Pubkey, message: &[u8]) -> Result<[u8; 64]> {
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+    require!(current_index > 0, PostError::InvalidSignature);
+    let ix = load_instruction_at_checked(current_index as usize - 1, instructions_sysvar)?;
+    require!(ix.program_id == ed25519_program::ID, PostError::InvalidSignature);
+    require!(ix.accounts.is_empty(), PostError::InvalidSignature);
+
+    // Header: num_signatures (u8), padding (u8), then one offsets struct of 7 u16
+    let data = &ix.data;
+    require!(data.len() >= 16 && data[0] == 1, PostError::InvalidSignature);
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]);
+    let signature_offset = read_u16(2) as usize;
+    let public_key_offset = read_u16(6) as usize;
+    let message_offset = read_u16(10) as usize;
+    let message_size = read_u16(12) as usize;
+
+    // Signature, public key and message must all live in the Ed25519 instruction itself
+    for index_at in [4, 8, 14] {
+        require!(read_u16(index_at) == u16::MAX, PostError::InvalidSignature);
+    }
+
+    let public_key = data.get(public_key_offset..public_key_offset + 32).ok_or(PostError::InvalidSignature)?;
+    let signed_message = data.get(message_offset..message_offset + message_size).ok_or(PostError::InvalidSignature)?;
+    let signature = data.get(signature_offset..signature_offset + 64).ok_or(PostError::InvalidSignature)?;
+    require!(public_key == signer.as_ref(), PostError::InvalidSignature);
+    require!(signed_message == message, PostError::InvalidSignature);
+    Ok(signature.try_into().unwrap())
+}
+
+// Message the author signs for Post::content_signature
+pub fn content_hash(content: &str) -> [u8; 32] {
+    hashv(&[content.as_bytes()]).to_bytes()
+}
+
+// Wallet stored in a private_messages UserAccount (discriminator + wallet + ...)
+pub fn messaging_user_wallet(user_account: &AccountInfo) -> Result<Pubkey> {
+    require!(user_account.owner == &PRIVATE_MESSAGES_PROGRAM_ID, PostError::InvalidRecipient);
+
+    let data = user_account.try_borrow_data()?;
+    let discriminator = hashv(&[b"account:UserAccount"]).to_bytes();
+    require!(data.len() >= 40 && data[..8] == discriminator[..8], PostError::InvalidRecipient);
+    Ok(Pubkey::try_from(&data[8..40]).unwrap())
+}
+
+// CPI into private_messages::send_message (accounts: sender, recipient_user, message_account, system_program)
+pub fn send_message_cpi<'info>(
+    sender: &AccountInfo<'info>,
+    recipient_user: &AccountInfo<'info>,
+    message_account: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    private_messages_program: &AccountInfo<'info>,
+    encrypted_content: Vec<u8>,
+    nonce: [u8; 24],
+) -> Result<()> {
+    let mut data = hashv(&[b"global:send_message"]).to_bytes()[..8].to_vec();
+    encrypted_content.serialize(&mut data)?;
+    nonce.serialize(&mut data)?;
+
+    invoke(
+        &Instruction {
+            program_id: PRIVATE_MESSAGES_PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new(sender.key(), true),
+                AccountMeta::new(recipient_user.key(), false),
+                AccountMeta::new(message_account.key(), false),
+                AccountMeta::new_readonly(system_program.key(), false),
+            ],
+            data,
+        },
+        &[
+            sender.to_account_info(),
+            recipient_user.to_account_info(),
+            message_account.to_account_info(),
+            system_program.to_account_info(),
+            private_messages_program.to_account_info(),
+        ],
+    )?;
+    Ok(())
+}
+
+// Bubblegum MetadataArgs as used for post receipts (no collection, uses or creators)
+#[derive(AnchorSerialize)]
+struct ReceiptMetadataArgs {
+    name: String,
+    symbol: String,
+    uri: String,
+    seller_fee_basis_points: u16,
+    primary_sale_happened: bool,
+    is_mutable: bool,
+    edition_nonce: Option<u8>,
+    // TokenStandard::NonFungible = 0
+    token_standard: Option<u8>,
+    collection: Option<(bool, Pubkey)>,
+    uses: Option<(u8, u64, u64)>,
+    // TokenProgramVersion::Original = 0
+    token_program_version: u8,
+    creators: Vec<(Pubkey, bool, u8)>,
+}
+
+// Longest prefix of `s` that fits in `max` bytes without splitting a character
+pub fn truncate_utf8(s: &str, max: usize) -> &str {
+    let mut end = s.len().min(max);
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    &s[..end]
+}
+
+// CPI into Bubblegum mint_v1: receipt for `post` owned by the author, signed by the tree delegate PDA
+#[allow(clippy::too_many_arguments)]
+pub fn mint_post_receipt<'info>(
+    author: &Signer<'info>,
+    tree_config: &AccountInfo<'info>,
+    merkle_tree: &AccountInfo<'info>,
+    receipt_authority: &AccountInfo<'info>,
+    bubblegum_program: &AccountInfo<'info>,
+    log_wrapper: &AccountInfo<'info>,
+    compression_program: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    receipt_authority_bump: u8,
+    post: &Pubkey,
+    target: &str,
+    content_hash: &[u8; 32],
+    bid: u64,
+) -> Result<()> {
+    let content_hash_hex: String = content_hash.iter().map(|b| format!("{:02x}", b)).collect();
+    let metadata = ReceiptMetadataArgs {
+        name: format!("{}{}", RECEIPT_NAME_PREFIX, truncate_utf8(target, 32 - RECEIPT_NAME_PREFIX.len())),
+        symbol: RECEIPT_SYMBOL.to_string(),
+        uri: format!("{}?post={}&hash={}&bid={}", RECEIPT_URI_BASE, post, content_hash_hex, bid),
+        seller_fee_basis_points: 0,
+        primary_sale_happened: true,
+        is_mutable: false,
+        edition_nonce: None,
+        token_standard: Some(0),
+        collection: None,
+        uses: None,
+        token_program_version: 0,
+        creators: vec![],
+    };
+
+    // mint_v1 discriminator
+    let mut data = vec![145, 98, 192, 118, 184, 147, 118, 104];
+    metadata.serialize(&mut data)?;
+
+    let seeds = &[RECEIPT_AUTHORITY_SEED, &[receipt_authority_bump]];
+    invoke_signed(
+        &Instruction {
+            program_id: BUBBLEGUM_PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new(tree_config.key(), false),
+                AccountMeta::new_readonly(author.key(), false),
+                AccountMeta::new_readonly(author.key(), false),
+                AccountMeta::new(merkle_tree.key(), false),
+                AccountMeta::new(author.key(), true),
+                AccountMeta::new_readonly(receipt_authority.key(), true),
+                AccountMeta::new_readonly(log_wrapper.key(), false),
+                AccountMeta::new_readonly(compression_program.key(), false),
+                AccountMeta::new_readonly(system_program.key(), false),
+            ],
+            data,
+        },
+        &[
+            tree_config.to_account_info(),
+            author.to_account_info(),
+            merkle_tree.to_account_info(),
+            receipt_authority.to_account_info(),
+            log_wrapper.to_account_info(),
+            compression_program.to_account_info(),
+            system_program.to_account_info(),
+            bubblegum_program.to_account_info(),
+        ],
+        &[&seeds[..]],
+    )?;
+    Ok(())
+}
+
+// Share of `amount` in basis points, rounded down (bps above BPS_DENOMINATOR are capped)
+pub fn bps_share(amount: u64, bps: u16) -> u64 {
+    (amount as u128 * (bps as u64).min(BPS_DENOMINATOR) as u128 / BPS_DENOMINATOR as u128) as u64
+}
+
+// Revenue split shared by the SOL and per-mint treasuries (45% / 10% / 45%)
+// (computed in u128: amount * 45 overflows u64 above ~4.1e17 lamports)
+pub fn revenue_split(amount: u64) -> (u64, u64, u64) {
+    let amount_1 = (amount as u128 * 45 / 100) as u64;  // 45%
+    let amount_2 = (amount as u128 * 10 / 100) as u64;  // 10%
+    let amount_3 = amount - amount_1 - amount_2;  // Remaining (handles rounding)
+    (amount_1, amount_2, amount_3)
+}
+
+// Split the treasury balance above the rent-exempt minimum between the revenue wallets.
+// Returns the amount distributed.
+pub fn distribute_treasury<'info>(
+    treasury: &AccountInfo<'info>,
+    wallet_1: &AccountInfo<'info>,
+    wallet_2: &AccountInfo<'info>,
+    wallet_3: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    treasury_bump: u8,
+    treasury_version: u8,
+) -> Result<u64> {
+    // Calculate distributable amount (keep rent-exempt minimum in treasury)
+    let treasury_balance = treasury.lamports();
+    let distributable = treasury_balance.saturating_sub(TREASURY_MIN_BALANCE);
+
+    // Only distribute if there's enough to split (skip if treasury is building up minimum)
+    if distributable > 0 {
+        let (amount_1, amount_2, amount_3) = revenue_split(distributable);
+
+        let seeds = &[TREASURY_SEED, treasury_version_seed(&treasury_version), &[treasury_bump]];
+        let signer_seeds = &[&seeds[..]];
+
+        // Transfer from PDA treasury to wallet 1 (45%)
+        transfer(
+            CpiContext::new_with_signer(
+                system_program.to_account_info(),
+                Transfer {
+                    from: treasury.to_account_info(),
+                    to: wallet_1.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount_1,
+        )?;
+
+        // Transfer from PDA treasury to wallet 2 (10%)
+        transfer(
+            CpiContext::new_with_signer(
+                system_program.to_account_info(),
+                Transfer {
+                    from: treasury.to_account_info(),
+                    to: wallet_2.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount_2,
+        )?;
+
+        // Transfer from PDA treasury to wallet 3 (45%)
+        transfer(
+            CpiContext::new_with_signer(
+                system_program.to_account_info(),
+                Transfer {
+                    from: treasury.to_account_info(),
+                    to: wallet_3.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount_3,
+        )?;
+    }
+
+    Ok(distributable)
+}
+
+// Split a mint vault's whole balance between the revenue wallets' token accounts.
+// Returns the amount distributed.
+#[allow(clippy::too_many_arguments)]
+pub fn distribute_mint_treasury<'info>(
+    mint_treasury: &Account<'info, MintTreasury>,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    wallet_1: &InterfaceAccount<'info, TokenAccount>,
+    wallet_2: &InterfaceAccount<'info, TokenAccount>,
+    wallet_3: &InterfaceAccount<'info, TokenAccount>,
+    token_program: &Interface<'info, TokenInterface>,
+) -> Result<u64> {
+    let distributable = vault.amount;
+    if distributable > 0 {
+        let (amount_1, amount_2, amount_3) = revenue_split(distributable);
+
+        let mint_key = mint.key();
+        let seeds = &[MINT_TREASURY_SEED, mint_key.as_ref(), &[mint_treasury.bump]];
+        let signer_seeds = &[&seeds[..]];
+
+        for (wallet, amount) in [(wallet_1, amount_1), (wallet_2, amount_2), (wallet_3, amount_3)] {
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    token_program.to_account_info(),
+                    TransferChecked {
+                        from: vault.to_account_info(),
+                        mint: mint.to_account_info(),
+                        to: wallet.to_account_info(),
+                        authority: mint_treasury.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                amount,
+                mint.decimals,
+            )?;
+        }
+    }
+
+    Ok(distributable)
+}
+
+#[derive(Accounts)]
+#[instruction(target: String)]
+pub struct CreatePost<'info>
+{
+    #[account(mut)]
+    pub author: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    // Pyth SOL/USD price update (feed id and staleness checked in handler)
+    pub price_update: Account<'info, PriceUpdateV2>,
+
+    // Accounting for the current epoch
+    #[account(
+        init_if_needed,
+        payer = author,
+        space = RevenueSnapshot::SIZE,
+        seeds = [REVENUE_SEED, Clock::get()?.epoch.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub revenue_snapshot: Box<Account<'info, RevenueSnapshot>>,
+
+    /// CHECK: PDA treasury - program controlled
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED, treasury_version_seed(&config.treasury_version)],
+        bump
+    )]
+    pub treasury: AccountInfo<'info>,
+
+    /// CHECK: Revenue wallet 1 (45%) - verified against hardcoded address
+    #[account(
+        mut,
+        constraint = wallet_1.key() == WALLET_1 @ PostError::InvalidWallet
+    )]
+    pub wallet_1: AccountInfo<'info>,
+
+    /// CHECK: Revenue wallet 2 (10%), or the vesting vault when vesting is enabled
+    #[account(
+        mut,
+        constraint = wallet_2.key() == config.tranche_recipient() @ PostError::InvalidWallet
+    )]
+    pub wallet_2: AccountInfo<'info>,
+
+    /// CHECK: Revenue wallet 3 (45%) - verified against hardcoded address
+    #[account(
+        mut,
+        constraint = wallet_3.key() == WALLET_3 @ PostError::InvalidWallet
+    )]
+    pub wallet_3: AccountInfo<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = author,
+        space = AuthorIndex::SIZE,
+        seeds = [AUTHOR_INDEX_SEED, author.key().as_ref()],
+        bump
+    )]
+    pub author_index: Box<Account<'info, AuthorIndex>>,
+
+    #[account(
+        init,
+        payer = author,
+        space = Post::SIZE,
+        seeds = [POST_SEED, author.key().as_ref(), target.as_bytes()],
+        bump
+    )]
+    pub post: Account<'info, Post>,
+
+    /// CHECK: Referrer wallet - verified against the referral registry entry
+    #[account(mut)]
+    pub referrer: Option<AccountInfo<'info>>,
+
+    #[account(
+        mut,
+        seeds = [REFERRAL_SEED, referral.wallet.as_ref()],
+        bump = referral.bump
+    )]
+    pub referral: Option<Account<'info, Referral>>,
+
+    /// CHECK: Charity wallet - verified against the charity registry entry
+    #[account(mut)]
+    pub charity_wallet: Option<AccountInfo<'info>>,
+
+    #[account(
+        mut,
+        seeds = [CHARITY_SEED, charity.wallet.as_ref()],
+        bump = charity.bump
+    )]
+    pub charity: Option<Box<Account<'info, Charity>>>,
+
+    #[account(
+        init_if_needed,
+        payer = author,
+        space = AuthorStats::SIZE,
+        seeds = [AUTHOR_STATS_SEED, author.key().as_ref()],
+        bump
+    )]
+    pub author_stats: Box<Account<'info, AuthorStats>>,
+
+    #[account(
+        seeds = [STAKE_SEED, author.key().as_ref()],
+        bump = stake_account.bump
+    )]
+    pub stake_account: Option<Box<Account<'info, StakeAccount>>>,
+
+    /// CHECK: Bubblegum tree config of the receipt tree - checked by Bubblegum
+    #[account(mut)]
+    pub receipt_tree_config: Option<AccountInfo<'info>>,
+
+    /// CHECK: Receipt merkle tree - verified against config
+    #[account(mut)]
+    pub receipt_tree: Option<AccountInfo<'info>>,
+
+    /// CHECK: PDA delegate of the receipt tree, signs the mint
+    #[account(
+        seeds = [RECEIPT_AUTHORITY_SEED],
+        bump
+    )]
+    pub receipt_authority: Option<AccountInfo<'info>>,
+
+    /// CHECK: Bubblegum program - verified against hardcoded address
+    #[account(address = BUBBLEGUM_PROGRAM_ID)]
+    pub bubblegum_program: Option<AccountInfo<'info>>,
+
+    /// CHECK: SPL Noop program - verified against hardcoded address
+    #[account(address = SPL_NOOP_PROGRAM_ID)]
+    pub log_wrapper: Option<AccountInfo<'info>>,
+
+    /// CHECK: SPL Account Compression program - verified against hardcoded address
+    #[account(address = SPL_ACCOUNT_COMPRESSION_PROGRAM_ID)]
+    pub compression_program: Option<AccountInfo<'info>>,
+
+    pub system_program: Program<'info, System>,
+
+    // Usage counters (optional, see count)
+    #[account(
+        mut,
+        seeds = [METRICS_SEED],
+        bump = metrics.bump
+    )]
+    pub metrics: Option<Account<'info, PostMetrics>>,
+}
+
+#[derive(Accounts)]
+#[instruction(target: String)]
+pub struct CreatePostPrivate<'info>
+{
+    // Pays rent and fees (e.g. a relayer), distinct from the author so the author needs no funding
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub author: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    // Pyth SOL/USD price update (feed id and staleness checked in handler)
+    pub price_update: Account<'info, PriceUpdateV2>,
+
+    // Accounting for the current epoch
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = RevenueSnapshot::SIZE,
+        seeds = [REVENUE_SEED, Clock::get()?.epoch.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub revenue_snapshot: Box<Account<'info, RevenueSnapshot>>,
+
+    /// CHECK: PDA treasury - program controlled, recipient of the Privacy Cash withdrawal
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED, treasury_version_seed(&config.treasury_version)],
+        bump
+    )]
+    pub treasury: AccountInfo<'info>,
+
+    /// CHECK: Revenue wallet 1 (45%) - verified against hardcoded address
+    #[account(
+        mut,
+        constraint = wallet_1.key() == WALLET_1 @ PostError::InvalidWallet
+    )]
+    pub wallet_1: AccountInfo<'info>,
+
+    /// CHECK: Revenue wallet 2 (10%), or the vesting vault when vesting is enabled
+    #[account(
+        mut,
+        constraint = wallet_2.key() == config.tranche_recipient() @ PostError::InvalidWallet
+    )]
+    pub wallet_2: AccountInfo<'info>,
+
+    /// CHECK: Revenue wallet 3 (45%) - verified against hardcoded address
+    #[account(
+        mut,
+        constraint = wallet_3.key() == WALLET_3 @ PostError::InvalidWallet
+    )]
+    pub wallet_3: AccountInfo<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = AuthorIndex::SIZE,
+        seeds = [AUTHOR_INDEX_SEED, author.key().as_ref()],
+        bump
+    )]
+    pub author_index: Box<Account<'info, AuthorIndex>>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = Post::SIZE,
+        seeds = [POST_SEED, author.key().as_ref(), target.as_bytes()],
+        bump
+    )]
+    pub post: Account<'info, Post>,
+
+    /// CHECK: Privacy Cash program - verified against hardcoded address
+    #[account(address = PRIVACY_CASH_PROGRAM_ID)]
+    pub privacy_cash_program: AccountInfo<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = AuthorStats::SIZE,
+        seeds = [AUTHOR_STATS_SEED, author.key().as_ref()],
+        bump
+    )]
+    pub author_stats: Box<Account<'info, AuthorStats>>,
+
+    #[account(
+        seeds = [STAKE_SEED, author.key().as_ref()],
+        bump = stake_account.bump
+    )]
+    pub stake_account: Option<Box<Account<'info, StakeAccount>>>,
+
+    pub system_program: Program<'info, System>,
+
+    // Usage counters (optional, see count). The Privacy Cash accounts follow as remaining
+    // accounts, so the slot must always be passed (the program ID when absent).
+    #[account(
+        mut,
+        seeds = [METRICS_SEED],
+        bump = metrics.bump
+    )]
+    pub metrics: Option<Account<'info, PostMetrics>>,
+}
+
+#[derive(Accounts)]
+#[instruction(author: Pubkey, target: String)]
+pub struct CreatePostRelayed<'info>
+{
+    // Signs, pays the bid and the rent on behalf of the pseudonymous author
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    // Pyth SOL/USD price update (feed id and staleness checked in handler)
+    pub price_update: Account<'info, PriceUpdateV2>,
+
+    // Accounting for the current epoch
+    #[account(
+        init_if_needed,
+        payer = relayer,
+        space = RevenueSnapshot::SIZE,
+        seeds = [REVENUE_SEED, Clock::get()?.epoch.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub revenue_snapshot: Box<Account<'info, RevenueSnapshot>>,
+
+    /// CHECK: PDA treasury - program controlled
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED, treasury_version_seed(&config.treasury_version)],
+        bump
+    )]
+    pub treasury: AccountInfo<'info>,
+
+    /// CHECK: Revenue wallet 1 (45%) - verified against hardcoded address
+    #[account(
+        mut,
+        constraint = wallet_1.key() == WALLET_1 @ PostError::InvalidWallet
+    )]
+    pub wallet_1: AccountInfo<'info>,
+
+    /// CHECK: Revenue wallet 2 (10%), or the vesting vault when vesting is enabled
+    #[account(
+        mut,
+        constraint = wallet_2.key() == config.tranche_recipient() @ PostError::InvalidWallet
+    )]
+    pub wallet_2: AccountInfo<'info>,
+
+    /// CHECK: Revenue wallet 3 (45%) - verified against hardcoded address
+    #[account(
+        mut,
+        constraint = wallet_3.key() == WALLET_3 @ PostError::InvalidWallet
+    )]
+    pub wallet_3: AccountInfo<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = relayer,
+        space = AuthorIndex::SIZE,
+        seeds = [AUTHOR_INDEX_SEED, author.as_ref()],
+        bump
+    )]
+    pub author_index: Box<Account<'info, AuthorIndex>>,
+
+    #[account(
+        init,
+        payer = relayer,
+        space = Post::SIZE,
+        seeds = [POST_SEED, author.as_ref(), target.as_bytes()],
+        bump
+    )]
+    pub post: Account<'info, Post>,
+
+    /// CHECK: Instructions sysvar, used to read the Ed25519 signature instruction
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = relayer,
+        space = AuthorStats::SIZE,
+        seeds = [AUTHOR_STATS_SEED, author.as_ref()],
+        bump
+    )]
+    pub author_stats: Box<Account<'info, AuthorStats>>,
+
+    #[account(
+        seeds = [STAKE_SEED, author.as_ref()],
+        bump = stake_account.bump
+    )]
+    pub stake_account: Option<Box<Account<'info, StakeAccount>>>,
+
+    pub system_program: Program<'info, System>,
+
+    // Usage counters (optional, see count)
+    #[account(
+        mut,
+        seeds = [METRICS_SEED],
+        bump = metrics.bump
+    )]
+    pub metrics: Option<Account<'info, PostMetrics>>,
+}
+
+#[derive(Accounts)]
+pub struct ReplyPrivatelyToPost<'info>
+{
+    #[account(mut)]
+    pub sender: Signer<'info>,
+
+    pub post: Account<'info, Post>,
+
+    /// CHECK: private_messages UserAccount of the post author - owner and wallet checked in handler
+    #[account(mut)]
+    pub recipient_user: AccountInfo<'info>,
+
+    /// CHECK: Message PDA created by private_messages (seeds checked there)
+    #[account(mut)]
+    pub message_account: AccountInfo<'info>,
+
+    /// CHECK: Private messages program - verified against hardcoded address
+    #[account(address = PRIVATE_MESSAGES_PROGRAM_ID)]
+    pub private_messages_program: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    // Usage counters (optional, see count)
+    #[account(
+        mut,
+        seeds = [METRICS_SEED],
+        bump = metrics.bump
+    )]
+    pub metrics: Option<Account<'info, PostMetrics>>,
+}
+
+#[derive(Accounts)]
+pub struct NotifyPostTarget<'info>
+{
+    #[account(mut)]
+    pub author: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = author @ PostError::Unauthorized
+    )]
+    pub post: Box<Account<'info, Post>>,
+
+    // Registry entry of the post's target, naming its owner
+    #[account(
+        seeds = [TARGET_SEED, target_seed(&post.target).as_ref()],
+        bump = target_account.bump
+    )]
+    pub target_account: Account<'info, TargetAccount>,
+
+    /// CHECK: private_messages UserAccount of the target owner - owner and wallet checked in handler
+    #[account(mut)]
+    pub recipient_user: AccountInfo<'info>,
+
+    /// CHECK: Message PDA created by private_messages (seeds checked there)
+    #[account(mut)]
+    pub message_account: AccountInfo<'info>,
+
+    /// CHECK: Private messages program - verified against hardcoded address
+    #[account(address = PRIVATE_MESSAGES_PROGRAM_ID)]
+    pub private_messages_program: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    // Usage counters (optional, see count)
+    #[account(
+        mut,
+        seeds = [METRICS_SEED],
+        bump = metrics.bump
+    )]
+    pub metrics: Option<Account<'info, PostMetrics>>,
+}
+
+#[derive(Accounts)]
+pub struct SetPostProvenance<'info>
+{
+    pub author: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = author @ PostError::Unauthorized
+    )]
+    pub post: Box<Account<'info, Post>>,
+
+    /// CHECK: Instructions sysvar, used to read the Ed25519 signature instruction
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeTreasury<'info>
+{
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: PDA treasury (version 1) - program controlled
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED],
+        bump
+    )]
+    pub treasury: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigratePost<'info>
+{
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: legacy post, owner and discriminator checked in the handler
+    #[account(mut)]
+    pub post: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClosePost<'info>
+{
+    #[account(mut)]
+    pub author: Signer<'info>,
+
+    #[account(
+        mut,
+        close = author,
+        has_one = author @ PostError::Unauthorized
+    )]
+    pub post: Box<Account<'info, Post>>,
+
+    /// CHECK: escrow PDA of the post, must be empty (checked in the handler)
+    #[account(
+        seeds = [ESCROW_SEED, post.key().as_ref()],
+        bump
+    )]
+    pub escrow: UncheckedAccount<'info>,
+
+    // Optional: authors who never got an index (older posts) close without one
+    #[account(
+        mut,
+        seeds = [AUTHOR_INDEX_SEED, author.key().as_ref()],
+        bump = author_index.bump
+    )]
+    pub author_index: Option<Account<'info, AuthorIndex>>,
+}
+
+#[derive(Accounts)]
+pub struct UpvotePost<'info>
+{
+    #[account(mut)]
+    pub voter: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub post: Box<Account<'info, Post>>,
+
+    #[account(
+        init,
+        payer = voter,
+        space = UpvoteReceipt::SIZE,
+        seeds = [UPVOTE_SEED, post.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub upvote_receipt: Account<'info, UpvoteReceipt>,
+
+    /// CHECK: PDA treasury - program controlled
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED, treasury_version_seed(&config.treasury_version)],
+        bump
+    )]
+    pub treasury: AccountInfo<'info>,
+
+    /// CHECK: Revenue wallet 1 (45%) - verified against hardcoded address
+    #[account(
+        mut,
+        constraint = wallet_1.key() == WALLET_1 @ PostError::InvalidWallet
+    )]
+    pub wallet_1: AccountInfo<'info>,
+
+    /// CHECK: Revenue wallet 2 (10%), or the vesting vault when vesting is enabled
+    #[account(
+        mut,
+        constraint = wallet_2.key() == config.tranche_recipient() @ PostError::InvalidWallet
+    )]
+    pub wallet_2: AccountInfo<'info>,
+
+    /// CHECK: Revenue wallet 3 (45%) - verified against hardcoded address
+    #[account(
+        mut,
+        constraint = wallet_3.key() == WALLET_3 @ PostError::InvalidWallet
+    )]
+    pub wallet_3: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    // Usage counters (optional, see count)
+    #[account(
+        mut,
+        seeds = [METRICS_SEED],
+        bump = metrics.bump
+    )]
+    pub metrics: Option<Account<'info, PostMetrics>>,
+}
+
+#[derive(Accounts)]
+pub struct RecordViews<'info>
+{
+    pub attestor: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = config.view_attestor == attestor.key() @ PostError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct RefreshRank<'info>
+{
+    #[account(mut)]
+    pub post: Account<'info, Post>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateTreasury<'info>
+{
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ PostError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: PDA treasury - program controlled
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED, treasury_version_seed(&config.treasury_version)],
+        bump
+    )]
+    pub treasury: AccountInfo<'info>,
+
+    /// CHECK: New treasury PDA, verified in the handler
+    #[account(mut)]
+    pub destination: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SweepTreasury<'info>
+{
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ PostError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: PDA treasury - program controlled
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED, treasury_version_seed(&config.treasury_version)],
+        bump
+    )]
+    pub treasury: AccountInfo<'info>,
+
+    /// CHECK: Revenue wallet 1 (45%) - verified against hardcoded address
+    #[account(
+        mut,
+        constraint = wallet_1.key() == WALLET_1 @ PostError::InvalidWallet
+    )]
+    pub wallet_1: AccountInfo<'info>,
+
+    /// CHECK: Revenue wallet 2 (10%), or the vesting vault when vesting is enabled
+    #[account(
+        mut,
+        constraint = wallet_2.key() == config.tranche_recipient() @ PostError::InvalidWallet
+    )]
+    pub wallet_2: AccountInfo<'info>,
+
+    /// CHECK: Revenue wallet 3 (45%) - verified against hardcoded address
+    #[account(
+        mut,
+        constraint = wallet_3.key() == WALLET_3 @ PostError::InvalidWallet
+    )]
+    pub wallet_3: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info>
+{
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = Config::SIZE,
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    // Only the upgrade authority can initialize, so the config can't be front-run after deploy
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()) @ PostError::Unauthorized)]
+    pub program: Program<'info, crate::program::PostMsgProgram>,
+
+    #[account(constraint = program_data.upgrade_authority_address == Some(admin.key()) @ PostError::Unauthorized)]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info>
+{
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ PostError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeGovernanceAdmin<'info>
+{
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ PostError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: SPL Governance program (Realms deployments may use their own program id)
+    #[account(executable)]
+    pub governance_program: AccountInfo<'info>,
+
+    /// CHECK: Governance account, must be owned by the governance program
+    #[account(owner = governance_program.key() @ PostError::InvalidGovernance)]
+    pub governance: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info>
+{
+    pub new_admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = config.pending_admin == new_admin.key() @ PostError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterReferrer<'info>
+{
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ PostError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: Wallet receiving the referral share
+    pub referrer: AccountInfo<'info>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = Referral::SIZE,
+        seeds = [REFERRAL_SEED, referrer.key().as_ref()],
+        bump
+    )]
+    pub referral: Account<'info, Referral>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterCharity<'info>
+{
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ PostError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: Wallet receiving the charity share
+    pub charity_wallet: AccountInfo<'info>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = Charity::SIZE,
+        seeds = [CHARITY_SEED, charity_wallet.key().as_ref()],
+        bump
+    )]
+    pub charity: Account<'info, Charity>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveCharity<'info>
+{
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ PostError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [CHARITY_SEED, charity.wallet.as_ref()],
+        bump = charity.bump
+    )]
+    pub charity: Account<'info, Charity>,
+}
+
+// ============================================================================
+// SEALED-BID AUCTION CONTEXTS
+// ============================================================================
+
+#[init_computation_definition_accounts("init_sealed_auction", payer)]
+#[derive(Accounts)]
+pub struct InitSealedAuctionCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("place_sealed_bid", payer)]
+#[derive(Accounts)]
+pub struct InitPlaceSealedBidCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("resolve_sealed_auction", payer)]
+#[derive(Accounts)]
+pub struct InitResolveSealedAuctionCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[queue_computation_accounts("init_sealed_auction", admin)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, auction_id: u64)]
+pub struct CreateAuction<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ PostError::Unauthorized
+    )]
+    pub config: Box<Account<'info, Config>>,
+    #[account(
+        init,
+        payer = admin,
+        space = Auction::SIZE,
+        seeds = [AUCTION_SEED, auction_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub auction: Box<Account<'info, Auction>>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = admin,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, PostError::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, PostError::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, PostError::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_SEALED_AUCTION))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, PostError::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(mut, seeds = [METRICS_SEED], bump = metrics.bump)]
+    pub metrics: Option<Account<'info, PostMetrics>>,
+}
+
+#[callback_accounts("init_sealed_auction")]
+#[derive(Accounts)]
+pub struct InitSealedAuctionCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_SEALED_AUCTION))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, PostError::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub auction: Box<Account<'info, Auction>>,
+    #[account(mut, seeds = [METRICS_SEED], bump = metrics.bump)]
+    pub metrics: Option<Account<'info, PostMetrics>>,
+}
+
+#[queue_computation_accounts("place_sealed_bid", bidder)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct PlaceSealedBid<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [AUCTION_SEED, auction.id.to_le_bytes().as_ref()],
+        bump = auction.bump
+    )]
+    pub auction: Box<Account<'info, Auction>>,
+    // Post to feature if this bid wins
+    #[account(constraint = post.author == bidder.key() @ PostError::Unauthorized)]
+    pub post: Box<Account<'info, Post>>,
+    #[account(
+        init,
+        payer = bidder,
+        space = SealedBid::SIZE,
+        seeds = [SEALED_BID_SEED, auction.key().as_ref(), bidder.key().as_ref()],
+        bump
+    )]
+    pub sealed_bid: Box<Account<'info, SealedBid>>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = bidder,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, PostError::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, PostError::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, PostError::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PLACE_SEALED_BID))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, PostError::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(mut, seeds = [METRICS_SEED], bump = metrics.bump)]
+    pub metrics: Option<Account<'info, PostMetrics>>,
+}
+
+#[callback_accounts("place_sealed_bid")]
+#[derive(Accounts)]
+pub struct PlaceSealedBidCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PLACE_SEALED_BID))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, PostError::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub auction: Box<Account<'info, Auction>>,
+    #[account(mut, seeds = [METRICS_SEED], bump = metrics.bump)]
+    pub metrics: Option<Account<'info, PostMetrics>>,
+}
+
+#[queue_computation_accounts("resolve_sealed_auction", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct ResolveAuction<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [AUCTION_SEED, auction.id.to_le_bytes().as_ref()],
+        bump = auction.bump
+    )]
+    pub auction: Box<Account<'info, Auction>>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, PostError::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, PostError::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, PostError::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_RESOLVE_SEALED_AUCTION))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, PostError::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(mut, seeds = [METRICS_SEED], bump = metrics.bump)]
+    pub metrics: Option<Account<'info, PostMetrics>>,
+}
+
+#[callback_accounts("resolve_sealed_auction")]
+#[derive(Accounts)]
+pub struct ResolveSealedAuctionCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_RESOLVE_SEALED_AUCTION))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, PostError::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub auction: Box<Account<'info, Auction>>,
+    #[account(mut, seeds = [METRICS_SEED], bump = metrics.bump)]
+    pub metrics: Option<Account<'info, PostMetrics>>,
+}
+
+#[derive(Accounts)]
+pub struct SettleAuction<'info>
+{
+    #[account(
+        mut,
+        seeds = [AUCTION_SEED, auction.id.to_le_bytes().as_ref()],
+        bump = auction.bump
+    )]
+    pub auction: Box<Account<'info, Auction>>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: PDA treasury - program controlled
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED, treasury_version_seed(&config.treasury_version)],
+        bump
+    )]
+    pub treasury: AccountInfo<'info>,
+
+    /// CHECK: Revenue wallet 1 (45%) - verified against hardcoded address
+    #[account(
+        mut,
+        constraint = wallet_1.key() == WALLET_1 @ PostError::InvalidWallet
+    )]
+    pub wallet_1: AccountInfo<'info>,
+
+    /// CHECK: Revenue wallet 2 (10%), or the vesting vault when vesting is enabled
+    #[account(
+        mut,
+        constraint = wallet_2.key() == config.tranche_recipient() @ PostError::InvalidWallet
+    )]
+    pub wallet_2: AccountInfo<'info>,
+
+    /// CHECK: Revenue wallet 3 (45%) - verified against hardcoded address
+    #[account(
+        mut,
+        constraint = wallet_3.key() == WALLET_3 @ PostError::InvalidWallet
+    )]
+    pub wallet_3: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawSealedBid<'info>
+{
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [AUCTION_SEED, auction.id.to_le_bytes().as_ref()],
+        bump = auction.bump
+    )]
+    pub auction: Box<Account<'info, Auction>>,
+
+    #[account(
+        mut,
+        close = bidder,
+        seeds = [SEALED_BID_SEED, auction.key().as_ref(), bidder.key().as_ref()],
+        bump = sealed_bid.bump,
+        has_one = bidder @ PostError::Unauthorized,
+        has_one = auction @ PostError::Unauthorized
+    )]
+    pub sealed_bid: Account<'info, SealedBid>,
+}
+
+#[derive(Accounts)]
+pub struct OpenRefundVault<'info>
+{
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ PostError::Unauthorized
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        mut,
+        seeds = [AUCTION_SEED, auction.id.to_le_bytes().as_ref()],
+        bump = auction.bump
+    )]
+    pub auction: Box<Account<'info, Auction>>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = RefundVault::SIZE,
+        seeds = [REFUND_VAULT_SEED, auction.key().as_ref()],
+        bump
+    )]
+    pub refund_vault: Box<Account<'info, RefundVault>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimRefund<'info>
+{
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [REFUND_VAULT_SEED, sealed_bid.auction.as_ref()],
+        bump = refund_vault.bump
+    )]
+    pub refund_vault: Box<Account<'info, RefundVault>>,
+
+    #[account(
+        mut,
+        seeds = [SEALED_BID_SEED, sealed_bid.auction.as_ref(), bidder.key().as_ref()],
+        bump = sealed_bid.bump,
+        has_one = bidder @ PostError::Unauthorized
+    )]
+    pub sealed_bid: Account<'info, SealedBid>,
+}
+
+#[derive(Accounts)]
+pub struct ExpireRefunds<'info>
+{
+    #[account(
+        mut,
+        close = treasury,
+        seeds = [REFUND_VAULT_SEED, refund_vault.auction.as_ref()],
+        bump = refund_vault.bump
+    )]
+    pub refund_vault: Box<Account<'info, RefundVault>>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: PDA treasury - program controlled
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED, treasury_version_seed(&config.treasury_version)],
+        bump
+    )]
+    pub treasury: AccountInfo<'info>,
+}
+
+// ============================================================================
+// BOOST & SEALED CONTENT CONTEXTS
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct BoostPost<'info>
+{
+    #[account(mut)]
+    pub booster: Signer<'info>,
+
+    #[account(mut)]
+    pub post: Box<Account<'info, Post>>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: PDA treasury - program controlled
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED, treasury_version_seed(&config.treasury_version)],
+        bump
+    )]
+    pub treasury: AccountInfo<'info>,
+
+    /// CHECK: Revenue wallet 1 (45%) - verified against hardcoded address
+    #[account(
+        mut,
+        constraint = wallet_1.key() == WALLET_1 @ PostError::InvalidWallet
+    )]
+    pub wallet_1: AccountInfo<'info>,
+
+    /// CHECK: Revenue wallet 2 (10%), or the vesting vault when vesting is enabled
+    #[account(
+        mut,
+        constraint = wallet_2.key() == config.tranche_recipient() @ PostError::InvalidWallet
+    )]
+    pub wallet_2: AccountInfo<'info>,
+
+    /// CHECK: Revenue wallet 3 (45%) - verified against hardcoded address
+    #[account(
+        mut,
+        constraint = wallet_3.key() == WALLET_3 @ PostError::InvalidWallet
+    )]
+    pub wallet_3: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [SEALED_CONTENT_SEED, post.key().as_ref()],
+        bump = sealed_content.bump
+    )]
+    pub sealed_content: Option<Box<Account<'info, SealedContent>>>,
+
+    pub system_program: Program<'info, System>,
+
+    // Usage counters (optional, see count)
+    #[account(
+        mut,
+        seeds = [METRICS_SEED],
+        bump = metrics.bump
+    )]
+    pub metrics: Option<Account<'info, PostMetrics>>,
+}
+
+#[derive(Accounts)]
+pub struct SealPostContent<'info>
+{
+    #[account(mut)]
+    pub author: Signer<'info>,
+
+    #[account(has_one = author @ PostError::Unauthorized)]
+    pub post: Box<Account<'info, Post>>,
+
+    #[account(
+        init,
+        payer = author,
+        space = SealedContent::SIZE,
+        seeds = [SEALED_CONTENT_SEED, post.key().as_ref()],
+        bump
+    )]
+    pub sealed_content: Box<Account<'info, SealedContent>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("reveal_content_key", payer)]
+#[derive(Accounts)]
+pub struct InitRevealContentKeyCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[queue_computation_accounts("reveal_content_key", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct RevealSealedPost<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [SEALED_CONTENT_SEED, sealed_content.post.as_ref()],
+        bump = sealed_content.bump
+    )]
+    pub sealed_content: Box<Account<'info, SealedContent>>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, PostError::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, PostError::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, PostError::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_CONTENT_KEY))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, PostError::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(mut, seeds = [METRICS_SEED], bump = metrics.bump)]
+    pub metrics: Option<Account<'info, PostMetrics>>,
+}
+
+#[callback_accounts("reveal_content_key")]
+#[derive(Accounts)]
+pub struct RevealContentKeyCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_CONTENT_KEY))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, PostError::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub sealed_content: Box<Account<'info, SealedContent>>,
+    #[account(mut, seeds = [METRICS_SEED], bump = metrics.bump)]
+    pub metrics: Option<Account<'info, PostMetrics>>,
+}
+
+#[derive(Accounts)]
+pub struct ResetSealedPostPending<'info> {
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [SEALED_CONTENT_SEED, sealed_content.post.as_ref()],
+        bump = sealed_content.bump
+    )]
+    pub sealed_content: Box<Account<'info, SealedContent>>,
+
+    // Usage counters (optional, see count)
+    #[account(
+        mut,
+        seeds = [METRICS_SEED],
+        bump = metrics.bump
+    )]
+    pub metrics: Option<Account<'info, PostMetrics>>,
+}
+
+#[derive(Accounts)]
+pub struct ResetAuctionPending<'info> {
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [AUCTION_SEED, auction.id.to_le_bytes().as_ref()],
+        bump = auction.bump
+    )]
+    pub auction: Box<Account<'info, Auction>>,
+
+    // Usage counters (optional, see count)
+    #[account(
+        mut,
+        seeds = [METRICS_SEED],
+        bump = metrics.bump
+    )]
+    pub metrics: Option<Account<'info, PostMetrics>>,
+}
+
+// ============================================================================
+// STAKE CONTEXTS
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct DepositStake<'info>
+{
+    #[account(mut)]
+    pub author: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = author,
+        space = StakeAccount::SIZE,
+        seeds = [STAKE_SEED, author.key().as_ref()],
+        bump
+    )]
+    pub stake_account: Account<'info, StakeAccount>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RequestUnstake<'info>
+{
+    pub author: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [STAKE_SEED, author.key().as_ref()],
+        bump = stake_account.bump,
+        has_one = author @ PostError::Unauthorized
+    )]
+    pub stake_account: Account<'info, StakeAccount>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawStake<'info>
+{
+    #[account(mut)]
+    pub author: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        close = author,
+        seeds = [STAKE_SEED, author.key().as_ref()],
+        bump = stake_account.bump,
+        has_one = author @ PostError::Unauthorized
+    )]
+    pub stake_account: Account<'info, StakeAccount>,
+}
+
+#[derive(Accounts)]
+#[instruction(target: String)]
+pub struct SlashStake<'info>
+{
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ PostError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: the abusive post, derived from the staker and target so it may already be closed
+    #[account(
+        seeds = [POST_SEED, stake_account.author.as_ref(), target.as_bytes()],
+        bump
+    )]
+    pub post: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [STAKE_SEED, stake_account.author.as_ref()],
+        bump = stake_account.bump
+    )]
+    pub stake_account: Account<'info, StakeAccount>,
+
+    /// CHECK: PDA treasury - program controlled
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED, treasury_version_seed(&config.treasury_version)],
+        bump
+    )]
+    pub treasury: AccountInfo<'info>,
+}
+
+// ============================================================================
+// VESTING CONTEXTS
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct InitializeVesting<'info>
+{
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ PostError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = VestingVault::SIZE,
+        seeds = [VESTING_VAULT_SEED],
+        bump
+    )]
+    pub vesting_vault: Account<'info, VestingVault>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimVested<'info>
+{
+    /// CHECK: Vesting beneficiary (WALLET_2), anyone can crank the claim
+    #[account(mut)]
+    pub beneficiary: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [VESTING_VAULT_SEED],
+        bump = vesting_vault.bump,
+        has_one = beneficiary @ PostError::InvalidWallet
+    )]
+    pub vesting_vault: Account<'info, VestingVault>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimVestedToken<'info>
+{
+    // WALLET_2's token account for the mint, anyone can crank the claim
+    #[account(
+        mut,
+        token::mint = mint,
+        constraint = beneficiary_token.owner == vesting_vault.beneficiary @ PostError::InvalidWallet
+    )]
+    pub beneficiary_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [VESTING_VAULT_SEED],
+        bump = vesting_vault.bump
+    )]
+    pub vesting_vault: Account<'info, VestingVault>,
+
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [MINT_TREASURY_SEED, mint.key().as_ref()],
+        bump = mint_treasury.bump,
+        has_one = mint
+    )]
+    pub mint_treasury: Box<Account<'info, MintTreasury>>,
+
+    // Token account the vault's tranche of this mint accrues in
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = vesting_vault
+    )]
+    pub vault_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+// Batched economic parameters for update_economics
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct EconomicsParams
+{
+    pub min_bid_usd: Option<u64>,
+    pub referral_bps: Option<u16>,
+    pub rate_window_secs: Option<i64>,
+    pub max_posts_per_window: Option<u16>,
+    pub rate_waiver_bid_usd: Option<u64>,
+    pub min_stake: Option<u64>,
+    pub stake_cooldown_secs: Option<i64>,
+    pub escrow_window_secs: Option<i64>,
+    pub escrow_keep_fee_bps: Option<u16>,
+    pub upvote_lamports: Option<u64>,
+}
+
+// ============================================================================
+// TARGET REGISTRY & ESCROW CONTEXTS
+// ============================================================================
+
+#[derive(Accounts)]
+#[instruction(target: String)]
+pub struct RegisterTarget<'info>
+{
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ PostError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = TargetAccount::SIZE,
+        seeds = [TARGET_SEED, target_seed(&target).as_ref()],
+        bump
+    )]
+    pub target_account: Account<'info, TargetAccount>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(target: String)]
+pub struct AttestTargetIdentity<'info>
+{
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ PostError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = TargetIdentity::SIZE,
+        seeds = [TARGET_IDENTITY_SEED, target_seed(&target).as_ref()],
+        bump
+    )]
+    pub target_identity: Account<'info, TargetIdentity>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(target: String)]
+pub struct ProveTargetOwnership<'info>
+{
+    #[account(mut)]
+    pub claimant: Signer<'info>,
+
+    #[account(
+        seeds = [TARGET_IDENTITY_SEED, target_seed(&target).as_ref()],
+        bump = target_identity.bump
+    )]
+    pub target_identity: Account<'info, TargetIdentity>,
+
+    // Created on first claim; accounts from before v2 must go through migrate_target first
+    #[account(
+        init_if_needed,
+        payer = claimant,
+        space = TargetAccount::SIZE,
+        seeds = [TARGET_SEED, target_seed(&target).as_ref()],
+        bump
+    )]
+    pub target_account: Account<'info, TargetAccount>,
+
+    /// CHECK: Instructions sysvar, used to read the Ed25519 signature instruction
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateTarget<'info>
+{
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: target account from before v2, owner and discriminator checked in the handler
+    #[account(mut)]
+    pub target_account: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(target: String)]
+pub struct CreatePostEscrowed<'info>
+{
+    #[account(mut)]
+    pub author: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    // Pyth SOL/USD price update (feed id and staleness checked in handler)
+    pub price_update: Account<'info, PriceUpdateV2>,
+
+    // Escrow requires a registered target owner to accept
+    #[account(
+        seeds = [TARGET_SEED, target_seed(&target).as_ref()],
+        bump = target_account.bump
+    )]
+    pub target_account: Account<'info, TargetAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = author,
+        space = AuthorIndex::SIZE,
+        seeds = [AUTHOR_INDEX_SEED, author.key().as_ref()],
+        bump
+    )]
+    pub author_index: Box<Account<'info, AuthorIndex>>,
+
+    #[account(
+        init,
+        payer = author,
+        space = Post::SIZE,
+        seeds = [POST_SEED, author.key().as_ref(), target.as_bytes()],
+        bump
+    )]
+    pub post: Box<Account<'info, Post>>,
+
+    #[account(
+        init,
+        payer = author,
+        space = PostEscrow::SIZE,
+        seeds = [ESCROW_SEED, post.key().as_ref()],
+        bump
+    )]
+    pub escrow: Account<'info, PostEscrow>,
+
+    #[account(
+        init_if_needed,
+        payer = author,
+        space = AuthorStats::SIZE,
+        seeds = [AUTHOR_STATS_SEED, author.key().as_ref()],
+        bump
+    )]
+    pub author_stats: Box<Account<'info, AuthorStats>>,
+
+    #[account(
+        seeds = [STAKE_SEED, author.key().as_ref()],
+        bump = stake_account.bump
+    )]
+    pub stake_account: Option<Box<Account<'info, StakeAccount>>>,
+
+    pub system_program: Program<'info, System>,
+
+    // Usage counters (optional, see count)
+    #[account(
+        mut,
+        seeds = [METRICS_SEED],
+        bump = metrics.bump
+    )]
+    pub metrics: Option<Account<'info, PostMetrics>>,
+}
+
+#[derive(Accounts)]
+pub struct PostOfficialReply<'info>
+{
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mut)]
+    pub post: Box<Account<'info, Post>>,
+
+    // Registry entry of the post's target, proving ownership
+    #[account(
+        seeds = [TARGET_SEED, target_seed(&post.target).as_ref()],
+        bump = target_account.bump,
+        has_one = owner @ PostError::Unauthorized
+    )]
+    pub target_account: Account<'info, TargetAccount>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = OfficialReply::SIZE,
+        seeds = [OFFICIAL_REPLY_SEED, post.key().as_ref()],
+        bump
+    )]
+    pub official_reply: Box<Account<'info, OfficialReply>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateTarget<'info>
+{
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = owner @ PostError::Unauthorized
+    )]
+    pub target_account: Account<'info, TargetAccount>,
+}
+
+#[derive(Accounts)]
+pub struct PinPost<'info>
+{
+    pub owner: Signer<'info>,
+
+    // Post author, required (and charged) only when the target has a pin fee
+    #[account(mut)]
+    pub author: Option<Signer<'info>>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    pub post: Box<Account<'info, Post>>,
+
+    #[account(
+        mut,
+        seeds = [TARGET_SEED, target_seed(&post.target).as_ref()],
+        bump = target_account.bump,
+        has_one = owner @ PostError::Unauthorized
+    )]
+    pub target_account: Account<'info, TargetAccount>,
+
+    /// CHECK: PDA treasury - program controlled
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED, treasury_version_seed(&config.treasury_version)],
+        bump
+    )]
+    pub treasury: AccountInfo<'info>,
+
+    /// CHECK: Revenue wallet 1 (45%) - verified against hardcoded address
+    #[account(
+        mut,
+        constraint = wallet_1.key() == WALLET_1 @ PostError::InvalidWallet
+    )]
+    pub wallet_1: AccountInfo<'info>,
+
+    /// CHECK: Revenue wallet 2 (10%), or the vesting vault when vesting is enabled
+    #[account(
+        mut,
+        constraint = wallet_2.key() == config.tranche_recipient() @ PostError::InvalidWallet
+    )]
+    pub wallet_2: AccountInfo<'info>,
+
+    /// CHECK: Revenue wallet 3 (45%) - verified against hardcoded address
+    #[account(
+        mut,
+        constraint = wallet_3.key() == WALLET_3 @ PostError::InvalidWallet
+    )]
+    pub wallet_3: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UnpinPost<'info>
+{
+    pub caller: Signer<'info>,
+
+    #[account(mut)]
+    pub target_account: Account<'info, TargetAccount>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptPost<'info>
+{
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(has_one = owner @ PostError::Unauthorized)]
+    pub target_account: Account<'info, TargetAccount>,
+
+    /// CHECK: Escrow author, receives the escrow rent
+    #[account(mut)]
+    pub author: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        close = author,
+        seeds = [ESCROW_SEED, escrow.post.as_ref()],
+        bump = escrow.bump,
+        has_one = author,
+        has_one = target_account @ PostError::Unauthorized
+    )]
+    pub escrow: Account<'info, PostEscrow>,
+
+    // Accounting for the current epoch
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = RevenueSnapshot::SIZE,
+        seeds = [REVENUE_SEED, Clock::get()?.epoch.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub revenue_snapshot: Box<Account<'info, RevenueSnapshot>>,
+
+    /// CHECK: PDA treasury - program controlled
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED, treasury_version_seed(&config.treasury_version)],
+        bump
+    )]
+    pub treasury: AccountInfo<'info>,
+
+    /// CHECK: Revenue wallet 1 (45%) - verified against hardcoded address
+    #[account(
+        mut,
+        constraint = wallet_1.key() == WALLET_1 @ PostError::InvalidWallet
+    )]
+    pub wallet_1: AccountInfo<'info>,
+
+    /// CHECK: Revenue wallet 2 (10%), or the vesting vault when vesting is enabled
+    #[account(
+        mut,
+        constraint = wallet_2.key() == config.tranche_recipient() @ PostError::InvalidWallet
+    )]
+    pub wallet_2: AccountInfo<'info>,
+
+    /// CHECK: Revenue wallet 3 (45%) - verified against hardcoded address
+    #[account(
+        mut,
+        constraint = wallet_3.key() == WALLET_3 @ PostError::InvalidWallet
+    )]
+    pub wallet_3: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReclaimEscrow<'info>
+{
+    #[account(mut)]
+    pub author: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        close = author,
+        seeds = [ESCROW_SEED, escrow.post.as_ref()],
+        bump = escrow.bump,
+        has_one = author @ PostError::Unauthorized
+    )]
+    pub escrow: Account<'info, PostEscrow>,
+
+    /// CHECK: PDA treasury - program controlled, receives the keep fee
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED, treasury_version_seed(&config.treasury_version)],
+        bump
+    )]
+    pub treasury: AccountInfo<'info>,
+}
+
+// ============================================================================
+// MULTI-MINT TREASURY CONTEXTS
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct InitializeMintTreasury<'info>
+{
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ PostError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = MintTreasury::SIZE,
+        seeds = [MINT_TREASURY_SEED, mint.key().as_ref()],
+        bump
+    )]
+    pub mint_treasury: Account<'info, MintTreasury>,
+
+    // Token vault owned by the sub-treasury PDA
+    #[account(
+        init,
+        payer = admin,
+        seeds = [MINT_VAULT_SEED, mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = mint_treasury,
+        token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateMintTreasury<'info>
+{
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ PostError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [MINT_TREASURY_SEED, mint_treasury.mint.as_ref()],
+        bump = mint_treasury.bump
+    )]
+    pub mint_treasury: Account<'info, MintTreasury>,
+}
+
+#[derive(Accounts)]
+#[instruction(target: String)]
+pub struct CreatePostToken<'info>
+{
+    #[account(mut)]
+    pub author: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = author
+    )]
+    pub author_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [MINT_TREASURY_SEED, mint.key().as_ref()],
+        bump = mint_treasury.bump,
+        has_one = mint,
+        has_one = vault
+    )]
+    pub mint_treasury: Box<Account<'info, MintTreasury>>,
+
+    #[account(mut)]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // Revenue wallet 1 (45%) token account for this mint
+    #[account(
+        mut,
+        token::mint = mint,
+        constraint = wallet_1.owner == WALLET_1 @ PostError::InvalidWallet
+    )]
+    pub wallet_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // Revenue wallet 2 (10%) token account for this mint, owned by the vesting vault when
+    // vesting is enabled (claimed with claim_vested_token)
+    #[account(
+        mut,
+        token::mint = mint,
+        constraint = wallet_2.owner == config.tranche_recipient() @ PostError::InvalidWallet
+    )]
+    pub wallet_2: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // Revenue wallet 3 (45%) token account for this mint
+    #[account(
+        mut,
+        token::mint = mint,
+        constraint = wallet_3.owner == WALLET_3 @ PostError::InvalidWallet
+    )]
+    pub wallet_3: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = author,
+        space = AuthorIndex::SIZE,
+        seeds = [AUTHOR_INDEX_SEED, author.key().as_ref()],
+        bump
+    )]
+    pub author_index: Box<Account<'info, AuthorIndex>>,
+
+    #[account(
+        init,
+        payer = author,
+        space = Post::SIZE,
+        seeds = [POST_SEED, author.key().as_ref(), target.as_bytes()],
+        bump
+    )]
+    pub post: Box<Account<'info, Post>>,
+
+    #[account(
+        init_if_needed,
+        payer = author,
+        space = AuthorStats::SIZE,
+        seeds = [AUTHOR_STATS_SEED, author.key().as_ref()],
+        bump
+    )]
+    pub author_stats: Box<Account<'info, AuthorStats>>,
+
+    #[account(
+        seeds = [STAKE_SEED, author.key().as_ref()],
+        bump = stake_account.bump
+    )]
+    pub stake_account: Option<Box<Account<'info, StakeAccount>>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+
+    // Usage counters (optional, see count)
+    #[account(
+        mut,
+        seeds = [METRICS_SEED],
+        bump = metrics.bump
+    )]
+    pub metrics: Option<Account<'info, PostMetrics>>,
+}
+
+// ============================================================================
+// SPONSORSHIP CONTEXTS
+// ============================================================================
+
+#[derive(Accounts)]
+#[instruction(target: String)]
+pub struct CreateSponsorship<'info>
+{
+    #[account(mut)]
+    pub sponsor: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    // Pyth SOL/USD price update (feed id and staleness checked in handler)
+    pub price_update: Account<'info, PriceUpdateV2>,
+
+    #[account(
+        init,
+        payer = sponsor,
+        space = Sponsorship::SIZE,
+        seeds = [SPONSORSHIP_SEED, sponsor.key().as_ref(), target_seed(&target).as_ref()],
+        bump
+    )]
+    pub sponsorship: Box<Account<'info, Sponsorship>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ProcessSponsorshipPeriod<'info>
+{
+    // Anyone can crank; pays the post rent on the first period
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [SPONSORSHIP_SEED, sponsorship.sponsor.as_ref(), target_seed(&sponsorship.target).as_ref()],
+        bump = sponsorship.bump
+    )]
+    pub sponsorship: Box<Account<'info, Sponsorship>>,
+
+    #[account(
+        init_if_needed,
+        payer = cranker,
+        space = Post::SIZE,
+        seeds = [POST_SEED, sponsorship.key().as_ref(), sponsorship.target.as_bytes()],
+        bump
+    )]
+    pub post: Box<Account<'info, Post>>,
+
+    // Accounting for the current epoch
+    #[account(
+        init_if_needed,
+        payer = cranker,
+        space = RevenueSnapshot::SIZE,
+        seeds = [REVENUE_SEED, Clock::get()?.epoch.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub revenue_snapshot: Box<Account<'info, RevenueSnapshot>>,
+
+    /// CHECK: PDA treasury - program controlled
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED, treasury_version_seed(&config.treasury_version)],
+        bump
+    )]
+    pub treasury: AccountInfo<'info>,
+
+    /// CHECK: Revenue wallet 1 (45%) - verified against hardcoded address
+    #[account(
+        mut,
+        constraint = wallet_1.key() == WALLET_1 @ PostError::InvalidWallet
+    )]
+    pub wallet_1: AccountInfo<'info>,
+
+    /// CHECK: Revenue wallet 2 (10%), or the vesting vault when vesting is enabled
+    #[account(
+        mut,
+        constraint = wallet_2.key() == config.tranche_recipient() @ PostError::InvalidWallet
+    )]
+    pub wallet_2: AccountInfo<'info>,
+
+    /// CHECK: Revenue wallet 3 (45%) - verified against hardcoded address
+    #[account(
+        mut,
+        constraint = wallet_3.key() == WALLET_3 @ PostError::InvalidWallet
+    )]
+    pub wallet_3: AccountInfo<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = cranker,
+        space = AuthorStats::SIZE,
+        seeds = [AUTHOR_STATS_SEED, sponsorship.sponsor.as_ref()],
+        bump
+    )]
+    pub author_stats: Box<Account<'info, AuthorStats>>,
+
+    #[account(
+        seeds = [STAKE_SEED, sponsorship.sponsor.as_ref()],
+        bump = stake_account.bump
+    )]
+    pub stake_account: Option<Box<Account<'info, StakeAccount>>>,
+
+    pub system_program: Program<'info, System>,
+
+    // Usage counters (optional, see count)
+    #[account(
+        mut,
+        seeds = [METRICS_SEED],
+        bump = metrics.bump
+    )]
+    pub metrics: Option<Account<'info, PostMetrics>>,
+}
+
+#[derive(Accounts)]
+pub struct CancelSponsorship<'info>
+{
+    #[account(mut)]
+    pub sponsor: Signer<'info>,
+
+    #[account(
+        mut,
+        close = sponsor,
+        has_one = sponsor @ PostError::Unauthorized
+    )]
+    pub sponsorship: Box<Account<'info, Sponsorship>>,
+
+    /// CHECK: post of the sponsorship, empty until its first period (handled in the handler)
+    #[account(
+        mut,
+        seeds = [POST_SEED, sponsorship.key().as_ref(), sponsorship.target.as_bytes()],
+        bump
+    )]
+    pub post: UncheckedAccount<'info>,
+}
+
+// ============================================================================
+// SCHEDULED POST CONTEXTS
+// ============================================================================
+
+#[derive(Accounts)]
+#[instruction(target: String)]
+pub struct SchedulePost<'info>
+{
+    #[account(mut)]
+    pub author: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    // Pyth SOL/USD price update (feed id and staleness checked in handler)
+    pub price_update: Account<'info, PriceUpdateV2>,
+
+    #[account(
+        init,
+        payer = author,
+        space = ScheduledPost::SIZE,
+        seeds = [SCHEDULED_SEED, author.key().as_ref(), target.as_bytes()],
+        bump
+    )]
+    pub scheduled_post: Box<Account<'info, ScheduledPost>>,
+
+    #[account(
+        init_if_needed,
+        payer = author,
+        space = AuthorStats::SIZE,
+        seeds = [AUTHOR_STATS_SEED, author.key().as_ref()],
+        bump
+    )]
+    pub author_stats: Box<Account<'info, AuthorStats>>,
+
+    #[account(
+        seeds = [STAKE_SEED, author.key().as_ref()],
+        bump = stake_account.bump
+    )]
+    pub stake_account: Option<Box<Account<'info, StakeAccount>>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PublishScheduledPost<'info>
+{
+    // Anyone can crank; fronts the post rent, reimbursed with the reward
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: Scheduled post author, receives the escrow rent on close
+    #[account(mut)]
+    pub author: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        close = author,
+        seeds = [SCHEDULED_SEED, author.key().as_ref(), scheduled_post.target.as_bytes()],
+        bump = scheduled_post.bump,
+        has_one = author
+    )]
+    pub scheduled_post: Box<Account<'info, ScheduledPost>>,
+
+    #[account(
+        init,
+        payer = cranker,
+        space = Post::SIZE,
+        seeds = [POST_SEED, author.key().as_ref(), scheduled_post.target.as_bytes()],
+        bump
+    )]
+    pub post: Box<Account<'info, Post>>,
+
+    // Profile index of the author (created on their first post, rent reimbursed from the escrow)
+    #[account(
+        init_if_needed,
+        payer = cranker,
+        space = AuthorIndex::SIZE,
+        seeds = [AUTHOR_INDEX_SEED, author.key().as_ref()],
+        bump
+    )]
+    pub author_index: Box<Account<'info, AuthorIndex>>,
+
+    // Accounting for the current epoch
+    #[account(
+        init_if_needed,
+        payer = cranker,
+        space = RevenueSnapshot::SIZE,
+        seeds = [REVENUE_SEED, Clock::get()?.epoch.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub revenue_snapshot: Box<Account<'info, RevenueSnapshot>>,
+
+    /// CHECK: PDA treasury - program controlled
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED, treasury_version_seed(&config.treasury_version)],
+        bump
+    )]
+    pub treasury: AccountInfo<'info>,
+
+    /// CHECK: Revenue wallet 1 (45%) - verified against hardcoded address
+    #[account(
+        mut,
+        constraint = wallet_1.key() == WALLET_1 @ PostError::InvalidWallet
+    )]
+    pub wallet_1: AccountInfo<'info>,
+
+    /// CHECK: Revenue wallet 2 (10%), or the vesting vault when vesting is enabled
+    #[account(
+        mut,
+        constraint = wallet_2.key() == config.tranche_recipient() @ PostError::InvalidWallet
+    )]
+    pub wallet_2: AccountInfo<'info>,
+
+    /// CHECK: Revenue wallet 3 (45%) - verified against hardcoded address
+    #[account(
+        mut,
+        constraint = wallet_3.key() == WALLET_3 @ PostError::InvalidWallet
+    )]
+    pub wallet_3: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    // Usage counters (optional, see count)
+    #[account(
+        mut,
+        seeds = [METRICS_SEED],
+        bump = metrics.bump
+    )]
+    pub metrics: Option<Account<'info, PostMetrics>>,
+}
+
+#[derive(Accounts)]
+pub struct CancelScheduledPost<'info>
+{
+    #[account(mut)]
+    pub author: Signer<'info>,
+
+    #[account(
+        mut,
+        close = author,
+        has_one = author @ PostError::Unauthorized
+    )]
+    pub scheduled_post: Box<Account<'info, ScheduledPost>>,
+}
+
+// ============================================================================
+// USAGE METRICS CONTEXTS
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct InitializeMetrics<'info>
+{
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = PostMetrics::SIZE,
+        seeds = [METRICS_SEED],
+        bump
+    )]
+    pub metrics: Account<'info, PostMetrics>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct GetMetrics<'info>
+{
+    #[account(
+        seeds = [METRICS_SEED],
+        bump = metrics.bump
+    )]
+    pub metrics: Account<'info, PostMetrics>,
+}
+
+#[account]
+pub struct Config
+{
+    pub admin: Pubkey,
+    // Minimum bid in micro-USD (should stay above what Privacy Cash delivers from its minimum deposit)
+    pub min_bid_usd: u64,
+    // Share of each bid paid to the referrer, in basis points
+    pub referral_bps: u16,
+    // Bubblegum tree receiving compressed NFT post receipts
+    pub receipt_tree: Pubkey,
+    // Per-author rate limit: at most max_posts_per_window posts per rate_window_secs (0 = no cap)
+    pub rate_window_secs: i64,
+    pub max_posts_per_window: u16,
+    // Bids of at least this many micro-USD bypass the rate limit (0 = no waiver)
+    pub rate_waiver_bid_usd: u64,
+    // Stake-to-post: minimum locked stake in lamports (0 = disabled) and unstake cooldown
+    pub min_stake: u64,
+    pub stake_cooldown_secs: i64,
+    // Vesting vault receiving the 10% tranche (default = paid instantly to WALLET_2)
+    pub vesting_vault: Pubkey,
+    // Admin handover target (e.g. an SPL Governance native treasury), default = none
+    pub pending_admin: Pubkey,
+    // Escrowed posts: acceptance window and share of the bid kept on reclaim
+    pub escrow_window_secs: i64,
+    pub escrow_keep_fee_bps: u16,
+    // Price of an upvote in lamports
+    pub upvote_lamports: u64,
+    // Treasury PDA version holding the funds (1 = ["treasury"], see migrate_treasury)
+    pub treasury_version: u8,
+    // Authority attesting off-chain post views (default = none)
+    pub view_attestor: Pubkey,
+    pub bump: u8,
+}
+
+impl Config {
+    pub const SIZE: usize = sizes::CONFIG_SIZE;
+
+    // Recipient of the 10% tranche in distribute_treasury
+    pub fn tranche_recipient(&self) -> Pubkey {
+        if self.vesting_vault == Pubkey::default() {
+            WALLET_2
+        } else {
+            self.vesting_vault
+        }
+    }
+}
+
+#[account]
+pub struct Referral
+{
+    pub wallet: Pubkey,
+    pub total_earned: u64,
+    pub referral_count: u64,
+    pub bump: u8,
+}
+
+impl Referral {
+    pub const SIZE: usize = sizes::REFERRAL_SIZE;
+}
+
+// Curated charity that authors can designate to receive share_bps of their bid
+#[account]
+pub struct Charity
+{
+    pub wallet: Pubkey,
+    pub share_bps: u16,
+    pub total_received: u64,
+    pub bump: u8,
+}
+
+impl Charity {
+    pub const SIZE: usize = sizes::CHARITY_SIZE;
+}
+
+// Per-author profile index: live post count and the most recent post addresses (newest last)
+#[account]
+pub struct AuthorIndex
+{
+    pub author: Pubkey,
+    pub post_count: u64,
+    pub recent_posts: Vec<Pubkey>,
+    pub bump: u8,
+}
+
+impl AuthorIndex {
+    pub const SIZE: usize = sizes::AUTHOR_INDEX_SIZE;
+
+    pub fn record_post(&mut self, author: Pubkey, post: Pubkey, bump: u8) {
+        self.author = author;
+        self.bump = bump;
+        self.post_count += 1;
+        if self.recent_posts.len() == AUTHOR_INDEX_RECENT {
+            self.recent_posts.remove(0);
+        }
+        self.recent_posts.push(post);
+    }
+
+    pub fn remove_post(&mut self, post: &Pubkey) {
+        self.post_count = self.post_count.saturating_sub(1);
+        self.recent_posts.retain(|p| p != post);
+    }
+}
+
+#[account]
+pub struct AuthorStats
+{
+    pub author: Pubkey,
+    // Start of the current rate-limit window
+    pub window_start: i64,
+    pub window_posts: u16,
+    pub total_posts: u64,
+    // Last nonce consumed by create_post_relayed (each relayed signature is single use)
+    pub relay_nonce: u64,
+    pub bump: u8,
+}
+
+impl AuthorStats {
+    pub const SIZE: usize = sizes::AUTHOR_STATS_SIZE;
+}
+
+#[account]
+pub struct StakeAccount
+{
+    pub author: Pubkey,
+    // Staked lamports (held by this account on top of its rent)
+    pub amount: u64,
+    // When the unstake cooldown started (0 = locked)
+    pub unlock_requested_at: i64,
+    pub bump: u8,
+}
+
+impl StakeAccount {
+    pub const SIZE: usize = sizes::STAKE_ACCOUNT_SIZE;
+}
+
+// Per-epoch revenue accounting, written by every post creation
+#[account]
+pub struct RevenueSnapshot
+{
+    pub epoch: u64,
+    // Bids paid for posts (referral slices included)
+    pub gross_bids: u64,
+    // Lamports split between the revenue wallets
+    pub distributed: u64,
+    pub post_count: u64,
+    pub bump: u8,
+}
+
+impl RevenueSnapshot {
+    pub const SIZE: usize = sizes::REVENUE_SNAPSHOT_SIZE;
+
+    pub fn record(&mut self, bump: u8, gross_bid: u64, distributed: u64) -> Result<()> {
+        self.epoch = Clock::get()?.epoch;
+        self.gross_bids += gross_bid;
+        self.distributed += distributed;
+        self.post_count += 1;
+        self.bump = bump;
+        Ok(())
+    }
+}
+
+// Holds the vested 10% tranche; its lamports above rent are what has been received but not claimed
+#[account]
+pub struct VestingVault
+{
+    pub beneficiary: Pubkey,
+    pub start: i64,
+    pub duration: i64,
+    pub total_claimed: u64,
+    pub bump: u8,
+}
+
+impl VestingVault {
+    pub const SIZE: usize = sizes::VESTING_VAULT_SIZE;
+
+    // Part of `received` (everything the vault ever got, claims included) vested at `now`
+    pub fn vested_at(&self, received: u64, now: i64) -> u64 {
+        released_refund(received, self.start, self.duration, now)
+    }
+
+    // What a claim at `now` pays out
+    pub fn claimable(&self, received: u64, now: i64) -> u64 {
+        self.vested_at(received, now).saturating_sub(self.total_claimed)
+    }
+}
+
+// Registered owner of a target, keyed by target_seed(target)
+#[account]
+pub struct TargetAccount
+{
+    pub target: String,
+    pub owner: Pubkey,
+    // Post pinned on the target page (default = none) and pin expiry (0 = until unpinned)
+    pub pinned_post: Pubkey,
+    pub pinned_until: i64,
+    // Fee in lamports charged to the author of a pinned post
+    pub pin_fee: u64,
+    pub bump: u8,
+    // v2: attested identity key that proved ownership (default = registered by the admin
+    // without proof) and when
+    pub identity_key: Pubkey,
+    pub verified_at: i64,
+}
+
+impl TargetAccount {
+    pub const SIZE: usize = sizes::TARGET_ACCOUNT_SIZE;
+}
+
+// Key the admin attests belongs to the identity behind a target, keyed by target_seed(target)
+#[account]
+pub struct TargetIdentity
+{
+    pub identity_key: Pubkey,
+    pub attested_by: Pubkey,
+    pub attested_at: i64,
+    pub bump: u8,
+}
+
+impl TargetIdentity {
+    pub const SIZE: usize = sizes::TARGET_IDENTITY_SIZE;
+}
+
+// Authoritative reply from the target owner to a post
+#[account]
+pub struct OfficialReply
+{
+    pub post: Pubkey,
+    pub owner: Pubkey,
+    pub content: String,
+    pub timestamp: i64,
+    pub bump: u8,
+}
+
+impl OfficialReply {
+    pub const SIZE: usize = sizes::OFFICIAL_REPLY_SIZE;
+}
+
+// Bid held for an escrowed post until the target owner accepts it or the author reclaims it
+#[account]
+pub struct PostEscrow
+{
+    pub post: Pubkey,
+    pub author: Pubkey,
+    pub target_account: Pubkey,
+    pub amount: u64,
+    pub deadline: i64,
+    pub bump: u8,
+}
+
+impl PostEscrow {
+    pub const SIZE: usize = sizes::POST_ESCROW_SIZE;
+}
+
+// Per-mint sub-treasury: bids in `mint` accumulate in `vault` and are split like SOL bids
+#[account]
+pub struct MintTreasury
+{
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    // Minimum bid in the mint's base units
+    pub min_bid: u64,
+    pub total_received: u64,
+    pub total_distributed: u64,
+    pub post_count: u64,
+    // Vested tranche of this mint claimed from the vesting vault's token account
+    pub vested_claimed: u64,
+    pub bump: u8,
+}
+
+impl MintTreasury {
+    pub const SIZE: usize = sizes::MINT_TREASURY_SIZE;
+}
+
+// Proof that `voter` upvoted `post` (prevents double counting)
+#[account]
+pub struct UpvoteReceipt
+{
+    pub post: Pubkey,
+    pub voter: Pubkey,
+    pub timestamp: i64,
+    pub bump: u8,
+}
+
+impl UpvoteReceipt {
+    pub const SIZE: usize = sizes::UPVOTE_RECEIPT_SIZE;
+}
+
+// Pre-funded recurring promotion; holds the unspent periods' bids on top of its rent
+#[account]
+pub struct Sponsorship
+{
+    pub sponsor: Pubkey,
+    pub target: String,
+    pub content: String,
+    pub bid_per_period: u64,
+    pub period_secs: i64,
+    pub periods_remaining: u32,
+    pub next_period_at: i64,
+    pub bump: u8,
+}
+
+impl Sponsorship {
+    pub const SIZE: usize = sizes::SPONSORSHIP_SIZE;
+}
+
+// Post queued for publication; escrows the bid, crank reward and post rent
+#[account]
+pub struct ScheduledPost
+{
+    pub author: Pubkey,
+    pub target: String,
+    pub content: String,
+    pub bid: u64,
+    pub publish_at: i64,
+    pub bump: u8,
+}
+
+impl ScheduledPost {
+    pub const SIZE: usize = sizes::SCHEDULED_POST_SIZE;
+}
+
+// Fields are only ever appended (see migrate_post): `author` and `target` keep the stable
+// offsets clients filter on (see xray_common::layout), so never move them.
+#[account]
+pub struct Post
+{
+    pub author: Pubkey,
+    pub target: String,
+    pub content: String,
+    pub bid: u64,
+    pub timestamp: i64,
+    pub bump: u8,
+    // Layout version (0 = legacy, upgraded with migrate_post)
+    pub version: u8,
+    // v2: cumulative boosts and time-decayed ranking score (see rank_score)
+    pub total_boosts: u64,
+    pub rank_score: u64,
+    pub rank_updated_at: i64,
+    // v3: mint the bid was paid in (default = native SOL)
+    pub bid_mint: Pubkey,
+    // v4: paid upvotes (one per voter, see UpvoteReceipt)
+    pub upvotes: u64,
+    // v5: POST_FLAG_* bits
+    pub flags: u8,
+    // v6: attested off-chain impressions (see record_views)
+    pub views: u64,
+    // v7: designated charity wallet (default = none) and the share of the bid it received
+    pub charity: Pubkey,
+    pub charity_amount: u64,
+    // v8: xray_common::license code (0 = none) and the author's Ed25519 signature over
+    // content_hash(content) (zeroed = none), both set with set_post_provenance
+    pub license: u8,
+    pub content_signature: [u8; 64],
+}
+
+impl Post {
+    pub const SIZE: usize = sizes::POST_SIZE;
+
+    pub fn refresh_rank(&mut self, now: i64) {
+        // Token bids are in mint units and not comparable with lamports, so only SOL bids weigh in
+        let bid = if self.bid_mint == Pubkey::default() { self.bid } else { 0 };
+        self.rank_score = rank_score(bid.saturating_add(self.total_boosts), now - self.timestamp);
+        self.rank_updated_at = now;
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AuctionStatus
+{
+    // Waiting for the encrypted empty state
+    Initializing,
+    // Accepting sealed bids
+    Open,
+    // Winner selection queued
+    Resolving,
+    // Winner and clearing price revealed
+    Resolved,
+    // No valid bid
+    NoWinner,
+    // Remaining deposits moved to the refund vault
+    Refunding,
+}
+
+#[account]
+pub struct Auction
+{
+    // Encrypted SealedAuctionState (MXE key) - read by the MPC at a fixed offset, keep first
+    pub encrypted_state: [[u8; 32]; 3],
+    pub state_nonce: u128,
+    pub id: u64,
+    pub authority: Pubkey,
+    // Uniform deposit escrowed by every bidder (upper bound on a bid)
+    pub deposit: u64,
+    pub reserve_price: u64,
+    pub end_time: i64,
+    pub featured_duration: i64,
+    pub status: AuctionStatus,
+    pub computation_pending: bool,
+    // When the pending computation was queued (see reset_auction_pending)
+    pub pending_since: i64,
+    // Offset of the pending computation, the only callback accepted
+    pub pending_computation: u64,
+    pub bid_count: u8,
+    pub bid_posts: [Pubkey; MAX_SEALED_BIDS],
+    pub winner_index: u8,
+    pub clearing_price: u64,
+    pub featured_post: Pubkey,
+    pub featured_until: i64,
+    pub settled: bool,
+    pub bump: u8,
+}
+
+impl Auction {
+    pub const ENCRYPTED_STATE_OFFSET: u32 = 8;
+    pub const ENCRYPTED_STATE_LEN: u32 = 32 * 3;
+    pub const SIZE: usize = sizes::AUCTION_SIZE;
+}
+
+#[account]
+pub struct SealedBid
+{
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    pub post: Pubkey,
+    pub index: u8,
+    pub deposit: u64,
+    pub bump: u8,
+}
+
+impl SealedBid {
+    pub const SIZE: usize = sizes::SEALED_BID_SIZE;
+}
+
+// Deposits owed to the bidders of a resolved auction, keyed by auction
+#[account]
+pub struct RefundVault
+{
+    pub auction: Pubkey,
+    // Auction terms needed to compute each bid's refund
+    pub deposit: u64,
+    pub winner_index: u8,
+    pub clearing_price: u64,
+    // Lamports moved in from the auction
+    pub amount: u64,
+    // Claimed so far, by bid index
+    pub claimed: [u64; MAX_SEALED_BIDS],
+    // Linear release of every refund from stream_start over stream_duration (0 = at once)
+    pub stream_start: i64,
+    pub stream_duration: i64,
+    // After this, unclaimed refunds can be sent to the treasury
+    pub expires_at: i64,
+    pub bump: u8,
+}
+
+impl RefundVault {
+    pub const SIZE: usize = sizes::REFUND_VAULT_SIZE;
+    pub const NO_WINNER: u8 = u8::MAX;
+
+    // Refund owed to bid `index`: its deposit, minus the clearing price for the winner
+    pub fn refund_of(&self, index: u8, deposit: u64) -> u64 {
+        if index == self.winner_index {
+            deposit.saturating_sub(self.clearing_price)
+        } else {
+            deposit
+        }
+    }
+}
+
+#[account]
+pub struct SealedContent
+{
+    pub post: Pubkey,
+    // Content key encrypted for the MXE (Enc<Shared, ContentKey>)
+    pub encrypted_key: [[u8; 32]; 2],
+    pub key_pubkey: [u8; 32],
+    pub key_nonce: u128,
+    // Cumulative boosts (lamports) required to reveal the key
+    pub unlock_threshold: u64,
+    pub total_boosts: u64,
+    pub computation_pending: bool,
+    // When the pending reveal was queued (see reset_sealed_post_pending)
+    pub pending_since: i64,
+    pub revealed: bool,
+    // Plain content key, set by the reveal callback
+    pub content_key: [u8; 32],
+    pub bump: u8,
+}
+
+impl SealedContent {
+    pub const SIZE: usize = sizes::SEALED_CONTENT_SIZE;
+}
+
+// Global usage counters. Only instructions given this account count, and a failed
+// transaction rolls its counters back: an aborted computation stays queued but not
+// completed until reset_sealed_post_pending clears it and counts it failed.
+#[account]
+pub struct PostMetrics
+{
+    pub posts_created: u64,
+    // Private replies and target notices sent through private_messages
+    pub messages_sent: u64,
+    pub computations_queued: u64,
+    pub computations_completed: u64,
+    // Aborted computations cleared by a pending reset
+    pub computations_failed: u64,
+    pub upvotes: u64,
+    pub boosts: u64,
+    pub bump: u8,
+}
+
+impl PostMetrics {
+    pub const SIZE: usize = sizes::POST_METRICS_SIZE;
+}
+
+// Return data of get_metrics
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct MetricsView
+{
+    pub posts_created: u64,
+    pub messages_sent: u64,
+    pub computations_queued: u64,
+    pub computations_completed: u64,
+    pub computations_failed: u64,
+    // Queued but neither completed nor failed: in flight, or aborted and not reset yet
+    pub computations_unfinished: u64,
+    pub upvotes: u64,
+    pub boosts: u64,
+}
+
+impl MetricsView {
+    pub fn of(metrics: &PostMetrics) -> Self {
+        Self {
+            posts_created: metrics.posts_created,
+            messages_sent: metrics.messages_sent,
+            computations_queued: metrics.computations_queued,
+            computations_completed: metrics.computations_completed,
+            computations_failed: metrics.computations_failed,
+            computations_unfinished: metrics
+                .computations_queued
+                .saturating_sub(metrics.computations_completed)
+                .saturating_sub(metrics.computations_failed),
+            upvotes: metrics.upvotes,
+            boosts: metrics.boosts,
+        }
+    }
+}
+
+#[event]
+pub struct PostCreated
+{
+    pub post: Pubkey,
+    pub author: Pubkey,
+    pub target: String,
+    pub bid: u64,
+    // Default = native SOL
+    pub bid_mint: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PrivateReplySent
+{
+    pub post: Pubkey,
+    pub message: Pubkey,
+    pub sender: Pubkey,
+    pub recipient: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PostTargetNotified
+{
+    pub post: Pubkey,
+    pub message: Pubkey,
+    pub author: Pubkey,
+    pub target_owner: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PostProvenanceSet
+{
+    pub post: Pubkey,
+    pub author: Pubkey,
+    pub license: u8,
+    pub content_hash: [u8; 32],
+}
+
+#[event]
+pub struct SealedBidPlaced
+{
+    pub auction: Pubkey,
+    pub bid_count: u8,
+}
+
+#[event]
+pub struct FeaturedPostSelected
+{
+    pub auction: Pubkey,
+    pub post: Pubkey,
+    pub clearing_price: u64,
+    pub featured_until: i64,
+}
+
+#[event]
+pub struct RefundVaultOpened
+{
+    pub auction: Pubkey,
+    pub amount: u64,
+    pub stream_end: i64,
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct RefundClaimed
+{
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    pub amount: u64,
+    pub remaining: u64,
+}
+
+#[event]
+pub struct RefundsExpired
+{
+    pub auction: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct PostBoosted
+{
+    pub post: Pubkey,
+    pub booster: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct PostContentUnlocked
+{
+    pub post: Pubkey,
+    pub content_key: [u8; 32],
+}
+
+#[event]
+pub struct PostReceiptMinted
+{
+    pub post: Pubkey,
+    pub author: Pubkey,
+    pub tree: Pubkey,
+    pub target: String,
+    pub content_hash: [u8; 32],
+    pub bid: u64,
+}
+
+#[event]
+pub struct TreasurySwept
+{
+    pub admin: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct StakeSlashed
+{
+    pub author: Pubkey,
+    pub post: Pubkey,
+    pub moderator: Pubkey,
+    pub amount: u64,
+    pub remaining: u64,
+}
+
+#[event]
+pub struct TargetOwnershipProven
+{
+    pub target_account: Pubkey,
+    pub owner: Pubkey,
+    pub previous_owner: Pubkey,
+    pub identity_key: Pubkey,
+}
+
+#[event]
+pub struct PostMigrated
+{
+    pub post: Pubkey,
+    pub from_version: u8,
+    pub to_version: u8,
+}
+
+#[event]
+pub struct VestedClaimed
+{
+    pub beneficiary: Pubkey,
+    pub amount: u64,
+    pub total_claimed: u64,
+}
+
+#[event]
+pub struct VestedTokenClaimed
+{
+    pub beneficiary: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub total_claimed: u64,
+}
+
+#[event]
+pub struct AdminChanged
+{
+    pub previous_admin: Pubkey,
+    pub new_admin: Pubkey,
+}
+
+#[event]
+pub struct PostAccepted
+{
+    pub post: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct EscrowReclaimed
+{
+    pub post: Pubkey,
+    pub author: Pubkey,
+    pub refunded: u64,
+    pub fee: u64,
+}
+
+#[event]
+pub struct PostUpvoted
+{
+    pub post: Pubkey,
+    pub voter: Pubkey,
+    pub amount: u64,
+    pub upvotes: u64,
+}
+
+#[event]
+pub struct TreasuryMigrated
+{
+    pub admin: Pubkey,
+    pub from: Pubkey,
+    pub to: Pubkey,
+    pub version: u8,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OfficialReplyPosted
+{
+    pub post: Pubkey,
+    pub reply: Pubkey,
+    pub owner: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PostPinned
+{
+    pub target: Pubkey,
+    pub post: Pubkey,
+    pub fee: u64,
+    pub pinned_until: i64,
+}
+
+#[event]
+pub struct SponsorshipPeriodProcessed
+{
+    pub sponsorship: Pubkey,
+    pub post: Pubkey,
+    pub amount: u64,
+    pub periods_remaining: u32,
+}
+
+#[event]
+pub struct ViewsRecorded
+{
+    pub attestor: Pubkey,
+    pub posts: u32,
+    pub total_views: u64,
+    pub timestamp: i64,
+}
+
+// Validation failure (emitted with the `telemetry` feature, see report_failure)
+#[event]
+pub struct InstructionFailed
+{
+    pub code: u32,
+    // Both programs number their codes from 6000
+    pub program: Pubkey,
+    pub signer: Pubkey,
+    pub context: u64,
+    pub timestamp: i64,
 }
 
+// Codes 6000..6999 (xray_common::errors::POST_ERRORS): new variants go last
 #[error_code]
 pub enum PostError {
-    #[msg("Bid must be at least 0.007 SOL")]
+    #[msg("Bid is below the USD-pegged minimum")]
     BidTooLow,
-    #[msg("Target too long (max 64 chars)")]
+    #[msg("Target too long (max 32 bytes)")]
     TargetTooLong,
-    #[msg("Content too long (max 512 chars)")]
+    #[msg("Content too long (max 512 bytes)")]
     ContentTooLong,
     #[msg("Invalid wallet address")]
     InvalidWallet,
+    #[msg("Invalid oracle price")]
+    InvalidPrice,
+    #[msg("Invalid config value")]
+    InvalidConfig,
+    #[msg("Unauthorized")]
+    Unauthorized,
+    #[msg("Invalid referrer")]
+    InvalidReferrer,
+    #[msg("Missing or invalid Ed25519 signature")]
+    InvalidSignature,
+    #[msg("Recipient is not the post author's messaging account")]
+    InvalidRecipient,
+    #[msg("The computation was aborted")]
+    AbortedComputation,
+    #[msg("Cluster not set")]
+    ClusterNotSet,
+    #[msg("Auction is not open for bids")]
+    AuctionNotOpen,
+    #[msg("Auction bidding period has not ended")]
+    AuctionStillOpen,
+    #[msg("Auction has a computation in progress")]
+    AuctionBusy,
+    #[msg("Auction has reached the maximum number of bids")]
+    AuctionFull,
+    #[msg("Auction is not resolved")]
+    AuctionNotResolved,
+    #[msg("Auction already settled")]
+    AuctionAlreadySettled,
+    #[msg("Content already revealed")]
+    AlreadyRevealed,
+    #[msg("A computation is already pending")]
+    ComputationPending,
+    #[msg("Boost threshold not reached")]
+    ThresholdNotReached,
+    #[msg("Invalid or incomplete receipt tree accounts")]
+    InvalidReceiptTree,
+    #[msg("Posting rate limit reached, retry later or bid higher")]
+    RateLimited,
+    #[msg("A locked stake of at least the minimum is required to post")]
+    StakeRequired,
+    #[msg("Invalid stake amount")]
+    InvalidStakeAmount,
+    #[msg("Stake is still locked")]
+    StakeLocked,
+    #[msg("Account is not a post")]
+    InvalidPost,
+    #[msg("Post is already at the current version")]
+    AlreadyMigrated,
+    #[msg("Target cannot be empty")]
+    TargetEmpty,
+    #[msg("Content cannot be empty or whitespace only")]
+    ContentEmpty,
+    #[msg("Target must be lowercase")]
+    TargetNotNormalized,
+    #[msg("Control or whitespace characters are not allowed")]
+    InvalidCharacters,
+    #[msg("Post must be migrated to the current version first")]
+    PostNotMigrated,
+    #[msg("Nothing to claim yet")]
+    NothingToClaim,
+    #[msg("Governance account is not owned by the governance program")]
+    InvalidGovernance,
+    #[msg("Escrow acceptance window is still open")]
+    EscrowStillOpen,
+    #[msg("Sponsorship has no periods left")]
+    SponsorshipExhausted,
+    #[msg("Sponsorship period is not due yet")]
+    PeriodNotDue,
+    #[msg("Scheduled post cannot be published yet")]
+    NotYetPublishable,
+    #[msg("Charity wallet does not match the registry entry")]
+    InvalidCharity,
+    #[msg("Post target was already notified")]
+    TargetAlreadyNotified,
+    #[msg("Deposits were moved to the refund vault, use claim_refund")]
+    RefundsVaulted,
+    #[msg("Refund claim window has expired")]
+    RefundExpired,
+    #[msg("Refund claim window is still open")]
+    RefundNotExpired,
+    #[msg("Unknown license code")]
+    InvalidLicense,
+    #[msg("Post provenance is already set")]
+    ProvenanceAlreadySet,
+    #[msg("Handle targets must be claimed with an ownership proof")]
+    TargetProofRequired,
+    #[msg("No computation is pending")]
+    NoComputationPending,
+    #[msg("The pending computation has not timed out yet")]
+    PendingNotExpired,
+    #[msg("Post has a live escrow, accept or reclaim it first")]
+    PostEscrowed,
+    #[msg("Relayed post nonce already used")]
+    RelayNonceUsed,
+    #[msg("Winning deposit is below the clearing price")]
+    DepositBelowClearingPrice,
+    #[msg("Callback does not match the pending computation")]
+    StaleCallback,
+    #[msg("Escrow deadline has passed, the offer can no longer be accepted")]
+    EscrowExpired,
 }
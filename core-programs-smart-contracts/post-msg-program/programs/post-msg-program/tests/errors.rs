@@ -0,0 +1,80 @@
+//! `PostError` against the registry in `xray_common::errors`: codes, names and messages.
+
+use post_msg_program::PostError;
+use xray_common::errors::{self, POST_ERRORS};
+
+/// Every variant, in declaration order
+const VARIANTS: &[PostError] = &[
+    PostError::BidTooLow,
+    PostError::TargetTooLong,
+    PostError::ContentTooLong,
+    PostError::InvalidWallet,
+    PostError::InvalidPrice,
+    PostError::InvalidConfig,
+    PostError::Unauthorized,
+    PostError::InvalidReferrer,
+    PostError::InvalidSignature,
+    PostError::InvalidRecipient,
+    PostError::AbortedComputation,
+    PostError::ClusterNotSet,
+    PostError::AuctionNotOpen,
+    PostError::AuctionStillOpen,
+    PostError::AuctionBusy,
+    PostError::AuctionFull,
+    PostError::AuctionNotResolved,
+    PostError::AuctionAlreadySettled,
+    PostError::AlreadyRevealed,
+    PostError::ComputationPending,
+    PostError::ThresholdNotReached,
+    PostError::InvalidReceiptTree,
+    PostError::RateLimited,
+    PostError::StakeRequired,
+    PostError::InvalidStakeAmount,
+    PostError::StakeLocked,
+    PostError::InvalidPost,
+    PostError::AlreadyMigrated,
+    PostError::TargetEmpty,
+    PostError::ContentEmpty,
+    PostError::TargetNotNormalized,
+    PostError::InvalidCharacters,
+    PostError::PostNotMigrated,
+    PostError::NothingToClaim,
+    PostError::InvalidGovernance,
+    PostError::EscrowStillOpen,
+    PostError::SponsorshipExhausted,
+    PostError::PeriodNotDue,
+    PostError::NotYetPublishable,
+    PostError::InvalidCharity,
+    PostError::TargetAlreadyNotified,
+    PostError::RefundsVaulted,
+    PostError::RefundExpired,
+    PostError::RefundNotExpired,
+    PostError::InvalidLicense,
+    PostError::ProvenanceAlreadySet,
+    PostError::TargetProofRequired,
+    PostError::NoComputationPending,
+    PostError::PendingNotExpired,
+    PostError::PostEscrowed,
+    PostError::RelayNonceUsed,
+    PostError::DepositBelowClearingPrice,
+    PostError::StaleCallback,
+    PostError::EscrowExpired,
+];
+
+#[test]
+fn variants_match_the_registry() {
+    assert_eq!(VARIANTS.len(), POST_ERRORS.errors.len(), "register new PostError variants in xray_common::errors");
+    for (variant, registered) in VARIANTS.iter().zip(POST_ERRORS.iter()) {
+        assert_eq!(u32::from(*variant), registered.code);
+        assert_eq!(variant.name(), registered.name);
+        assert_eq!(variant.to_string(), registered.msg);
+    }
+}
+
+#[test]
+fn codes_resolve_to_this_program() {
+    for variant in VARIANTS {
+        let code = u32::from(*variant);
+        assert_eq!(errors::lookup_in(&post_msg_program::ID, code).map(|registered| registered.code), Some(code));
+    }
+}
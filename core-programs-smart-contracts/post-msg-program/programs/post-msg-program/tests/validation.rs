@@ -0,0 +1,184 @@
+//! Property tests for target/content validation and bid arithmetic.
+
+use anchor_lang::error::Error;
+use post_msg_program::{
+    bps_share, rank_score, released_refund, revenue_split, usd_to_lamports, validate_post_fields, validate_target,
+    PostError, VestingVault, BPS_DENOMINATOR, RANK_HALF_LIFE, WALLET_2,
+};
+use proptest::prelude::*;
+use xray_common::sizes::{MAX_CONTENT_LEN, MAX_TARGET_LEN};
+
+fn vesting_vault(start: i64, duration: i64) -> VestingVault {
+    VestingVault { beneficiary: WALLET_2, start, duration, total_claimed: 0, bump: 255 }
+}
+
+/// Applies a claim at `now` the way claim_vested does and returns the amount paid
+fn claim(vault: &mut VestingVault, received: u64, now: i64) -> u64 {
+    let amount = vault.claimable(received, now);
+    vault.total_claimed += amount;
+    amount
+}
+
+#[test]
+fn vesting_follows_the_original_schedule_across_claims() {
+    let mut vault = vesting_vault(1_000, 1_000);
+
+    // A quarter of the first deposit, then half of both deposits minus what was claimed
+    assert_eq!(claim(&mut vault, 1_000, 1_250), 250);
+    assert_eq!(claim(&mut vault, 2_000, 1_500), 750);
+    assert_eq!(claim(&mut vault, 2_000, 1_500), 0);
+    // Frequent deposits don't push the end of the schedule back
+    assert_eq!(claim(&mut vault, 2_100, 2_000), 1_100);
+    assert_eq!(vault.total_claimed, 2_100);
+    assert_eq!(claim(&mut vault, 3_000, 2_001), 900);
+}
+
+fn is_error(result: Result<impl std::fmt::Debug, Error>, expected: PostError) -> bool {
+    matches!(result, Err(Error::AnchorError(err)) if err.error_code_number == u32::from(expected))
+}
+
+/// Targets already in canonical form
+fn canonical_target() -> impl Strategy<Value = String> {
+    proptest::string::string_regex(&format!("[a-z0-9_.@-]{{1,{MAX_TARGET_LEN}}}")).unwrap()
+}
+
+proptest! {
+    #[test]
+    fn canonical_targets_are_accepted(target in canonical_target()) {
+        prop_assert!(validate_target(&target).is_ok());
+    }
+
+    #[test]
+    fn accepted_targets_fit_their_layout(target in any::<String>()) {
+        if validate_target(&target).is_ok() {
+            prop_assert!(!target.is_empty() && target.len() <= MAX_TARGET_LEN);
+            prop_assert_eq!(target.trim(), target.as_str());
+        }
+    }
+
+    #[test]
+    fn long_targets_are_rejected(target in proptest::string::string_regex("[a-z]{33,200}").unwrap()) {
+        prop_assert!(is_error(validate_target(&target), PostError::TargetTooLong));
+    }
+
+    #[test]
+    fn targets_with_whitespace_are_rejected(
+        target in canonical_target(),
+        at in any::<prop::sample::Index>(),
+        space in prop::sample::select(vec![' ', '\t', '\n', '\u{a0}', '\u{2003}']),
+    ) {
+        let mut target: Vec<char> = target.chars().collect();
+        target.insert(at.index(target.len() + 1), space);
+        let target: String = target.into_iter().collect();
+        prop_assert!(validate_target(&target).is_err());
+    }
+
+    #[test]
+    fn uppercase_targets_are_rejected(target in proptest::string::string_regex("[a-z]{0,15}[A-Z][a-z]{0,15}").unwrap()) {
+        prop_assert!(is_error(validate_target(&target), PostError::TargetNotNormalized));
+    }
+
+    #[test]
+    fn accepted_content_is_trimmed_and_bounded(target in canonical_target(), content in any::<String>()) {
+        if let Ok(stored) = validate_post_fields(&target, &content) {
+            prop_assert!(!stored.is_empty() && stored.len() <= MAX_CONTENT_LEN);
+            prop_assert_eq!(stored.trim(), stored.as_str());
+        }
+    }
+
+    #[test]
+    fn surrounding_whitespace_does_not_change_content(
+        target in canonical_target(),
+        content in proptest::string::string_regex("[ -~]{1,400}").unwrap(),
+        padding in proptest::string::string_regex("[ \t\n]{0,40}").unwrap(),
+    ) {
+        let padded = format!("{padding}{content}{padding}");
+        prop_assert_eq!(
+            validate_post_fields(&target, &content).ok(),
+            validate_post_fields(&target, &padded).ok()
+        );
+    }
+
+    #[test]
+    fn oversized_content_is_rejected(target in canonical_target(), content in proptest::string::string_regex("[a-z]{513,1024}").unwrap()) {
+        prop_assert!(is_error(validate_post_fields(&target, &content), PostError::ContentTooLong));
+    }
+
+    #[test]
+    fn revenue_split_distributes_the_whole_bid(amount in any::<u64>()) {
+        let (amount_1, amount_2, amount_3) = revenue_split(amount);
+        prop_assert_eq!(amount_1 as u128 + amount_2 as u128 + amount_3 as u128, amount as u128);
+        prop_assert!(amount_2 <= amount_1 && amount_1 <= amount_3);
+    }
+
+    #[test]
+    fn bps_share_never_exceeds_the_amount(amount in any::<u64>(), bps in any::<u16>()) {
+        let share = bps_share(amount, bps);
+        prop_assert!(share <= amount);
+        if bps as u64 >= BPS_DENOMINATOR {
+            prop_assert_eq!(share, amount);
+        }
+    }
+
+    #[test]
+    fn usd_to_lamports_never_panics(usd in any::<u64>(), price in any::<i64>(), exponent in -30i32..30) {
+        let lamports = usd_to_lamports(usd, price, exponent);
+        if price <= 0 {
+            prop_assert!(is_error(lamports, PostError::InvalidPrice));
+        }
+    }
+
+    #[test]
+    fn usd_to_lamports_grows_with_the_amount(
+        usd in 0u64..1_000_000_000_000,
+        extra in 0u64..1_000_000_000_000,
+        // Pyth SOL/USD range: $0.01 to $1000 at exponent -8
+        price in 1_000_000i64..100_000_000_000,
+    ) {
+        let low = usd_to_lamports(usd, price, -8).unwrap();
+        let high = usd_to_lamports(usd + extra, price, -8).unwrap();
+        prop_assert!(low <= high);
+    }
+
+    #[test]
+    fn rank_score_decays_with_age(weight in any::<u64>(), age in any::<i64>(), later in 0i64..100 * RANK_HALF_LIFE) {
+        let score = rank_score(weight, age);
+        prop_assert!(score <= weight);
+        prop_assert!(rank_score(weight, age.saturating_add(later)) <= score);
+    }
+
+    #[test]
+    fn released_refund_grows_to_the_whole_refund(
+        refund in any::<u64>(),
+        start in -1_000_000_000i64..1_000_000_000,
+        duration in 0i64..100_000_000,
+        now in any::<i64>(),
+        later in 0i64..100_000_000,
+    ) {
+        let released = released_refund(refund, start, duration, now);
+        prop_assert!(released <= refund);
+        prop_assert!(released <= released_refund(refund, start, duration, now.saturating_add(later)));
+        prop_assert_eq!(released_refund(refund, start, duration, start + duration), refund);
+    }
+
+    #[test]
+    fn vesting_claims_add_up_to_the_vested_amount(
+        duration in 1i64..10_000_000,
+        deposits in prop::collection::vec((0u64..1_000_000_000_000, 0i64..20_000_000), 1..20),
+    ) {
+        let mut vault = vesting_vault(0, duration);
+        let mut deposits = deposits;
+        deposits.sort_by_key(|&(_, at)| at);
+
+        let mut received = 0;
+        let mut paid = 0;
+        for (amount, at) in deposits {
+            received += amount;
+            paid += claim(&mut vault, received, at);
+            prop_assert_eq!(paid, vault.vested_at(received, at));
+            prop_assert!(paid <= received);
+        }
+        paid += claim(&mut vault, received, duration);
+        prop_assert_eq!(paid, received);
+    }
+}
@@ -20,6 +20,22 @@ const WALLET_1 = new PublicKey("69TwH2GJiBSA8Eo3DunPGsXGWjNFY267zRrpHptYWCuC");
 const WALLET_2 = new PublicKey("EbhZhYumUZyHQCPbeaLLt57SS2obHiFdp7TMLjUBBqcD"); // GUARDIAN - 10%
 const WALLET_3 = new PublicKey("HxtzFZhjNCsQb9ZqEyK8xYftqv6j6AM2MAT6uwWG3KYd"); // SACHA - 45%
 
+// Pyth SOL/USD price update account (pegs the minimum bid to USD)
+const PRICE_UPDATE = new PublicKey(
+  process.env.NEXT_PUBLIC_PYTH_SOL_USD_ACCOUNT || "7UVimffxr9ow1uXYxsr4LHAcV58mLzhmwaeKvJ1pjLiE"
+);
+
+// Compressed NFT receipts (only passed when the config has a receipt tree)
+const BUBBLEGUM_PROGRAM_ID = new PublicKey("BGUMAp9Gq7iTEuizy4pqaxsTyUCBK68MDfK752saRPUY");
+const SPL_NOOP_PROGRAM_ID = new PublicKey("noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV");
+const SPL_ACCOUNT_COMPRESSION_PROGRAM_ID = new PublicKey("cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK");
+
+// Config field offsets (after the 8-byte discriminator, see Config in the program)
+const CONFIG_RECEIPT_TREE_OFFSET = 50;
+const CONFIG_MIN_STAKE_OFFSET = 100;
+const CONFIG_VESTING_VAULT_OFFSET = 116;
+const CONFIG_TREASURY_VERSION_OFFSET = 198;
+
 let connection: Connection | null = null;
 
 function getConnection(): Connection {
@@ -49,6 +65,41 @@ function isValidString(str: string): boolean {
   return /^[\x20-\x7E]*$/.test(str);
 }
 
+function findPDA(seeds: (Buffer | Uint8Array)[], programId: PublicKey = PROGRAM_ID): PublicKey {
+  return PublicKey.findProgramAddressSync(seeds, programId)[0];
+}
+
+// Absent optional accounts are passed as the program id (Anchor convention)
+function optionalAccount(pubkey: PublicKey | null, isWritable: boolean) {
+  return pubkey
+    ? { pubkey, isSigner: false, isWritable }
+    : { pubkey: PROGRAM_ID, isSigner: false, isWritable: false };
+}
+
+interface PostConfig {
+  receiptTree: PublicKey | null;
+  minStake: bigint;
+  trancheRecipient: PublicKey;
+  treasuryVersion: number;
+}
+
+async function fetchPostConfig(conn: Connection): Promise<PostConfig> {
+  const info = await conn.getAccountInfo(findPDA([Buffer.from("config")]));
+  if (!info) throw new Error("Post program config not initialized");
+  const data = info.data;
+
+  const receiptTree = new PublicKey(data.subarray(CONFIG_RECEIPT_TREE_OFFSET, CONFIG_RECEIPT_TREE_OFFSET + 32));
+  const vestingVault = new PublicKey(data.subarray(CONFIG_VESTING_VAULT_OFFSET, CONFIG_VESTING_VAULT_OFFSET + 32));
+
+  return {
+    receiptTree: receiptTree.equals(PublicKey.default) ? null : receiptTree,
+    minStake: data.readBigUInt64LE(CONFIG_MIN_STAKE_OFFSET),
+    // The 10% tranche goes to the vesting vault when vesting is enabled
+    trancheRecipient: vestingVault.equals(PublicKey.default) ? WALLET_2 : vestingVault,
+    treasuryVersion: data[CONFIG_TREASURY_VERSION_OFFSET],
+  };
+}
+
 // ============================================================================
 // TYPES
 // ============================================================================
@@ -71,7 +122,7 @@ export interface ShadowPost {
  * The bid transfer to treasury is now handled atomically inside the Solana program via CPI
  * @param shadowKeypair - The shadow wallet keypair (signer)
  * @param target - Target URL (e.g., "https://x.com/elonmusk" or "https://xray.one/user/username")
- * @param content - Post content (max 512 chars)
+ * @param content - Post content (max 512 bytes)
  * @param bid - Bid amount in lamports (1 SOL = 1_000_000_000 lamports)
  */
 export async function createShadowPost(
@@ -81,18 +132,27 @@ export async function createShadowPost(
   bid: bigint
 ): Promise<string> {
   const conn = getConnection();
+  const author = shadowKeypair.publicKey;
+  const config = await fetchPostConfig(conn);
+  const { epoch } = await conn.getEpochInfo();
 
-  // Derive PDA for the treasury account
-  const [treasuryPDA] = PublicKey.findProgramAddressSync(
-    [Buffer.from("treasury")],
-    PROGRAM_ID
-  );
+  // Treasury PDA (version 1 keeps the original ["treasury"] seeds)
+  const treasuryVersionSeed = config.treasuryVersion <= 1 ? [] : [Buffer.from([config.treasuryVersion])];
+  const treasuryPDA = findPDA([Buffer.from("treasury"), ...treasuryVersionSeed]);
 
-  // Derive PDA for the post account
-  const [postPDA] = PublicKey.findProgramAddressSync(
-    [Buffer.from("post"), shadowKeypair.publicKey.toBuffer(), Buffer.from(target)],
-    PROGRAM_ID
-  );
+  // Revenue snapshot of the current epoch
+  const epochBuffer = Buffer.alloc(8);
+  epochBuffer.writeBigUInt64LE(BigInt(epoch));
+  const revenueSnapshotPDA = findPDA([Buffer.from("revenue"), epochBuffer]);
+
+  const authorIndexPDA = findPDA([Buffer.from("author_index"), author.toBuffer()]);
+  const postPDA = findPDA([Buffer.from("post"), author.toBuffer(), Buffer.from(target)]);
+  const authorStatsPDA = findPDA([Buffer.from("author_stats"), author.toBuffer()]);
+  const stakePDA = config.minStake > BigInt(0) ? findPDA([Buffer.from("stake"), author.toBuffer()]) : null;
+
+  const tree = config.receiptTree;
+  const treeConfig = tree ? findPDA([tree.toBuffer()], BUBBLEGUM_PROGRAM_ID) : null;
+  const withReceipt = (pubkey: PublicKey) => (tree ? pubkey : null);
 
   const discriminator = await getDiscriminator("create_post");
 
@@ -118,15 +178,31 @@ export async function createShadowPost(
     bidBuffer
   ]);
 
-  // Account metas (order MUST match Rust struct: author, treasury, wallet_1, wallet_2, wallet_3, post, system_program)
+  // Account metas (order MUST match the CreatePost struct in the program)
   const keys = [
-    { pubkey: shadowKeypair.publicKey, isSigner: true, isWritable: true },   // author
-    { pubkey: treasuryPDA, isSigner: false, isWritable: true },              // treasury PDA
-    { pubkey: WALLET_1, isSigner: false, isWritable: true },                 // wallet_1 (GRINGO - 45%)
-    { pubkey: WALLET_2, isSigner: false, isWritable: true },                 // wallet_2 (GUARDIAN - 10%)
-    { pubkey: WALLET_3, isSigner: false, isWritable: true },                 // wallet_3 (SACHA - 45%)
-    { pubkey: postPDA, isSigner: false, isWritable: true },                  // post PDA
-    { pubkey: SystemProgram.programId, isSigner: false, isWritable: false }  // system_program
+    { pubkey: author, isSigner: true, isWritable: true },                            // author
+    { pubkey: findPDA([Buffer.from("config")]), isSigner: false, isWritable: false }, // config
+    { pubkey: PRICE_UPDATE, isSigner: false, isWritable: false },                    // Pyth SOL/USD price
+    { pubkey: revenueSnapshotPDA, isSigner: false, isWritable: true },               // revenue snapshot
+    { pubkey: treasuryPDA, isSigner: false, isWritable: true },                      // treasury PDA
+    { pubkey: WALLET_1, isSigner: false, isWritable: true },                         // wallet_1 (GRINGO - 45%)
+    { pubkey: config.trancheRecipient, isSigner: false, isWritable: true },          // wallet_2 (GUARDIAN - 10%) or vesting vault
+    { pubkey: WALLET_3, isSigner: false, isWritable: true },                         // wallet_3 (SACHA - 45%)
+    { pubkey: authorIndexPDA, isSigner: false, isWritable: true },                   // author index
+    { pubkey: postPDA, isSigner: false, isWritable: true },                          // post PDA
+    optionalAccount(null, true),                                                     // referrer
+    optionalAccount(null, true),                                                     // referral
+    optionalAccount(null, true),                                                     // charity wallet
+    optionalAccount(null, true),                                                     // charity
+    { pubkey: authorStatsPDA, isSigner: false, isWritable: true },                   // author stats (rate limit)
+    optionalAccount(stakePDA, false),                                                // stake account
+    optionalAccount(treeConfig, true),                                               // receipt tree config
+    optionalAccount(tree, true),                                                     // receipt tree
+    optionalAccount(withReceipt(findPDA([Buffer.from("receipt_authority")])), false), // receipt authority
+    optionalAccount(withReceipt(BUBBLEGUM_PROGRAM_ID), false),                       // bubblegum program
+    optionalAccount(withReceipt(SPL_NOOP_PROGRAM_ID), false),                        // log wrapper
+    optionalAccount(withReceipt(SPL_ACCOUNT_COMPRESSION_PROGRAM_ID), false),         // compression program
+    { pubkey: SystemProgram.programId, isSigner: false, isWritable: false }          // system_program
   ];
 
   const postInstruction = new TransactionInstruction({
@@ -1,10 +1,26 @@
-import { PublicKey, Connection, SystemProgram, Transaction } from "@solana/web3.js";
+import {
+  PublicKey,
+  Connection,
+  SystemProgram,
+  Transaction,
+  TransactionInstruction,
+} from "@solana/web3.js";
+import BN from "bn.js";
 import * as nacl from "tweetnacl";
 import { getShadowWalletName } from "@/lib/api";
 import {
   RescueCipher,
   x25519,
+  getArciumProgramId,
+  getClockAccAddress,
   getClusterAccAddress,
+  getCompDefAccAddress,
+  getCompDefAccOffset,
+  getComputationAccAddress,
+  getExecutingPoolAccAddress,
+  getFeePoolAccAddress,
+  getMempoolAccAddress,
+  getMXEAccAddress,
 } from "@arcium-hq/client";
 
 // ============================================================================
@@ -16,6 +32,8 @@ const RPC_URL = process.env.NEXT_PUBLIC_SOLANA_RPC_URL || "https://api.devnet.so
 
 // Arcium Configuration
 const ARCIUM_CLUSTER_OFFSET = 456; // Devnet cluster offset
+const MPC_POLL_INTERVAL_MS = 2000;
+const MPC_TIMEOUT_MS = 120_000;
 
 // ============================================================================
 // TYPES
@@ -70,35 +88,41 @@ async function hashPublicKey(pubkey: PublicKey): Promise<Uint8Array> {
 }
 
 /**
- * Encrypt a 32-byte hash with RescueCipher for MPC (returns 32-byte ciphertext)
+ * Encrypt 32-byte hashes with RescueCipher for MPC (one 32-byte ciphertext per hash).
+ * All hashes go through a single encrypt call so they never share keystream under the
+ * same nonce: the first one decrypts on its own under `nonce`, the next ones continue the counter.
  */
-function encryptHashForMPC(
-  hash: Uint8Array,
+function encryptHashesForMPC(
+  hashes: Uint8Array[],
   cipher: RescueCipher,
   nonce: Uint8Array
-): Uint8Array {
-  // Split the 32-byte hash into 4 u64 values for RescueCipher
+): Uint8Array[] {
+  // Split each 32-byte hash into 4 u64 values for RescueCipher
   const values: bigint[] = [];
-  for (let i = 0; i < 4; i++) {
-    let val = BigInt(0);
-    for (let j = 0; j < 8; j++) {
-      val |= BigInt(hash[i * 8 + j]) << BigInt(j * 8);
+  for (const hash of hashes) {
+    for (let i = 0; i < 4; i++) {
+      let val = BigInt(0);
+      for (let j = 0; j < 8; j++) {
+        val |= BigInt(hash[i * 8 + j]) << BigInt(j * 8);
+      }
+      values.push(val);
     }
-    values.push(val);
   }
 
   // Encrypt with RescueCipher
   const ciphertexts = cipher.encrypt(values, nonce);
 
-  // Flatten ciphertexts back to 32 bytes (take first 32 bytes)
-  const result = new Uint8Array(32);
-  for (let i = 0; i < 4 && i < ciphertexts.length; i++) {
-    const ct = ciphertexts[i];
-    for (let j = 0; j < 8 && i * 8 + j < 32; j++) {
-      result[i * 8 + j] = ct[j];
+  // Flatten each hash's 4 ciphertexts back to 32 bytes (first 8 bytes of each)
+  return hashes.map((_, h) => {
+    const result = new Uint8Array(32);
+    for (let i = 0; i < 4; i++) {
+      const ct = ciphertexts[h * 4 + i];
+      for (let j = 0; j < 8; j++) {
+        result[i * 8 + j] = ct[j];
+      }
     }
-  }
-  return result;
+    return result;
+  });
 }
 
 /**
@@ -212,6 +236,20 @@ export function getPrivateMessagePDA(
   );
 }
 
+export function getRateBucketPDA(sender: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("rate_bucket"), sender.toBuffer()],
+    PRIVATE_MESSAGES_PROGRAM_ID
+  );
+}
+
+function getArciumSignerPDA(): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("ArciumSignerAccount")],
+    PRIVATE_MESSAGES_PROGRAM_ID
+  );
+}
+
 // ============================================================================
 // ON-CHAIN OPERATIONS
 // ============================================================================
@@ -321,6 +359,139 @@ export async function updateUserKey(
   return txid;
 }
 
+// ============================================================================
+// RATE LIMITING (Arcium MPC token bucket)
+// ============================================================================
+
+// RateBucket layout: discriminator (8) + encrypted_state (64) + state_nonce (16)
+// + sender (32) + send_authorized (1) + computation_pending (1) + ...
+const RATE_BUCKET_SEND_AUTHORIZED_OFFSET = 120;
+const RATE_BUCKET_PENDING_OFFSET = 121;
+
+/**
+ * Accounts queuing an Arcium computation, after the instruction's own accounts
+ */
+function arciumQueueAccounts(circuit: string, computationOffset: BN) {
+  const compDefOffset = Buffer.from(getCompDefAccOffset(circuit)).readUInt32LE();
+  return [
+    { pubkey: getArciumSignerPDA()[0], isSigner: false, isWritable: true },
+    { pubkey: getMXEAccAddress(PRIVATE_MESSAGES_PROGRAM_ID), isSigner: false, isWritable: false },
+    { pubkey: getMempoolAccAddress(ARCIUM_CLUSTER_OFFSET), isSigner: false, isWritable: true },
+    { pubkey: getExecutingPoolAccAddress(ARCIUM_CLUSTER_OFFSET), isSigner: false, isWritable: true },
+    {
+      pubkey: getComputationAccAddress(ARCIUM_CLUSTER_OFFSET, computationOffset),
+      isSigner: false,
+      isWritable: true,
+    },
+    {
+      pubkey: getCompDefAccAddress(PRIVATE_MESSAGES_PROGRAM_ID, compDefOffset),
+      isSigner: false,
+      isWritable: false,
+    },
+    { pubkey: getClusterAccAddress(ARCIUM_CLUSTER_OFFSET), isSigner: false, isWritable: true },
+    { pubkey: getFeePoolAccAddress(), isSigner: false, isWritable: true },
+    { pubkey: getClockAccAddress(), isSigner: false, isWritable: true },
+    { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+    { pubkey: getArciumProgramId(), isSigner: false, isWritable: false },
+  ];
+}
+
+async function sendInstruction(
+  connection: Connection,
+  feePayer: PublicKey,
+  instruction: TransactionInstruction,
+  signTransaction: (tx: Transaction) => Promise<Transaction>
+): Promise<string> {
+  const transaction = new Transaction().add(instruction);
+  const { blockhash, lastValidBlockHeight } = await connection.getLatestBlockhash();
+  transaction.recentBlockhash = blockhash;
+  transaction.feePayer = feePayer;
+
+  const signed = await signTransaction(transaction);
+  const txid = await connection.sendRawTransaction(signed.serialize());
+
+  const confirmation = await connection.confirmTransaction({
+    signature: txid,
+    blockhash,
+    lastValidBlockHeight,
+  }, "confirmed");
+
+  if (confirmation.value.err) {
+    throw new Error(`Transaction failed: ${JSON.stringify(confirmation.value.err)}`);
+  }
+
+  return txid;
+}
+
+/**
+ * Wait for the MPC callback on the rate bucket (computation_pending cleared)
+ */
+async function waitForRateBucket(connection: Connection, rateBucketPDA: PublicKey): Promise<Buffer> {
+  const deadline = Date.now() + MPC_TIMEOUT_MS;
+  while (Date.now() < deadline) {
+    const accountInfo = await connection.getAccountInfo(rateBucketPDA, "confirmed");
+    if (accountInfo && accountInfo.data[RATE_BUCKET_PENDING_OFFSET] === 0) {
+      return accountInfo.data;
+    }
+    await new Promise((resolve) => setTimeout(resolve, MPC_POLL_INTERVAL_MS));
+  }
+  throw new Error("Arcium MPC computation timed out. Try again later.");
+}
+
+/**
+ * Get a send authorization from the sender's encrypted rate bucket (created
+ * on first use). The MPC consumes one token and only reveals whether the
+ * message may be sent.
+ */
+async function requestSendAuthorization(
+  connection: Connection,
+  senderPubkey: PublicKey,
+  signTransaction: (tx: Transaction) => Promise<Transaction>
+): Promise<void> {
+  const [rateBucketPDA] = getRateBucketPDA(senderPubkey);
+
+  if (!(await connection.getAccountInfo(rateBucketPDA, "confirmed"))) {
+    const computationOffset = new BN(nacl.randomBytes(8), "le");
+    const mxeNonce = nacl.randomBytes(16);
+    await sendInstruction(connection, senderPubkey, new TransactionInstruction({
+      keys: [
+        { pubkey: senderPubkey, isSigner: true, isWritable: true },
+        { pubkey: rateBucketPDA, isSigner: false, isWritable: true },
+        ...arciumQueueAccounts("init_rate_bucket", computationOffset),
+      ],
+      programId: PRIVATE_MESSAGES_PROGRAM_ID,
+      data: Buffer.concat([
+        Buffer.from([0, 116, 38, 113, 83, 149, 252, 33]),
+        computationOffset.toArrayLike(Buffer, "le", 8),
+        Buffer.from(mxeNonce),
+      ]),
+    }), signTransaction);
+  }
+
+  // An authorization left over from a send that failed is still valid
+  let bucket = await waitForRateBucket(connection, rateBucketPDA);
+  if (bucket[RATE_BUCKET_SEND_AUTHORIZED_OFFSET] === 1) return;
+
+  const computationOffset = new BN(nacl.randomBytes(8), "le");
+  await sendInstruction(connection, senderPubkey, new TransactionInstruction({
+    keys: [
+      { pubkey: senderPubkey, isSigner: true, isWritable: true },
+      { pubkey: rateBucketPDA, isSigner: false, isWritable: true },
+      ...arciumQueueAccounts("consume_rate_token", computationOffset),
+    ],
+    programId: PRIVATE_MESSAGES_PROGRAM_ID,
+    data: Buffer.concat([
+      Buffer.from([19, 121, 202, 191, 97, 161, 35, 11]),
+      computationOffset.toArrayLike(Buffer, "le", 8),
+    ]),
+  }), signTransaction);
+
+  bucket = await waitForRateBucket(connection, rateBucketPDA);
+  if (bucket[RATE_BUCKET_SEND_AUTHORIZED_OFFSET] !== 1) {
+    throw new Error("Rate limit reached. Wait a moment before sending another message.");
+  }
+}
+
 export async function sendPrivateMessageOnChain(
   connection: Connection,
   senderPubkey: PublicKey,
@@ -329,6 +500,8 @@ export async function sendPrivateMessageOnChain(
   nonce: Uint8Array,
   signTransaction: (tx: Transaction) => Promise<Transaction>
 ): Promise<string> {
+  await requestSendAuthorization(connection, senderPubkey, signTransaction);
+
   const messageIndex = await getMessageIndex(senderPubkey.toString());
 
   // Fetch MXE public key for Arcium encryption
@@ -357,9 +530,12 @@ export async function sendPrivateMessageOnChain(
   mpcNonceBytes = new Uint8Array(16);
   crypto.getRandomValues(mpcNonceBytes);
 
-  // Encrypt sender and recipient hashes with RescueCipher
-  encryptedSenderHash = encryptHashForMPC(senderHash, cipher, mpcNonceBytes);
-  encryptedRecipientHash = encryptHashForMPC(recipientHash, cipher, mpcNonceBytes);
+  // Encrypt both hashes in one pass, recipient first (the access check decrypts it under mpcNonceBytes)
+  [encryptedRecipientHash, encryptedSenderHash] = encryptHashesForMPC(
+    [recipientHash, senderHash],
+    cipher,
+    mpcNonceBytes
+  );
 
   const discriminator = Buffer.from([241, 158, 126, 220, 116, 108, 212, 168]);
   const messageIndexBuffer = bigintToLeBytes(messageIndex, 8);
@@ -379,12 +555,14 @@ export async function sendPrivateMessageOnChain(
 
   const [counterPDA] = getPrivateMessageCounterPDA();
   const [privateMessagePDA] = getPrivateMessagePDA(senderPubkey, messageIndex);
+  const [rateBucketPDA] = getRateBucketPDA(senderPubkey);
 
   const transaction = new Transaction().add({
     keys: [
       { pubkey: senderPubkey, isSigner: true, isWritable: true },
       { pubkey: counterPDA, isSigner: false, isWritable: true },
       { pubkey: privateMessagePDA, isSigner: false, isWritable: true },
+      { pubkey: rateBucketPDA, isSigner: false, isWritable: true },
       { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
     ],
     programId: PRIVATE_MESSAGES_PROGRAM_ID,